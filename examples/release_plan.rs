@@ -0,0 +1,27 @@
+//! Prints the release plan for the workspace in the current directory using only
+//! the library, so it also builds with `--no-default-features`:
+//!
+//! ```sh
+//! cargo run --no-default-features --example release_plan
+//! ```
+
+use changelogs::error::Result;
+use changelogs::{Config, Workspace, changelog_entry, plan};
+
+fn main() -> Result<()> {
+    let workspace = Workspace::discover()?;
+    let config = Config::load(&workspace.changelog_dir())?;
+    let changelogs = changelog_entry::read_all(&workspace.changelog_dir())?;
+
+    let release_plan = plan::assemble(&workspace, changelogs, &config);
+    for release in &release_plan.releases {
+        println!(
+            "{} {} -> {} ({})",
+            release.name, release.old_version, release.new_version, release.bump
+        );
+    }
+    for warning in &release_plan.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    Ok(())
+}
@@ -1,7 +1,11 @@
 use crate::BumpType;
+use crate::config::ChangelogSort;
 use crate::error::{Error, Result};
+use crate::fs_util::atomic_write;
+use crate::git::{Author, Git};
 use rand::Rng;
 
+use std::collections::HashMap;
 use std::path::Path;
 
 const ADJECTIVES: &[&str] = &[
@@ -42,15 +46,29 @@ pub struct Changelog {
     pub summary: String,
     pub releases: Vec<Release>,
     pub commit: Option<String>,
+    /// Per-package summaries from `<!-- package: name -->` sections, used instead
+    /// of `summary` in that package's changelog.
+    pub package_summaries: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone)]
+impl Changelog {
+    /// Returns the summary to write for `package`.
+    pub fn summary_for(&self, package: &str) -> &str {
+        self.package_summaries
+            .get(package)
+            .map(String::as_str)
+            .unwrap_or(&self.summary)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Release {
     pub package: String,
     pub bump: BumpType,
 }
 
-pub fn parse(id: &str, content: &str) -> Result<Changelog> {
+/// Splits a changelog file into its frontmatter and summary.
+fn split_frontmatter<'a>(id: &str, content: &'a str) -> Result<(&'a str, &'a str)> {
     let content = content.trim();
 
     if !content.starts_with("---") {
@@ -65,8 +83,50 @@ pub fn parse(id: &str, content: &str) -> Result<Changelog> {
         Error::ChangelogParse(id.to_string(), "missing frontmatter end".to_string())
     })?;
 
-    let frontmatter = &rest[..end].trim();
-    let summary = rest[end + 3..].trim().to_string();
+    Ok((rest[..end].trim(), rest[end + 3..].trim()))
+}
+
+/// Marks the start of a package-specific summary, e.g. `<!-- package: utils -->`.
+fn package_marker(line: &str) -> Option<&str> {
+    let name = line
+        .trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim()
+        .strip_prefix("package:")?
+        .trim();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Splits a body into the shared summary and the package-specific sections that
+/// follow it.
+fn split_package_summaries(body: &str) -> (String, HashMap<String, String>) {
+    let mut shared = String::new();
+    let mut sections: Vec<(String, String)> = Vec::new();
+
+    for line in body.lines() {
+        if let Some(package) = package_marker(line) {
+            sections.push((package.to_string(), String::new()));
+            continue;
+        }
+        let text = match sections.last_mut() {
+            Some((_, text)) => text,
+            None => &mut shared,
+        };
+        text.push_str(line);
+        text.push('\n');
+    }
+
+    let overrides = sections
+        .into_iter()
+        .map(|(package, text)| (package, text.trim().to_string()))
+        .collect();
+    (shared.trim().to_string(), overrides)
+}
+
+pub fn parse(id: &str, content: &str) -> Result<Changelog> {
+    let (frontmatter, body) = split_frontmatter(id, content)?;
+    let (summary, package_summaries) = split_package_summaries(body);
 
     let frontmatter_value: serde_yaml::Value = serde_yaml::from_str(frontmatter)?;
 
@@ -100,41 +160,169 @@ pub fn parse(id: &str, content: &str) -> Result<Changelog> {
         summary,
         releases,
         commit,
+        package_summaries,
     })
 }
 
+/// Ids become file names, so they are limited to ASCII letters, digits, `-`, `_`
+/// and `.`, and may not start with `.`.
+pub fn is_safe_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('.')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Checks a changelog file and returns every problem found, rather than stopping
+/// at the first one like `parse`.
+pub fn lint(id: &str, content: &str, package_names: &[&str]) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if !is_safe_id(id) {
+        problems.push(format!(
+            "unsafe id '{}': use only letters, digits, '-', '_' and '.'",
+            id
+        ));
+    }
+
+    let (frontmatter, summary) = match split_frontmatter(id, content) {
+        Ok(parts) => parts,
+        Err(Error::ChangelogParse(_, reason)) => {
+            problems.push(reason);
+            return problems;
+        }
+        Err(e) => {
+            problems.push(e.to_string());
+            return problems;
+        }
+    };
+
+    let value: serde_yaml::Value = match serde_yaml::from_str(frontmatter) {
+        Ok(value) => value,
+        Err(e) => {
+            problems.push(format!("invalid frontmatter: {}", e));
+            return problems;
+        }
+    };
+
+    let map = match value {
+        serde_yaml::Value::Mapping(map) => map,
+        serde_yaml::Value::Null => serde_yaml::Mapping::new(),
+        _ => {
+            problems.push("frontmatter must map package names to bump types".to_string());
+            return problems;
+        }
+    };
+
+    let (shared, package_summaries) = split_package_summaries(summary);
+    let mut packages = Vec::new();
+
+    for (key, value) in map {
+        let Some(package) = key.as_str() else {
+            problems.push(format!("invalid package name: {:?}", key));
+            continue;
+        };
+        if package == "commit" {
+            continue;
+        }
+        packages.push(package.to_string());
+
+        match value.as_str().map(str::parse::<BumpType>) {
+            Some(Ok(_)) => {}
+            Some(Err(_)) => problems.push(format!(
+                "{}: invalid bump type '{}' (expected patch, minor or major)",
+                package,
+                value.as_str().unwrap_or_default()
+            )),
+            None => problems.push(format!(
+                "{}: bump type must be patch, minor or major",
+                package
+            )),
+        }
+
+        if !package_names.contains(&package) {
+            problems.push(format!("unknown package '{}'", package));
+        }
+    }
+
+    let mut overridden: Vec<&String> = package_summaries.keys().collect();
+    overridden.sort();
+    for package in overridden {
+        if !packages.contains(package) {
+            problems.push(format!(
+                "summary for '{}' but it is not in the frontmatter",
+                package
+            ));
+        }
+    }
+
+    // `changelogs add --empty` writes no packages and no summary; that is allowed.
+    let missing_summary = packages.iter().any(|p| {
+        package_summaries
+            .get(p)
+            .map_or(shared.is_empty(), |s| s.is_empty())
+    });
+    if missing_summary {
+        problems.push("summary is empty".to_string());
+    }
+
+    problems
+}
+
 pub fn serialize(changelog: &Changelog) -> String {
-    let mut frontmatter = String::new();
-    for release in &changelog.releases {
-        frontmatter.push_str(&format!("{}: {}\n", release.package, release.bump));
+    let frontmatter = frontmatter(&changelog.releases);
+
+    let mut body = changelog.summary.clone();
+    let mut packages: Vec<_> = changelog.package_summaries.iter().collect();
+    packages.sort();
+    for (package, summary) in packages {
+        body.push_str(&format!("\n\n<!-- package: {} -->\n\n{}", package, summary));
     }
 
-    format!("---\n{}---\n\n{}\n", frontmatter, changelog.summary)
+    format!("---\n{}---\n\n{}\n", frontmatter, body.trim_start())
+}
+
+/// Returns the `package: bump` lines of the frontmatter. serde_yaml quotes package
+/// names that would not read back as the same string (e.g. `@scope/pkg`, `true` or
+/// a name with `: ` in it).
+fn frontmatter(releases: &[Release]) -> String {
+    if releases.is_empty() {
+        return String::new();
+    }
+    let map: serde_yaml::Mapping = releases
+        .iter()
+        .map(|r| (r.package.clone().into(), r.bump.to_string().into()))
+        .collect();
+    serde_yaml::to_string(&map).unwrap_or_default()
 }
 
 pub struct CommitInfo {
     pub pr_number: Option<u32>,
     pub commit_sha: String,
-    pub authors: Vec<String>,
+    pub authors: Vec<Author>,
 }
 
-pub fn get_commit_info(_changelog_dir: &Path, id: &str) -> Option<CommitInfo> {
-    let file_path = format!(".changelog/{}.md", id);
+/// Finds the commit that added changelog `id` and the PR it came from. With
+/// `pr_trailers`, `PR-URL:` and `Reviewed-on:` trailers also name the PR.
+pub fn get_commit_info(
+    git: &dyn Git,
+    changelog_dir: &Path,
+    id: &str,
+    pr_trailers: bool,
+) -> Option<CommitInfo> {
+    let file_path = changelog_dir.join(format!("{}.md", id));
+    let file_path = file_path.to_string_lossy();
 
     // Step 1: Find the commit that originally added the file
-    let add_commit = find_add_commit(&file_path)?;
+    let add_commit = git.file_add_commit(&file_path)?;
 
     // Step 2: Check if the add commit itself has a PR number (squash merge case)
     // For squash merges, the commit message contains "(#123)"
-    let output = std::process::Command::new("git")
-        .args(["log", "--format=%s", "-1", &add_commit])
-        .output()
-        .ok()?;
+    let commit_message = git.commit_message(&add_commit)?;
+    let authors = get_commit_authors(git, &file_path, &add_commit);
 
-    let commit_message = String::from_utf8_lossy(&output.stdout);
-    let authors = get_commit_authors(&file_path, &add_commit);
-
-    if let Some(pr_number) = extract_pr_number(commit_message.trim()) {
+    if let Some(pr_number) = extract_pr_number(&commit_message, pr_trailers) {
         return Some(CommitInfo {
             pr_number: Some(pr_number),
             commit_sha: add_commit,
@@ -144,36 +332,15 @@ pub fn get_commit_info(_changelog_dir: &Path, id: &str) -> Option<CommitInfo> {
 
     // Step 3: Look for merge commit (traditional merge case)
     // Find the first merge commit that contains the add commit
-    let merge_output = std::process::Command::new("git")
-        .args([
-            "log",
-            "--merges",
-            "--ancestry-path",
-            "--reverse",
-            "--format=%H %s",
-            &format!("{}..HEAD", add_commit),
-        ])
-        .output()
-        .ok()?;
-
-    let merge_stdout = String::from_utf8_lossy(&merge_output.stdout);
-
-    if let Some(merge_line) = merge_stdout.lines().next() {
-        let merge_line = merge_line.trim();
-        if !merge_line.is_empty() {
-            let parts: Vec<&str> = merge_line.splitn(2, ' ').collect();
-            if parts.len() >= 2 {
-                let commit_sha = parts[0].to_string();
-                let commit_message = parts[1];
-                if let Some(pr_number) = extract_pr_number(commit_message) {
-                    return Some(CommitInfo {
-                        pr_number: Some(pr_number),
-                        commit_sha,
-                        authors,
-                    });
-                }
-            }
-        }
+    if let Some((commit_sha, commit_message)) =
+        git.merges_containing(&add_commit).into_iter().next()
+        && let Some(pr_number) = extract_pr_number(&commit_message, pr_trailers)
+    {
+        return Some(CommitInfo {
+            pr_number: Some(pr_number),
+            commit_sha,
+            authors,
+        });
     }
 
     // Fallback: no PR number found
@@ -184,81 +351,40 @@ pub fn get_commit_info(_changelog_dir: &Path, id: &str) -> Option<CommitInfo> {
     })
 }
 
-fn find_add_commit(file_path: &str) -> Option<String> {
-    let output = std::process::Command::new("git")
-        .args([
-            "log",
-            "--follow",
-            "--diff-filter=A",
-            "--format=%H",
-            "-1",
-            "--",
-            file_path,
-        ])
-        .output()
-        .ok()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let sha = stdout.trim();
-    if sha.is_empty() {
-        None
-    } else {
-        Some(sha.to_string())
-    }
-}
-
-fn get_commit_authors(file_path: &str, add_commit: &str) -> Vec<String> {
+fn get_commit_authors(git: &dyn Git, file_path: &str, add_commit: &str) -> Vec<Author> {
     // Get authors from the add commit and any commits that touched the file
     // up to that point (for PRs with multiple commits before squash/merge)
-    let output = std::process::Command::new("git")
-        .args([
-            "log",
-            "--follow",
-            "--format=%aN",
-            &format!("{}^..{}", add_commit, add_commit),
-            "--",
-            file_path,
-        ])
-        .output()
-        .ok();
-
-    let Some(output) = output else {
-        return Vec::new();
-    };
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut authors: Vec<String> = stdout
-        .lines()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
+    let mut authors = git.authors_for(add_commit, Some(file_path));
 
     // If no authors found with range, try just the add commit
     if authors.is_empty() {
-        let fallback = std::process::Command::new("git")
-            .args(["log", "--format=%aN", "-1", add_commit])
-            .output()
-            .ok();
-
-        if let Some(fb_output) = fallback {
-            let fb_stdout = String::from_utf8_lossy(&fb_output.stdout);
-            let author = fb_stdout.trim();
-            if !author.is_empty() {
-                authors.push(author.to_string());
-            }
-        }
+        authors = git.authors_for(add_commit, None);
     }
 
-    authors.sort();
-    authors.dedup();
+    authors.sort_by(|a, b| (&a.name, &a.email).cmp(&(&b.name, &b.email)));
+    authors.dedup_by(|a, b| a.name == b.name && a.email == b.email);
     authors
 }
 
-fn extract_pr_number(message: &str) -> Option<u32> {
-    let re = regex::Regex::new(r"\(#(\d+)\)").ok()?;
-    re.captures(message)
-        .and_then(|cap| cap.get(1))
-        .and_then(|m| m.as_str().parse().ok())
+/// Finds the PR number in a commit message, preferring in order: a `(#N)` ending
+/// the subject (the last one, as merge queues append their own), a `Merge pull
+/// request #N` subject, a `PR-URL:` or `Reviewed-on:` trailer ending in the number
+/// when `trailers` is set, and then the first `(#N)` anywhere in the subject.
+fn extract_pr_number(message: &str, trailers: bool) -> Option<u32> {
+    let subject = message.lines().next().unwrap_or_default().trim();
+    let number = |pattern: &str, text: &str| -> Option<u32> {
+        let re = regex::Regex::new(pattern).ok()?;
+        re.captures(text)?.get(1)?.as_str().parse().ok()
+    };
+
+    number(r"\(#(\d+)\)$", subject)
+        .or_else(|| number(r"^Merge pull request #(\d+)\b", subject))
+        .or_else(|| {
+            trailers
+                .then(|| number(r"(?m)^(?:PR-URL|Reviewed-on):\s*\S*?(\d+)/?\s*$", message))
+                .flatten()
+        })
+        .or_else(|| number(r"\(#(\d+)\)", subject))
 }
 
 pub fn read_all(changelog_dir: &Path) -> Result<Vec<Changelog>> {
@@ -272,7 +398,8 @@ pub fn read_all(changelog_dir: &Path) -> Result<Vec<Changelog>> {
         let entry = entry?;
         let path = entry.path();
 
-        if path.extension().is_some_and(|ext| ext == "md") {
+        // Channel subdirectories hold their own pending changelogs.
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "md") {
             let filename = path.file_stem().unwrap().to_string_lossy().to_string();
 
             if filename == "README" {
@@ -289,10 +416,67 @@ pub fn read_all(changelog_dir: &Path) -> Result<Vec<Changelog>> {
     Ok(changelogs)
 }
 
+/// Orders changelogs, and so the bullets written from them, by `order`. Ties and
+/// `Id` fall back to the id, so the result doesn't depend on the read order.
+pub fn sort(
+    changelogs: &mut [Changelog],
+    order: ChangelogSort,
+    changelog_dir: &Path,
+    git: &dyn Git,
+) {
+    sort_with(changelogs, order, |id| date_added(git, changelog_dir, id));
+}
+
+/// [`sort`] with `added` giving each changelog's timestamp for `DateAdded`.
+fn sort_with(
+    changelogs: &mut [Changelog],
+    order: ChangelogSort,
+    added: impl Fn(&str) -> Option<i64>,
+) {
+    match order {
+        ChangelogSort::Id => changelogs.sort_by(|a, b| a.id.cmp(&b.id)),
+        ChangelogSort::Summary => {
+            changelogs.sort_by(|a, b| (a.summary.trim(), &a.id).cmp(&(b.summary.trim(), &b.id)))
+        }
+        // Changelogs without a timestamp go last.
+        ChangelogSort::DateAdded => {
+            changelogs.sort_by_cached_key(|c| (added(&c.id).unwrap_or(i64::MAX), c.id.clone()))
+        }
+    }
+}
+
+/// When changelog `id` was added: the timestamp of the commit that added it, or
+/// its mtime while it is uncommitted.
+fn date_added(git: &dyn Git, changelog_dir: &Path, id: &str) -> Option<i64> {
+    let path = changelog_dir.join(format!("{}.md", id));
+    if let Some(timestamp) = git
+        .file_add_commit(&path.to_string_lossy())
+        .and_then(|commit| git.commit_timestamp(&commit))
+    {
+        return Some(timestamp);
+    }
+    let modified = std::fs::metadata(&path).ok()?.modified().ok()?;
+    let secs = modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    i64::try_from(secs).ok()
+}
+
+/// Reads the pending changelog `id` from `changelog_dir`.
+pub fn read(changelog_dir: &Path, id: &str) -> Result<Changelog> {
+    let path = changelog_dir.join(format!("{}.md", id));
+    if !is_safe_id(id) || !path.is_file() {
+        return Err(Error::ChangelogNotFound(id.to_string()));
+    }
+    let content = std::fs::read_to_string(&path)?;
+    parse(id, &content)
+}
+
 pub fn write(changelog_dir: &Path, changelog: &Changelog) -> Result<()> {
     let path = changelog_dir.join(format!("{}.md", changelog.id));
     let content = serialize(changelog);
-    std::fs::write(path, content)?;
+    atomic_write(&path, content)?;
     Ok(())
 }
 
@@ -307,6 +491,51 @@ pub fn delete(changelog_dir: &Path, id: &str) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::fake::FakeGit;
+
+    #[test]
+    fn test_commit_info_squash_merge() {
+        let git = FakeGit {
+            add_commit: Some("abc1234"),
+            message: "feat: thing (#42)",
+            authors: vec!["bob", "alice", "bob"],
+            merges: vec![("def5678", "Merge pull request #1 (#1)")],
+            ..FakeGit::default()
+        };
+        let info = get_commit_info(&git, Path::new(".changes"), "id", false).unwrap();
+        assert_eq!(git.lookups.borrow()[0], "file_add_commit .changes/id.md");
+        assert_eq!(info.pr_number, Some(42));
+        assert_eq!(info.commit_sha, "abc1234");
+        let names: Vec<&str> = info.authors.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn test_commit_info_merge_commit() {
+        let git = FakeGit {
+            add_commit: Some("abc1234"),
+            message: "wip",
+            merges: vec![("def5678", "Merge branch 'x' (#7)")],
+            ..FakeGit::default()
+        };
+        let info = get_commit_info(&git, Path::new(".changes"), "id", false).unwrap();
+        assert_eq!(info.pr_number, Some(7));
+        assert_eq!(info.commit_sha, "def5678");
+        assert_eq!(info.authors[0].name, "fallback");
+    }
+
+    #[test]
+    fn test_commit_info_no_pr() {
+        let git = FakeGit {
+            add_commit: Some("abc1234"),
+            message: "wip",
+            authors: vec!["alice"],
+            ..FakeGit::default()
+        };
+        let info = get_commit_info(&git, Path::new(".changes"), "id", false).unwrap();
+        assert_eq!(info.pr_number, None);
+        assert_eq!(info.commit_sha, "abc1234");
+    }
 
     #[test]
     fn test_parse_changelog() {
@@ -336,6 +565,7 @@ Fixed bug Y.
                 bump: BumpType::Minor,
             }],
             commit: None,
+            package_summaries: HashMap::new(),
         };
 
         let serialized = serialize(&changelog);
@@ -343,6 +573,101 @@ Fixed bug Y.
         assert!(serialized.contains("Test summary"));
     }
 
+    fn roundtrip(package: &str) -> Changelog {
+        let changelog = Changelog {
+            id: "test".to_string(),
+            summary: "Summary".to_string(),
+            releases: vec![Release {
+                package: package.to_string(),
+                bump: BumpType::Patch,
+            }],
+            commit: None,
+            package_summaries: HashMap::new(),
+        };
+        parse("test", &serialize(&changelog)).unwrap()
+    }
+
+    #[test]
+    fn test_serialize_quotes_special_names() {
+        for name in [
+            "@scope/pkg",
+            "@wevm/viem",
+            "my.pkg.name",
+            "on",
+            "yes",
+            "true",
+            "null",
+            "1.0",
+            "*star",
+            "with space",
+            "key: value",
+            "#hash",
+            "tab\tname",
+            "line\nbreak",
+        ] {
+            let reparsed = roundtrip(name);
+            assert_eq!(reparsed.releases.len(), 1, "{name}");
+            assert_eq!(reparsed.releases[0].package, name);
+            assert_eq!(reparsed.releases[0].bump, BumpType::Patch);
+        }
+
+        // Plain names stay unquoted.
+        let releases = |name: &str| {
+            vec![Release {
+                package: name.to_string(),
+                bump: BumpType::Patch,
+            }]
+        };
+        assert_eq!(frontmatter(&releases("my-crate")), "my-crate: patch\n");
+        assert_eq!(
+            frontmatter(&releases("my.pkg.name")),
+            "my.pkg.name: patch\n"
+        );
+        assert_ne!(frontmatter(&releases("true")), "true: patch\n");
+    }
+
+    #[test]
+    fn test_parse_quoted_names() {
+        let content =
+            "---\n'@scope/pkg': minor\n\"on\": patch\nyes: major\nmy.pkg: patch\n---\n\nSummary\n";
+        let changelog = parse("test", content).unwrap();
+
+        let names: Vec<&str> = changelog
+            .releases
+            .iter()
+            .map(|r| r.package.as_str())
+            .collect();
+        assert_eq!(names, ["@scope/pkg", "on", "yes", "my.pkg"]);
+        assert_eq!(changelog.releases[2].bump, BumpType::Major);
+    }
+
+    const OVERRIDE_ENTRY: &str = "---\ncore: minor\nutils: patch\n---\n\nAdded `Client::retry`.\n\n<!-- package: utils -->\n\nRe-exported `Client::retry` from `core`.\n";
+
+    #[test]
+    fn test_parse_package_summaries() {
+        let changelog = parse("test", OVERRIDE_ENTRY).unwrap();
+
+        assert_eq!(changelog.summary, "Added `Client::retry`.");
+        assert_eq!(changelog.package_summaries.len(), 1);
+        assert_eq!(changelog.summary_for("core"), "Added `Client::retry`.");
+        assert_eq!(
+            changelog.summary_for("utils"),
+            "Re-exported `Client::retry` from `core`."
+        );
+    }
+
+    #[test]
+    fn test_serialize_package_summaries_roundtrip() {
+        let changelog = parse("test", OVERRIDE_ENTRY).unwrap();
+
+        let serialized = serialize(&changelog);
+        assert_eq!(serialized, OVERRIDE_ENTRY);
+
+        let reparsed = parse("test", &serialized).unwrap();
+        assert_eq!(reparsed.summary, changelog.summary);
+        assert_eq!(reparsed.package_summaries, changelog.package_summaries);
+    }
+
     #[test]
     fn test_generate_id() {
         let id = generate_id();
@@ -401,26 +726,60 @@ Fixed bug Y.
     }
 
     #[test]
-    fn test_extract_pr_number_squash_merge() {
-        assert_eq!(extract_pr_number("feat: add feature (#42)"), Some(42));
-    }
-
-    #[test]
-    fn test_extract_pr_number_merge_commit() {
-        assert_eq!(
-            extract_pr_number("Merge pull request (#123) from branch"),
-            Some(123)
-        );
-    }
-
-    #[test]
-    fn test_extract_pr_number_no_pr() {
-        assert_eq!(extract_pr_number("regular commit message"), None);
-    }
-
-    #[test]
-    fn test_extract_pr_number_multiple_takes_first() {
-        assert_eq!(extract_pr_number("fix (#1) and (#2)"), Some(1));
+    fn test_extract_pr_number() {
+        let cases: &[(&str, bool, Option<u32>)] = &[
+            ("feat: add feature (#42)", false, Some(42)),
+            ("Merge pull request (#123) from branch", false, Some(123)),
+            ("regular commit message", false, None),
+            // A trailing number wins over an issue reference earlier in the subject.
+            (
+                "fix overflow (#99) reported upstream (#120)",
+                false,
+                Some(120),
+            ),
+            ("fix (#1) and (#2)", false, Some(2)),
+            // Merge queues append the PR that re-merged it.
+            ("feat: thing (#123) (#456)", false, Some(456)),
+            ("Merge pull request #123 from org/branch", false, Some(123)),
+            (
+                "Merge pull request #123 from org/branch\n\nfix (#7)",
+                false,
+                Some(123),
+            ),
+            // Without a better match, the first reference in the subject.
+            ("fix overflow (#99) reported by users", false, Some(99)),
+            // Only the subject is searched for `(#N)`.
+            ("wip\n\nCloses (#5)", false, None),
+            (
+                "fix: parse dates\n\nPR-URL: https://gitea.example.com/org/repo/pulls/314",
+                true,
+                Some(314),
+            ),
+            (
+                "fix: parse dates\n\nChange-Id: I8c1e\nReviewed-on: https://review.example.com/c/repo/+/2718\n",
+                true,
+                Some(2718),
+            ),
+            (
+                "fix: parse dates\n\nPR-URL: https://gitea.example.com/org/repo/pulls/314",
+                false,
+                None,
+            ),
+            // The subject still comes first.
+            (
+                "fix: parse dates (#8)\n\nReviewed-on: https://review.example.com/c/repo/+/2718",
+                true,
+                Some(8),
+            ),
+        ];
+
+        for (message, trailers, expected) in cases {
+            assert_eq!(
+                extract_pr_number(message, *trailers),
+                *expected,
+                "{message:?}"
+            );
+        }
     }
 
     #[test]
@@ -458,6 +817,33 @@ Fixed bug Y.
         assert_eq!(changelogs[0].id, "real-entry");
     }
 
+    #[test]
+    fn test_read_all_skips_channel_subdirectories() {
+        let dir = tempfile::tempdir().unwrap();
+        let beta = dir.path().join("beta");
+        std::fs::create_dir(&beta).unwrap();
+        std::fs::write(
+            dir.path().join("stable-fix.md"),
+            "---\npkg: patch\n---\n\nStable fix\n",
+        )
+        .unwrap();
+        std::fs::write(
+            beta.join("beta-feature.md"),
+            "---\npkg: minor\n---\n\nBeta feature\n",
+        )
+        .unwrap();
+        // A directory that happens to end in `.md` is not an entry either.
+        std::fs::create_dir(dir.path().join("notes.md")).unwrap();
+
+        let stable = read_all(dir.path()).unwrap();
+        assert_eq!(stable.len(), 1);
+        assert_eq!(stable[0].id, "stable-fix");
+
+        let beta = read_all(&beta).unwrap();
+        assert_eq!(beta.len(), 1);
+        assert_eq!(beta[0].id, "beta-feature");
+    }
+
     #[test]
     fn test_read_all_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
@@ -484,6 +870,7 @@ Fixed bug Y.
                 bump: BumpType::Minor,
             }],
             commit: None,
+            package_summaries: HashMap::new(),
         };
 
         write(dir.path(), &changelog).unwrap();
@@ -496,6 +883,29 @@ Fixed bug Y.
         assert_eq!(changelogs[0].releases[0].bump, BumpType::Minor);
     }
 
+    #[test]
+    fn test_read_single() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("brave-cat.md"),
+            "---\npkg: minor\n---\n\nAdded a thing\n",
+        )
+        .unwrap();
+
+        let changelog = read(dir.path(), "brave-cat").unwrap();
+        assert_eq!(changelog.id, "brave-cat");
+        assert_eq!(changelog.releases[0].bump, BumpType::Minor);
+
+        assert!(matches!(
+            read(dir.path(), "missing"),
+            Err(Error::ChangelogNotFound(_))
+        ));
+        assert!(matches!(
+            read(dir.path(), "../brave-cat"),
+            Err(Error::ChangelogNotFound(_))
+        ));
+    }
+
     #[test]
     fn test_delete_existing() {
         let dir = tempfile::tempdir().unwrap();
@@ -512,4 +922,169 @@ Fixed bug Y.
         let dir = tempfile::tempdir().unwrap();
         assert!(delete(dir.path(), "no-such-id").is_ok());
     }
+
+    const PACKAGES: &[&str] = &["foo", "bar"];
+
+    #[test]
+    fn test_lint_valid() {
+        let content = "---\nfoo: minor\ncommit: abc123\n---\n\nAdded x\n";
+        assert!(lint("brave-lions-dance", content, PACKAGES).is_empty());
+    }
+
+    #[test]
+    fn test_lint_empty_changelog_allowed() {
+        assert!(lint("calm-owls-rest", "---\n---\n", PACKAGES).is_empty());
+    }
+
+    #[test]
+    fn test_lint_missing_frontmatter() {
+        assert_eq!(
+            lint("x", "just text", PACKAGES),
+            vec!["missing frontmatter"]
+        );
+        assert_eq!(
+            lint("x", "---\nfoo: patch\n", PACKAGES),
+            vec!["missing frontmatter end"]
+        );
+    }
+
+    #[test]
+    fn test_lint_invalid_yaml() {
+        let problems = lint("x", "---\nfoo: [patch\n---\n\nText\n", PACKAGES);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("invalid frontmatter: "));
+
+        assert_eq!(
+            lint("x", "---\n- foo\n---\n\nText\n", PACKAGES),
+            vec!["frontmatter must map package names to bump types"]
+        );
+    }
+
+    #[test]
+    fn test_lint_invalid_bump_types() {
+        let content = "---\nfoo: huge\nbar: 1\n---\n\nText\n";
+        assert_eq!(
+            lint("x", content, PACKAGES),
+            vec![
+                "foo: invalid bump type 'huge' (expected patch, minor or major)",
+                "bar: bump type must be patch, minor or major",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_unknown_package() {
+        let content = "---\nfoo: patch\nbaz: patch\n---\n\nText\n";
+        assert_eq!(lint("x", content, PACKAGES), vec!["unknown package 'baz'"]);
+    }
+
+    #[test]
+    fn test_lint_empty_summary() {
+        assert_eq!(
+            lint("x", "---\nfoo: patch\n---\n", PACKAGES),
+            vec!["summary is empty"]
+        );
+    }
+
+    #[test]
+    fn test_lint_package_summaries() {
+        // Only `bar` gets an override, so the shared summary is still required for `foo`.
+        let content = "---\nfoo: patch\nbar: patch\n---\n\n<!-- package: bar -->\nFor bar\n<!-- package: baz -->\nFor baz\n";
+        assert_eq!(
+            lint("x", content, PACKAGES),
+            vec![
+                "summary for 'baz' but it is not in the frontmatter",
+                "summary is empty"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_unsafe_id() {
+        let content = "---\nfoo: patch\n---\n\nText\n";
+        assert_eq!(
+            lint("my change", content, PACKAGES),
+            vec!["unsafe id 'my change': use only letters, digits, '-', '_' and '.'"]
+        );
+        assert!(!is_safe_id(".hidden"));
+        assert!(is_safe_id("v1.2_fix-3"));
+    }
+
+    #[test]
+    fn test_lint_reports_all_problems() {
+        let content = "---\nfoo: huge\nbaz: patch\n---\n";
+        assert_eq!(lint("a b", content, PACKAGES).len(), 4);
+    }
+
+    fn entries(specs: &[(&str, &str)]) -> Vec<Changelog> {
+        specs
+            .iter()
+            .map(|(id, summary)| {
+                parse(id, &format!("---\nfoo: patch\n---\n\n{summary}\n")).unwrap()
+            })
+            .collect()
+    }
+
+    fn ids(changelogs: &[Changelog]) -> Vec<&str> {
+        changelogs.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_is_independent_of_read_order() {
+        let specs = [("b-fox", "Zebra"), ("a-cat", "Apple"), ("c-owl", "Mango")];
+        for order in [
+            ChangelogSort::Id,
+            ChangelogSort::Summary,
+            ChangelogSort::DateAdded,
+        ] {
+            let mut forward = entries(&specs);
+            let mut reversed = entries(&specs);
+            reversed.reverse();
+            sort_with(&mut forward, order, |_| Some(0));
+            sort_with(&mut reversed, order, |_| Some(0));
+            assert_eq!(ids(&forward), ids(&reversed), "{order:?}");
+        }
+
+        let mut changelogs = entries(&specs);
+        sort_with(&mut changelogs, ChangelogSort::Summary, |_| None);
+        assert_eq!(ids(&changelogs), ["a-cat", "c-owl", "b-fox"]);
+    }
+
+    #[test]
+    fn test_sort_by_date_added() {
+        let mut changelogs = entries(&[
+            ("a-cat", "First by id"),
+            ("b-fox", "Uncommitted"),
+            ("c-owl", "Merged first"),
+            ("d-elk", "Merged with c-owl"),
+        ]);
+        let added = |id: &str| match id {
+            "a-cat" => Some(1_700_000_300),
+            "c-owl" | "d-elk" => Some(1_700_000_100),
+            _ => None,
+        };
+
+        sort_with(&mut changelogs, ChangelogSort::DateAdded, added);
+
+        assert_eq!(ids(&changelogs), ["c-owl", "d-elk", "a-cat", "b-fox"]);
+    }
+
+    #[test]
+    fn test_date_added_falls_back_to_mtime() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a-cat.md"), "---\nfoo: patch\n---\n\nx\n").unwrap();
+        let git = FakeGit {
+            add_commit: Some("abc1234"),
+            ..FakeGit::default()
+        };
+
+        let added = date_added(&git, dir.path(), "a-cat").unwrap();
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        assert!((now - added).abs() < 60, "{added}");
+        assert_eq!(date_added(&git, dir.path(), "missing"), None);
+    }
 }
@@ -0,0 +1,143 @@
+use chrono::NaiveDate;
+use semver::Version;
+
+/// A CHANGELOG.md split into the text before the first `## ` heading and one
+/// section per `## ` heading. Concatenating `preamble` and every section's `text`
+/// reproduces the input exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedChangelog<'a> {
+    pub preamble: &'a str,
+    pub sections: Vec<Section<'a>>,
+}
+
+/// A `## ` section, from its heading line up to the next `## ` heading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    pub heading: &'a str,
+    pub version: Option<Version>,
+    pub text: &'a str,
+}
+
+impl ParsedChangelog<'_> {
+    pub fn find_version(&self, version: &Version) -> Option<usize> {
+        self.sections
+            .iter()
+            .position(|s| s.version.as_ref() == Some(version))
+    }
+}
+
+pub fn parse(content: &str) -> ParsedChangelog<'_> {
+    let mut starts = Vec::new();
+    let mut in_code_block = false;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        } else if !in_code_block && line.starts_with("## ") {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+
+    let preamble = &content[..starts.first().copied().unwrap_or(content.len())];
+    let sections = starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(content.len());
+            let text = &content[start..end];
+            let heading = text.lines().next().unwrap_or_default();
+            Section {
+                heading,
+                version: heading_version(heading),
+                text,
+            }
+        })
+        .collect();
+
+    ParsedChangelog { preamble, sections }
+}
+
+/// Extracts the version from a heading such as `## 1.2.0 (2025-01-15)`,
+/// ``## `pkg@1.2.0` ``, `## v1.2.0` or `## [1.2.0] - 2025-01-15`.
+pub fn heading_version(heading: &str) -> Option<Version> {
+    let title = heading.strip_prefix("## ")?.trim();
+    let token = title.split_whitespace().next()?;
+    let token = token.trim_matches(|c| c == '`' || c == '[' || c == ']');
+    let token = token.rsplit('@').next().unwrap_or(token);
+    let token = token.strip_prefix('v').unwrap_or(token);
+    Version::parse(token).ok()
+}
+
+/// Extracts the release date from a heading such as `## 1.2.0 (2025-01-15)` or
+/// `## [1.2.0] - 2025-01-15`.
+pub fn heading_date(heading: &str) -> Option<NaiveDate> {
+    let title = heading.strip_prefix("## ")?;
+    title.split_whitespace().find_map(|token| {
+        let token = token.trim_matches(|c| c == '(' || c == ')' || c == '[' || c == ']');
+        NaiveDate::parse_from_str(token, "%Y-%m-%d").ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog\n\n## `foo@1.1.0`\n\n### Minor Changes\n\n- Added x\n\n## `foo@1.0.0`\n\n- Initial release\n";
+
+    #[test]
+    fn test_parse_sections() {
+        let parsed = parse(CHANGELOG);
+
+        assert_eq!(parsed.preamble, "# Changelog\n\n");
+        assert_eq!(parsed.sections.len(), 2);
+        assert_eq!(parsed.sections[0].heading, "## `foo@1.1.0`");
+        assert_eq!(parsed.sections[0].version, Some(Version::new(1, 1, 0)));
+        assert_eq!(
+            parsed.sections[0].text,
+            "## `foo@1.1.0`\n\n### Minor Changes\n\n- Added x\n\n"
+        );
+        assert_eq!(parsed.sections[1].version, Some(Version::new(1, 0, 0)));
+        assert_eq!(parsed.find_version(&Version::new(1, 0, 0)), Some(1));
+        assert_eq!(parsed.find_version(&Version::new(2, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_parse_roundtrips() {
+        let parsed = parse(CHANGELOG);
+        let rebuilt: String = std::iter::once(parsed.preamble)
+            .chain(parsed.sections.iter().map(|s| s.text))
+            .collect();
+        assert_eq!(rebuilt, CHANGELOG);
+    }
+
+    #[test]
+    fn test_parse_ignores_headings_in_code_blocks() {
+        let content = "## 1.0.0 (2025-01-15)\n\n```md\n## 0.9.0\n```\n";
+        let parsed = parse(content);
+
+        assert_eq!(parsed.sections.len(), 1);
+        assert_eq!(parsed.sections[0].text, content);
+    }
+
+    #[test]
+    fn test_heading_version_formats() {
+        let v = Some(Version::new(1, 2, 0));
+        assert_eq!(heading_version("## 1.2.0 (2025-01-15)"), v);
+        assert_eq!(heading_version("## `my-crate@1.2.0`"), v);
+        assert_eq!(heading_version("## v1.2.0"), v);
+        assert_eq!(heading_version("## [1.2.0] - 2025-01-15"), v);
+        assert_eq!(heading_version("## Unreleased"), None);
+        assert_eq!(heading_version("### 1.2.0"), None);
+    }
+
+    #[test]
+    fn test_heading_date_formats() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 15);
+        assert_eq!(heading_date("## 1.2.0 (2025-01-15)"), date);
+        assert_eq!(heading_date("## [1.2.0] - 2025-01-15"), date);
+        assert_eq!(heading_date("## `my-crate@1.2.0`"), None);
+        assert_eq!(heading_date("### 1.2.0 (2025-01-15)"), None);
+    }
+}
@@ -1,30 +1,60 @@
 use crate::BumpType;
 use crate::changelog_entry::{self, Changelog};
-use crate::config::ChangelogFormat;
-use crate::error::Result;
+use crate::changelog_reader;
+use crate::config::{ChangelogConfig, ChangelogFormat, ChangelogStyle, Config};
+use crate::ecosystems::Package;
+use crate::error::{Error, Result};
+use crate::fs_util::{atomic_write, is_writable};
+use crate::git::{self, Git};
+use crate::github::AuthorResolver;
 use crate::plan::PackageRelease;
 use crate::workspace::Workspace;
 use chrono::Utc;
-use std::collections::{BTreeMap, HashSet};
-use std::path::Path;
-use std::process::Command;
-
-fn get_github_url() -> Option<String> {
-    let output = Command::new("git")
-        .args(["remote", "get-url", "origin"])
-        .output()
-        .ok()?;
-
-    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-    if url.starts_with("git@github.com:") {
-        let repo = url
-            .strip_prefix("git@github.com:")?
-            .strip_suffix(".git")
-            .unwrap_or(&url);
-        Some(format!("https://github.com/{}", repo))
+use semver::Version;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Version of the markdown this crate writes. It goes up whenever the same entries
+/// would render differently, so CI can notice before golden diffs break.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The repository's `https://github.com/owner/repo` URL, from the `origin` remote.
+pub fn get_github_url(git: &dyn Git) -> Option<String> {
+    parse_github_url(&git.remote_url("origin")?)
+}
+
+/// The repository's `https://github.com/owner/repo` URL: from `repo_url`
+/// (`[changelog] repo_url`), then the `origin` remote. The override covers shallow
+/// CI checkouts without a remote and `origin` pointing at a fork. A `repo_url` that
+/// isn't a GitHub repository is warned about and the remote is used instead.
+pub fn resolve_github_url(repo_url: Option<&str>, git: &dyn Git) -> Option<String> {
+    if let Some(url) = repo_url {
+        match parse_github_url(url) {
+            Some(url) => return Some(url),
+            None => warn!(
+                "repo_url '{}' is not a GitHub repository (owner/repo or a github.com URL); using the origin remote",
+                url
+            ),
+        }
+    }
+    get_github_url(git)
+}
+
+/// Accepts remote URLs (`git@github.com:owner/repo.git`, `https://github.com/owner/repo`)
+/// and the `owner/repo` shorthand.
+fn parse_github_url(url: &str) -> Option<String> {
+    let url = url.trim_end_matches('/');
+    if let Some(repo) = url.strip_prefix("git@github.com:") {
+        Some(format!(
+            "https://github.com/{}",
+            repo.strip_suffix(".git").unwrap_or(repo)
+        ))
     } else if url.starts_with("https://github.com/") {
-        Some(url.strip_suffix(".git").unwrap_or(&url).to_string())
+        Some(url.strip_suffix(".git").unwrap_or(url).to_string())
+    } else if !url.contains(':') && url.split('/').count() == 2 {
+        Some(format!("https://github.com/{}", url))
     } else {
         None
     }
@@ -36,24 +66,139 @@ struct ChangeWithMeta {
     authors: Vec<String>,
 }
 
+/// Returns the PR or commit link and the author attributions for a changelog.
+/// Both are empty when the repository isn't on GitHub or the file has no history.
+fn change_meta(
+    git: &dyn Git,
+    authors: &AuthorResolver,
+    changelog_dir: &Path,
+    github_url: Option<&str>,
+    id: &str,
+    pr_trailers: bool,
+) -> (Option<(String, String)>, Vec<String>) {
+    let Some(base) = github_url else {
+        return (None, Vec::new());
+    };
+    let Some(info) = changelog_entry::get_commit_info(git, changelog_dir, id, pr_trailers) else {
+        return (None, Vec::new());
+    };
+
+    let link = if let Some(pr) = info.pr_number {
+        (format!("{}/pull/{}", base, pr), format!("#{}", pr))
+    } else {
+        let short_sha = &info.commit_sha[..7.min(info.commit_sha.len())];
+        (
+            format!("{}/commit/{}", base, short_sha),
+            short_sha.to_string(),
+        )
+    };
+
+    let repo = base.trim_start_matches("https://github.com/");
+    let mut attributions: Vec<String> = Vec::new();
+    for author in &info.authors {
+        // Several emails can belong to the same GitHub account.
+        let attribution = authors.attribute(repo, author);
+        if !attributions.contains(&attribution) {
+            attributions.push(attribution);
+        }
+    }
+
+    (Some(link), attributions)
+}
+
+/// Link and author lookups shared by every entry of a run: the GitHub URL is
+/// resolved once, and each changelog's commit info once per id however many
+/// packages it touches.
+struct EntryMeta<'a> {
+    git: &'a dyn Git,
+    authors: &'a AuthorResolver,
+    changelog_dir: &'a Path,
+    github_url: Option<String>,
+    pr_trailers: bool,
+    cache: RefCell<HashMap<String, ChangeMeta>>,
+}
+
+/// A changelog's PR or commit link and its author attributions.
+type ChangeMeta = (Option<(String, String)>, Vec<String>);
+
+impl<'a> EntryMeta<'a> {
+    fn new(
+        git: &'a dyn Git,
+        authors: &'a AuthorResolver,
+        changelog_dir: &'a Path,
+        config: &ChangelogConfig,
+    ) -> Self {
+        Self {
+            git,
+            authors,
+            changelog_dir,
+            github_url: resolve_github_url(config.repo_url.as_deref(), git),
+            pr_trailers: config.pr_trailers,
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, id: &str) -> ChangeMeta {
+        self.cache
+            .borrow_mut()
+            .entry(id.to_string())
+            .or_insert_with(|| {
+                change_meta(
+                    self.git,
+                    self.authors,
+                    self.changelog_dir,
+                    self.github_url.as_deref(),
+                    id,
+                    self.pr_trailers,
+                )
+            })
+            .clone()
+    }
+}
+
 pub fn generate_entry(
     release: &PackageRelease,
     changelogs: &[Changelog],
     changelog_dir: &Path,
+    config: &ChangelogConfig,
+    authors: &AuthorResolver,
 ) -> String {
     let date = Utc::now().format("%Y-%m-%d").to_string();
-    generate_entry_with_date(release, changelogs, changelog_dir, &date)
+    let git = git::open();
+    generate_entry_with_date(
+        release,
+        changelogs,
+        changelog_dir,
+        config,
+        git.as_ref(),
+        authors,
+        &date,
+    )
 }
 
 pub fn generate_entry_with_date(
     release: &PackageRelease,
     changelogs: &[Changelog],
     changelog_dir: &Path,
+    config: &ChangelogConfig,
+    git: &dyn Git,
+    authors: &AuthorResolver,
     date: &str,
 ) -> String {
-    let mut entry = format!("## {} ({})\n\n", release.new_version, date);
+    let meta = EntryMeta::new(git, authors, changelog_dir, config);
+    render_entry(release, changelogs, config, &meta, date)
+}
 
-    let github_url = get_github_url();
+fn render_entry(
+    release: &PackageRelease,
+    changelogs: &[Changelog],
+    config: &ChangelogConfig,
+    meta: &EntryMeta,
+    date: &str,
+) -> String {
+    let version = release.new_version.to_string();
+    let mut entry = version_heading(&version, date, config);
+    entry.push_str(&entry_prefix(&version, config));
 
     let mut major_changes = Vec::new();
     let mut minor_changes = Vec::new();
@@ -69,26 +214,9 @@ pub fn generate_entry_with_date(
                 continue;
             }
 
-            let summary = changelog.summary.trim().to_string();
+            let summary = changelog.summary_for(&release.name).trim().to_string();
 
-            let (link_info, authors) = github_url
-                .as_ref()
-                .and_then(|base| {
-                    let info = changelog_entry::get_commit_info(changelog_dir, &changelog.id)?;
-
-                    let link_info = if let Some(pr) = info.pr_number {
-                        Some((format!("{}/pull/{}", base, pr), format!("#{}", pr)))
-                    } else {
-                        let short_sha = &info.commit_sha[..7.min(info.commit_sha.len())];
-                        Some((
-                            format!("{}/commit/{}", base, short_sha),
-                            short_sha.to_string(),
-                        ))
-                    };
-
-                    Some((link_info, info.authors))
-                })
-                .unwrap_or((None, Vec::new()));
+            let (link_info, authors) = meta.get(&changelog.id);
 
             let change = ChangeWithMeta {
                 summary,
@@ -103,31 +231,247 @@ pub fn generate_entry_with_date(
         }
     }
 
-    if !major_changes.is_empty() {
-        entry.push_str("### Major Changes\n\n");
-        for change in major_changes {
-            write_change_lines(&mut entry, &change);
+    // A root changelog describes the workspace as a whole, so internal dependency
+    // bumps are only listed in per-crate changelogs.
+    if config.format == ChangelogFormat::PerCrate && !release.updated_dependencies.is_empty() {
+        let change = dependency_updates_change(&release.updated_dependencies);
+        match release.bump {
+            BumpType::Major => major_changes.push(change),
+            BumpType::Minor => minor_changes.push(change),
+            BumpType::Patch => patch_changes.push(change),
         }
-        entry.push('\n');
     }
 
-    if !minor_changes.is_empty() {
-        entry.push_str("### Minor Changes\n\n");
-        for change in minor_changes {
-            write_change_lines(&mut entry, &change);
+    write_sections(
+        &mut entry,
+        major_changes,
+        minor_changes,
+        patch_changes,
+        config,
+    );
+
+    entry
+}
+
+/// The `## ` heading of a release, followed by a blank line.
+fn version_heading(version: &str, date: &str, config: &ChangelogConfig) -> String {
+    match config.style {
+        ChangelogStyle::BumpTypes => format!("## {} ({})\n\n", version, date),
+        ChangelogStyle::KeepAChangelog => format!("## [{}] - {}\n\n", version, date),
+    }
+}
+
+/// `[changelog] entry_prefix` with `{version}` filled in, as its own paragraph.
+fn entry_prefix(version: &str, config: &ChangelogConfig) -> String {
+    match config.entry_prefix.as_deref().map(str::trim) {
+        Some(prefix) if !prefix.is_empty() => {
+            format!("{}\n\n", prefix.replace("{version}", version))
         }
-        entry.push('\n');
+        _ => String::new(),
+    }
+}
+
+/// An "Updated dependencies" bullet with one nested bullet per dependency.
+fn dependency_updates_change(updates: &[(String, Version)]) -> ChangeWithMeta {
+    let mut summary = "Updated dependencies:".to_string();
+    for (name, version) in updates {
+        summary.push_str(&format!("\n  - {}@{}", name, version));
+    }
+    ChangeWithMeta {
+        summary,
+        link: None,
+        authors: Vec::new(),
+    }
+}
+
+/// Writes the bump sections of an entry, or the keepachangelog.com sections with
+/// that style.
+///
+/// Summary lines starting with a configured tag (e.g. `[breaking]`) are pulled out
+/// of their bump section and written under the tag's section header instead, ahead
+/// of the bump sections.
+fn write_sections(
+    entry: &mut String,
+    major_changes: Vec<ChangeWithMeta>,
+    minor_changes: Vec<ChangeWithMeta>,
+    patch_changes: Vec<ChangeWithMeta>,
+    config: &ChangelogConfig,
+) {
+    let mut tagged: BTreeMap<&str, Vec<ChangeWithMeta>> = BTreeMap::new();
+    let mut by_bump = Vec::new();
+
+    for (bump, header, changes) in [
+        (BumpType::Major, "Major Changes", major_changes),
+        (BumpType::Minor, "Minor Changes", minor_changes),
+        (BumpType::Patch, "Patch Changes", patch_changes),
+    ] {
+        let mut untagged = Vec::new();
+        for change in changes {
+            let (rest, tagged_lines) = split_tagged_lines(change, config);
+            for (tag, change) in tagged_lines {
+                tagged.entry(tag).or_default().push(change);
+            }
+            untagged.extend(rest);
+        }
+        by_bump.push((bump, header, untagged));
     }
 
-    if !patch_changes.is_empty() {
-        entry.push_str("### Patch Changes\n\n");
-        for change in patch_changes {
-            write_change_lines(&mut entry, &change);
+    let sections: Vec<(String, Vec<ChangeWithMeta>)> = match config.style {
+        ChangelogStyle::BumpTypes => by_bump
+            .into_iter()
+            .map(|(bump, header, changes)| {
+                let header = match config.section_emoji.for_bump(bump) {
+                    Some(emoji) => format!("{} {}", emoji, header),
+                    None => header.to_string(),
+                };
+                (header, changes)
+            })
+            .collect(),
+        ChangelogStyle::KeepAChangelog => {
+            let mut sections: Vec<(String, Vec<ChangeWithMeta>)> = KEEP_A_CHANGELOG_SECTIONS
+                .iter()
+                .map(|header| (header.to_string(), Vec::new()))
+                .collect();
+            for change in by_bump.into_iter().flat_map(|(_, _, changes)| changes) {
+                let header = keep_a_changelog_section(&change.summary);
+                if let Some((_, changes)) = sections.iter_mut().find(|(h, _)| h == header) {
+                    changes.push(change);
+                }
+            }
+            sections
+        }
+    };
+
+    let tagged = config
+        .tags
+        .iter()
+        .filter_map(|(tag, header)| Some((header.clone(), tagged.remove(tag.as_str())?)));
+
+    for (header, changes) in tagged.chain(sections) {
+        if changes.is_empty() {
+            continue;
+        }
+        entry.push_str(&format!("### {}\n\n", header));
+        for change in changes {
+            write_change_lines(entry, &change);
         }
         entry.push('\n');
     }
+}
 
-    entry
+/// The keepachangelog.com sections, in the order they are written.
+const KEEP_A_CHANGELOG_SECTIONS: [&str; 6] = [
+    "Added",
+    "Changed",
+    "Deprecated",
+    "Removed",
+    "Fixed",
+    "Security",
+];
+
+/// Picks the keepachangelog.com section for a change from the leading verb of its
+/// summary, e.g. "Add ..." or "Fixed ...". Anything unrecognized is "Changed".
+fn keep_a_changelog_section(summary: &str) -> &'static str {
+    let lower = summary.to_lowercase();
+    let first_line = lower.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let word: String = first_line
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '-' || c == '*')
+        .chars()
+        .take_while(|c| c.is_alphabetic())
+        .collect();
+
+    let is = |verbs: &[&str]| verbs.iter().any(|verb| is_inflection_of(&word, verb));
+
+    if word == "security" || lower.contains("cve-") || lower.contains("vulnerab") {
+        "Security"
+    } else if is(&["deprecate"]) {
+        "Deprecated"
+    } else if is(&["remove", "drop", "delete"]) {
+        "Removed"
+    } else if is(&["fix", "resolve", "correct", "prevent", "repair"]) {
+        "Fixed"
+    } else if word == "new"
+        || is(&[
+            "add",
+            "introduce",
+            "implement",
+            "support",
+            "create",
+            "allow",
+        ])
+    {
+        "Added"
+    } else {
+        "Changed"
+    }
+}
+
+/// Whether `word` is `verb` or one of its inflections: "fixes", "fixed", "fixing",
+/// "removed", "dropped".
+fn is_inflection_of(word: &str, verb: &str) -> bool {
+    let stem = verb.strip_suffix('e').unwrap_or(verb);
+    let doubled = format!("{}{}", verb, &verb[verb.len() - 1..]);
+    word == verb
+        || ["s", "es"]
+            .iter()
+            .any(|s| word.strip_suffix(s) == Some(verb))
+        || ["ed", "ing"].iter().any(|s| {
+            word.strip_suffix(s)
+                .is_some_and(|w| w == stem || w == doubled)
+        })
+}
+
+/// Splits tagged summary lines out of a change. Each tagged line becomes its own
+/// change (with the tag stripped) that shares the original link and authors.
+fn split_tagged_lines(
+    change: ChangeWithMeta,
+    config: &ChangelogConfig,
+) -> (Option<ChangeWithMeta>, Vec<(&str, ChangeWithMeta)>) {
+    if config.tags.is_empty() {
+        return (Some(change), Vec::new());
+    }
+
+    let mut rest = Vec::new();
+    let mut tagged = Vec::new();
+
+    for line in change.summary.lines() {
+        match parse_tag(line, config) {
+            Some((tag, text)) => tagged.push((
+                tag,
+                ChangeWithMeta {
+                    summary: text.to_string(),
+                    link: change.link.clone(),
+                    authors: change.authors.clone(),
+                },
+            )),
+            None => rest.push(line),
+        }
+    }
+
+    let rest = rest.join("\n");
+    let rest = if rest.trim().is_empty() {
+        None
+    } else {
+        Some(ChangeWithMeta {
+            summary: rest,
+            ..change
+        })
+    };
+
+    (rest, tagged)
+}
+
+/// Returns the configured tag and remaining text if `line` starts with `[tag]`,
+/// optionally after a `-` or `*` bullet.
+fn parse_tag<'a, 'b>(line: &'b str, config: &'a ChangelogConfig) -> Option<(&'a str, &'b str)> {
+    let text = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .unwrap_or(line)
+        .trim_start();
+    let (tag, text) = text.strip_prefix('[')?.split_once(']')?;
+    let (tag, _) = config.tags.get_key_value(tag)?;
+    Some((tag.as_str(), text.trim_start()))
 }
 
 fn write_change_lines(entry: &mut String, change: &ChangeWithMeta) {
@@ -137,7 +481,7 @@ fn write_change_lines(entry: &mut String, change: &ChangeWithMeta) {
         let authors_str = change
             .authors
             .iter()
-            .map(|a| format!("@{}", a.replace(' ', "")))
+            .map(String::as_str)
             .collect::<Vec<_>>()
             .join(", ");
         suffix_parts.push(format!("by {}", authors_str));
@@ -158,33 +502,595 @@ fn write_change_lines(entry: &mut String, change: &ChangeWithMeta) {
         let is_last = i == lines.len() - 1;
         let line_suffix = if is_last { &suffix } else { "" };
 
-        if line.starts_with('-') || line.starts_with('*') {
+        // Indented lines (nested bullets, continuations) belong to the bullet above
+        // and keep their indentation.
+        if line.trim().is_empty() {
+            continue;
+        } else if line.starts_with(['-', '*', ' ', '\t']) {
             entry.push_str(&format!("{}{}\n", line, line_suffix));
-        } else if !line.is_empty() {
+        } else {
             entry.push_str(&format!("- {}{}\n", line, line_suffix));
         }
     }
 }
 
-pub fn update_changelog(path: &Path, new_entry: &str) -> Result<()> {
+/// What to do when a changelog already has a section for a version being written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnExistingVersion {
+    /// Replace the existing section in place with the new one.
+    #[default]
+    Replace,
+    /// Fail with `Error::ChangelogVersionExists`.
+    Fail,
+    /// Prepend the new section anyway, keeping the existing one.
+    Prepend,
+}
+
+/// A changelog section that was replaced instead of prepended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplacedSection {
+    pub path: PathBuf,
+    pub version: Version,
+}
+
+/// Prepends `new_entry` to the changelog at `path`.
+///
+/// Sections of `new_entry` whose version already has a section in the file are
+/// handled according to `on_existing`; the versions that were replaced are returned.
+pub fn update_changelog(
+    path: &Path,
+    new_entry: &str,
+    on_existing: OnExistingVersion,
+) -> Result<Vec<Version>> {
+    update_changelog_with_style(path, new_entry, on_existing, ChangelogStyle::BumpTypes)
+}
+
+/// `update_changelog` for a changelog of the given style. A keep-a-changelog file
+/// keeps an `## [Unreleased]` section, added if missing, above the releases; new
+/// sections go right below it.
+pub fn update_changelog_with_style(
+    path: &Path,
+    new_entry: &str,
+    on_existing: OnExistingVersion,
+    style: ChangelogStyle,
+) -> Result<Vec<Version>> {
+    update_changelog_with(path, new_entry, on_existing, style, false)
+}
+
+/// `update_changelog_with_style`, where a root changelog (`merge`) keeps the entries
+/// of a replaced section that the new one doesn't repeat: its headings carry no
+/// package, so the section may hold another package's release of that version.
+fn update_changelog_with(
+    path: &Path,
+    new_entry: &str,
+    on_existing: OnExistingVersion,
+    style: ChangelogStyle,
+    merge: bool,
+) -> Result<Vec<Version>> {
     let existing = if path.exists() {
         std::fs::read_to_string(path)?
     } else {
         String::new()
     };
 
-    let new_content = if existing.starts_with("# Changelog") {
+    let rest = if existing.starts_with("# Changelog") {
         let rest = existing.strip_prefix("# Changelog").unwrap_or(&existing);
-        let rest = rest.trim_start_matches('\n');
-        format!("# Changelog\n\n{}{}", new_entry, rest)
-    } else if existing.is_empty() {
-        format!("# Changelog\n\n{}", new_entry)
+        rest.trim_start_matches('\n')
     } else {
-        format!("# Changelog\n\n{}{}", new_entry, existing)
+        existing.as_str()
+    };
+
+    let mut parsed = changelog_reader::parse(rest);
+    let unreleased = match style {
+        ChangelogStyle::BumpTypes => None,
+        ChangelogStyle::KeepAChangelog => Some(
+            parsed
+                .sections
+                .iter()
+                .position(|s| is_unreleased_heading(s.heading))
+                .map_or(UNRELEASED_STUB, |idx| parsed.sections.remove(idx).text),
+        ),
+    };
+    let new = changelog_reader::parse(new_entry);
+    let mut prepended = new.preamble.to_string();
+    let mut replaced = Vec::new();
+    let mut merged: HashMap<usize, String> = HashMap::new();
+
+    for section in new.sections {
+        let existing_idx = section
+            .version
+            .clone()
+            .filter(|_| on_existing != OnExistingVersion::Prepend)
+            .and_then(|v| Some((parsed.find_version(&v)?, v)));
+
+        match existing_idx {
+            Some((_, version)) if on_existing == OnExistingVersion::Fail => {
+                return Err(Error::ChangelogVersionExists(
+                    path.display().to_string(),
+                    version.to_string(),
+                ));
+            }
+            Some((idx, version)) => {
+                if merge {
+                    merged.insert(idx, merge_section(parsed.sections[idx].text, section.text));
+                }
+                parsed.sections[idx] = section;
+                replaced.push(version);
+            }
+            None => prepended.push_str(section.text),
+        }
+    }
+
+    let mut new_content = match unreleased {
+        Some(unreleased) => format!(
+            "# Changelog\n\n{}{}{}",
+            parsed.preamble,
+            with_blank_line(unreleased),
+            prepended
+        ),
+        None => format!("# Changelog\n\n{}{}", prepended, parsed.preamble),
     };
+    for (idx, section) in parsed.sections.iter().enumerate() {
+        new_content.push_str(merged.get(&idx).map_or(section.text, String::as_str));
+    }
 
-    std::fs::write(path, new_content)?;
-    Ok(())
+    atomic_write(path, new_content)?;
+    Ok(replaced)
+}
+
+/// `new`, plus the `- ` entries of `old` it doesn't have, each under its `### `
+/// heading. Headings only `old` has entries under are added where they were in
+/// `old`; its other text, such as the heading and tables, is replaced.
+fn merge_section(old: &str, new: &str) -> String {
+    let mut merged = subsections(new);
+    let mut anchor = 0;
+    for (heading, blocks) in subsections(old) {
+        let idx = match merged.iter().position(|(h, _)| *h == heading) {
+            Some(idx) => idx,
+            None if blocks.iter().any(|b| is_entry(b)) => {
+                let idx = (anchor + 1).min(merged.len());
+                merged.insert(idx, (heading, Vec::new()));
+                idx
+            }
+            None => continue,
+        };
+        anchor = idx;
+        for entry in blocks.into_iter().filter(|b| is_entry(b)) {
+            if !merged[idx].1.contains(&entry) {
+                merged[idx].1.push(entry);
+            }
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|(heading, blocks)| {
+            let mut text = heading.map_or(String::new(), |h| format!("{}\n\n", h));
+            for (i, block) in blocks.iter().enumerate() {
+                // Entries follow each other directly; anything else is a paragraph.
+                if i > 0 {
+                    let entries = is_entry(&blocks[i - 1]) && is_entry(block);
+                    text.push_str(if entries { "\n" } else { "\n\n" });
+                }
+                text.push_str(&block.join("\n"));
+            }
+            with_blank_line(&text)
+        })
+        .collect()
+}
+
+fn is_entry(block: &[&str]) -> bool {
+    block[0].starts_with("- ")
+}
+
+/// The text of a section split at its `### ` headings; the first part holds the
+/// `## ` heading itself. Each part is a list of blocks: an entry with its indented
+/// lines, or a run of other lines such as a table.
+#[allow(clippy::type_complexity)]
+fn subsections(text: &str) -> Vec<(Option<&str>, Vec<Vec<&str>>)> {
+    let mut parts: Vec<(Option<&str>, Vec<Vec<&str>>)> = vec![(None, Vec::new())];
+    let mut paragraph = false;
+    for line in text.lines() {
+        if line.starts_with("### ") {
+            parts.push((Some(line), Vec::new()));
+            paragraph = false;
+            continue;
+        }
+        if line.trim().is_empty() {
+            paragraph = false;
+            continue;
+        }
+        let blocks = &mut parts.last_mut().expect("starts with one part").1;
+        match blocks.last_mut() {
+            Some(block) if is_entry(block) && line.starts_with([' ', '\t']) => block.push(line),
+            Some(block) if paragraph && !line.starts_with("- ") => block.push(line),
+            _ => {
+                paragraph = !line.starts_with("- ");
+                blocks.push(vec![line]);
+            }
+        }
+    }
+    parts
+}
+
+const UNRELEASED_STUB: &str = "## [Unreleased]\n\n";
+
+/// Whether a `## ` heading is keepachangelog.com's `## [Unreleased]`.
+fn is_unreleased_heading(heading: &str) -> bool {
+    heading
+        .trim_start_matches('#')
+        .trim()
+        .trim_matches(|c| c == '[' || c == ']')
+        .eq_ignore_ascii_case("unreleased")
+}
+
+/// `section`, ending in a blank line so the next heading doesn't run into it.
+fn with_blank_line(section: &str) -> String {
+    format!("{}\n\n", section.trim_end_matches('\n'))
+}
+
+/// Returns the per-crate changelog path for `package`.
+///
+/// The root package of a non-virtual workspace shares its directory with the root
+/// changelog. If `CHANGELOG.md` there was written in root format for the whole
+/// workspace, the package gets `root_package_file` instead so the two don't end up
+/// in one file. A single-package repo has only the one changelog either way.
+///
+/// The same goes when other packages are configured to write the root changelog.
+///
+/// A `changelog_path` override in `[[packages]]` wins over all of this.
+pub fn package_changelog_path(
+    workspace: &Workspace,
+    package: &Package,
+    config: &Config,
+) -> PathBuf {
+    if let Some(path) = config
+        .package(&package.name)
+        .and_then(|p| p.changelog_path.as_deref())
+    {
+        return package.path.join(path);
+    }
+
+    let path = package.path.join("CHANGELOG.md");
+    let shares_root = workspace.packages.len() > 1 && workspace.is_root_package(package);
+    let root_changelog = workspace
+        .packages
+        .iter()
+        .any(|p| config.changelog_format(&p.name) == ChangelogFormat::Root);
+    if !shares_root || !(root_changelog || is_root_format(&path, &package.name)) {
+        return path;
+    }
+    package.path.join(
+        config
+            .changelog
+            .root_package_file
+            .replace("{name}", &package.name),
+    )
+}
+
+/// Returns true if the changelog at `path` has version sections, none of which
+/// use the per-crate `` ## `name@version` `` heading.
+fn is_root_format(path: &Path, name: &str) -> bool {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    let parsed = changelog_reader::parse(&content);
+    let per_crate = format!("## `{}@", name);
+    parsed.sections.iter().any(|s| s.version.is_some())
+        && !parsed
+            .sections
+            .iter()
+            .any(|s| s.heading.starts_with(&per_crate))
+}
+
+/// The releases whose changes are written: all but `[changelog] skip` packages, which
+/// the root format keeps only with `include_skipped`.
+fn written_releases<'a>(
+    releases: &'a [PackageRelease],
+    config: &Config,
+) -> Vec<&'a PackageRelease> {
+    let keep_skipped = |name: &str| {
+        config.changelog_format(name) == ChangelogFormat::Root && config.changelog.include_skipped
+    };
+    releases
+        .iter()
+        .filter(|r| keep_skipped(&r.name) || !config.changelog.is_skipped(&r.name))
+        .collect()
+}
+
+/// Returns the changelog files `write_changelogs` would touch and the versions it
+/// would add to each, so callers can check for conflicts before mutating anything.
+pub fn changelog_targets(
+    workspace: &Workspace,
+    releases: &[PackageRelease],
+    config: &Config,
+) -> Vec<(PathBuf, Version)> {
+    let (root, per_crate): (Vec<&PackageRelease>, Vec<&PackageRelease>) =
+        written_releases(releases, config)
+            .into_iter()
+            .partition(|r| config.changelog_format(&r.name) == ChangelogFormat::Root);
+
+    let mut targets: Vec<(PathBuf, Version)> = per_crate
+        .into_iter()
+        .filter_map(|r| {
+            let package = workspace.get_package(&r.name)?;
+            Some((
+                package_changelog_path(workspace, package, config),
+                r.new_version.clone(),
+            ))
+        })
+        .collect();
+    let versions: BTreeSet<&Version> = root.iter().map(|r| &r.new_version).collect();
+    targets.extend(
+        versions
+            .into_iter()
+            .map(|v| (workspace.root.join("CHANGELOG.md"), v.clone())),
+    );
+    targets
+}
+
+/// Returns the targets from `changelog_targets` that can't be written, e.g. a
+/// read-only directory, so `version` can refuse to start.
+pub fn unwritable_targets(
+    workspace: &Workspace,
+    releases: &[PackageRelease],
+    config: &Config,
+) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = changelog_targets(workspace, releases, config)
+        .into_iter()
+        .map(|(path, _)| path)
+        .filter(|path| !is_writable(path))
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Returns the targets from `changelog_targets` that already have a section for
+/// their version.
+pub fn existing_sections(
+    workspace: &Workspace,
+    releases: &[PackageRelease],
+    config: &Config,
+) -> Result<Vec<(PathBuf, Version)>> {
+    let mut existing = Vec::new();
+    for (path, version) in changelog_targets(workspace, releases, config) {
+        if !path.exists() {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)?;
+        if changelog_reader::parse(&content)
+            .find_version(&version)
+            .is_some()
+        {
+            existing.push((path, version));
+        }
+    }
+    Ok(existing)
+}
+
+/// A `### Package versions` table listing every workspace package, at its new
+/// version if it is being released and its current one otherwise.
+fn package_versions_table(workspace: &Workspace, releases: &[PackageRelease]) -> String {
+    let mut packages: Vec<_> = workspace.packages.iter().collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut table =
+        String::from("### Package versions\n\n| Package | Version |\n|---------|---------|\n");
+    for package in packages {
+        let version = releases
+            .iter()
+            .find(|r| r.name == package.name)
+            .map_or(&package.version, |r| &r.new_version);
+        table.push_str(&format!("| `{}` | {} |\n", package.name, version));
+    }
+    table.push('\n');
+    table
+}
+
+pub fn write_changelogs(
+    workspace: &Workspace,
+    releases: &[PackageRelease],
+    changelogs: &[Changelog],
+    config: &Config,
+    on_existing: OnExistingVersion,
+    channel: Option<&str>,
+    authors: &AuthorResolver,
+) -> Result<Vec<ReplacedSection>> {
+    let date = Utc::now().format("%Y-%m-%d").to_string();
+    let git = git::open();
+    let options = WriteOptions {
+        on_existing,
+        channel,
+        git: git.as_ref(),
+        authors,
+        date: &date,
+    };
+    write_changelogs_with_date(workspace, releases, changelogs, config, options)
+}
+
+/// How [`write_changelogs_with_date`] writes the changelogs.
+#[derive(Clone, Copy)]
+pub struct WriteOptions<'a> {
+    /// What to do when a changelog already has a section for a released version.
+    pub on_existing: OnExistingVersion,
+    /// The channel whose pending entries are released; their commits give the
+    /// links, authors and date order.
+    pub channel: Option<&'a str>,
+    pub git: &'a dyn Git,
+    pub authors: &'a AuthorResolver,
+    /// The release date in the headings, as `YYYY-MM-DD`.
+    pub date: &'a str,
+}
+
+/// Writes the changelogs for `releases`.
+pub fn write_changelogs_with_date(
+    workspace: &Workspace,
+    releases: &[PackageRelease],
+    changelogs: &[Changelog],
+    config: &Config,
+    options: WriteOptions,
+) -> Result<Vec<ReplacedSection>> {
+    let WriteOptions {
+        on_existing,
+        channel,
+        git,
+        authors,
+        date,
+    } = options;
+    let changelog_dir = &workspace.channel_dir(channel);
+    let mut replaced = Vec::new();
+    let mut changelogs = changelogs.to_vec();
+    changelog_entry::sort(&mut changelogs, config.changelog.sort, changelog_dir, git);
+    let changelogs = changelogs.as_slice();
+    let written = written_releases(releases, config);
+    let meta = EntryMeta::new(git, authors, changelog_dir, &config.changelog);
+
+    // Packages with the root format share the root CHANGELOG.md; the others each
+    // get their own.
+    let (root, per_crate): (Vec<&PackageRelease>, Vec<&PackageRelease>) = written
+        .into_iter()
+        .partition(|r| config.changelog_format(&r.name) == ChangelogFormat::Root);
+    let per_crate_config = ChangelogConfig {
+        format: ChangelogFormat::PerCrate,
+        ..config.changelog.clone()
+    };
+    let root_config = ChangelogConfig {
+        format: ChangelogFormat::Root,
+        ..config.changelog.clone()
+    };
+
+    for release in per_crate {
+        if let Some(package) = workspace.get_package(&release.name) {
+            let generated = render_entry(release, changelogs, &per_crate_config, &meta, date);
+            // Bump-type changelogs name the package in the heading; keep-a-changelog
+            // headings are always `## [version] - date`.
+            let entry = match per_crate_config.style {
+                ChangelogStyle::BumpTypes => {
+                    let mut entry = format!("## `{}@{}`\n\n", release.name, release.new_version);
+                    let entry_body = generated.lines().skip(2).collect::<Vec<_>>().join("\n");
+                    entry.push_str(&entry_body);
+                    entry.push('\n');
+                    entry
+                }
+                ChangelogStyle::KeepAChangelog => generated,
+            };
+
+            let changelog_path = package_changelog_path(workspace, package, config);
+            for version in update_changelog_with_style(
+                &changelog_path,
+                &entry,
+                on_existing,
+                per_crate_config.style,
+            )? {
+                replaced.push(ReplacedSection {
+                    path: changelog_path.clone(),
+                    version,
+                });
+            }
+        }
+    }
+
+    if !root.is_empty() || config.changelog.format == ChangelogFormat::Root {
+        // Group releases by version so fixed-group packages sharing the same
+        // version get a single heading instead of duplicate `## version` blocks.
+        let mut by_version: BTreeMap<String, Vec<&PackageRelease>> = BTreeMap::new();
+        for release in root {
+            by_version
+                .entry(release.new_version.to_string())
+                .or_default()
+                .push(release);
+        }
+
+        let mut combined_entry = String::new();
+
+        for (version, group) in &by_version {
+            if group.len() == 1 {
+                // Single release at this version — use existing per-package generation.
+                let entry = render_entry(group[0], changelogs, &root_config, &meta, date);
+                combined_entry.push_str(&entry);
+            } else {
+                // Multiple releases share this version — merge into one heading
+                // and deduplicate changelog entries that appear in multiple packages.
+                combined_entry.push_str(&version_heading(version, date, &root_config));
+                combined_entry.push_str(&entry_prefix(version, &root_config));
+
+                let mut major_changes = Vec::new();
+                let mut minor_changes = Vec::new();
+                let mut patch_changes = Vec::new();
+                let mut seen_changelog_ids: HashSet<&str> = HashSet::new();
+
+                for release in group {
+                    for changelog in changelogs {
+                        if !release.changelog_ids.contains(&changelog.id) {
+                            continue;
+                        }
+                        if !seen_changelog_ids.insert(&changelog.id) {
+                            continue;
+                        }
+
+                        // Find the highest bump level for this changelog across
+                        // all packages in the group.
+                        let bump = group
+                            .iter()
+                            .filter(|r| r.changelog_ids.contains(&changelog.id))
+                            .flat_map(|r| {
+                                changelog
+                                    .releases
+                                    .iter()
+                                    .filter(|rel| rel.package == r.name)
+                                    .map(|rel| rel.bump)
+                            })
+                            .max()
+                            .unwrap_or(BumpType::Patch);
+
+                        let summary = changelog.summary.trim().to_string();
+
+                        let (link_info, authors) = meta.get(&changelog.id);
+
+                        let change = ChangeWithMeta {
+                            summary,
+                            link: link_info,
+                            authors,
+                        };
+                        match bump {
+                            BumpType::Major => major_changes.push(change),
+                            BumpType::Minor => minor_changes.push(change),
+                            BumpType::Patch => patch_changes.push(change),
+                        }
+                    }
+                }
+
+                write_sections(
+                    &mut combined_entry,
+                    major_changes,
+                    minor_changes,
+                    patch_changes,
+                    &root_config,
+                );
+            }
+        }
+
+        if root_config.include_unchanged && !combined_entry.is_empty() {
+            combined_entry.push_str(&package_versions_table(workspace, releases));
+        }
+
+        let changelog_path = workspace.root.join("CHANGELOG.md");
+        for version in update_changelog_with(
+            &changelog_path,
+            &combined_entry,
+            on_existing,
+            root_config.style,
+            true,
+        )? {
+            replaced.push(ReplacedSection {
+                path: changelog_path.clone(),
+                version,
+            });
+        }
+    }
+
+    Ok(replaced)
 }
 
 #[cfg(test)]
@@ -192,13 +1098,75 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_parse_github_url() {
+        assert_eq!(
+            parse_github_url("git@github.com:wevm/changelogs-rs.git").as_deref(),
+            Some("https://github.com/wevm/changelogs-rs")
+        );
+        assert_eq!(
+            parse_github_url("https://github.com/wevm/changelogs-rs.git").as_deref(),
+            Some("https://github.com/wevm/changelogs-rs")
+        );
+        assert_eq!(
+            parse_github_url("https://github.com/wevm/changelogs-rs").as_deref(),
+            Some("https://github.com/wevm/changelogs-rs")
+        );
+        assert_eq!(
+            parse_github_url("wevm/changelogs-rs").as_deref(),
+            Some("https://github.com/wevm/changelogs-rs")
+        );
+        assert_eq!(
+            parse_github_url("https://gitlab.com/wevm/changelogs-rs"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_repo_url_takes_precedence_over_remote() {
+        let dir = TempDir::new().unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(dir.path())
+                .output()
+                .unwrap()
+        };
+        git(&["init", "-q"]);
+        git(&["remote", "add", "origin", "git@github.com:someone/fork.git"]);
+        let repo = git::CliGit::in_dir(dir.path());
+
+        assert_eq!(
+            get_github_url(&repo).as_deref(),
+            Some("https://github.com/someone/fork")
+        );
+        assert_eq!(
+            resolve_github_url(None, &repo).as_deref(),
+            Some("https://github.com/someone/fork")
+        );
+        assert_eq!(
+            resolve_github_url(Some("wevm/changelogs-rs"), &repo).as_deref(),
+            Some("https://github.com/wevm/changelogs-rs")
+        );
+        // Not a GitHub repository: falls back to the remote.
+        assert_eq!(
+            resolve_github_url(Some("https://gitlab.com/wevm/changelogs-rs"), &repo).as_deref(),
+            Some("https://github.com/someone/fork")
+        );
+    }
+
     #[test]
     fn test_update_empty_file() {
         let dir = TempDir::new().unwrap();
         let path = dir.path().join("CHANGELOG.md");
         std::fs::write(&path, "").unwrap();
 
-        update_changelog(&path, "## 1.0.0\n\n- Initial release\n\n").unwrap();
+        update_changelog(
+            &path,
+            "## 1.0.0\n\n- Initial release\n\n",
+            OnExistingVersion::Replace,
+        )
+        .unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         assert_eq!(content, "# Changelog\n\n## 1.0.0\n\n- Initial release\n\n");
@@ -209,20 +1177,88 @@ mod tests {
         let dir = TempDir::new().unwrap();
         let path = dir.path().join("CHANGELOG.md");
 
-        update_changelog(&path, "## 1.0.0\n\n- First\n\n").unwrap();
+        update_changelog(&path, "## 1.0.0\n\n- First\n\n", OnExistingVersion::Replace).unwrap();
 
         assert!(path.exists());
         let content = std::fs::read_to_string(&path).unwrap();
         assert_eq!(content, "# Changelog\n\n## 1.0.0\n\n- First\n\n");
     }
 
+    #[test]
+    fn test_update_keep_a_changelog_adds_unreleased_section() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+
+        update_changelog_with_style(
+            &path,
+            "## [1.0.0] - 2025-01-15\n\n### Added\n\n- First\n\n",
+            OnExistingVersion::Replace,
+            ChangelogStyle::KeepAChangelog,
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "# Changelog\n\n## [Unreleased]\n\n## [1.0.0] - 2025-01-15\n\n### Added\n\n- First\n\n"
+        );
+    }
+
+    #[test]
+    fn test_keep_a_changelog_section() {
+        assert_eq!(keep_a_changelog_section("Add a cache"), "Added");
+        assert_eq!(keep_a_changelog_section("Added `--json`"), "Added");
+        assert_eq!(keep_a_changelog_section("New `status` output"), "Added");
+        assert_eq!(keep_a_changelog_section("Fixed a panic"), "Fixed");
+        assert_eq!(keep_a_changelog_section("Dropped Python 3.8"), "Removed");
+        assert_eq!(
+            keep_a_changelog_section("Deprecate `Client::new`"),
+            "Deprecated"
+        );
+        assert_eq!(
+            keep_a_changelog_section("Bump `reqwest` for CVE-2025-1"),
+            "Security"
+        );
+        assert_eq!(
+            keep_a_changelog_section("Renamed the config file"),
+            "Changed"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_update_symlinked_changelog() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        let target = dir.path().join("docs/changelog.md");
+        std::fs::write(&target, "# Changelog\n\n## 1.0.0\n\n- First\n").unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+        std::os::unix::fs::symlink("docs/changelog.md", &path).unwrap();
+
+        update_changelog(&path, "## 1.1.0\n\n- Second\n\n", OnExistingVersion::Fail).unwrap();
+
+        assert_eq!(
+            std::fs::read_link(&path).unwrap(),
+            Path::new("docs/changelog.md")
+        );
+        assert_eq!(
+            std::fs::read_to_string(&target).unwrap(),
+            "# Changelog\n\n## 1.1.0\n\n- Second\n\n## 1.0.0\n\n- First\n"
+        );
+    }
+
     #[test]
     fn test_update_with_existing_header() {
         let dir = TempDir::new().unwrap();
         let path = dir.path().join("CHANGELOG.md");
         std::fs::write(&path, "# Changelog\n\nold content\n").unwrap();
 
-        update_changelog(&path, "## 2.0.0\n\n- New stuff\n\n").unwrap();
+        update_changelog(
+            &path,
+            "## 2.0.0\n\n- New stuff\n\n",
+            OnExistingVersion::Replace,
+        )
+        .unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         assert_eq!(
@@ -237,7 +1273,7 @@ mod tests {
         let path = dir.path().join("CHANGELOG.md");
         std::fs::write(&path, "some existing content\n").unwrap();
 
-        update_changelog(&path, "## 1.0.0\n\n- Entry\n\n").unwrap();
+        update_changelog(&path, "## 1.0.0\n\n- Entry\n\n", OnExistingVersion::Replace).unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         assert_eq!(
@@ -251,8 +1287,18 @@ mod tests {
         let dir = TempDir::new().unwrap();
         let path = dir.path().join("CHANGELOG.md");
 
-        update_changelog(&path, "## 1.0.0\n\n- First release\n\n").unwrap();
-        update_changelog(&path, "## 2.0.0\n\n- Second release\n\n").unwrap();
+        update_changelog(
+            &path,
+            "## 1.0.0\n\n- First release\n\n",
+            OnExistingVersion::Replace,
+        )
+        .unwrap();
+        update_changelog(
+            &path,
+            "## 2.0.0\n\n- Second release\n\n",
+            OnExistingVersion::Replace,
+        )
+        .unwrap();
 
         let content = std::fs::read_to_string(&path).unwrap();
         assert_eq!(
@@ -263,8 +1309,93 @@ mod tests {
 
     use crate::BumpType;
     use crate::changelog_entry::{Changelog, Release};
+    use crate::config::PackageConfig;
+    use crate::git::fake::FakeGit;
     use crate::plan::PackageRelease;
     use semver::Version;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_update_same_version_twice_keeps_one_section() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+        let entry = "## `foo@1.1.0`\n\n- Added x\n\n";
+
+        update_changelog(
+            &path,
+            "## `foo@1.0.0`\n\n- First\n\n",
+            OnExistingVersion::Replace,
+        )
+        .unwrap();
+        let replaced = update_changelog(&path, entry, OnExistingVersion::Replace).unwrap();
+        assert!(replaced.is_empty());
+        let once = std::fs::read_to_string(&path).unwrap();
+
+        let replaced = update_changelog(&path, entry, OnExistingVersion::Replace).unwrap();
+        assert_eq!(replaced, vec![Version::new(1, 1, 0)]);
+        let twice = std::fs::read_to_string(&path).unwrap();
+
+        assert_eq!(once, twice);
+        assert_eq!(twice.matches("## `foo@1.1.0`").count(), 1);
+    }
+
+    #[test]
+    fn test_update_same_version_replaces_content_in_place() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+        std::fs::write(
+            &path,
+            "# Changelog\n\n## 1.1.0 (2025-01-14)\n\n- Old\n\n## 1.0.0 (2025-01-01)\n\n- First\n",
+        )
+        .unwrap();
+
+        update_changelog(
+            &path,
+            "## 1.1.0 (2025-01-15)\n\n- New\n\n",
+            OnExistingVersion::Replace,
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "# Changelog\n\n## 1.1.0 (2025-01-15)\n\n- New\n\n## 1.0.0 (2025-01-01)\n\n- First\n"
+        );
+    }
+
+    #[test]
+    fn test_update_same_version_fails_without_overwrite() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+        let original = "# Changelog\n\n## 1.1.0 (2025-01-14)\n\n- Old\n";
+        std::fs::write(&path, original).unwrap();
+
+        let err = update_changelog(
+            &path,
+            "## 1.1.0 (2025-01-15)\n\n- New\n\n",
+            OnExistingVersion::Fail,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, Error::ChangelogVersionExists(_, v) if v == "1.1.0"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn test_update_same_version_prepends_when_forced() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+        std::fs::write(&path, "# Changelog\n\n## 1.1.0\n\n- Old\n").unwrap();
+
+        let replaced =
+            update_changelog(&path, "## 1.1.0\n\n- New\n\n", OnExistingVersion::Prepend).unwrap();
+
+        assert!(replaced.is_empty());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# Changelog\n\n## 1.1.0\n\n- New\n\n## 1.1.0\n\n- Old\n"
+        );
+    }
 
     #[test]
     fn test_generate_entry_single_patch() {
@@ -275,6 +1406,8 @@ mod tests {
             old_version: Version::new(1, 0, 0),
             new_version: Version::new(1, 0, 1),
             changelog_ids: vec!["change-1".to_string()],
+            reasons: Vec::new(),
+            updated_dependencies: vec![],
         };
         let changelogs = vec![Changelog {
             id: "change-1".to_string(),
@@ -284,9 +1417,16 @@ mod tests {
                 bump: BumpType::Patch,
             }],
             commit: None,
+            package_summaries: HashMap::new(),
         }];
 
-        let output = generate_entry(&release, &changelogs, dir.path());
+        let output = generate_entry(
+            &release,
+            &changelogs,
+            dir.path(),
+            &ChangelogConfig::default(),
+            &AuthorResolver::new(None),
+        );
 
         assert!(output.contains("## 1.0.1 ("));
         assert!(output.contains("### Patch Changes"));
@@ -306,6 +1446,8 @@ mod tests {
                 "c-minor".to_string(),
                 "c-patch".to_string(),
             ],
+            reasons: Vec::new(),
+            updated_dependencies: vec![],
         };
         let changelogs = vec![
             Changelog {
@@ -316,6 +1458,7 @@ mod tests {
                     bump: BumpType::Major,
                 }],
                 commit: None,
+                package_summaries: HashMap::new(),
             },
             Changelog {
                 id: "c-minor".to_string(),
@@ -325,6 +1468,7 @@ mod tests {
                     bump: BumpType::Minor,
                 }],
                 commit: None,
+                package_summaries: HashMap::new(),
             },
             Changelog {
                 id: "c-patch".to_string(),
@@ -334,10 +1478,17 @@ mod tests {
                     bump: BumpType::Patch,
                 }],
                 commit: None,
+                package_summaries: HashMap::new(),
             },
         ];
 
-        let output = generate_entry(&release, &changelogs, dir.path());
+        let output = generate_entry(
+            &release,
+            &changelogs,
+            dir.path(),
+            &ChangelogConfig::default(),
+            &AuthorResolver::new(None),
+        );
 
         assert!(output.contains("### Major Changes"));
         assert!(output.contains("### Minor Changes"));
@@ -362,6 +1513,8 @@ mod tests {
             old_version: Version::new(1, 0, 0),
             new_version: Version::new(2, 0, 0),
             changelog_ids: vec!["c-1".to_string()],
+            reasons: Vec::new(),
+            updated_dependencies: vec![],
         };
         let changelogs = vec![Changelog {
             id: "c-1".to_string(),
@@ -371,9 +1524,16 @@ mod tests {
                 bump: BumpType::Major,
             }],
             commit: None,
+            package_summaries: HashMap::new(),
         }];
 
-        let output = generate_entry(&release, &changelogs, dir.path());
+        let output = generate_entry(
+            &release,
+            &changelogs,
+            dir.path(),
+            &ChangelogConfig::default(),
+            &AuthorResolver::new(None),
+        );
 
         assert!(output.contains("### Major Changes"));
         assert!(!output.contains("### Minor Changes"));
@@ -389,6 +1549,8 @@ mod tests {
             old_version: Version::new(1, 0, 0),
             new_version: Version::new(1, 1, 0),
             changelog_ids: vec!["c-1".to_string()],
+            reasons: Vec::new(),
+            updated_dependencies: vec![],
         };
         let changelogs = vec![Changelog {
             id: "c-1".to_string(),
@@ -398,15 +1560,61 @@ mod tests {
                 bump: BumpType::Minor,
             }],
             commit: None,
+            package_summaries: HashMap::new(),
         }];
 
-        let output = generate_entry(&release, &changelogs, dir.path());
+        let output = generate_entry(
+            &release,
+            &changelogs,
+            dir.path(),
+            &ChangelogConfig::default(),
+            &AuthorResolver::new(None),
+        );
 
         assert!(output.contains("added new feature"));
         assert!(output.contains("with detailed explanation"));
         assert!(output.contains("and examples"));
     }
 
+    #[test]
+    fn test_generate_entry_keeps_nested_bullets() {
+        let dir = TempDir::new().unwrap();
+        let release = PackageRelease {
+            name: "foo".to_string(),
+            bump: BumpType::Minor,
+            old_version: Version::new(1, 0, 0),
+            new_version: Version::new(1, 1, 0),
+            changelog_ids: vec!["c-1".to_string()],
+            reasons: Vec::new(),
+            updated_dependencies: vec![],
+        };
+        let changelogs = vec![Changelog {
+            id: "c-1".to_string(),
+            summary: "Reworked retries:\n  - backoff is exponential\n    up to a minute\n\t* jitter is added\n- Dropped `retry_once`".to_string(),
+            releases: vec![Release {
+                package: "foo".to_string(),
+                bump: BumpType::Minor,
+            }],
+            commit: None,
+            package_summaries: HashMap::new(),
+        }];
+
+        let output = generate_entry(
+            &release,
+            &changelogs,
+            dir.path(),
+            &ChangelogConfig::default(),
+            &AuthorResolver::new(None),
+        );
+
+        assert!(
+            output.contains(
+                "- Reworked retries:\n  - backoff is exponential\n    up to a minute\n\t* jitter is added\n- Dropped `retry_once`\n"
+            ),
+            "{output}"
+        );
+    }
+
     #[test]
     fn test_generate_entry_no_matching_changelogs() {
         let dir = TempDir::new().unwrap();
@@ -416,6 +1624,8 @@ mod tests {
             old_version: Version::new(1, 0, 0),
             new_version: Version::new(1, 0, 1),
             changelog_ids: vec!["nonexistent".to_string()],
+            reasons: Vec::new(),
+            updated_dependencies: vec![],
         };
         let changelogs = vec![Changelog {
             id: "other-change".to_string(),
@@ -425,170 +1635,356 @@ mod tests {
                 bump: BumpType::Patch,
             }],
             commit: None,
+            package_summaries: HashMap::new(),
         }];
 
-        let output = generate_entry(&release, &changelogs, dir.path());
+        let output = generate_entry(
+            &release,
+            &changelogs,
+            dir.path(),
+            &ChangelogConfig::default(),
+            &AuthorResolver::new(None),
+        );
 
         assert!(output.contains("## 1.0.1 ("));
         assert!(!output.contains("### Major Changes"));
         assert!(!output.contains("### Minor Changes"));
         assert!(!output.contains("### Patch Changes"));
     }
-}
 
-pub fn write_changelogs(
-    workspace: &Workspace,
-    releases: &[PackageRelease],
-    changelogs: &[Changelog],
-    format: ChangelogFormat,
-) -> Result<()> {
-    let date = Utc::now().format("%Y-%m-%d").to_string();
-    write_changelogs_with_date(workspace, releases, changelogs, format, &date)
-}
+    #[test]
+    fn test_generate_entry_tagged_line_gets_own_section() {
+        let dir = TempDir::new().unwrap();
+        let release = PackageRelease {
+            name: "foo".to_string(),
+            bump: BumpType::Minor,
+            old_version: Version::new(1, 0, 0),
+            new_version: Version::new(1, 1, 0),
+            changelog_ids: vec!["change-1".to_string()],
+            reasons: Vec::new(),
+            updated_dependencies: vec![],
+        };
+        let changelogs = vec![Changelog {
+            id: "change-1".to_string(),
+            summary: "Added `Client::retry`.\n- [breaking] Removed `Client::legacy`.\n[unknown] Kept as is."
+                .to_string(),
+            releases: vec![Release {
+                package: "foo".to_string(),
+                bump: BumpType::Minor,
+            }],
+            commit: None,
+            package_summaries: HashMap::new(),
+        }];
+        let config = ChangelogConfig {
+            tags: BTreeMap::from([("breaking".to_string(), "Breaking".to_string())]),
+            ..ChangelogConfig::default()
+        };
 
-pub fn write_changelogs_with_date(
-    workspace: &Workspace,
-    releases: &[PackageRelease],
-    changelogs: &[Changelog],
-    format: ChangelogFormat,
-    date: &str,
-) -> Result<()> {
-    let changelog_dir = &workspace.changelog_dir;
+        let output = generate_entry(
+            &release,
+            &changelogs,
+            dir.path(),
+            &config,
+            &AuthorResolver::new(None),
+        );
 
-    match format {
-        ChangelogFormat::PerCrate => {
-            for release in releases {
-                if let Some(package) = workspace.get_package(&release.name) {
-                    let mut entry = format!("## `{}@{}`\n\n", release.name, release.new_version);
-                    let generated =
-                        generate_entry_with_date(release, changelogs, changelog_dir, date);
-                    let entry_body = generated.lines().skip(2).collect::<Vec<_>>().join("\n");
-                    entry.push_str(&entry_body);
-                    entry.push('\n');
+        assert!(output.contains("### Breaking\n\n- Removed `Client::legacy`.\n"));
+        assert!(
+            output.contains(
+                "### Minor Changes\n\n- Added `Client::retry`.\n- [unknown] Kept as is.\n"
+            )
+        );
+        assert!(!output.contains("[breaking]"));
+        assert!(output.find("### Breaking").unwrap() < output.find("### Minor Changes").unwrap());
+    }
 
-                    let changelog_path = package.path.join("CHANGELOG.md");
-                    update_changelog(&changelog_path, &entry)?;
-                }
-            }
+    fn non_virtual_workspace(root: &Path) -> Workspace {
+        let package = |name: &str, path: PathBuf| Package {
+            name: name.to_string(),
+            version: Version::new(1, 0, 0),
+            manifest_path: path.join("Cargo.toml"),
+            path,
+            dependencies: vec![],
+        };
+        Workspace::new(
+            root.to_path_buf(),
+            vec![
+                package("app", root.to_path_buf()),
+                package("core", root.join("core")),
+            ],
+            crate::Ecosystem::Rust,
+        )
+    }
+
+    #[test]
+    fn test_root_package_changelog_path() {
+        let dir = TempDir::new().unwrap();
+        let workspace = non_virtual_workspace(dir.path());
+        let mut config = Config::default();
+        let app = &workspace.packages[0];
+        let core = &workspace.packages[1];
+
+        // No changelog yet, or one already in per-crate form: the root CHANGELOG.md.
+        assert_eq!(
+            package_changelog_path(&workspace, app, &config),
+            dir.path().join("CHANGELOG.md")
+        );
+        std::fs::write(
+            dir.path().join("CHANGELOG.md"),
+            "# Changelog\n\n## `app@1.0.0`\n\n- Initial\n",
+        )
+        .unwrap();
+        assert_eq!(
+            package_changelog_path(&workspace, app, &config),
+            dir.path().join("CHANGELOG.md")
+        );
+
+        // A root-format changelog stays with the workspace.
+        std::fs::write(
+            dir.path().join("CHANGELOG.md"),
+            "# Changelog\n\n## 1.0.0 (2025-01-01)\n\n- Initial\n",
+        )
+        .unwrap();
+        assert_eq!(
+            package_changelog_path(&workspace, app, &config),
+            dir.path().join("CHANGELOG-app.md")
+        );
+        assert_eq!(
+            package_changelog_path(&workspace, core, &config),
+            dir.path().join("core/CHANGELOG.md")
+        );
+
+        // A `[[packages]]` override wins.
+        config.packages.push(PackageConfig {
+            name: "app".into(),
+            tag_template: None,
+            changelog_path: Some("docs/CHANGES.md".into()),
+            min_bump: None,
+            format: None,
+        });
+        assert_eq!(
+            package_changelog_path(&workspace, app, &config),
+            dir.path().join("docs/CHANGES.md")
+        );
+    }
+
+    #[test]
+    fn test_changelog_targets_leave_out_skipped_packages() {
+        let dir = TempDir::new().unwrap();
+        let workspace = non_virtual_workspace(dir.path());
+        let release = |name: &str, minor: u64| PackageRelease {
+            name: name.to_string(),
+            bump: BumpType::Minor,
+            old_version: Version::new(1, 0, 0),
+            new_version: Version::new(1, minor, 0),
+            changelog_ids: vec![],
+            reasons: Vec::new(),
+            updated_dependencies: vec![],
+        };
+        let releases = [release("app", 1), release("core", 2)];
+        let mut config = Config::default();
+        config.changelog.skip = vec!["co*".into()];
+
+        assert_eq!(
+            changelog_targets(&workspace, &releases, &config),
+            [(dir.path().join("CHANGELOG.md"), Version::new(1, 1, 0))]
+        );
+
+        config.changelog.format = ChangelogFormat::Root;
+        assert_eq!(changelog_targets(&workspace, &releases, &config).len(), 1);
+        config.changelog.include_skipped = true;
+        assert_eq!(changelog_targets(&workspace, &releases, &config).len(), 2);
+    }
+
+    #[test]
+    fn test_write_changelogs_looks_up_each_changelog_once() {
+        let dir = TempDir::new().unwrap();
+        let package = |name: &str| Package {
+            name: name.to_string(),
+            version: Version::new(1, 0, 0),
+            manifest_path: dir.path().join(name).join("Cargo.toml"),
+            path: dir.path().join(name),
+            dependencies: vec![],
+        };
+        for name in ["a", "b"] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        let workspace = Workspace::new(
+            dir.path().to_path_buf(),
+            ["a", "b", "c", "d"].map(package).to_vec(),
+            crate::Ecosystem::Rust,
+        );
+        let changelog = |id: &str, packages: &[&str]| Changelog {
+            id: id.to_string(),
+            summary: format!("Changed {}", id),
+            releases: packages
+                .iter()
+                .map(|p| Release {
+                    package: p.to_string(),
+                    bump: BumpType::Minor,
+                })
+                .collect(),
+            commit: None,
+            package_summaries: HashMap::new(),
+        };
+        let changelogs = [
+            changelog("brave-fox", &["a", "b", "c", "d"]),
+            changelog("calm-owl", &["a", "c"]),
+        ];
+        let release = |name: &str, ids: &[&str]| PackageRelease {
+            name: name.to_string(),
+            bump: BumpType::Minor,
+            old_version: Version::new(1, 0, 0),
+            new_version: Version::new(1, 1, 0),
+            changelog_ids: ids.iter().map(|id| id.to_string()).collect(),
+            reasons: Vec::new(),
+            updated_dependencies: vec![],
+        };
+        let releases = [
+            release("a", &["brave-fox", "calm-owl"]),
+            release("b", &["brave-fox"]),
+            release("c", &["brave-fox", "calm-owl"]),
+            release("d", &["brave-fox"]),
+        ];
+        // `c` and `d` share the root CHANGELOG.md at the same version.
+        let mut config = Config::default();
+        for name in ["c", "d"] {
+            config.packages.push(PackageConfig {
+                name: name.into(),
+                tag_template: None,
+                changelog_path: None,
+                min_bump: None,
+                format: Some(ChangelogFormat::Root),
+            });
         }
-        ChangelogFormat::Root => {
-            // Group releases by version so fixed-group packages sharing the same
-            // version get a single heading instead of duplicate `## version` blocks.
-            let mut by_version: BTreeMap<String, Vec<&PackageRelease>> = BTreeMap::new();
-            for release in releases {
-                by_version
-                    .entry(release.new_version.to_string())
-                    .or_default()
-                    .push(release);
-            }
 
-            let mut combined_entry = String::new();
-
-            for (version, group) in &by_version {
-                if group.len() == 1 {
-                    // Single release at this version — use existing per-package generation.
-                    let entry = generate_entry_with_date(group[0], changelogs, changelog_dir, date);
-                    combined_entry.push_str(&entry);
-                } else {
-                    // Multiple releases share this version — merge into one heading
-                    // and deduplicate changelog entries that appear in multiple packages.
-                    combined_entry.push_str(&format!("## {} ({})\n\n", version, date));
-
-                    let github_url = get_github_url();
-
-                    let mut major_changes = Vec::new();
-                    let mut minor_changes = Vec::new();
-                    let mut patch_changes = Vec::new();
-                    let mut seen_changelog_ids: HashSet<&str> = HashSet::new();
-
-                    for release in group {
-                        for changelog in changelogs {
-                            if !release.changelog_ids.contains(&changelog.id) {
-                                continue;
-                            }
-                            if !seen_changelog_ids.insert(&changelog.id) {
-                                continue;
-                            }
-
-                            // Find the highest bump level for this changelog across
-                            // all packages in the group.
-                            let bump = group
-                                .iter()
-                                .filter(|r| r.changelog_ids.contains(&changelog.id))
-                                .flat_map(|r| {
-                                    changelog
-                                        .releases
-                                        .iter()
-                                        .filter(|rel| rel.package == r.name)
-                                        .map(|rel| rel.bump)
-                                })
-                                .max()
-                                .unwrap_or(BumpType::Patch);
-
-                            let summary = changelog.summary.trim().to_string();
-
-                            let (link_info, authors) = github_url
-                                .as_ref()
-                                .and_then(|base| {
-                                    let info = changelog_entry::get_commit_info(
-                                        changelog_dir,
-                                        &changelog.id,
-                                    )?;
-                                    let link_info = if let Some(pr) = info.pr_number {
-                                        Some((format!("{}/pull/{}", base, pr), format!("#{}", pr)))
-                                    } else {
-                                        let short_sha =
-                                            &info.commit_sha[..7.min(info.commit_sha.len())];
-                                        Some((
-                                            format!("{}/commit/{}", base, short_sha),
-                                            short_sha.to_string(),
-                                        ))
-                                    };
-                                    Some((link_info, info.authors))
-                                })
-                                .unwrap_or((None, Vec::new()));
-
-                            let change = ChangeWithMeta {
-                                summary,
-                                link: link_info,
-                                authors,
-                            };
-                            match bump {
-                                BumpType::Major => major_changes.push(change),
-                                BumpType::Minor => minor_changes.push(change),
-                                BumpType::Patch => patch_changes.push(change),
-                            }
-                        }
-                    }
+        // Every changelog was squash-merged in its own PR.
+        let git = FakeGit {
+            remote_url: Some("git@github.com:wevm/changelogs-rs.git"),
+            message: "Add a thing (#12)",
+            ..FakeGit::default()
+        };
+        write_changelogs_with_date(
+            &workspace,
+            &releases,
+            &changelogs,
+            &config,
+            WriteOptions {
+                on_existing: OnExistingVersion::Fail,
+                channel: None,
+                git: &git,
+                authors: &AuthorResolver::new(None),
+                date: "2025-01-01",
+            },
+        )
+        .unwrap();
 
-                    if !major_changes.is_empty() {
-                        combined_entry.push_str("### Major Changes\n\n");
-                        for change in major_changes {
-                            write_change_lines(&mut combined_entry, &change);
-                        }
-                        combined_entry.push('\n');
-                    }
-                    if !minor_changes.is_empty() {
-                        combined_entry.push_str("### Minor Changes\n\n");
-                        for change in minor_changes {
-                            write_change_lines(&mut combined_entry, &change);
-                        }
-                        combined_entry.push('\n');
-                    }
-                    if !patch_changes.is_empty() {
-                        combined_entry.push_str("### Patch Changes\n\n");
-                        for change in patch_changes {
-                            write_change_lines(&mut combined_entry, &change);
-                        }
-                        combined_entry.push('\n');
-                    }
-                }
-            }
+        let lookups = git.lookups.borrow();
+        let count = |method: &str| lookups.iter().filter(|l| l.starts_with(method)).count();
+        assert_eq!(count("remote_url "), 1);
+        let mut looked_up: Vec<&str> = lookups
+            .iter()
+            .filter_map(|l| l.strip_prefix("commit_message "))
+            .collect();
+        looked_up.sort();
+        let pending = |id: &str| {
+            let path = workspace.pending_dir.join(format!("{}.md", id));
+            path.to_string_lossy().to_string()
+        };
+        assert_eq!(looked_up, [pending("brave-fox"), pending("calm-owl")]);
 
-            let changelog_path = workspace.root.join("CHANGELOG.md");
-            update_changelog(&changelog_path, &combined_entry)?;
+        let link = "[#12](https://github.com/wevm/changelogs-rs/pull/12)";
+        for path in ["a/CHANGELOG.md", "b/CHANGELOG.md", "CHANGELOG.md"] {
+            let content = std::fs::read_to_string(dir.path().join(path)).unwrap();
+            assert!(content.contains(link), "{path}: {content}");
         }
     }
 
-    Ok(())
+    #[test]
+    fn test_root_sections_at_same_version_are_merged() {
+        let dir = TempDir::new().unwrap();
+        let package = |name: &str| Package {
+            name: name.to_string(),
+            version: Version::new(1, 0, 0),
+            manifest_path: dir.path().join(name).join("Cargo.toml"),
+            path: dir.path().join(name),
+            dependencies: vec![],
+        };
+        let workspace = Workspace::new(
+            dir.path().to_path_buf(),
+            ["client", "server"].map(package).to_vec(),
+            crate::Ecosystem::Rust,
+        );
+        let mut config = Config::default();
+        config.changelog.format = ChangelogFormat::Root;
+        let release = |name: &str, summary: &str| {
+            let changelog = Changelog {
+                id: name.to_string(),
+                summary: summary.to_string(),
+                releases: vec![Release {
+                    package: name.to_string(),
+                    bump: BumpType::Minor,
+                }],
+                commit: None,
+                package_summaries: HashMap::new(),
+            };
+            let release = PackageRelease {
+                name: name.to_string(),
+                bump: BumpType::Minor,
+                old_version: Version::new(1, 0, 0),
+                new_version: Version::new(1, 1, 0),
+                changelog_ids: vec![name.to_string()],
+                reasons: Vec::new(),
+                updated_dependencies: vec![],
+            };
+            (release, changelog)
+        };
+        let write = |name: &str, summary: &str| {
+            let (release, changelog) = release(name, summary);
+            write_changelogs_with_date(
+                &workspace,
+                &[release],
+                &[changelog],
+                &config,
+                WriteOptions {
+                    on_existing: OnExistingVersion::Replace,
+                    channel: None,
+                    git: &FakeGit::default(),
+                    authors: &AuthorResolver::new(None),
+                    date: "2025-01-01",
+                },
+            )
+            .unwrap()
+        };
+
+        write("client", "Added retries.");
+        let (server, _) = release("server", "");
+        assert_eq!(
+            existing_sections(&workspace, &[server], &config)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(write("server", "Added TLS.").len(), 1);
+        // Re-running the server release doesn't repeat its entry.
+        assert_eq!(write("server", "Added TLS.").len(), 1);
+
+        let content = std::fs::read_to_string(dir.path().join("CHANGELOG.md")).unwrap();
+        assert_eq!(
+            content,
+            "# Changelog\n\n## 1.1.0 (2025-01-01)\n\n### Minor Changes\n\n- Added TLS.\n- Added retries.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_section() {
+        let old = "## 1.1.0 (2025-01-01)\n\n### Minor Changes\n\n- Added x\n  - with y\n\n### Patch Changes\n\n- Fixed z\n\n### Package versions\n\n| Package | Version |\n|---------|---------|\n";
+        let new = "## 1.1.0 (2025-01-02)\n\nRelease notes for 1.1.0.\n\n### Major Changes\n\n- Removed w\n\n### Minor Changes\n\n- Added v\n- Added x\n  - with y\n\n### Package versions\n\n| Package | Version |\n|---------|---------|\n";
+
+        assert_eq!(
+            merge_section(old, new),
+            "## 1.1.0 (2025-01-02)\n\nRelease notes for 1.1.0.\n\n### Major Changes\n\n- Removed w\n\n### Minor Changes\n\n- Added v\n- Added x\n  - with y\n\n### Patch Changes\n\n- Fixed z\n\n### Package versions\n\n| Package | Version |\n|---------|---------|\n\n"
+        );
+        assert_eq!(merge_section(new, new), with_blank_line(new));
+    }
 }
@@ -0,0 +1,246 @@
+//! Detects a `.changeset/` directory from [changesets](https://github.com/changesets/changesets)
+//! and migrates it: its config becomes `.changelog/config.toml` and pending entries,
+//! which use the same frontmatter format, move to `.changelog/`.
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::fs_util::atomic_write;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, ArrayOfTables, DocumentMut, Table, value};
+
+/// A `.changeset/` directory found next to (or instead of) `.changelog/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Changesets {
+    pub dir: PathBuf,
+    /// `.changeset/config.json`, if present.
+    pub config: Option<PathBuf>,
+    /// Pending changeset files, sorted.
+    pub entries: Vec<PathBuf>,
+}
+
+/// What `migrate` did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Migration {
+    /// Entries moved into `.changelog/`, by file name.
+    pub moved: Vec<String>,
+    /// `config.json` options with no equivalent here, left unconverted.
+    pub unsupported: Vec<String>,
+}
+
+/// The `config.json` options that carry over.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangesetsConfig {
+    #[serde(default)]
+    fixed: Vec<Vec<String>>,
+    #[serde(default)]
+    linked: Vec<Vec<String>>,
+    #[serde(default)]
+    ignore: Vec<String>,
+    base_branch: Option<String>,
+}
+
+const CONVERTED: &[&str] = &["$schema", "fixed", "linked", "ignore", "baseBranch"];
+
+/// Looks for `.changeset/config.json` or pending `.changeset/*.md` files under `root`.
+pub fn detect(root: &Path) -> Option<Changesets> {
+    let dir = root.join(".changeset");
+    let config = Some(dir.join("config.json")).filter(|p| p.is_file());
+
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .filter(|p| p.file_name().is_some_and(|n| n != "README.md"))
+        .collect();
+    entries.sort();
+
+    if config.is_none() && entries.is_empty() {
+        return None;
+    }
+    Some(Changesets {
+        dir,
+        config,
+        entries,
+    })
+}
+
+/// Writes `config.toml` in `changelog_dir` from the changesets config and moves the
+/// pending entries there. `config.json` and the changesets README are removed, and
+/// `.changeset/` with them once nothing else is left in it.
+pub fn migrate(changesets: &Changesets, changelog_dir: &Path) -> Result<Migration> {
+    let (config, unsupported) = match &changesets.config {
+        Some(path) => convert_config(&std::fs::read_to_string(path)?)?,
+        None => (Config::default_toml().to_string(), Vec::new()),
+    };
+
+    // Check every entry before touching anything, so a clash leaves both directories as they were.
+    for entry in &changesets.entries {
+        let target = changelog_dir.join(entry.file_name().unwrap_or_default());
+        if target.exists() {
+            return Err(Error::ChangesetsMigration(format!(
+                "{} already exists",
+                target.display()
+            )));
+        }
+    }
+
+    std::fs::create_dir_all(changelog_dir)?;
+    atomic_write(&changelog_dir.join("config.toml"), config)?;
+
+    let mut moved = Vec::new();
+    for entry in &changesets.entries {
+        let name = entry.file_name().unwrap_or_default();
+        std::fs::rename(entry, changelog_dir.join(name))?;
+        moved.push(name.to_string_lossy().to_string());
+    }
+
+    for file in ["config.json", "README.md"] {
+        let path = changesets.dir.join(file);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    // Fails, and keeps the directory, when anything else is still in it.
+    let _ = std::fs::remove_dir(&changesets.dir);
+
+    Ok(Migration { moved, unsupported })
+}
+
+/// Converts `config.json` into a `config.toml` based on the default one. Returns the
+/// TOML and the options that were left out.
+fn convert_config(json: &str) -> Result<(String, Vec<String>)> {
+    let raw: serde_json::Map<String, serde_json::Value> = serde_json::from_str(json)
+        .map_err(|e| Error::ChangesetsMigration(format!("invalid config.json: {}", e)))?;
+    let config: ChangesetsConfig =
+        serde_json::from_value(serde_json::Value::Object(raw.clone()))
+            .map_err(|e| Error::ChangesetsMigration(format!("invalid config.json: {}", e)))?;
+    let unsupported = raw
+        .keys()
+        .filter(|k| !CONVERTED.contains(&k.as_str()))
+        .cloned()
+        .collect();
+
+    let mut doc: DocumentMut = Config::default_toml().parse()?;
+    doc["ignore"] = value(config.ignore.iter().collect::<Array>());
+    if let Some(base_branch) = &config.base_branch {
+        doc["base_branch"] = value(base_branch);
+    }
+    for (key, groups) in [("fixed", &config.fixed), ("linked", &config.linked)] {
+        if groups.is_empty() {
+            continue;
+        }
+        let mut tables = ArrayOfTables::new();
+        for members in groups {
+            let mut table = Table::new();
+            table["members"] = value(members.iter().collect::<Array>());
+            tables.push(table);
+        }
+        doc[key] = toml_edit::Item::ArrayOfTables(tables);
+    }
+
+    Ok((doc.to_string(), unsupported))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_convert_config() {
+        let (toml, unsupported) = convert_config(
+            r#"{
+                "$schema": "https://unpkg.com/@changesets/config@3.0.0/schema.json",
+                "changelog": "@changesets/cli/changelog",
+                "access": "public",
+                "fixed": [["pkg-a", "pkg-b"]],
+                "linked": [],
+                "ignore": ["docs"],
+                "baseBranch": "main"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(unsupported, ["access", "changelog"]);
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(config.fixed.len(), 1);
+        assert_eq!(config.fixed[0].members, ["pkg-a", "pkg-b"]);
+        assert!(config.linked.is_empty());
+        assert_eq!(config.ignore, ["docs"]);
+        assert_eq!(config.base_branch.as_deref(), Some("main"));
+        // The default config's comments are kept.
+        assert!(toml.contains("# How to bump packages that depend on changed packages"));
+    }
+
+    #[test]
+    fn test_convert_config_invalid() {
+        let err = convert_config(r#"{ "fixed": "pkg-a" }"#).unwrap_err();
+        assert!(matches!(err, Error::ChangesetsMigration(_)));
+    }
+
+    #[test]
+    fn test_detect_and_migrate() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(detect(dir.path()), None);
+
+        let changeset_dir = dir.path().join(".changeset");
+        std::fs::create_dir(&changeset_dir).unwrap();
+        std::fs::write(changeset_dir.join("README.md"), "# Changesets\n").unwrap();
+        assert_eq!(detect(dir.path()), None);
+
+        std::fs::write(
+            changeset_dir.join("brave-fox.md"),
+            "---\n\"pkg-a\": minor\n---\n\nAdded a thing\n",
+        )
+        .unwrap();
+        std::fs::write(
+            changeset_dir.join("config.json"),
+            r#"{ "ignore": ["docs"] }"#,
+        )
+        .unwrap();
+
+        let changesets = detect(dir.path()).unwrap();
+        assert_eq!(changesets.entries, [changeset_dir.join("brave-fox.md")]);
+
+        let changelog_dir = dir.path().join(".changelog");
+        let migration = migrate(&changesets, &changelog_dir).unwrap();
+
+        assert_eq!(migration.moved, ["brave-fox.md"]);
+        assert!(migration.unsupported.is_empty());
+        assert!(!changeset_dir.exists());
+        let config = Config::load(&changelog_dir).unwrap();
+        assert_eq!(config.ignore, ["docs"]);
+        let entry = crate::changelog_entry::read_all(&changelog_dir).unwrap();
+        assert_eq!(entry[0].id, "brave-fox");
+        assert_eq!(entry[0].releases[0].package, "pkg-a");
+    }
+
+    #[test]
+    fn test_migrate_refuses_to_overwrite_entries() {
+        let dir = TempDir::new().unwrap();
+        let changeset_dir = dir.path().join(".changeset");
+        let changelog_dir = dir.path().join(".changelog");
+        std::fs::create_dir(&changeset_dir).unwrap();
+        std::fs::create_dir(&changelog_dir).unwrap();
+        std::fs::write(
+            changeset_dir.join("brave-fox.md"),
+            "---\na: minor\n---\n\nx\n",
+        )
+        .unwrap();
+        std::fs::write(
+            changelog_dir.join("brave-fox.md"),
+            "---\nb: patch\n---\n\ny\n",
+        )
+        .unwrap();
+
+        let err = migrate(&detect(dir.path()).unwrap(), &changelog_dir).unwrap_err();
+
+        assert!(err.to_string().contains("already exists"), "{err}");
+        assert!(changeset_dir.join("brave-fox.md").exists());
+        assert!(!changelog_dir.join("config.toml").exists());
+    }
+}
@@ -1,29 +1,86 @@
 use anyhow::{Context, Result};
 use changelogs::changelog_entry;
+use changelogs::config::Config;
 use changelogs::error::Error;
-use changelogs::workspace::Workspace;
-use changelogs::{BumpType, Changelog, Ecosystem, Release};
+use changelogs::git::{self, DiffSpec, Git};
+use changelogs::lint;
+use changelogs::workspace::{DiscoverOptions, Workspace};
+use changelogs::{BumpType, Changelog, Release};
 use console::style;
 use inquire::{MultiSelect, Select, Text};
-use std::io::Write;
+use std::collections::HashMap;
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+/// Packages, bump and summary given on the command line. With `packages` set the
+/// changelog is written without prompting.
+#[derive(Debug, Clone, Default)]
+pub struct Preset {
+    pub packages: Vec<String>,
+    pub bump: Option<BumpType>,
+    /// The summary, or `-` to read it from stdin.
+    pub summary: Option<String>,
+    /// Write the summary in `$VISUAL`/`$EDITOR` instead of the inline prompt.
+    pub editor: bool,
+    /// Summary template: one changelog per package in `packages`, with `{package}`
+    /// replaced by its name.
+    pub template: Option<PathBuf>,
+}
+
+/// `--ai` and the options that only apply to it.
+#[derive(Debug, Clone, Default)]
+pub struct AiOptions {
+    /// The command the prompt is piped to.
+    pub command: String,
+    /// Replaces the default prompt; `{packages}` and `{diff}` are filled in.
+    pub instructions: Option<String>,
+    /// Base ref to diff against.
+    pub base_ref: Option<String>,
+    /// Review the generated entry even without a terminal, failing instead of
+    /// writing it unseen.
+    pub review: bool,
+}
+
+/// Where an interactive `add` gets its summary.
+#[derive(Debug, PartialEq, Eq)]
+enum SummarySource<'a> {
+    /// `--summary`: the text itself, or `-` for stdin.
+    Given(&'a str),
+    /// `--editor`: `$VISUAL`, `$EDITOR` or vim.
+    Editor,
+    /// The multi-line prompt in the terminal.
+    Inline,
+}
+
+fn summary_source(preset: &Preset) -> SummarySource<'_> {
+    match (preset.summary.as_deref(), preset.editor) {
+        (Some(summary), _) => SummarySource::Given(summary),
+        (None, true) => SummarySource::Editor,
+        (None, false) => SummarySource::Inline,
+    }
+}
+
 pub fn run(
     empty: bool,
-    ai: Option<String>,
-    instructions: Option<String>,
-    base_ref: Option<String>,
-    ecosystem: Option<Ecosystem>,
+    ai: Option<AiOptions>,
+    preset: Preset,
+    channel: Option<String>,
+    discover: &DiscoverOptions,
 ) -> Result<()> {
-    let workspace = Workspace::discover_with_ecosystem(ecosystem).context(
-        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python>",
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
     )?;
 
     if !workspace.is_initialized() {
         return Err(Error::NotInitialized.into());
     }
 
-    let changelog_dir = workspace.changelog_dir();
+    // Validates the channel name against the config before anything is written.
+    let config = Config::load(&workspace.changelog_dir())?;
+    config.for_channel(channel.as_deref())?;
+    let changelog_dir = workspace.channel_dir(channel.as_deref());
+    std::fs::create_dir_all(&changelog_dir)?;
 
     if empty {
         let id = changelog_entry::generate_id();
@@ -32,25 +89,20 @@ pub fn run(
             summary: String::new(),
             releases: Vec::new(),
             commit: None,
+            package_summaries: HashMap::new(),
         };
         changelog_entry::write(&changelog_dir, &cs)?;
 
         println!(
             "{} Created empty changelog: {}",
             style("✓").green().bold(),
-            style(format!(".changelog/{}.md", id)).cyan()
+            style(entry_path(&workspace, &changelog_dir, &id)).cyan()
         );
         return Ok(());
     }
 
-    if let Some(ai_command) = ai {
-        return run_ai_generation(
-            &workspace,
-            &changelog_dir,
-            &ai_command,
-            instructions.as_deref(),
-            base_ref.as_deref(),
-        );
+    if let Some(ai) = ai {
+        return run_ai_generation(&workspace, &config, &changelog_dir, &ai);
     }
 
     let package_names: Vec<String> = workspace
@@ -67,55 +119,51 @@ pub fn run(
         return Ok(());
     }
 
-    let selected_packages = if package_names.len() == 1 {
-        package_names.clone()
-    } else {
-        let selected = MultiSelect::new(
-            "Which packages would you like to include?",
-            package_names.clone(),
-        )
-        .prompt()?;
-
-        if selected.is_empty() {
-            return Err(Error::NoPackagesSelected.into());
-        }
-        selected
-    };
-
-    let bump_options = vec!["patch", "minor", "major"];
-    let mut releases = Vec::new();
-
-    for package in &selected_packages {
-        let bump_str =
-            Select::new(&format!("Bump type for {}:", package), bump_options.clone()).prompt()?;
-
-        let bump = match bump_str {
-            "patch" => BumpType::Patch,
-            "minor" => BumpType::Minor,
-            "major" => BumpType::Major,
-            _ => unreachable!(),
-        };
-
-        releases.push(Release {
-            package: package.clone(),
-            bump,
-        });
+    if let Some(template) = &preset.template {
+        return add_from_template(&workspace, &config, &changelog_dir, template, &preset);
     }
 
-    let inline = Text::new("Summary (leave empty for vim):").prompt()?;
-
-    let summary = if inline.trim().is_empty() {
-        let temp_file =
-            std::env::temp_dir().join(format!("changelog-{}.md", changelog_entry::generate_id()));
-        std::fs::write(&temp_file, "")?;
-
-        std::process::Command::new("vim").arg(&temp_file).status()?;
+    let (releases, summary) = if preset.packages.is_empty() {
+        let selected_packages = if package_names.len() == 1 {
+            package_names.clone()
+        } else {
+            let selected = MultiSelect::new(
+                "Which packages would you like to include?",
+                package_names.clone(),
+            )
+            .prompt()?;
+
+            if selected.is_empty() {
+                return Err(Error::NoPackagesSelected.into());
+            }
+            selected
+        };
 
-        let content = std::fs::read_to_string(&temp_file)?;
-        std::fs::remove_file(&temp_file).ok();
-        content
+        let releases = prompt_bumps(&selected_packages, &[])?;
+        let summary = match summary_source(&preset) {
+            SummarySource::Given(summary) => {
+                read_summary(Some(summary), &mut std::io::stdin().lock(), true)?
+            }
+            SummarySource::Editor => edit_summary(&editor_command())?,
+            SummarySource::Inline => prompt_summary()?,
+        };
+        (releases, summary)
     } else {
-        inline
+        if let Some(unknown) = preset.packages.iter().find(|p| !package_names.contains(p)) {
+            return Err(Error::PackageNotFound(unknown.clone()).into());
+        }
+        let releases = merge_releases(&[], &preset.packages, preset.bump);
+        let summary = read_summary(
+            preset.summary.as_deref(),
+            &mut std::io::stdin().lock(),
+            std::io::stdin().is_terminal(),
+        )?;
+        if summary.trim().is_empty() {
+            return Err(anyhow::anyhow!(
+                "Empty summary, changelog not created (pass --summary or pipe one on stdin)"
+            ));
+        }
+        (releases, summary)
     };
 
     if summary.trim().is_empty() {
@@ -132,18 +180,329 @@ pub fn run(
         summary: summary.trim().to_string(),
         releases,
         commit: None,
+        package_summaries: HashMap::new(),
     };
 
+    validate_entry(&workspace, &config, &cs)?;
     changelog_entry::write(&changelog_dir, &cs)?;
 
     println!(
         "\n{} Created changelog: {}",
         style("✓").green().bold(),
-        style(format!(".changelog/{}.md", id)).cyan()
+        style(entry_path(&workspace, &changelog_dir, &id)).cyan()
+    );
+
+    print_releases(&cs.releases);
+
+    Ok(())
+}
+
+/// Writes one changelog per preset package from the summary template at `path`.
+/// Every entry is checked before any is written.
+fn add_from_template(
+    workspace: &Workspace,
+    config: &Config,
+    changelog_dir: &Path,
+    path: &Path,
+    preset: &Preset,
+) -> Result<()> {
+    let template = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let entries = template_entries(&template, &preset.packages, preset.bump);
+    for cs in &entries {
+        validate_entry(workspace, config, cs)?;
+    }
+
+    for cs in &entries {
+        changelog_entry::write(changelog_dir, cs)?;
+        println!(
+            "{} Created changelog: {} ({} {})",
+            style("✓").green().bold(),
+            style(entry_path(workspace, changelog_dir, &cs.id)).cyan(),
+            cs.releases[0].package,
+            style(cs.releases[0].bump.to_string()).yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// One changelog per package, releasing it at `bump` (default patch) with
+/// `{package}` in `template` replaced by its name.
+fn template_entries(template: &str, packages: &[String], bump: Option<BumpType>) -> Vec<Changelog> {
+    packages
+        .iter()
+        .map(|package| Changelog {
+            id: changelog_entry::generate_id(),
+            summary: template.replace("{package}", package).trim().to_string(),
+            releases: merge_releases(&[], std::slice::from_ref(package), bump),
+            commit: None,
+            package_summaries: HashMap::new(),
+        })
+        .collect()
+}
+
+/// Asks for the summary in the terminal, one line at a time until an empty line,
+/// so no external editor is needed.
+fn prompt_summary() -> Result<String> {
+    collect_lines(|n| {
+        let message = if n == 0 {
+            "Summary (empty line to finish, or use --editor):"
+        } else {
+            "…"
+        };
+        Ok(Text::new(message).prompt()?)
+    })
+}
+
+/// Joins the lines from `next_line`, which gets the number of lines so far, up to
+/// the first empty one.
+fn collect_lines(mut next_line: impl FnMut(usize) -> Result<String>) -> Result<String> {
+    let mut lines = Vec::new();
+    loop {
+        let line = next_line(lines.len())?;
+        if line.trim().is_empty() {
+            return Ok(lines.join("\n"));
+        }
+        lines.push(line);
+    }
+}
+
+/// `$VISUAL`, `$EDITOR` or vim.
+fn editor_command() -> String {
+    std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vim".to_string())
+}
+
+fn edit_summary(editor: &str) -> Result<String> {
+    edit_text(editor, "")
+}
+
+/// Opens `editor` on a markdown temp file holding `initial` and returns what was
+/// saved. The file has a random name, is private to the user and is removed however
+/// this returns. It is read back by path, since editors may replace it on save.
+fn edit_text(editor: &str, initial: &str) -> Result<String> {
+    let mut temp_file = tempfile::Builder::new()
+        .prefix("changelog-")
+        .suffix(".md")
+        .tempfile()?;
+    temp_file.write_all(initial.as_bytes())?;
+    let temp_file = temp_file.into_temp_path();
+
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vim");
+    let status = Command::new(program)
+        .args(parts)
+        .arg(&temp_file)
+        .status()
+        .with_context(|| format!("failed to run editor `{}`", editor))?;
+    if !status.success() {
+        return Err(anyhow::anyhow!(
+            "Editor exited with {}: aborted, nothing was saved",
+            status
+        ));
+    }
+
+    Ok(std::fs::read_to_string(&temp_file)?)
+}
+
+/// Resolves `--summary`: `-` reads `stdin`, as does no flag at all when `stdin` is
+/// piped rather than a terminal.
+fn read_summary(summary: Option<&str>, stdin: &mut dyn Read, stdin_is_tty: bool) -> Result<String> {
+    match summary {
+        Some("-") => {}
+        Some(summary) => return Ok(summary.to_string()),
+        None if stdin_is_tty => return Ok(String::new()),
+        None => {}
+    }
+    let mut content = String::new();
+    stdin
+        .read_to_string(&mut content)
+        .context("failed to read the summary from stdin")?;
+    Ok(content)
+}
+
+/// Updates a pending changelog in place, keeping its ID.
+///
+/// With `packages`, the given packages are merged into the entry without any
+/// prompts. Otherwise the package, bump and summary prompts run again, starting
+/// from what the entry already has. `id` is prompted for when missing.
+pub fn amend(
+    id: Option<String>,
+    packages: Vec<String>,
+    bump: Option<BumpType>,
+    empty: bool,
+    channel: Option<String>,
+    discover: &DiscoverOptions,
+) -> Result<()> {
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
+    )?;
+
+    if !workspace.is_initialized() {
+        return Err(Error::NotInitialized.into());
+    }
+
+    let config = Config::load(&workspace.changelog_dir())?;
+    config.for_channel(channel.as_deref())?;
+    let changelog_dir = workspace.channel_dir(channel.as_deref());
+
+    let mut cs = match id {
+        Some(id) => changelog_entry::read(&changelog_dir, &id)?,
+        None => select_pending(&changelog_dir)?,
+    };
+
+    let package_names: Vec<String> = workspace
+        .package_names()
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+    if packages.is_empty() {
+        let current: Vec<&str> = cs.releases.iter().map(|r| r.package.as_str()).collect();
+        let selected_packages = if package_names.len() == 1 {
+            package_names.clone()
+        } else {
+            let defaults: Vec<usize> = package_names
+                .iter()
+                .enumerate()
+                .filter(|(_, name)| current.contains(&name.as_str()))
+                .map(|(i, _)| i)
+                .collect();
+            MultiSelect::new(
+                "Which packages would you like to include?",
+                package_names.clone(),
+            )
+            .with_default(&defaults)
+            .prompt()?
+        };
+
+        if selected_packages.is_empty() && !empty {
+            return Err(Error::NoPackagesSelected.into());
+        }
+
+        cs.releases = prompt_bumps(&selected_packages, &cs.releases)?;
+        cs.summary = edit_text(&editor_command(), &cs.summary)?
+            .trim()
+            .to_string();
+    } else {
+        if let Some(unknown) = packages.iter().find(|p| !package_names.contains(p)) {
+            return Err(Error::PackageNotFound(unknown.clone()).into());
+        }
+        cs.releases = merge_releases(&cs.releases, &packages, bump);
+    }
+
+    let releases = &cs.releases;
+    cs.package_summaries
+        .retain(|package, _| releases.iter().any(|r| &r.package == package));
+
+    check_lint(&workspace, &config, &cs)?;
+    changelog_entry::write(&changelog_dir, &cs)?;
+
+    println!(
+        "\n{} Updated changelog: {}",
+        style("✓").green().bold(),
+        style(entry_path(&workspace, &changelog_dir, &cs.id)).cyan()
     );
+    print_releases(&cs.releases);
+
+    Ok(())
+}
+
+/// Checks a new entry before it is written: every package must be in the workspace,
+/// the summary must not be empty, and the `[lint]` checks must pass.
+fn validate_entry(workspace: &Workspace, config: &Config, cs: &Changelog) -> Result<()> {
+    if let Some(unknown) = cs
+        .releases
+        .iter()
+        .find(|r| workspace.get_package(&r.package).is_none())
+    {
+        return Err(Error::PackageNotFound(unknown.package.clone()).into());
+    }
+    if cs.summary.trim().is_empty() {
+        return Err(anyhow::anyhow!("Empty summary"));
+    }
+    check_lint(workspace, config, cs)
+}
+
+/// Runs the `[lint]` checks on an entry about to be written, rejecting it with the
+/// problems found.
+fn check_lint(workspace: &Workspace, config: &Config, cs: &Changelog) -> Result<()> {
+    let markdown = changelog_entry::serialize(cs);
+    let problems = lint::check(&config.lint, &workspace.root, cs, &markdown);
+    if !problems.is_empty() {
+        return Err(Error::LintFailed(problems.join("\n")).into());
+    }
+    Ok(())
+}
+
+/// Asks which pending changelog to amend.
+fn select_pending(changelog_dir: &Path) -> Result<Changelog> {
+    let mut pending = changelog_entry::read_all(changelog_dir)?;
+    if pending.is_empty() {
+        return Err(anyhow::anyhow!("No pending changelogs to amend"));
+    }
+
+    let options: Vec<String> = pending
+        .iter()
+        .map(|cs| {
+            let first_line = cs.summary.lines().next().unwrap_or_default();
+            format!("{} — {}", cs.id, first_line)
+        })
+        .collect();
+    let selected = Select::new("Which changelog would you like to amend?", options).raw_prompt()?;
+
+    Ok(pending.swap_remove(selected.index))
+}
+
+/// Asks for a bump per package, starting on the bump in `existing` if any.
+fn prompt_bumps(packages: &[String], existing: &[Release]) -> Result<Vec<Release>> {
+    let bump_options = vec![BumpType::Patch, BumpType::Minor, BumpType::Major];
+    let mut releases = Vec::new();
+
+    for package in packages {
+        let cursor = existing
+            .iter()
+            .find(|r| &r.package == package)
+            .and_then(|r| bump_options.iter().position(|b| *b == r.bump))
+            .unwrap_or(0);
+        let bump = Select::new(&format!("Bump type for {}:", package), bump_options.clone())
+            .with_starting_cursor(cursor)
+            .prompt()?;
+
+        releases.push(Release {
+            package: package.clone(),
+            bump,
+        });
+    }
+
+    Ok(releases)
+}
+
+/// Adds `packages` to `existing` releases. `bump` replaces the bump of packages
+/// already listed; new packages get `bump`, or patch without one.
+fn merge_releases(
+    existing: &[Release],
+    packages: &[String],
+    bump: Option<BumpType>,
+) -> Vec<Release> {
+    let mut releases = existing.to_vec();
+    for package in packages {
+        match releases.iter_mut().find(|r| &r.package == package) {
+            Some(release) => release.bump = bump.unwrap_or(release.bump),
+            None => releases.push(Release {
+                package: package.clone(),
+                bump: bump.unwrap_or(BumpType::Patch),
+            }),
+        }
+    }
+    releases
+}
 
+fn print_releases(releases: &[Release]) {
     println!("\nPackages to be released:");
-    for release in &cs.releases {
+    for release in releases {
         println!(
             "  {} {} ({})",
             style("•").dim(),
@@ -151,8 +510,6 @@ pub fn run(
             style(release.bump.to_string()).yellow()
         );
     }
-
-    Ok(())
 }
 
 const DEFAULT_INSTRUCTIONS: &str = r#"Generate a changelog entry for this git diff. 
@@ -178,25 +535,25 @@ Rules:
 Git diff:
 {diff}"#;
 
-fn run_ai_generation(
-    workspace: &Workspace,
-    changelog_dir: &std::path::Path,
-    ai_command: &str,
-    instructions: Option<&str>,
-    base_ref: Option<&str>,
-) -> Result<()> {
-    println!(
-        "{} Generating changelog with AI...",
-        style("→").cyan().bold()
-    );
+/// Path of a new entry relative to the workspace root, for display.
+fn entry_path(workspace: &Workspace, changelog_dir: &Path, id: &str) -> String {
+    let path = changelog_dir.join(format!("{}.md", id));
+    path.strip_prefix(&workspace.root)
+        .unwrap_or(&path)
+        .display()
+        .to_string()
+}
 
-    let diff_to_use = if let Some(base) = base_ref {
+/// Picks the diff to send to the AI command: `--ref` first, then staged changes,
+/// then unstaged changes, then the configured `base_branch`.
+fn resolve_diff(
+    git: &dyn Git,
+    base_ref: Option<&str>,
+    base_branch: Option<&str>,
+) -> Result<String> {
+    if let Some(base) = base_ref {
         // Diff against base ref (for CI/PR workflows)
-        let diff = Command::new("git")
-            .args(["diff", &format!("{}...HEAD", base)])
-            .output()
-            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
-            .unwrap_or_default();
+        let diff = git.diff(DiffSpec::Base(base));
 
         if diff.is_empty() {
             return Err(anyhow::anyhow!(
@@ -204,33 +561,61 @@ fn run_ai_generation(
                 base
             ));
         }
-        diff
-    } else {
-        // Try staged changes first
-        let staged = Command::new("git")
-            .args(["diff", "--cached"])
-            .output()
-            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
-            .unwrap_or_default();
-
-        if !staged.is_empty() {
-            staged
-        } else {
-            // Try unstaged changes
-            let unstaged = Command::new("git")
-                .args(["diff"])
-                .output()
-                .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
-                .unwrap_or_default();
-
-            if unstaged.is_empty() {
-                return Err(anyhow::anyhow!(
-                    "No changes detected. Stage your changes with `git add` first, or use --ref to diff against a branch."
-                ));
-            }
-            unstaged
+        return Ok(diff);
+    }
+
+    // Try staged changes first
+    let staged = git.diff(DiffSpec::Staged);
+    if !staged.is_empty() {
+        return Ok(staged);
+    }
+
+    // Try unstaged changes
+    let unstaged = git.diff(DiffSpec::Unstaged);
+    if !unstaged.is_empty() {
+        return Ok(unstaged);
+    }
+
+    // Clean tree (e.g. CI): fall back to the configured base branch
+    if let Some(base) = base_branch {
+        let diff = git.diff(DiffSpec::Base(base));
+        if !diff.is_empty() {
+            return Ok(diff);
         }
-    };
+        return Err(anyhow::anyhow!(
+            "No changes detected between {} (base_branch) and HEAD.",
+            base
+        ));
+    }
+
+    Err(anyhow::anyhow!(
+        "No changes detected. Stage your changes with `git add` first, or use --ref to diff against a branch."
+    ))
+}
+
+fn run_ai_generation(
+    workspace: &Workspace,
+    config: &Config,
+    changelog_dir: &Path,
+    ai: &AiOptions,
+) -> Result<()> {
+    let interactive = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    if ai.review && !interactive {
+        return Err(anyhow::anyhow!(
+            "--review needs a terminal to ask about the generated changelog"
+        ));
+    }
+
+    println!(
+        "{} Generating changelog with AI...",
+        style("→").cyan().bold()
+    );
+
+    let diff_to_use = resolve_diff(
+        git::open().as_ref(),
+        ai.base_ref.as_deref(),
+        config.base_branch.as_deref(),
+    )?;
 
     let package_names = workspace.package_names().join(", ");
 
@@ -251,11 +636,177 @@ fn run_ai_generation(
         diff_to_use
     };
 
-    let template = instructions.unwrap_or(DEFAULT_INSTRUCTIONS);
+    let template = ai.instructions.as_deref().unwrap_or(DEFAULT_INSTRUCTIONS);
     let prompt = template
         .replace("{packages}", &package_names)
         .replace("{diff}", &diff_to_use);
 
+    let response = run_ai_command(&ai.command, &prompt)?;
+    let parse = |markdown: &str| parse_generated(workspace, config, markdown);
+
+    let cs = if interactive {
+        let mut reviewer = TerminalReviewer {
+            editor: editor_command(),
+        };
+        let regenerate = |instruction: &str| {
+            println!(
+                "{} Regenerating changelog with AI...",
+                style("→").cyan().bold()
+            );
+            run_ai_command(&ai.command, &format!("{}\n\n{}", prompt, instruction))
+        };
+        match review_entry(response, &mut reviewer, parse, regenerate)? {
+            Some(cs) => cs,
+            None => {
+                println!(
+                    "{} Aborted, changelog not created",
+                    style("!").yellow().bold()
+                );
+                return Ok(());
+            }
+        }
+    } else {
+        parse(&response)?
+    };
+
+    changelog_entry::write(changelog_dir, &cs)?;
+
+    println!(
+        "\n{} Created changelog: {}",
+        style("✓").green().bold(),
+        style(entry_path(workspace, changelog_dir, &cs.id)).cyan()
+    );
+
+    print_releases(&cs.releases);
+
+    println!("\nSummary:\n{}", cs.summary);
+
+    Ok(())
+}
+
+/// Parses a generated entry, tolerating a surrounding code fence, and checks it as
+/// [`validate_entry`] does for any new entry.
+fn parse_generated(workspace: &Workspace, config: &Config, markdown: &str) -> Result<Changelog> {
+    let cleaned = markdown
+        .trim()
+        .trim_start_matches("```markdown")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim();
+
+    let changelog = changelog_entry::parse("ai-generated", cleaned)?;
+    let cs = Changelog {
+        id: changelog_entry::generate_id(),
+        summary: changelog.summary,
+        releases: changelog.releases,
+        commit: None,
+        package_summaries: changelog.package_summaries,
+    };
+    validate_entry(workspace, config, &cs)?;
+    Ok(cs)
+}
+
+/// What to do with a generated entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReviewChoice {
+    Accept,
+    Edit,
+    Regenerate,
+    Abort,
+}
+
+impl std::fmt::Display for ReviewChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReviewChoice::Accept => "Accept",
+            ReviewChoice::Edit => "Edit in $EDITOR",
+            ReviewChoice::Regenerate => "Regenerate with an extra instruction",
+            ReviewChoice::Abort => "Abort",
+        })
+    }
+}
+
+/// The questions asked while reviewing a generated entry. Tests answer them
+/// without a terminal.
+trait Reviewer {
+    /// Shows the entry, or why it was rejected, and picks one of `choices`.
+    fn choose(
+        &mut self,
+        entry: std::result::Result<&Changelog, &str>,
+        choices: &[ReviewChoice],
+    ) -> Result<ReviewChoice>;
+
+    /// Returns `markdown` as edited by the user.
+    fn edit(&mut self, markdown: &str) -> Result<String>;
+
+    /// Asks for the instruction appended to the prompt when regenerating.
+    fn instruction(&mut self) -> Result<String>;
+}
+
+/// Asks `reviewer` about `markdown` until an entry is accepted, or returns `None`
+/// when it aborts. Every version goes through `parse`, and an entry `parse`
+/// rejects can only be edited, regenerated or dropped. `regenerate` reruns the AI
+/// command with an extra instruction.
+fn review_entry(
+    mut markdown: String,
+    reviewer: &mut dyn Reviewer,
+    parse: impl Fn(&str) -> Result<Changelog>,
+    mut regenerate: impl FnMut(&str) -> Result<String>,
+) -> Result<Option<Changelog>> {
+    use ReviewChoice::*;
+
+    loop {
+        let parsed = parse(&markdown);
+        let choice = match &parsed {
+            Ok(cs) => reviewer.choose(Ok(cs), &[Accept, Edit, Regenerate, Abort])?,
+            Err(e) => reviewer.choose(Err(&format!("{:#}", e)), &[Edit, Regenerate, Abort])?,
+        };
+        match choice {
+            Accept => return parsed.map(Some),
+            Edit => markdown = reviewer.edit(&markdown)?,
+            Regenerate => markdown = regenerate(&reviewer.instruction()?)?,
+            Abort => return Ok(None),
+        }
+    }
+}
+
+struct TerminalReviewer {
+    editor: String,
+}
+
+impl Reviewer for TerminalReviewer {
+    fn choose(
+        &mut self,
+        entry: std::result::Result<&Changelog, &str>,
+        choices: &[ReviewChoice],
+    ) -> Result<ReviewChoice> {
+        match entry {
+            Ok(cs) => {
+                print_releases(&cs.releases);
+                println!("\nSummary:\n{}\n", cs.summary);
+            }
+            Err(problem) => println!(
+                "\n{} The generated changelog can't be used: {}\n",
+                style("!").yellow().bold(),
+                style(problem).yellow()
+            ),
+        }
+        Ok(Select::new("Write this changelog?", choices.to_vec()).prompt()?)
+    }
+
+    fn edit(&mut self, markdown: &str) -> Result<String> {
+        edit_text(&self.editor, markdown)
+    }
+
+    fn instruction(&mut self) -> Result<String> {
+        Ok(Text::new("Extra instruction for the AI:").prompt()?)
+    }
+}
+
+/// Runs the AI command with `prompt` and returns its answer. `openai api
+/// chat.completions.create` gets the prompt as a message and answers in JSON; any
+/// other command reads it on stdin and answers in plain text.
+fn run_ai_command(ai_command: &str, prompt: &str) -> Result<String> {
     let parts: Vec<&str> = ai_command.split_whitespace().collect();
     let (cmd, args) = parts
         .split_first()
@@ -278,7 +829,7 @@ fn run_ai_generation(
     let mut command = Command::new(cmd);
     command.args(args);
     if is_openai_chat_completions && !has_message_arg {
-        command.args(["-g", "user"]).arg(&prompt);
+        command.args(["-g", "user"]).arg(prompt);
     }
 
     let mut child = command
@@ -333,7 +884,7 @@ fn run_ai_generation(
         ));
     }
 
-    let response = if is_openai_chat_completions {
+    Ok(if is_openai_chat_completions {
         serde_json::from_slice::<serde_json::Value>(&output.stdout)
             .ok()
             .and_then(|value| {
@@ -345,46 +896,7 @@ fn run_ai_generation(
             .unwrap_or_else(|| String::from_utf8_lossy(&output.stdout).to_string())
     } else {
         String::from_utf8_lossy(&output.stdout).to_string()
-    };
-
-    let cleaned = response
-        .trim()
-        .trim_start_matches("```markdown")
-        .trim_start_matches("```")
-        .trim_end_matches("```")
-        .trim();
-
-    let changelog = changelogs::changelog_entry::parse("ai-generated", cleaned)?;
-
-    let id = changelog_entry::generate_id();
-    let cs = Changelog {
-        id: id.clone(),
-        summary: changelog.summary,
-        releases: changelog.releases,
-        commit: None,
-    };
-
-    changelog_entry::write(changelog_dir, &cs)?;
-
-    println!(
-        "\n{} Created changelog: {}",
-        style("✓").green().bold(),
-        style(format!(".changelog/{}.md", id)).cyan()
-    );
-
-    println!("\nPackages to be released:");
-    for release in &cs.releases {
-        println!(
-            "  {} {} ({})",
-            style("•").dim(),
-            release.package,
-            style(release.bump.to_string()).yellow()
-        );
-    }
-
-    println!("\nSummary:\n{}", cs.summary);
-
-    Ok(())
+    })
 }
 
 /// Detects the AI provider from the command and returns a helpful hint about the required API key.
@@ -433,3 +945,381 @@ fn detect_api_key_hint(ai_command: &str) -> String {
      and passed to the workflow step via the 'env' block."
         .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::fake_git::FakeGit;
+    use changelogs::Ecosystem;
+
+    #[test]
+    fn test_summary_source() {
+        let preset = |summary: Option<&str>, editor| Preset {
+            summary: summary.map(String::from),
+            editor,
+            ..Preset::default()
+        };
+
+        assert_eq!(summary_source(&preset(None, false)), SummarySource::Inline);
+        assert_eq!(summary_source(&preset(None, true)), SummarySource::Editor);
+        assert_eq!(
+            summary_source(&preset(Some("Fixed x"), false)),
+            SummarySource::Given("Fixed x")
+        );
+        assert_eq!(
+            summary_source(&preset(Some("-"), true)),
+            SummarySource::Given("-")
+        );
+    }
+
+    #[test]
+    fn test_collect_lines() {
+        let mut input = ["Added x", "", "never read"].into_iter();
+        let mut seen = Vec::new();
+        let summary = collect_lines(|n| {
+            seen.push(n);
+            Ok(input.next().unwrap().to_string())
+        })
+        .unwrap();
+        assert_eq!(summary, "Added x");
+        assert_eq!(seen, [0, 1]);
+
+        let mut input = ["Added x", "  - with y", "  "].into_iter();
+        let summary = collect_lines(|_| Ok(input.next().unwrap().to_string())).unwrap();
+        assert_eq!(summary, "Added x\n  - with y");
+
+        assert_eq!(collect_lines(|_| Ok(String::new())).unwrap(), "");
+    }
+
+    #[test]
+    fn test_resolve_diff_precedence() {
+        let git = FakeGit {
+            staged: "staged",
+            unstaged: "unstaged",
+            base: vec![("origin/feature", "ref"), ("origin/develop", "configured")],
+            ..FakeGit::default()
+        };
+
+        // --ref wins over everything
+        let diff = resolve_diff(&git, Some("origin/feature"), Some("origin/develop")).unwrap();
+        assert_eq!(diff, "ref");
+
+        // then staged, then unstaged
+        assert_eq!(
+            resolve_diff(&git, None, Some("origin/develop")).unwrap(),
+            "staged"
+        );
+        let git = FakeGit { staged: "", ..git };
+        assert_eq!(
+            resolve_diff(&git, None, Some("origin/develop")).unwrap(),
+            "unstaged"
+        );
+
+        // a clean tree falls back to base_branch
+        let git = FakeGit {
+            unstaged: "",
+            ..git
+        };
+        assert_eq!(
+            resolve_diff(&git, None, Some("origin/develop")).unwrap(),
+            "configured"
+        );
+        assert!(resolve_diff(&git, None, None).is_err());
+    }
+
+    #[test]
+    fn test_resolve_diff_empty_base_branch() {
+        let git = FakeGit::default();
+        let err = resolve_diff(&git, None, Some("origin/main")).unwrap_err();
+        assert!(err.to_string().contains("origin/main (base_branch)"));
+    }
+
+    fn release(package: &str, bump: BumpType) -> Release {
+        Release {
+            package: package.to_string(),
+            bump,
+        }
+    }
+
+    #[test]
+    fn test_merge_releases() {
+        let existing = vec![release("a", BumpType::Minor), release("b", BumpType::Patch)];
+
+        let merged = merge_releases(&existing, &["c".to_string()], None);
+        assert_eq!(
+            merged,
+            vec![
+                release("a", BumpType::Minor),
+                release("b", BumpType::Patch),
+                release("c", BumpType::Patch),
+            ]
+        );
+
+        // An explicit bump applies to listed packages too; others stay as they were.
+        let merged = merge_releases(&existing, &["b".into(), "c".into()], Some(BumpType::Major));
+        assert_eq!(
+            merged,
+            vec![
+                release("a", BumpType::Minor),
+                release("b", BumpType::Major),
+                release("c", BumpType::Major),
+            ]
+        );
+
+        // Without a bump an already-listed package keeps its own.
+        let merged = merge_releases(&existing, &["a".to_string()], None);
+        assert_eq!(merged, existing);
+    }
+
+    #[test]
+    fn test_read_summary_from_stdin() {
+        let mut stdin = std::io::Cursor::new("Added a parser.\n");
+        assert_eq!(
+            read_summary(Some("-"), &mut stdin, true).unwrap(),
+            "Added a parser.\n"
+        );
+
+        // Piped stdin is read without a flag; a terminal is not.
+        let mut stdin = std::io::Cursor::new("Fixed a leak.");
+        assert_eq!(
+            read_summary(None, &mut stdin, false).unwrap(),
+            "Fixed a leak."
+        );
+        let mut stdin = std::io::Cursor::new("ignored");
+        assert_eq!(read_summary(None, &mut stdin, true).unwrap(), "");
+
+        // An inline summary wins over stdin.
+        let mut stdin = std::io::Cursor::new("ignored");
+        assert_eq!(
+            read_summary(Some("Inline"), &mut stdin, false).unwrap(),
+            "Inline"
+        );
+    }
+
+    /// Answers the review with scripted choices and records what it was shown.
+    #[derive(Default)]
+    struct FakeReviewer {
+        choices: Vec<ReviewChoice>,
+        edits: Vec<&'static str>,
+        instructions: Vec<&'static str>,
+        shown: Vec<(std::result::Result<String, String>, Vec<ReviewChoice>)>,
+    }
+
+    impl Reviewer for FakeReviewer {
+        fn choose(
+            &mut self,
+            entry: std::result::Result<&Changelog, &str>,
+            choices: &[ReviewChoice],
+        ) -> Result<ReviewChoice> {
+            let entry = entry.map(|cs| cs.summary.clone()).map_err(str::to_string);
+            self.shown.push((entry, choices.to_vec()));
+            Ok(self.choices.remove(0))
+        }
+
+        fn edit(&mut self, _markdown: &str) -> Result<String> {
+            Ok(self.edits.remove(0).to_string())
+        }
+
+        fn instruction(&mut self) -> Result<String> {
+            Ok(self.instructions.remove(0).to_string())
+        }
+    }
+
+    fn review(
+        first: &str,
+        reviewer: &mut FakeReviewer,
+        answers: &[&str],
+    ) -> (Option<Changelog>, Vec<String>) {
+        let workspace = Workspace::new(
+            std::path::PathBuf::from("/tmp/test"),
+            vec![changelogs::ecosystems::Package {
+                name: "foo".to_string(),
+                version: semver::Version::new(1, 0, 0),
+                path: std::path::PathBuf::from("foo"),
+                manifest_path: std::path::PathBuf::from("foo/Cargo.toml"),
+                dependencies: vec![],
+            }],
+            Ecosystem::Rust,
+        );
+        let config = Config::default();
+        let mut answers = answers.iter();
+        let mut prompts = Vec::new();
+
+        let result = review_entry(
+            first.to_string(),
+            reviewer,
+            |markdown| parse_generated(&workspace, &config, markdown),
+            |instruction| {
+                prompts.push(instruction.to_string());
+                Ok(answers.next().unwrap().to_string())
+            },
+        )
+        .unwrap();
+        (result, prompts)
+    }
+
+    const GOOD: &str = "```markdown\n---\nfoo: minor\n---\n\nAdded bar.\n```";
+
+    #[test]
+    fn test_review_accepts_valid_entry() {
+        let mut reviewer = FakeReviewer {
+            choices: vec![ReviewChoice::Accept],
+            ..Default::default()
+        };
+
+        let (cs, prompts) = review(GOOD, &mut reviewer, &[]);
+
+        let cs = cs.unwrap();
+        assert_eq!(cs.summary, "Added bar.");
+        assert_eq!(cs.releases, [release("foo", BumpType::Minor)]);
+        assert!(prompts.is_empty());
+        assert_eq!(reviewer.shown.len(), 1);
+        assert_eq!(reviewer.shown[0].1.len(), 4);
+    }
+
+    #[test]
+    fn test_review_edit_revalidates() {
+        let mut reviewer = FakeReviewer {
+            choices: vec![ReviewChoice::Edit, ReviewChoice::Edit, ReviewChoice::Accept],
+            edits: vec![
+                "---\nfoo: patch\n---\n\n",
+                "---\nfoo: patch\n---\n\nFixed baz.\n",
+            ],
+            ..Default::default()
+        };
+
+        let (cs, _) = review(
+            "---\nimaginary: major\n---\n\nRewrote everything.\n",
+            &mut reviewer,
+            &[],
+        );
+
+        assert_eq!(cs.unwrap().summary, "Fixed baz.");
+        let shown: Vec<_> = reviewer
+            .shown
+            .iter()
+            .map(|(entry, _)| entry.clone())
+            .collect();
+        assert!(
+            shown[0].as_ref().unwrap_err().contains("imaginary"),
+            "{shown:?}"
+        );
+        assert!(
+            shown[1].as_ref().unwrap_err().contains("Empty summary"),
+            "{shown:?}"
+        );
+        assert_eq!(shown[2], Ok("Fixed baz.".to_string()));
+        // Rejected entries can't be accepted.
+        assert!(!reviewer.shown[0].1.contains(&ReviewChoice::Accept));
+    }
+
+    #[test]
+    fn test_review_regenerate_passes_instruction() {
+        let mut reviewer = FakeReviewer {
+            choices: vec![ReviewChoice::Regenerate, ReviewChoice::Accept],
+            instructions: vec!["Mention the new flag"],
+            ..Default::default()
+        };
+
+        let (cs, prompts) = review(
+            GOOD,
+            &mut reviewer,
+            &["---\nfoo: minor\n---\n\nAdded bar with --flag.\n"],
+        );
+
+        assert_eq!(cs.unwrap().summary, "Added bar with --flag.");
+        assert_eq!(prompts, ["Mention the new flag"]);
+    }
+
+    #[test]
+    fn test_review_abort() {
+        let mut reviewer = FakeReviewer {
+            choices: vec![ReviewChoice::Abort],
+            ..Default::default()
+        };
+
+        let (cs, _) = review("not a changelog", &mut reviewer, &[]);
+
+        assert!(cs.is_none());
+        assert_eq!(
+            reviewer.shown[0].1,
+            [
+                ReviewChoice::Edit,
+                ReviewChoice::Regenerate,
+                ReviewChoice::Abort
+            ]
+        );
+    }
+
+    /// Writes an executable shell script standing in for the user's editor.
+    #[cfg(unix)]
+    fn fake_editor(dir: &Path, script: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join("editor.sh");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.display().to_string()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_edit_summary_reads_saved_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        // Save by replacing the file, as editors with atomic saves do.
+        let editor = fake_editor(
+            dir.path(),
+            &format!(
+                "echo \"$1\" > {0}/path; printf 'Added x.' > {0}/new.md; mv {0}/new.md \"$1\"",
+                dir.path().display()
+            ),
+        );
+
+        assert_eq!(edit_summary(&editor).unwrap(), "Added x.");
+
+        let temp_path = std::fs::read_to_string(dir.path().join("path")).unwrap();
+        let temp_path = Path::new(temp_path.trim());
+        assert!(temp_path.extension().is_some_and(|ext| ext == "md"));
+        assert!(!temp_path.exists(), "temp file left behind");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_edit_text_starts_with_initial_text() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let editor = fake_editor(
+            dir.path(),
+            &format!(
+                "sed 's/patch/minor/' \"$1\" > {0}/out; mv {0}/out \"$1\"",
+                dir.path().display()
+            ),
+        );
+
+        assert_eq!(
+            edit_text(&editor, "---\nfoo: patch\n---\n").unwrap(),
+            "---\nfoo: minor\n---\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_edit_summary_aborts_on_editor_failure() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let editor = fake_editor(
+            dir.path(),
+            &format!(
+                "echo \"$1\" > {}/path; printf 'Half-written' > \"$1\"; exit 1",
+                dir.path().display()
+            ),
+        );
+
+        let err = edit_summary(&editor).unwrap_err().to_string();
+        assert!(err.contains("aborted, nothing was saved"), "{err}");
+
+        let temp_path = std::fs::read_to_string(dir.path().join("path")).unwrap();
+        assert!(
+            !Path::new(temp_path.trim()).exists(),
+            "temp file left behind"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use changelogs::config::{self, Config};
+use changelogs::error::Error;
+use changelogs::workspace::{DiscoverOptions, Workspace};
+use console::style;
+use std::path::PathBuf;
+
+fn changelog_dir(discover: &DiscoverOptions) -> Result<PathBuf> {
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
+    )?;
+    Ok(workspace.changelog_dir())
+}
+
+pub fn get(key: String, discover: &DiscoverOptions) -> Result<()> {
+    let config = Config::load(&changelog_dir(discover)?)?.to_value()?;
+    let value = config::lookup(&config, &key).ok_or(Error::UnknownConfigKey(key))?;
+
+    match value {
+        // Strings are printed bare so scripts don't have to unquote them.
+        toml::Value::String(s) => println!("{}", s),
+        toml::Value::Table(table) => print!("{}", toml::to_string(table)?),
+        other => println!("{}", other),
+    }
+    Ok(())
+}
+
+pub fn set(key: String, value: String, discover: &DiscoverOptions) -> Result<()> {
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
+    )?;
+
+    if !workspace.is_initialized() {
+        return Err(Error::NotInitialized.into());
+    }
+
+    Config::set_key(&workspace.changelog_dir(), &key, &value)?;
+    println!(
+        "{} Set {} = {}",
+        style("✓").green(),
+        style(&key).cyan(),
+        value
+    );
+    Ok(())
+}
+
+pub fn list(json: bool, discover: &DiscoverOptions) -> Result<()> {
+    let dir = changelog_dir(discover)?;
+    let effective = Config::load(&dir)?.to_value()?;
+    let config_path = dir.join("config.toml");
+    let file: toml::Value = if config_path.exists() {
+        toml::from_str(&std::fs::read_to_string(&config_path)?)?
+    } else {
+        toml::Value::Table(Default::default())
+    };
+
+    let entries = provenance(&effective, &file);
+
+    if json {
+        let entries: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|(key, value, source)| {
+                serde_json::json!({ "key": key, "value": value, "source": source })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    for (key, value, source) in &entries {
+        println!(
+            "{} = {} {}",
+            key,
+            value,
+            style(format!("({})", source)).dim()
+        );
+    }
+    Ok(())
+}
+
+/// Flattens the effective config into `(key, value, source)` rows, where source
+/// is `file` if `config.toml` sets the key (or one of its parents) and `default`
+/// otherwise.
+fn provenance<'a>(
+    effective: &'a toml::Value,
+    file: &toml::Value,
+) -> Vec<(String, &'a toml::Value, &'static str)> {
+    let mut leaves = Vec::new();
+    flatten("", effective, &mut leaves);
+
+    leaves
+        .into_iter()
+        .map(|(key, value)| {
+            let source = if config::lookup(file, &key).is_some() {
+                "file"
+            } else {
+                "default"
+            };
+            (key, value, source)
+        })
+        .collect()
+}
+
+fn flatten<'a>(prefix: &str, value: &'a toml::Value, out: &mut Vec<(String, &'a toml::Value)>) {
+    let join = |segment: &str| {
+        if prefix.is_empty() {
+            segment.to_string()
+        } else {
+            format!("{}.{}", prefix, segment)
+        }
+    };
+
+    match value {
+        toml::Value::Table(table) if !table.is_empty() => {
+            for (key, child) in table {
+                flatten(&join(key), child, out);
+            }
+        }
+        toml::Value::Array(array) if !array.is_empty() && array.iter().all(|v| v.is_table()) => {
+            for (index, child) in array.iter().enumerate() {
+                flatten(&join(&index.to_string()), child, out);
+            }
+        }
+        _ => out.push((prefix.to_string(), value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provenance_marks_file_keys() {
+        let file: toml::Value =
+            toml::from_str("dependent_bump = \"minor\"\n\n[[fixed]]\nmembers = [\"a\", \"b\"]\n")
+                .unwrap();
+        let config: Config = toml::from_str(&toml::to_string(&file).unwrap()).unwrap();
+        let effective = config.to_value().unwrap();
+
+        let rows = provenance(&effective, &file);
+        let source = |key: &str| rows.iter().find(|(k, _, _)| k == key).map(|(_, _, s)| *s);
+
+        assert_eq!(source("dependent_bump"), Some("file"));
+        assert_eq!(source("fixed.0.members"), Some("file"));
+        assert_eq!(source("changelog.format"), Some("default"));
+        assert_eq!(source("rust.release_members"), Some("default"));
+    }
+}
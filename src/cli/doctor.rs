@@ -1,13 +1,16 @@
 use anyhow::Result;
-use changelogs::Ecosystem;
 use changelogs::changelog_entry;
 use changelogs::config::Config;
-use changelogs::workspace::Workspace;
+use changelogs::git;
+use changelogs::lint;
+use changelogs::workspace::{DiscoverOptions, Workspace};
 use console::style;
-use std::process::Command;
 
 enum CheckResult {
     Pass(String),
+    /// A problem worth knowing about that doesn't fail the run, e.g. an
+    /// unreachable registry.
+    Warn(String),
     Fail(String),
 }
 
@@ -15,6 +18,7 @@ impl CheckResult {
     fn print(&self) {
         match self {
             CheckResult::Pass(msg) => println!("  {} {msg}", style("✓").green()),
+            CheckResult::Warn(msg) => println!("  {} {msg}", style("!").yellow()),
             CheckResult::Fail(msg) => println!("  {} {msg}", style("✗").red()),
         }
     }
@@ -22,21 +26,52 @@ impl CheckResult {
     fn is_pass(&self) -> bool {
         matches!(self, CheckResult::Pass(_))
     }
+
+    fn is_fail(&self) -> bool {
+        matches!(self, CheckResult::Fail(_))
+    }
 }
 
-fn check_workspace(ecosystem: Option<Ecosystem>) -> (CheckResult, Option<Workspace>) {
-    match Workspace::discover_with_ecosystem(ecosystem) {
+fn check_workspace(discover: &DiscoverOptions) -> (CheckResult, Option<Workspace>) {
+    match Workspace::discover_with(discover) {
         Ok(ws) => {
             let msg = format!("Workspace detected ({})", style(ws.root.display()).dim());
             (CheckResult::Pass(msg), Some(ws))
         }
         Err(e) => (
-            CheckResult::Fail(format!("Workspace detection failed: {e}")),
+            CheckResult::Fail(format!(
+                "Workspace detection failed: {}",
+                first_cause_line(&e)
+            )),
             None,
         ),
     }
 }
 
+/// The first meaningful line of an error, e.g. cargo's `error: failed to parse manifest ...`.
+fn first_cause_line(err: &changelogs::error::Error) -> String {
+    match err {
+        changelogs::error::Error::ManifestLoad { details, .. } => details
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .unwrap_or_default()
+            .to_string(),
+        other => error_chain(other),
+    }
+}
+
+/// An error followed by its causes, e.g. `failed to parse config: expected ...`.
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    message
+}
+
 fn check_initialized(workspace: &Workspace) -> CheckResult {
     if workspace.is_initialized() {
         CheckResult::Pass("Changelog directory initialized".into())
@@ -51,84 +86,35 @@ fn check_initialized(workspace: &Workspace) -> CheckResult {
 fn check_config(changelog_dir: &std::path::Path) -> (CheckResult, Option<Config>) {
     match Config::load(changelog_dir) {
         Ok(c) => (CheckResult::Pass("Config is valid".into()), Some(c)),
-        Err(e) => (CheckResult::Fail(format!("Config parse failed: {e}")), None),
+        Err(e) => (
+            CheckResult::Fail(format!("Config parse failed: {}", error_chain(&e))),
+            None,
+        ),
     }
 }
 
-fn check_fixed_groups(config: &Config, package_names: &[&str]) -> Vec<CheckResult> {
-    config
-        .fixed
-        .iter()
-        .enumerate()
-        .map(|(i, group)| {
-            let invalid: Vec<_> = group
-                .members
-                .iter()
-                .filter(|m| !package_names.contains(&m.as_str()))
-                .collect();
-            if invalid.is_empty() {
-                CheckResult::Pass(format!("Fixed group {} — all members valid", i + 1))
-            } else {
-                CheckResult::Fail(format!(
-                    "Fixed group {} references unknown packages: {}",
-                    i + 1,
-                    invalid
-                        .iter()
-                        .map(|s| s.as_str())
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                ))
-            }
-        })
-        .collect()
-}
-
-fn check_linked_groups(config: &Config, package_names: &[&str]) -> Vec<CheckResult> {
-    config
-        .linked
-        .iter()
-        .enumerate()
-        .map(|(i, group)| {
-            let invalid: Vec<_> = group
-                .members
-                .iter()
-                .filter(|m| !package_names.contains(&m.as_str()))
-                .collect();
-            if invalid.is_empty() {
-                CheckResult::Pass(format!("Linked group {} — all members valid", i + 1))
-            } else {
-                CheckResult::Fail(format!(
-                    "Linked group {} references unknown packages: {}",
-                    i + 1,
-                    invalid
-                        .iter()
-                        .map(|s| s.as_str())
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                ))
-            }
-        })
-        .collect()
-}
-
-fn check_ignore_list(config: &Config, package_names: &[&str]) -> CheckResult {
-    let invalid: Vec<_> = config
-        .ignore
-        .iter()
-        .filter(|m| !package_names.contains(&m.as_str()))
-        .collect();
-    if invalid.is_empty() {
-        CheckResult::Pass("Ignore list — all entries valid".into())
-    } else {
-        CheckResult::Fail(format!(
-            "Ignore list references unknown packages: {}",
-            invalid
-                .iter()
-                .map(|s| s.as_str())
-                .collect::<Vec<_>>()
-                .join(", ")
-        ))
+fn check_config_packages(config: &Config, package_names: &[&str]) -> CheckResult {
+    let list = |issues: &[String]| {
+        issues
+            .iter()
+            .map(|issue| format!("      {}", style(issue).dim()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    if let Err(issues) = config.validate(package_names) {
+        return CheckResult::Fail(format!(
+            "Config doesn't match the workspace:\n{}",
+            list(&issues)
+        ));
+    }
+    let warnings = config.group_warnings();
+    if !warnings.is_empty() {
+        return CheckResult::Warn(format!(
+            "Groups are valid but look unintended:\n{}",
+            list(&warnings)
+        ));
     }
+    CheckResult::Pass("Groups, ignore list and package overrides are valid".into())
 }
 
 fn check_pending_changelogs(
@@ -162,14 +148,77 @@ fn check_pending_changelogs(
     }
 }
 
-fn check_git_remote() -> CheckResult {
-    let remote_ok = Command::new("git")
-        .args(["remote", "get-url", "origin"])
-        .output()
-        .map(|o| o.status.success() && !String::from_utf8_lossy(&o.stdout).trim().is_empty())
-        .unwrap_or(false);
+/// Runs the `[lint]` checks on every pending changelog; `None` when none are configured.
+fn check_lint(
+    root: &std::path::Path,
+    changelog_dir: &std::path::Path,
+    config: &Config,
+) -> Option<CheckResult> {
+    if config.lint.command.is_none() && config.lint.builtin.is_empty() {
+        return None;
+    }
+
+    let changelogs = match changelog_entry::read_all(changelog_dir) {
+        Ok(changelogs) => changelogs,
+        Err(e) => return Some(CheckResult::Fail(format!("Failed to read changelogs: {e}"))),
+    };
+    let mut failures = Vec::new();
+    for changelog in &changelogs {
+        let path = changelog_dir.join(format!("{}.md", changelog.id));
+        let markdown = std::fs::read_to_string(&path).unwrap_or_default();
+        for problem in lint::check(&config.lint, root, changelog, &markdown) {
+            failures.push(format!("{}: {}", changelog.id, problem));
+        }
+    }
 
-    if remote_ok {
+    Some(if failures.is_empty() {
+        CheckResult::Pass("Pending changelogs — [lint] checks passed".into())
+    } else {
+        let details = failures
+            .iter()
+            .map(|f| format!("      {}", style(f).dim()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        CheckResult::Fail(format!("Pending changelogs fail [lint] checks:\n{details}"))
+    })
+}
+
+fn check_registry_versions(
+    ahead: changelogs::error::Result<Vec<(&changelogs::Package, semver::Version)>>,
+) -> CheckResult {
+    match ahead {
+        Ok(ahead) if ahead.is_empty() => {
+            CheckResult::Pass("Manifest versions — none behind the registry".into())
+        }
+        Ok(ahead) => {
+            let details = ahead
+                .iter()
+                .map(|(pkg, published)| {
+                    format!(
+                        "      {}",
+                        style(format!(
+                            "{}: {} published, manifest at {}",
+                            pkg.name, published, pkg.version
+                        ))
+                        .dim()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            CheckResult::Fail(format!(
+                "Registry is ahead of the manifest — the local repo is out of sync:\n{details}"
+            ))
+        }
+        // Often just offline; not a problem with the repo.
+        Err(e) => CheckResult::Warn(format!(
+            "Registry versions could not be checked: {}",
+            error_chain(&e)
+        )),
+    }
+}
+
+fn check_git_remote() -> CheckResult {
+    if git::open().remote_url("origin").is_some() {
         CheckResult::Pass("Git remote detected".into())
     } else {
         CheckResult::Fail(
@@ -179,18 +228,19 @@ fn check_git_remote() -> CheckResult {
     }
 }
 
+/// Records `checks`, returning false if any failed.
 fn run_checks(results: &mut Vec<CheckResult>, checks: Vec<CheckResult>) -> bool {
-    let all_passed = checks.iter().all(|r| r.is_pass());
+    let none_failed = !checks.iter().any(|r| r.is_fail());
     results.extend(checks);
-    all_passed
+    none_failed
 }
 
-pub fn run(ecosystem: Option<Ecosystem>) -> Result<()> {
+pub fn run(discover: &DiscoverOptions) -> Result<()> {
     println!("{} Running diagnostics...\n", style("→").blue().bold());
 
     let mut results: Vec<CheckResult> = Vec::new();
 
-    let (ws_check, workspace) = check_workspace(ecosystem);
+    let (ws_check, workspace) = check_workspace(discover);
     if !run_checks(&mut results, vec![ws_check]) {
         print_results(&results);
         return Ok(());
@@ -203,6 +253,7 @@ pub fn run(ecosystem: Option<Ecosystem>) -> Result<()> {
     }
 
     let changelog_dir = workspace.changelog_dir();
+    let pending_dir = workspace.channel_dir(None);
     let package_names: Vec<&str> = workspace.package_names();
 
     let (config_check, config) = check_config(&changelog_dir);
@@ -212,40 +263,84 @@ pub fn run(ecosystem: Option<Ecosystem>) -> Result<()> {
     }
     let config = config.unwrap();
 
-    run_checks(&mut results, check_fixed_groups(&config, &package_names));
-    run_checks(&mut results, check_linked_groups(&config, &package_names));
     run_checks(
         &mut results,
-        vec![check_ignore_list(&config, &package_names)],
+        vec![check_config_packages(&config, &package_names)],
     );
     run_checks(
         &mut results,
-        vec![check_pending_changelogs(&changelog_dir, &package_names)],
+        vec![check_pending_changelogs(&pending_dir, &package_names)],
     );
+    if let Some(check) = check_lint(&workspace.root, &pending_dir, &config) {
+        run_checks(&mut results, vec![check]);
+    }
+    if config.check_registry {
+        let packages: Vec<_> = workspace.packages.iter().collect();
+        run_checks(
+            &mut results,
+            vec![check_registry_versions(
+                workspace.ahead_on_registry(&packages),
+            )],
+        );
+    }
     run_checks(&mut results, vec![check_git_remote()]);
 
     print_results(&results);
     Ok(())
 }
 
+fn print_results(results: &[CheckResult]) {
+    for result in results {
+        result.print();
+    }
+
+    let passed = results.iter().filter(|r| r.is_pass()).count();
+    let failed = results.iter().filter(|r| r.is_fail()).count();
+    let warned = results.len() - passed - failed;
+
+    println!();
+    if failed > 0 {
+        println!(
+            "{} {passed} passed, {warned} warning(s), {failed} failed",
+            style("✗").red().bold()
+        );
+    } else if warned > 0 {
+        println!(
+            "{} {passed} passed, {warned} warning(s)",
+            style("!").yellow().bold()
+        );
+    } else {
+        println!("{} All {passed} checks passed", style("✓").green().bold());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use changelogs::Ecosystem;
     use tempfile::TempDir;
 
     #[test]
     fn test_check_workspace_missing() {
-        let (result, ws) = check_workspace(None);
+        let (result, ws) = check_workspace(&DiscoverOptions::default());
         assert!(!result.is_pass() || ws.is_some());
     }
 
     fn fake_workspace(root: &std::path::Path) -> Workspace {
-        Workspace {
-            root: root.to_path_buf(),
-            changelog_dir: root.join(".changelog"),
-            packages: vec![],
+        Workspace::new(root.to_path_buf(), vec![], changelogs::Ecosystem::Rust)
+    }
+
+    #[test]
+    fn test_first_cause_line_manifest_load() {
+        let err = changelogs::error::Error::ManifestLoad {
             ecosystem: changelogs::Ecosystem::Rust,
-        }
+            details: "\nerror: failed to parse manifest at `/x/Cargo.toml`\n\nCaused by:\n  oops"
+                .into(),
+        };
+        assert_eq!(
+            first_cause_line(&err),
+            "error: failed to parse manifest at `/x/Cargo.toml`"
+        );
     }
 
     #[test]
@@ -274,47 +369,102 @@ mod tests {
     }
 
     #[test]
-    fn test_check_ignore_list_all_valid() {
+    fn test_check_config_packages_valid() {
         let config = Config {
             ignore: vec!["pkg-a".into()],
             ..Default::default()
         };
-        let result = check_ignore_list(&config, &["pkg-a", "pkg-b"]);
-        assert!(result.is_pass());
+        assert!(check_config_packages(&config, &["pkg-a", "pkg-b"]).is_pass());
+        assert!(check_config_packages(&Config::default(), &[]).is_pass());
     }
 
     #[test]
-    fn test_check_ignore_list_invalid() {
+    fn test_check_config_packages_invalid() {
         let config = Config {
             ignore: vec!["pkg-missing".into()],
             ..Default::default()
         };
-        let result = check_ignore_list(&config, &["pkg-a"]);
-        assert!(!result.is_pass());
+        let CheckResult::Fail(message) = check_config_packages(&config, &["pkg-a"]) else {
+            panic!("expected a failure");
+        };
+        assert!(message.contains("ignore references unknown packages: pkg-missing"));
+
+        let config: Config = toml::from_str(
+            "[[fixed]]\nmembers = [\"a\"]\n\n[[linked]]\nmembers = [\"a\", \"b\"]\n",
+        )
+        .unwrap();
+        let CheckResult::Warn(message) = check_config_packages(&config, &["a", "b"]) else {
+            panic!("expected a warning");
+        };
+        assert!(
+            message.contains("fixed group takes precedence"),
+            "{message}"
+        );
     }
 
     #[test]
-    fn test_check_git_remote_in_non_git_dir() {
-        let result = check_git_remote();
-        assert!(result.is_pass() || !result.is_pass());
-    }
-}
+    fn test_check_lint() {
+        let temp = TempDir::new().unwrap();
+        let changelog_dir = temp.path().join(".changelog");
+        std::fs::create_dir(&changelog_dir).unwrap();
+        std::fs::write(
+            changelog_dir.join("brave-fox.md"),
+            "---\ncore: patch\n---\n\nFixed a bug.\n",
+        )
+        .unwrap();
 
-fn print_results(results: &[CheckResult]) {
-    for result in results {
-        result.print();
+        let mut config = Config::default();
+        assert!(check_lint(temp.path(), &changelog_dir, &config).is_none());
+
+        config.lint.builtin = vec![changelogs::config::LintRule::NoTrailingPeriod];
+        let result = check_lint(temp.path(), &changelog_dir, &config).unwrap();
+        let CheckResult::Fail(msg) = result else {
+            panic!("expected a failure");
+        };
+        assert!(msg.contains("brave-fox: summary line 1 ends with a period"));
     }
 
-    let passed = results.iter().filter(|r| r.is_pass()).count();
-    let failed = results.len() - passed;
+    #[test]
+    fn test_check_registry_versions_ahead() {
+        let pkg = changelogs::Package {
+            name: "core".into(),
+            version: semver::Version::new(1, 2, 0),
+            path: "/fake/core".into(),
+            manifest_path: "/fake/core/pyproject.toml".into(),
+            dependencies: vec![],
+        };
+        let ws = Workspace::new("/fake".into(), vec![pkg], Ecosystem::Python);
+        let packages: Vec<_> = ws.packages.iter().collect();
 
-    println!();
-    if failed > 0 {
-        println!(
-            "{} {passed} passed, {failed} failed",
-            style("✗").red().bold()
+        // A registry response listing a version past the manifest's.
+        let ahead = ws.ahead_on_registry_with(&packages, |_| Ok(Some("1.3.0".parse().unwrap())));
+        let CheckResult::Fail(msg) = check_registry_versions(ahead) else {
+            panic!("expected a failure");
+        };
+        assert!(msg.contains("out of sync"), "{msg}");
+        assert!(
+            msg.contains("core: 1.3.0 published, manifest at 1.2.0"),
+            "{msg}"
         );
-    } else {
-        println!("{} All {passed} checks passed", style("✓").green().bold());
+
+        let current = ws.ahead_on_registry_with(&packages, |_| Ok(Some("1.2.0".parse().unwrap())));
+        assert!(check_registry_versions(current).is_pass());
+
+        // An unreachable registry only warns.
+        let offline = ws.ahead_on_registry_with(&packages, |_| {
+            Err(changelogs::error::Error::PypiCheckFailed(
+                "timed out".into(),
+            ))
+        });
+        let CheckResult::Warn(msg) = check_registry_versions(offline) else {
+            panic!("expected a warning");
+        };
+        assert!(msg.contains("timed out"), "{msg}");
+    }
+
+    #[test]
+    fn test_check_git_remote_in_non_git_dir() {
+        let result = check_git_remote();
+        assert!(result.is_pass() || !result.is_pass());
     }
 }
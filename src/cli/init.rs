@@ -1,25 +1,134 @@
 use anyhow::{Context, Result};
-use changelogs::Ecosystem;
+use changelogs::changesets::{self, Changesets};
 use changelogs::config::Config;
 use changelogs::error::Error;
-use changelogs::workspace::Workspace;
+use changelogs::fs_util::atomic_write;
+use changelogs::workspace::{DiscoverOptions, Workspace};
 use console::style;
+use inquire::Select;
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// What to do with an existing `.changeset/` directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangesetsAction {
+    Migrate,
+    Coexist,
+    Abort,
+}
+
+impl std::fmt::Display for ChangesetsAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangesetsAction::Migrate => {
+                write!(f, "Migrate (convert config.json and move entries)")
+            }
+            ChangesetsAction::Coexist => write!(f, "Coexist (keep .changeset/ as is)"),
+            ChangesetsAction::Abort => write!(f, "Abort"),
+        }
+    }
+}
+
+/// `action` is the choice given on the command line; without one, init prompts when
+/// changesets are found, or aborts with `yes` or when stdin is not a terminal.
+pub fn run(
+    force: bool,
+    yes: bool,
+    action: Option<ChangesetsAction>,
+    discover: &DiscoverOptions,
+) -> Result<()> {
+    let workspace = discover_workspace(discover)?;
+
+    if workspace.is_initialized() && !force {
+        return Err(Error::AlreadyInitialized.into());
+    }
+
+    let found = changesets::detect(&workspace.root);
+    let action = match (&found, action) {
+        (None, _) => None,
+        (Some(_), Some(action)) => Some(action),
+        (Some(found), None) if !yes && std::io::stdin().is_terminal() => {
+            eprintln!(
+                "{} Found {} from changesets ({} pending)",
+                style("!").yellow().bold(),
+                found.dir.display(),
+                found.entries.len()
+            );
+            Some(
+                Select::new(
+                    "What would you like to do with it?",
+                    vec![
+                        ChangesetsAction::Migrate,
+                        ChangesetsAction::Coexist,
+                        ChangesetsAction::Abort,
+                    ],
+                )
+                .prompt()?,
+            )
+        }
+        (Some(_), None) => Some(ChangesetsAction::Abort),
+    };
+
+    match (found, action) {
+        (_, Some(ChangesetsAction::Abort)) => Err(Error::ChangesetsFound.into()),
+        (Some(found), Some(ChangesetsAction::Migrate)) => initialize(&workspace, Some(&found)),
+        (Some(found), Some(ChangesetsAction::Coexist)) => {
+            initialize(&workspace, None)?;
+            eprintln!(
+                "\n{} {} is still in place. Both tools will read their own entries, so \
+                 every change must be recorded with one of them only.",
+                style("WARNING:").yellow().bold(),
+                found.dir.display()
+            );
+            Ok(())
+        }
+        _ => initialize(&workspace, None),
+    }
+}
 
-pub fn run(ecosystem: Option<Ecosystem>) -> Result<()> {
-    let workspace = Workspace::discover_with_ecosystem(ecosystem)
-        .context("could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python> init")?;
+/// Converts `.changeset/` into `.changelog/`, the same as choosing migrate in `init`.
+pub fn migrate(force: bool, discover: &DiscoverOptions) -> Result<()> {
+    let workspace = discover_workspace(discover)?;
+    let found = changesets::detect(&workspace.root).ok_or(Error::ChangesetsNotFound)?;
 
-    if workspace.is_initialized() {
+    if workspace.is_initialized() && !force {
         return Err(Error::AlreadyInitialized.into());
     }
 
+    initialize(&workspace, Some(&found))
+}
+
+fn discover_workspace(discover: &DiscoverOptions) -> Result<Workspace> {
+    Workspace::discover_with(discover)
+        .context("could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet> init")
+}
+
+/// Writes `.changelog/`, with its config converted from `changesets` when given.
+fn initialize(workspace: &Workspace, changesets: Option<&Changesets>) -> Result<()> {
     let changelog_dir = workspace.changelog_dir();
     std::fs::create_dir_all(&changelog_dir)?;
 
-    std::fs::write(changelog_dir.join("config.toml"), Config::default_toml())?;
+    // Only config.toml and README.md are rewritten; pending changelogs stay put.
+    let config_path = changelog_dir.join("config.toml");
+    if let Ok(existing) = std::fs::read_to_string(&config_path) {
+        if existing != Config::default_toml() {
+            let backup = changelog_dir.join("config.toml.bak");
+            atomic_write(&backup, &existing)?;
+            eprintln!(
+                "{} Overwriting customized {} (saved a copy to {})",
+                style("!").yellow().bold(),
+                config_path.display(),
+                backup.display()
+            );
+        }
+    }
+    match changesets {
+        Some(found) => migrate_changesets(found, &changelog_dir)?,
+        None => atomic_write(&config_path, Config::default_toml())?,
+    }
 
-    std::fs::write(
-        changelog_dir.join("README.md"),
+    atomic_write(
+        &changelog_dir.join("README.md"),
         r#"# Changelogs
 
 This folder contains changelog files that describe changes to be released.
@@ -66,3 +175,27 @@ Run `changelogs version` to apply version bumps and generate changelogs.
 
     Ok(())
 }
+
+fn migrate_changesets(found: &Changesets, changelog_dir: &Path) -> Result<()> {
+    let migration = changesets::migrate(found, changelog_dir)?;
+
+    println!(
+        "{} Migrated {} ({} {} moved)",
+        style("✓").green().bold(),
+        found.dir.display(),
+        migration.moved.len(),
+        if migration.moved.len() == 1 {
+            "entry"
+        } else {
+            "entries"
+        }
+    );
+    if !migration.unsupported.is_empty() {
+        eprintln!(
+            "{} Not converted from config.json: {}",
+            style("!").yellow().bold(),
+            migration.unsupported.join(", ")
+        );
+    }
+    Ok(())
+}
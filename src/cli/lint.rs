@@ -0,0 +1,71 @@
+use anyhow::{Context, Result, bail};
+use changelogs::changelog_entry;
+use changelogs::config::Config;
+use changelogs::error::Error;
+use changelogs::lint;
+use changelogs::workspace::{DiscoverOptions, Workspace};
+use console::style;
+use std::path::PathBuf;
+
+pub fn run(discover: &DiscoverOptions) -> Result<()> {
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
+    )?;
+
+    if !workspace.is_initialized() {
+        return Err(Error::NotInitialized.into());
+    }
+
+    let config = Config::load(&workspace.changelog_dir())?;
+    let package_names = workspace.package_names();
+
+    // `[changelog] dir` may point at a directory no entry was added to yet.
+    let pending_dir = workspace.channel_dir(None);
+    let mut files: Vec<PathBuf> = if pending_dir.exists() {
+        std::fs::read_dir(&pending_dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+            .filter(|p| p.file_stem().is_some_and(|s| s != "README"))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    files.sort();
+
+    let mut problem_count = 0;
+    for path in &files {
+        let id = path.file_stem().unwrap().to_string_lossy();
+        let content = std::fs::read_to_string(path)?;
+        let mut problems = changelog_entry::lint(&id, &content, &package_names);
+        // The `[lint]` checks need a well-formed entry.
+        if problems.is_empty() {
+            let changelog = changelog_entry::parse(&id, &content)?;
+            problems = lint::check(&config.lint, &workspace.root, &changelog, &content);
+        }
+        if problems.is_empty() {
+            continue;
+        }
+
+        let display = path.strip_prefix(&workspace.root).unwrap_or(path);
+        println!("{}", style(display.display()).bold());
+        for problem in &problems {
+            println!("  {} {}", style("✗").red(), problem);
+        }
+        problem_count += problems.len();
+    }
+
+    if problem_count > 0 {
+        bail!(
+            "found {} problem(s) in {} changelog(s)",
+            problem_count,
+            files.len()
+        );
+    }
+
+    println!(
+        "{} {} changelog(s) look good",
+        style("✓").green().bold(),
+        files.len()
+    );
+    Ok(())
+}
@@ -1,7 +1,35 @@
 pub mod add;
+pub mod config;
 pub mod doctor;
 pub mod init;
+pub mod lint;
+pub mod notes;
+pub mod output;
+pub mod packages;
+pub mod preview;
 pub mod publish;
+pub mod set_version;
 pub mod status;
+pub mod summary;
 pub mod up;
 pub mod version;
+pub mod why;
+
+#[cfg(test)]
+#[path = "../git/fake.rs"]
+mod fake_git;
+
+use changelogs::Config;
+
+/// Overrides `[changelog] repo_url`, e.g. in CI where `origin` is a fork.
+const REPO_URL_ENV: &str = "CHANGELOGS_REPO_URL";
+
+/// Applies `CHANGELOGS_REPO_URL`, when set, over `[changelog] repo_url`.
+pub fn apply_repo_url_env(config: &mut Config) {
+    if let Some(url) = std::env::var(REPO_URL_ENV)
+        .ok()
+        .filter(|url| !url.is_empty())
+    {
+        config.changelog.repo_url = Some(url);
+    }
+}
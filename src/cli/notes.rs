@@ -0,0 +1,40 @@
+use anyhow::{Context, Result};
+use changelogs::config::Config;
+use changelogs::fs_util::atomic_write;
+use changelogs::release_notes::{self, Since};
+use changelogs::workspace::{DiscoverOptions, Workspace};
+use changelogs::{changelog_writer, git};
+use console::style;
+use std::path::PathBuf;
+
+pub fn run(since: String, output: Option<PathBuf>, discover: &DiscoverOptions) -> Result<()> {
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
+    )?;
+
+    // Only committed CHANGELOG.md files are read, so `.changelog/` may be missing.
+    let mut config = Config::load(&workspace.changelog_dir())?;
+    super::apply_repo_url_env(&mut config);
+    let since: Since = since.parse()?;
+    let git = git::open();
+    let notes = release_notes::collect(&workspace, &config, &since, git.as_ref())?;
+    let github_url =
+        changelog_writer::resolve_github_url(config.changelog.repo_url.as_deref(), git.as_ref());
+    let markdown = release_notes::render_markdown(&notes, &since, github_url.as_deref());
+
+    match output {
+        Some(path) => {
+            atomic_write(&path, markdown)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            eprintln!(
+                "{} Wrote release notes for {} changelog(s) to {}",
+                style("✓").green().bold(),
+                notes.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", markdown),
+    }
+
+    Ok(())
+}
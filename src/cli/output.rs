@@ -0,0 +1,91 @@
+//! Whether output is colored, decided once at startup from `--color` and the
+//! environment. Every `console::style` call follows the decision, so subcommands
+//! don't check it themselves.
+
+use std::io::IsTerminal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal, following `NO_COLOR`, `CLICOLOR` and
+    /// `CLICOLOR_FORCE`.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Applies `choice` to stdout and stderr.
+pub fn init(choice: ColorChoice) {
+    let env = |key: &str| std::env::var(key).ok();
+    console::set_colors_enabled(colors_enabled(choice, std::io::stdout().is_terminal(), env));
+    console::set_colors_enabled_stderr(colors_enabled(
+        choice,
+        std::io::stderr().is_terminal(),
+        env,
+    ));
+}
+
+/// Whether a stream gets colors. With `Auto`, a non-empty `NO_COLOR` turns them off,
+/// then `CLICOLOR_FORCE` (other than `0`) turns them on, then `CLICOLOR=0` turns them
+/// off; otherwise only terminals get them.
+fn colors_enabled(
+    choice: ColorChoice,
+    is_terminal: bool,
+    env: impl Fn(&str) -> Option<String>,
+) -> bool {
+    let set = |key: &str| env(key).filter(|v| !v.is_empty());
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto if set("NO_COLOR").is_some() => false,
+        ColorChoice::Auto if set("CLICOLOR_FORCE").is_some_and(|v| v != "0") => true,
+        ColorChoice::Auto if set("CLICOLOR").is_some_and(|v| v == "0") => false,
+        ColorChoice::Auto => is_terminal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(choice: ColorChoice, is_terminal: bool, vars: &[(&str, &str)]) -> bool {
+        colors_enabled(choice, is_terminal, |key| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn test_colors_enabled() {
+        assert!(enabled(ColorChoice::Auto, true, &[]));
+        assert!(!enabled(ColorChoice::Auto, false, &[]));
+        assert!(!enabled(ColorChoice::Auto, true, &[("NO_COLOR", "1")]));
+        assert!(enabled(ColorChoice::Auto, true, &[("NO_COLOR", "")]));
+        assert!(enabled(
+            ColorChoice::Auto,
+            false,
+            &[("CLICOLOR_FORCE", "1")]
+        ));
+        assert!(!enabled(
+            ColorChoice::Auto,
+            false,
+            &[("CLICOLOR_FORCE", "0")]
+        ));
+        assert!(!enabled(ColorChoice::Auto, true, &[("CLICOLOR", "0")]));
+        // NO_COLOR wins over CLICOLOR_FORCE.
+        assert!(!enabled(
+            ColorChoice::Auto,
+            true,
+            &[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")]
+        ));
+
+        // An explicit choice wins over the environment.
+        assert!(enabled(ColorChoice::Always, false, &[("NO_COLOR", "1")]));
+        assert!(!enabled(
+            ColorChoice::Never,
+            true,
+            &[("CLICOLOR_FORCE", "1")]
+        ));
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::{Context, Result};
+use changelogs::Package;
+use changelogs::config::Config;
+use changelogs::ecosystems::DependencyKind;
+use changelogs::graph::DependencyGraph;
+use changelogs::workspace::{DiscoverOptions, Workspace};
+use console::style;
+use serde::Serialize;
+use std::path::Path;
+
+/// One package as reported by `packages --json`. Paths are relative to the
+/// workspace root and `/`-separated.
+#[derive(Debug, Serialize)]
+struct PackageInfo {
+    name: String,
+    version: String,
+    path: String,
+    manifest_path: String,
+    ecosystem: String,
+    /// Dependencies on other workspace packages only.
+    dependencies: Vec<DependencyInfo>,
+    /// The manifest opts out of publishing, e.g. `publish = false`.
+    private: bool,
+    /// Listed in `ignore` in `.changelog/config.toml`.
+    ignored: bool,
+    publishable: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+struct DependencyInfo {
+    name: String,
+    kind: &'static str,
+}
+
+pub fn run(graph: bool, json: bool, discover: &DiscoverOptions) -> Result<()> {
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
+    )?;
+
+    if graph {
+        print!("{}", DependencyGraph::from_workspace(&workspace).to_dot());
+        return Ok(());
+    }
+
+    // Listing packages is how to check discovery, so it works before `init` too.
+    let config = Config::load(&workspace.changelog_dir())?;
+    let mut packages: Vec<&Package> = workspace.packages.iter().collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    let packages = packages
+        .into_iter()
+        .map(|pkg| package_info(&workspace, &config, pkg))
+        .collect::<Result<Vec<_>>>()?;
+
+    if json {
+        let output = serde_json::json!({
+            "root": workspace.root.display().to_string(),
+            "ecosystem": workspace.ecosystem.to_string(),
+            "packages": packages,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} {} package(s) in {} ({})\n",
+        style("ℹ").blue().bold(),
+        packages.len(),
+        workspace.root.display(),
+        workspace.ecosystem
+    );
+    for pkg in &packages {
+        let flags: Vec<&str> = [(pkg.private, "private"), (pkg.ignored, "ignored")]
+            .into_iter()
+            .filter_map(|(set, label)| set.then_some(label))
+            .collect();
+        let flags = if flags.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", style(format!("({})", flags.join(", "))).yellow())
+        };
+
+        println!(
+            "  {} {} {}{}",
+            style("•").dim(),
+            style(&pkg.name).cyan(),
+            style(&pkg.version).dim(),
+            flags
+        );
+        println!("    {} {}", style("manifest:").dim(), pkg.manifest_path);
+        if !pkg.dependencies.is_empty() {
+            let dependencies: Vec<String> = pkg
+                .dependencies
+                .iter()
+                .map(|dep| match dep.kind {
+                    "normal" => dep.name.clone(),
+                    kind => format!("{} ({})", dep.name, kind),
+                })
+                .collect();
+            println!(
+                "    {} {}",
+                style("depends on:").dim(),
+                dependencies.join(", ")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn package_info(workspace: &Workspace, config: &Config, pkg: &Package) -> Result<PackageInfo> {
+    let mut dependencies: Vec<DependencyInfo> = pkg
+        .dependencies
+        .iter()
+        .filter(|dep| workspace.get_package(&dep.name).is_some())
+        .map(|dep| DependencyInfo {
+            name: dep.name.clone(),
+            kind: match dep.kind {
+                DependencyKind::Normal => "normal",
+                DependencyKind::Dev => "dev",
+                DependencyKind::Build => "build",
+            },
+        })
+        .collect();
+    dependencies.sort();
+    dependencies.dedup();
+
+    let private = workspace
+        .is_private(pkg)
+        .with_context(|| format!("failed to read {}", pkg.manifest_path.display()))?;
+    let ignored = config.ignore.contains(&pkg.name);
+
+    Ok(PackageInfo {
+        name: pkg.name.clone(),
+        version: pkg.version.to_string(),
+        path: relative(&workspace.root, &pkg.path),
+        manifest_path: relative(&workspace.root, &pkg.manifest_path),
+        ecosystem: workspace.ecosystem.to_string(),
+        dependencies,
+        private,
+        ignored,
+        publishable: !private && !ignored,
+    })
+}
+
+/// `path` relative to `root`, or `.` for the root itself.
+fn relative(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    if relative.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        relative.to_string_lossy().replace('\\', "/")
+    }
+}
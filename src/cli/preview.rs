@@ -0,0 +1,165 @@
+use anyhow::{Context, Result};
+use changelogs::BumpType;
+use changelogs::changelog_entry;
+use changelogs::config::Config;
+use changelogs::plan::{self, ReleasePlan};
+use changelogs::workspace::{DiscoverOptions, Workspace};
+use console::style;
+
+use super::summary;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum PreviewFormat {
+    #[default]
+    Text,
+    Json,
+    Markdown,
+}
+
+pub fn run(path: PathBuf, format: PreviewFormat, discover: &DiscoverOptions) -> Result<()> {
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
+    )?;
+
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let entry = changelog_entry::parse(&entry_id(&path), &content)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+
+    let config = Config::load(&workspace.changelog_dir())?;
+    let release_plan = plan::preview_entry(&workspace, &config, &entry);
+
+    match format {
+        PreviewFormat::Text => print_text(&release_plan),
+        PreviewFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&summary::plan_json(&release_plan))?
+        ),
+        PreviewFormat::Markdown => print!("{}", render_markdown(&release_plan)),
+    }
+
+    Ok(())
+}
+
+/// The entry id is the file stem, as it would be inside `.changelog/`.
+fn entry_id(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "preview".to_string())
+}
+
+fn print_text(release_plan: &ReleasePlan) {
+    if release_plan.releases.is_empty() {
+        println!("{} No packages will be released", style("ℹ").blue().bold());
+    } else {
+        println!("{}", style("Releases:").bold().underlined());
+        for release in &release_plan.releases {
+            let bump_style = match release.bump {
+                BumpType::Major => style(release.bump.to_string()).red().bold(),
+                BumpType::Minor => style(release.bump.to_string()).yellow(),
+                BumpType::Patch => style(release.bump.to_string()).dim(),
+            };
+
+            println!(
+                "  {} {} {} → {} ({})",
+                style("•").dim(),
+                style(&release.name).cyan(),
+                style(&release.old_version.to_string()).dim(),
+                style(&release.new_version.to_string()).green(),
+                bump_style
+            );
+        }
+    }
+
+    if !release_plan.warnings.is_empty() {
+        println!();
+        for warning in &release_plan.warnings {
+            println!(
+                "  {} {}",
+                style("!").yellow().bold(),
+                style(warning).yellow()
+            );
+        }
+    }
+}
+
+fn render_markdown(release_plan: &ReleasePlan) -> String {
+    let mut out = String::new();
+
+    if release_plan.releases.is_empty() {
+        out.push_str("This changelog does not release any packages.\n");
+    } else {
+        out.push_str(&summary::releases_table(&release_plan.releases));
+    }
+
+    if !release_plan.warnings.is_empty() {
+        out.push('\n');
+        for warning in &release_plan.warnings {
+            out.push_str(&format!("> [!WARNING]\n> {}\n", warning));
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use changelogs::Ecosystem;
+    use changelogs::ecosystems::Package;
+    use semver::Version;
+    use tempfile::TempDir;
+
+    fn preview(content: &str) -> ReleasePlan {
+        let workspace = Workspace::new(
+            PathBuf::from("/tmp/test"),
+            vec![Package {
+                name: "foo".to_string(),
+                version: Version::new(1, 2, 3),
+                path: PathBuf::from("foo"),
+                manifest_path: PathBuf::from("foo/Cargo.toml"),
+                dependencies: vec![],
+            }],
+            Ecosystem::Rust,
+        );
+
+        // The entry lives outside of any `.changelog` directory.
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("pr-42.md");
+        std::fs::write(&path, content).unwrap();
+
+        let entry =
+            changelog_entry::parse(&entry_id(&path), &std::fs::read_to_string(&path).unwrap())
+                .unwrap();
+        assert_eq!(entry.id, "pr-42");
+        plan::preview_entry(&workspace, &Config::default(), &entry)
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let plan = preview("---\nfoo: minor\nbar: patch\n---\n\nAdded x\n");
+
+        assert_eq!(
+            render_markdown(&plan),
+            "| Package | Current | Next | Bump |\n\
+             | --- | --- | --- | --- |\n\
+             | `foo` | 1.2.3 | 1.3.0 | minor |\n\
+             \n\
+             > [!WARNING]\n\
+             > changelog references unknown package 'bar'\n"
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        let plan = preview("---\nfoo: patch\n---\n\nFixed y\n");
+        let value = summary::plan_json(&plan);
+
+        assert_eq!(value["releases"][0]["name"], "foo");
+        assert_eq!(value["releases"][0]["new_version"], "1.2.4");
+        assert_eq!(value["releases"][0]["reasons"][0]["kind"], "changelog");
+        assert_eq!(value["warnings"].as_array().unwrap().len(), 0);
+        assert_eq!(value["summary"]["patch"], 1);
+    }
+}
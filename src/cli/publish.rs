@@ -1,21 +1,73 @@
 use anyhow::Result;
-use changelogs::config::ChangelogFormat;
-use changelogs::{Config, Ecosystem, Package, PublishResult, SkipReason, Workspace};
-use std::process::Command;
+use changelogs::config::HooksConfig;
+use changelogs::ecosystems::shell_command;
+use changelogs::git::{self, Git};
+use changelogs::graph::DependencyGraph;
+use changelogs::workspace::DiscoverOptions;
+use changelogs::{Config, Package, PublishOptions, PublishResult, SkipReason, Workspace};
+use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+/// Registry lookups in flight at once while looking for unpublished packages.
+const REGISTRY_CHECK_JOBS: usize = 8;
+
+/// How release tags are created.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TagOptions {
+    /// Sign tags (`git tag -s`), on top of `[git] sign_tags` and `tag.gpgSign`.
+    pub sign: bool,
+    /// Move tags that already point at another commit to HEAD.
+    pub retag: bool,
+}
+
+/// Checks on the packages and the working tree before anything is published.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CheckOptions {
+    /// Build each crate before uploading it, on top of `[rust] verify`.
+    pub verify: bool,
+    /// Publish and tag even when tracked files have uncommitted changes, on top of
+    /// `[rust] allow_dirty`.
+    pub allow_dirty: bool,
+}
+
+/// Which packages are considered for publishing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SelectOptions {
+    /// Treat every candidate as unpublished instead of asking the registry.
+    pub skip_published_check: bool,
+    /// Only consider packages whose release tag doesn't exist yet, i.e. the ones the
+    /// last `version` run released.
+    pub changed_only: bool,
+}
 
 pub fn run_with_ecosystem(
     dry_run: bool,
-    tag: Option<String>,
-    ecosystem: Option<Ecosystem>,
+    registry: Option<String>,
+    tag_options: TagOptions,
+    checks: CheckOptions,
+    jobs: usize,
+    select: SelectOptions,
+    discover: &DiscoverOptions,
 ) -> Result<()> {
-    let workspace = Workspace::load_with_ecosystem(ecosystem)?;
-    let config = Config::load(&workspace.changelog_dir)?;
+    let workspace = Workspace::discover_with(discover)?;
+    let config = Config::load(&workspace.changelog_dir())?;
+    let options = PublishOptions {
+        registry,
+        verify: checks.verify || config.rust.verify,
+        allow_dirty: checks.allow_dirty || config.rust.allow_dirty,
+    };
+    let git = git::open();
 
-    let all_publishable = workspace.get_publishable_packages()?;
-    let packages: Vec<&Package> = all_publishable
-        .into_iter()
-        .filter(|pkg| !config.ignore.contains(&pkg.name))
-        .collect();
+    // A dry run neither uploads nor tags anything.
+    if !dry_run {
+        ensure_clean(git.as_ref(), &workspace, &options)?;
+    }
+
+    let packages = unpublished(&workspace, &config, git.as_ref(), select, |pkg| {
+        workspace.is_published(pkg)
+    })?;
 
     if packages.is_empty() {
         println!("No unpublished packages found");
@@ -28,50 +80,81 @@ pub fn run_with_ecosystem(
     let mut skipped: Vec<&Package> = Vec::new();
     let mut failed: Vec<&Package> = Vec::new();
 
-    for pkg in packages {
-        print!("  {} v{} ... ", pkg.name, pkg.version);
+    let graph = DependencyGraph::for_publish(&workspace);
+    let names: Vec<String> = packages.iter().map(|p| p.name.clone()).collect();
+
+    for wave in graph.publish_waves(&names) {
+        let wave: Vec<&Package> = wave
+            .iter()
+            .filter_map(|name| packages.iter().find(|p| p.name == *name).copied())
+            .collect();
 
-        match workspace.publish_package(pkg, dry_run, tag.as_deref()) {
-            Ok(PublishResult::Success) => {
-                if dry_run {
-                    println!("(dry-run)");
-                } else {
-                    println!("✓");
+        publish_wave(
+            &wave,
+            jobs,
+            |pkg| {
+                publish_with_hooks(pkg, &config.hooks, dry_run, || {
+                    workspace.publish_package(pkg, dry_run, &options)
+                })
+            },
+            |pkg, (result, post_hook_error)| {
+                // Each package reports in one piece so concurrent publishes don't
+                // interleave; errors and warnings go to stderr right after.
+                let mut out = format!("  {} v{} ... ", pkg.name, pkg.version);
+                let mut problems = Vec::new();
+                match result {
+                    Ok(PublishResult::Success) => {
+                        out.push_str(if dry_run { "(dry-run)" } else { "✓" });
+                        published.push(pkg);
+                    }
+                    Ok(PublishResult::Skipped(reason)) => {
+                        out.push_str(match reason {
+                            SkipReason::NoToken => "⊘ (no token)",
+                            SkipReason::NotPublishable => "⊘ (publish = false)",
+                        });
+                        skipped.push(pkg);
+                    }
+                    Ok(PublishResult::Failed) => {
+                        out.push('✗');
+                        failed.push(pkg);
+                    }
+                    Err(e) => {
+                        out.push('✗');
+                        problems.push(format!("    {}: {:#}", pkg.name, e));
+                        failed.push(pkg);
+                    }
                 }
-                published.push(pkg);
-            }
-            Ok(PublishResult::Skipped(reason)) => {
-                match reason {
-                    SkipReason::NoToken => println!("⊘ (no token)"),
-                    SkipReason::NotPublishable => println!("⊘ (publish = false)"),
+                if let Some(e) = post_hook_error {
+                    problems.push(format!("    {}: warning: {}", pkg.name, e));
                 }
-                skipped.push(pkg);
-            }
-            Ok(PublishResult::Failed) => {
-                println!("✗");
-                failed.push(pkg);
-            }
-            Err(e) => {
-                println!("✗");
-                eprintln!("    {}", e);
-                failed.push(pkg);
-            }
-        }
+                println!("{}", out);
+                if !problems.is_empty() {
+                    eprintln!("{}", problems.join("\n"));
+                }
+            },
+        );
     }
 
     println!();
 
+    let mut conflicted_tags = 0;
     if !dry_run {
         let taggable: Vec<&Package> = published.iter().chain(skipped.iter()).copied().collect();
-        if !taggable.is_empty() {
-            if config.changelog.format == ChangelogFormat::Root {
-                // Root format = single product: create one `v{version}` tag
-                if let Some(pkg) = taggable.first() {
-                    create_unified_tag(&pkg.version)?;
-                }
-            } else {
-                create_git_tags(&workspace, &taggable)?;
+        let tags = workspace.release_tags(&taggable, &config);
+        if !tags.is_empty() {
+            let sign = tag_options.sign
+                || config
+                    .git
+                    .sign_tags
+                    .unwrap_or_else(|| git.config_bool("tag.gpgSign").unwrap_or(false));
+            let report = create_tags(git.as_ref(), &tags, sign, tag_options.retag);
+
+            println!("\n{}", report.summary());
+            println!("\nDon't forget to push tags: git push --follow-tags");
+            if !report.failed.is_empty() {
+                eprintln!("\n{}", failed_tags_summary(&report.failed, sign));
             }
+            conflicted_tags = report.conflicted.len();
         }
     }
 
@@ -79,6 +162,14 @@ pub fn run_with_ecosystem(
         anyhow::bail!("{} package(s) failed to publish", failed.len());
     }
 
+    if conflicted_tags > 0 {
+        anyhow::bail!(
+            "{} tag(s) already point at a different commit; check the earlier release, \
+             then re-run with --retag to move them",
+            conflicted_tags
+        );
+    }
+
     if dry_run {
         println!(
             "Dry run complete. {} package(s) would be published.",
@@ -96,42 +187,660 @@ pub fn run_with_ecosystem(
     Ok(())
 }
 
-fn create_unified_tag(version: &semver::Version) -> Result<()> {
-    let tag = format!("v{}", version);
+/// Publishes `pkg` between the configured hooks. A failing `pre_publish` hook
+/// fails the package without publishing it; `post_publish` only runs after a
+/// successful publish and its failure is returned separately, as the package is
+/// already out. Hooks don't run on a dry run.
+fn publish_with_hooks(
+    pkg: &Package,
+    hooks: &HooksConfig,
+    dry_run: bool,
+    publish: impl FnOnce() -> changelogs::error::Result<PublishResult>,
+) -> (Result<PublishResult>, Option<anyhow::Error>) {
+    if dry_run {
+        return (publish().map_err(Into::into), None);
+    }
+
+    if let Some(command) = &hooks.pre_publish {
+        if let Err(e) = run_hook("pre_publish", command, pkg) {
+            return (Err(e), None);
+        }
+    }
 
-    let output = Command::new("git")
-        .args(["tag", "-a", &tag, "-m", &format!("Release {}", tag)])
+    let result = publish().map_err(anyhow::Error::from);
+
+    let post_hook_error = match (&result, &hooks.post_publish) {
+        (Ok(PublishResult::Success), Some(command)) => run_hook("post_publish", command, pkg).err(),
+        _ => None,
+    };
+
+    (result, post_hook_error)
+}
+
+/// Runs a hook through the shell in the package directory, failing with its output
+/// when it exits non-zero.
+fn run_hook(name: &str, command: &str, pkg: &Package) -> Result<()> {
+    let output = shell_command(command)
+        .current_dir(&pkg.path)
+        .env("CHANGELOGS_PACKAGE_NAME", &pkg.name)
+        .env("CHANGELOGS_PACKAGE_VERSION", pkg.version.to_string())
         .output()
-        .map_err(|e| anyhow::anyhow!("failed to run 'git tag': {}", e))?;
+        .map_err(|e| anyhow::anyhow!("failed to run {} hook `{}`: {}", name, command, e))?;
 
-    if output.status.success() {
-        println!("Created git tag: {}", tag);
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        eprintln!("Failed to create git tag {}: {}", tag, stderr.trim());
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} hook `{}` failed ({}):\nstdout: {}\nstderr: {}",
+            name,
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stdout).trim(),
+            String::from_utf8_lossy(&output.stderr).trim(),
+        );
     }
-
-    println!("\nDon't forget to push tags: git push --follow-tags");
     Ok(())
 }
 
-fn create_git_tags(workspace: &Workspace, packages: &[&Package]) -> Result<()> {
-    for pkg in packages {
-        let tag = workspace.tag_name(pkg);
+/// Fails when tracked files have uncommitted changes: the release tags would point
+/// at a commit that doesn't match what was published. Untracked files, such as
+/// build output, are fine. Skipped when `allow_dirty` is set, from the CLI or from
+/// `[rust] allow_dirty`, since cargo is then told to accept the same changes.
+fn ensure_clean(
+    git: &dyn Git,
+    workspace: &Workspace,
+    options: &PublishOptions,
+) -> changelogs::error::Result<()> {
+    if options.allow_dirty {
+        return Ok(());
+    }
+    let dirty = git.dirty_paths()?;
+    if dirty.is_empty() {
+        return Ok(());
+    }
+    let list: Vec<String> = dirty
+        .iter()
+        .map(|p| {
+            format!(
+                "  {}",
+                p.strip_prefix(&workspace.root).unwrap_or(p).display()
+            )
+        })
+        .collect();
+    Err(changelogs::error::Error::DirtyWorkingTree(list.join("\n")))
+}
 
-        let output = Command::new("git")
-            .args(["tag", "-a", &tag, "-m", &format!("Release {}", tag)])
-            .output()
-            .map_err(|e| anyhow::anyhow!("failed to run 'git tag': {}", e))?;
+/// The packages to publish, in workspace order: those not ignored whose version
+/// `is_published` says is not on the registry yet. With `changed_only`, packages
+/// whose release tag already exists are taken as published without a lookup.
+fn unpublished<'a>(
+    workspace: &'a Workspace,
+    config: &Config,
+    git: &dyn Git,
+    select: SelectOptions,
+    is_published: impl Fn(&Package) -> changelogs::error::Result<bool> + Sync,
+) -> Result<Vec<&'a Package>> {
+    let candidates: Vec<&Package> = workspace
+        .packages
+        .iter()
+        .filter(|pkg| !config.ignore.contains(&pkg.name))
+        .filter(|pkg| {
+            !select.changed_only
+                || !workspace
+                    .release_tags(&[pkg], config)
+                    .iter()
+                    .all(|tag| git.tag_exists(tag))
+        })
+        .collect();
+    if select.skip_published_check || candidates.is_empty() {
+        return Ok(candidates);
+    }
+
+    let progress = std::io::stderr().is_terminal();
+    let mut checked = 0;
+    let mut published = HashSet::new();
+    let mut error = None;
+    publish_wave(
+        &candidates,
+        REGISTRY_CHECK_JOBS,
+        &is_published,
+        |pkg, result| {
+            checked += 1;
+            if progress {
+                eprint!(
+                    "\rChecking the registry... {}/{}",
+                    checked,
+                    candidates.len()
+                );
+            }
+            match result {
+                Ok(true) => {
+                    published.insert(pkg.name.as_str());
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        },
+    );
+    if progress {
+        eprint!("\r\x1b[2K");
+    }
+    if let Some(e) = error {
+        return Err(e.into());
+    }
+
+    Ok(candidates
+        .into_iter()
+        .filter(|pkg| !published.contains(pkg.name.as_str()))
+        .collect())
+}
+
+/// Runs `publish` on one wave with up to `jobs` packages in flight. `report` runs
+/// on the calling thread as each package finishes.
+fn publish_wave<'a, T: Send>(
+    wave: &[&'a Package],
+    jobs: usize,
+    publish: impl Fn(&Package) -> T + Sync,
+    mut report: impl FnMut(&'a Package, T),
+) {
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, wave.len().max(1)) {
+            let tx = tx.clone();
+            let (next, publish) = (&next, &publish);
+            scope.spawn(move || {
+                while let Some(&pkg) = wave.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    if tx.send((pkg, publish(pkg))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
 
-        if output.status.success() {
-            println!("Created git tag: {}", tag);
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            eprintln!("Failed to create git tag {}: {}", tag, stderr.trim());
+        for (pkg, result) in rx {
+            report(pkg, result);
         }
+    });
+}
+
+/// What happened to each release tag.
+#[derive(Debug, Default)]
+struct TagReport {
+    /// Tags created, or moved to HEAD with `--retag`.
+    created: Vec<String>,
+    /// Tags that already pointed at HEAD, e.g. from an earlier, interrupted run.
+    existing: Vec<String>,
+    /// Tags pointing at another commit: `(tag, tag commit, HEAD)`.
+    conflicted: Vec<(String, String, String)>,
+    /// Tags git failed to create, with its error.
+    failed: Vec<(String, String)>,
+}
+
+impl TagReport {
+    fn summary(&self) -> String {
+        let mut out = format!(
+            "Tags: {} created, {} already existed, {} conflicted",
+            self.created.len(),
+            self.existing.len(),
+            self.conflicted.len()
+        );
+        for (label, tags) in [("created", &self.created), ("existing", &self.existing)] {
+            if !tags.is_empty() {
+                out.push_str(&format!("\n  {}: {}", label, tags.join(", ")));
+            }
+        }
+        if !self.conflicted.is_empty() {
+            let conflicted: Vec<&str> = self.conflicted.iter().map(|(t, ..)| t.as_str()).collect();
+            out.push_str(&format!("\n  conflicted: {}", conflicted.join(", ")));
+        }
+        out
     }
+}
 
-    println!("\nDon't forget to push tags: git push --follow-tags");
-    Ok(())
+/// Creates every tag, continuing past failures so that one bad tag (e.g. gpg-agent
+/// locked) doesn't hide the rest.
+///
+/// A tag that already exists at HEAD is left alone. One pointing at another commit
+/// is a conflict, usually from a botched earlier release; `retag` deletes and
+/// recreates it at HEAD instead.
+fn create_tags(git: &dyn Git, tags: &[String], sign: bool, retag: bool) -> TagReport {
+    let head = git.rev_parse("HEAD").unwrap_or_default();
+    let mut report = TagReport::default();
+
+    for tag in tags {
+        let message = format!("Release {}", tag);
+        let mut moved = false;
+
+        if git.tag_exists(tag) {
+            let target = git
+                .rev_parse(&format!("refs/tags/{}", tag))
+                .unwrap_or_default();
+            if target == head {
+                println!("Git tag {} exists (ok)", tag);
+                report.existing.push(tag.clone());
+                continue;
+            }
+            if !retag {
+                eprintln!(
+                    "Git tag {} points at {}, not HEAD ({}); pass --retag to move it",
+                    tag, target, head
+                );
+                report.conflicted.push((tag.clone(), target, head.clone()));
+                continue;
+            }
+            if let Err(e) = git.delete_tag(tag) {
+                eprintln!("Failed to delete git tag {}: {}", tag, e);
+                report.failed.push((tag.clone(), e.to_string()));
+                continue;
+            }
+            moved = true;
+        }
+
+        match git.create_tag(tag, &message, sign) {
+            Ok(()) if moved => {
+                println!(
+                    "Moved git tag: {} (push it with: git push --force origin {})",
+                    tag, tag
+                );
+                report.created.push(tag.clone());
+            }
+            Ok(()) => {
+                println!("Created git tag: {}", tag);
+                report.created.push(tag.clone());
+            }
+            Err(e) => {
+                eprintln!("Failed to create git tag {}: {}", tag, e);
+                report.failed.push((tag.clone(), e.to_string()));
+            }
+        }
+    }
+
+    report
+}
+
+/// Lists failed tags with the command to retry each one by hand.
+fn failed_tags_summary(failed: &[(String, String)], sign: bool) -> String {
+    let mut out = format!(
+        "{} tag(s) could not be created (packages were published). Retry manually:\n",
+        failed.len()
+    );
+    for (tag, _) in failed {
+        let message = format!("\"Release {}\"", tag);
+        let [_, flag, ..] = git::tag_args(tag, &message, sign);
+        out.push_str(&format!("  git tag {} {} -m {}\n", flag, tag, message));
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::fake_git::FakeGit;
+    use changelogs::Ecosystem;
+
+    #[test]
+    fn test_ensure_clean() {
+        let workspace = workspace(&["a"]);
+        let options = PublishOptions::default();
+        assert!(ensure_clean(&FakeGit::default(), &workspace, &options).is_ok());
+
+        let git = FakeGit {
+            dirty: vec!["./a/Cargo.toml", "/elsewhere/notes.md"],
+            ..FakeGit::default()
+        };
+        let err = ensure_clean(&git, &workspace, &options).unwrap_err();
+        assert!(matches!(
+            err,
+            changelogs::error::Error::DirtyWorkingTree(ref list)
+                if list == "  a/Cargo.toml\n  /elsewhere/notes.md"
+        ));
+
+        // `[rust] allow_dirty` lets cargo publish the changes, so the check is skipped.
+        let options = PublishOptions {
+            allow_dirty: true,
+            ..PublishOptions::default()
+        };
+        assert!(ensure_clean(&git, &workspace, &options).is_ok());
+    }
+
+    #[test]
+    fn test_ensure_clean_fails_when_git_cant_be_queried() {
+        let git = FakeGit {
+            unreadable: true,
+            ..FakeGit::default()
+        };
+        let err = ensure_clean(&git, &workspace(&["a"]), &PublishOptions::default()).unwrap_err();
+        assert!(matches!(err, changelogs::error::Error::Git(_)));
+    }
+
+    fn package(name: &str) -> Package {
+        Package {
+            name: name.to_string(),
+            version: semver::Version::new(1, 0, 0),
+            path: name.into(),
+            manifest_path: format!("{}/Cargo.toml", name).into(),
+            dependencies: vec![],
+        }
+    }
+
+    fn workspace(names: &[&str]) -> Workspace {
+        Workspace::new(
+            ".".into(),
+            names.iter().map(|name| package(name)).collect(),
+            Ecosystem::Rust,
+        )
+    }
+
+    #[test]
+    fn test_unpublished_asks_the_registry_about_every_package() {
+        let workspace = workspace(&["a", "b", "c", "ignored"]);
+        let config = Config {
+            ignore: vec!["ignored".into()],
+            ..Config::default()
+        };
+        let lookups = AtomicUsize::new(0);
+
+        let packages = unpublished(
+            &workspace,
+            &config,
+            &FakeGit::default(),
+            SelectOptions::default(),
+            |pkg| {
+                lookups.fetch_add(1, Ordering::SeqCst);
+                Ok(pkg.name == "b")
+            },
+        )
+        .unwrap();
+
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["a", "c"]);
+        assert_eq!(lookups.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_unpublished_changed_only_skips_tagged_packages() {
+        let workspace = workspace(&["a", "b", "c", "d"]);
+        let git = FakeGit {
+            tags: vec!["a@1.0.0", "c@1.0.0"],
+            ..FakeGit::default()
+        };
+        let lookups = AtomicUsize::new(0);
+        let select = SelectOptions {
+            changed_only: true,
+            ..SelectOptions::default()
+        };
+
+        let packages = unpublished(&workspace, &Config::default(), &git, select, |pkg| {
+            lookups.fetch_add(1, Ordering::SeqCst);
+            Ok(pkg.name == "d")
+        })
+        .unwrap();
+
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["b"]);
+        assert_eq!(lookups.load(Ordering::SeqCst), 2);
+
+        // Without the registry, every untagged package is published.
+        let select = SelectOptions {
+            skip_published_check: true,
+            changed_only: true,
+        };
+        let packages = unpublished(&workspace, &Config::default(), &git, select, |_| {
+            unreachable!("the registry is skipped")
+        })
+        .unwrap();
+        assert_eq!(packages.len(), 2);
+    }
+
+    #[test]
+    fn test_unpublished_reports_registry_errors() {
+        let workspace = workspace(&["a", "b"]);
+
+        let err = unpublished(
+            &workspace,
+            &Config::default(),
+            &FakeGit::default(),
+            SelectOptions::default(),
+            |pkg| match pkg.name.as_str() {
+                "b" => Err(changelogs::error::Error::CratesIoCheckFailed(
+                    "timed out".into(),
+                )),
+                _ => Ok(false),
+            },
+        )
+        .unwrap_err();
+
+        assert!(format!("{err:#}").contains("timed out"), "{err:#}");
+    }
+
+    #[test]
+    fn test_publish_wave_bounds_concurrency() {
+        let packages: Vec<Package> = ["a", "b", "c", "d", "e"].map(package).into();
+        let wave: Vec<&Package> = packages.iter().collect();
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+
+        let mut reported = Vec::new();
+        publish_wave(
+            &wave,
+            2,
+            |pkg| {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                pkg.name.to_uppercase()
+            },
+            |pkg, result| reported.push((pkg.name.clone(), result)),
+        );
+
+        reported.sort();
+        assert_eq!(
+            reported,
+            ["a", "b", "c", "d", "e"].map(|n| (n.to_string(), n.to_uppercase()))
+        );
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_publish_hooks_run_in_package_dir_with_env() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pkg = Package {
+            path: dir.path().to_path_buf(),
+            ..package("core")
+        };
+        let hooks = HooksConfig {
+            pre_publish: Some(
+                r#"printf '%s@%s' "$CHANGELOGS_PACKAGE_NAME" "$CHANGELOGS_PACKAGE_VERSION" > pre"#
+                    .into(),
+            ),
+            post_publish: Some("cp pre post".into()),
+        };
+
+        let (result, post_hook_error) = publish_with_hooks(&pkg, &hooks, false, || {
+            assert!(dir.path().join("pre").exists());
+            assert!(!dir.path().join("post").exists());
+            Ok(PublishResult::Success)
+        });
+
+        assert!(matches!(result, Ok(PublishResult::Success)));
+        assert!(post_hook_error.is_none());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("post")).unwrap(),
+            "core@1.0.0"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failing_pre_publish_hook_skips_publish() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let pkg = Package {
+            path: dir.path().to_path_buf(),
+            ..package("core")
+        };
+        let hooks = HooksConfig {
+            pre_publish: Some("echo 'wasm-pack not found' >&2; exit 3".into()),
+            post_publish: Some("touch post".into()),
+        };
+
+        let (result, post_hook_error) =
+            publish_with_hooks(&pkg, &hooks, false, || panic!("published"));
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("pre_publish hook"), "{err}");
+        assert!(err.contains("wasm-pack not found"), "{err}");
+        assert!(post_hook_error.is_none());
+        assert!(!dir.path().join("post").exists());
+
+        let (result, _) = publish_with_hooks(&pkg, &hooks, true, || Ok(PublishResult::Success));
+        assert!(matches!(result, Ok(PublishResult::Success)));
+        assert!(!dir.path().join("post").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failing_post_publish_hook_is_a_warning() {
+        let pkg = Package {
+            path: std::env::temp_dir(),
+            ..package("core")
+        };
+        let hooks = HooksConfig {
+            pre_publish: None,
+            post_publish: Some("exit 1".into()),
+        };
+
+        let (result, post_hook_error) =
+            publish_with_hooks(&pkg, &hooks, false, || Ok(PublishResult::Success));
+
+        assert!(matches!(result, Ok(PublishResult::Success)));
+        assert!(
+            post_hook_error
+                .unwrap()
+                .to_string()
+                .contains("post_publish hook `exit 1` failed")
+        );
+    }
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_create_tags_signed() {
+        let git = FakeGit::default();
+
+        let report = create_tags(&git, &tags(&["a@1.0.0", "b@2.0.0"]), true, false);
+
+        assert!(report.failed.is_empty());
+        assert_eq!(report.created, tags(&["a@1.0.0", "b@2.0.0"]));
+        assert_eq!(
+            *git.created.borrow(),
+            vec![
+                ("a@1.0.0".into(), "Release a@1.0.0".into(), true),
+                ("b@2.0.0".into(), "Release b@2.0.0".into(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_tags_continues_after_signing_failure() {
+        let git = FakeGit {
+            fail_signing: vec!["a@1.0.0"],
+            ..Default::default()
+        };
+
+        let failed = create_tags(&git, &tags(&["a@1.0.0", "b@2.0.0"]), true, false).failed;
+
+        assert_eq!(git.created.borrow().len(), 1);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "a@1.0.0");
+        assert!(failed[0].1.contains("gpg failed to sign the data"));
+
+        assert_eq!(
+            failed_tags_summary(&failed, true),
+            "1 tag(s) could not be created (packages were published). Retry manually:\n  \
+             git tag -s a@1.0.0 -m \"Release a@1.0.0\""
+        );
+    }
+
+    fn git_cmd(dir: &std::path::Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// A repository with two commits: `a@1.0.0` at HEAD and `b@2.0.0` at its parent.
+    fn tagged_repo() -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().unwrap();
+        git_cmd(dir.path(), &["init", "-q"]);
+        git_cmd(dir.path(), &["config", "user.name", "Jane Doe"]);
+        git_cmd(dir.path(), &["config", "user.email", "jane@example.com"]);
+        git_cmd(dir.path(), &["config", "tag.gpgSign", "false"]);
+        git_cmd(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "first"],
+        );
+        git_cmd(dir.path(), &["tag", "-a", "b@2.0.0", "-m", "botched"]);
+        git_cmd(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "second"],
+        );
+        git_cmd(
+            dir.path(),
+            &["tag", "-a", "a@1.0.0", "-m", "Release a@1.0.0"],
+        );
+        dir
+    }
+
+    #[test]
+    fn test_create_tags_existing_and_conflicted() {
+        let dir = tagged_repo();
+        let git = git::CliGit::in_dir(dir.path());
+        let head = git.rev_parse("HEAD").unwrap();
+        let parent = git.rev_parse("HEAD~1").unwrap();
+
+        let report = create_tags(
+            &git,
+            &tags(&["a@1.0.0", "b@2.0.0", "c@3.0.0"]),
+            false,
+            false,
+        );
+
+        assert_eq!(report.created, tags(&["c@3.0.0"]));
+        assert_eq!(report.existing, tags(&["a@1.0.0"]));
+        assert_eq!(
+            report.conflicted,
+            vec![("b@2.0.0".to_string(), parent.clone(), head.clone())]
+        );
+        assert!(report.failed.is_empty());
+        assert_eq!(git.rev_parse("c@3.0.0"), Some(head));
+        assert_eq!(git.rev_parse("b@2.0.0"), Some(parent));
+        assert_eq!(
+            report.summary(),
+            "Tags: 1 created, 1 already existed, 1 conflicted\n  \
+             created: c@3.0.0\n  existing: a@1.0.0\n  conflicted: b@2.0.0"
+        );
+    }
+
+    #[test]
+    fn test_create_tags_retag_moves_conflicted() {
+        let dir = tagged_repo();
+        let git = git::CliGit::in_dir(dir.path());
+
+        let report = create_tags(&git, &tags(&["a@1.0.0", "b@2.0.0"]), false, true);
+
+        assert_eq!(report.created, tags(&["b@2.0.0"]));
+        assert_eq!(report.existing, tags(&["a@1.0.0"]));
+        assert!(report.conflicted.is_empty());
+        assert_eq!(git.rev_parse("b@2.0.0"), git.rev_parse("HEAD"));
+    }
 }
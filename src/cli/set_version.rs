@@ -0,0 +1,39 @@
+use anyhow::{Context, Result};
+use changelogs::error::Error;
+use changelogs::workspace::{DiscoverOptions, Workspace};
+use console::style;
+use semver::Version;
+
+pub fn run(version: String, packages: Vec<String>, discover: &DiscoverOptions) -> Result<()> {
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
+    )?;
+
+    let version =
+        Version::parse(&version).map_err(|e| Error::VersionParse(format!("{}: {}", version, e)))?;
+
+    workspace.set_versions(&packages, &version)?;
+
+    for name in &packages {
+        let old_version = workspace
+            .get_package(name)
+            .map(|p| p.version.to_string())
+            .unwrap_or_default();
+        println!(
+            "  {} {} {} → {}",
+            style("✓").green(),
+            style(name).cyan(),
+            style(old_version).dim(),
+            style(version.to_string()).green()
+        );
+    }
+
+    println!(
+        "\n{} Set {} package(s) to {}",
+        style("✓").green().bold(),
+        packages.len(),
+        version
+    );
+
+    Ok(())
+}
@@ -1,32 +1,114 @@
 use anyhow::{Context, Result};
+use changelogs::BumpType;
 use changelogs::changelog_entry;
 use changelogs::config::Config;
 use changelogs::error::Error;
-use changelogs::plan;
-use changelogs::workspace::Workspace;
-use changelogs::{BumpType, Ecosystem};
+use changelogs::git;
+use changelogs::plan::{self, ReleasePlan};
+use changelogs::workspace::{DiscoverOptions, Workspace};
 use console::style;
 
-pub fn run(verbose: bool, ecosystem: Option<Ecosystem>) -> Result<()> {
-    let workspace = Workspace::discover_with_ecosystem(ecosystem).context(
-        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python>",
+use super::summary;
+
+pub fn run(
+    verbose: bool,
+    channel: Option<String>,
+    json: bool,
+    discover: &DiscoverOptions,
+) -> Result<()> {
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
     )?;
 
     if !workspace.is_initialized() {
         return Err(Error::NotInitialized.into());
     }
 
-    let changelog_dir = workspace.changelog_dir();
-    let changelogs = changelog_entry::read_all(&changelog_dir)?;
+    let config = Config::load(&workspace.changelog_dir())?;
+    let filtered = !discover.filter.is_empty();
+
+    // JSON covers a single channel: the one passed with --channel, or the default.
+    if json {
+        let channel = channel.as_deref();
+        let config = config.for_channel(channel)?;
+        let mut changelogs = changelog_entry::read_all(&workspace.channel_dir(channel))?;
+        if filtered {
+            changelogs = plan::retain_workspace_releases(&workspace, changelogs).0;
+        }
+        let release_plan = plan::assemble(&workspace, changelogs, &config);
+        summary::append_step_summary(channel, &release_plan)?;
+
+        let mut output = summary::plan_json(&release_plan);
+        output["channel"] = channel.into();
+        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        output["messages"] = serde_json::to_value(summary::ReleaseMessages::new(
+            &config,
+            &release_plan.releases,
+            &date,
+        ))?;
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    // Without --channel, every configured channel gets its own section.
+    let channels: Vec<Option<&str>> = match channel.as_deref() {
+        Some(name) => vec![Some(name)],
+        None => std::iter::once(None)
+            .chain(config.channels.iter().map(|c| Some(c.as_str())))
+            .collect(),
+    };
+
+    for (i, channel) in channels.iter().enumerate() {
+        if channels.len() > 1 {
+            if i > 0 {
+                println!();
+            }
+            println!(
+                "{} {}",
+                style("Channel:").bold(),
+                style(channel.unwrap_or("default")).cyan().bold()
+            );
+        }
+        print_channel(
+            &workspace,
+            &config.for_channel(*channel)?,
+            *channel,
+            verbose,
+            filtered,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn print_channel(
+    workspace: &Workspace,
+    config: &Config,
+    channel: Option<&str>,
+    verbose: bool,
+    filtered: bool,
+) -> Result<()> {
+    let changelog_dir = workspace.channel_dir(channel);
+    let mut changelogs = changelog_entry::read_all(&changelog_dir)?;
+    let mut partial = Vec::new();
+    if filtered {
+        (changelogs, partial) = plan::retain_workspace_releases(workspace, changelogs);
+    }
+    changelog_entry::sort(
+        &mut changelogs,
+        config.changelog.sort,
+        &changelog_dir,
+        git::open().as_ref(),
+    );
+
+    let release_plan = plan::assemble(workspace, changelogs.clone(), config);
+    summary::append_step_summary(channel, &release_plan)?;
 
     if changelogs.is_empty() {
         println!("{} No changelogs found", style("ℹ").blue().bold());
         return Ok(());
     }
 
-    let config = Config::load(&changelog_dir)?;
-    let release_plan = plan::assemble(&workspace, changelogs.clone(), &config);
-
     println!(
         "{} {} changelog(s) found\n",
         style("ℹ").blue().bold(),
@@ -54,6 +136,9 @@ pub fn run(verbose: bool, ecosystem: Option<Ecosystem>) -> Result<()> {
 
     if release_plan.releases.is_empty() {
         println!("{} No packages will be released", style("ℹ").blue().bold());
+        if verbose {
+            print_inactive_groups(config, &release_plan);
+        }
         return Ok(());
     }
 
@@ -65,16 +150,32 @@ pub fn run(verbose: bool, ecosystem: Option<Ecosystem>) -> Result<()> {
             BumpType::Patch => style(release.bump.to_string()).dim(),
         };
 
+        let skipped = if config.changelog.is_skipped(&release.name) {
+            format!(" {}", style("(changelog skipped)").dim())
+        } else {
+            String::new()
+        };
+
+        let origin = summary::group_origin(release)
+            .map(|origin| format!(", {}", origin))
+            .unwrap_or_default();
+
         println!(
-            "  {} {} {} → {} ({})",
+            "  {} {} {} → {} ({}{}){}",
             style("•").dim(),
             style(&release.name).cyan(),
             style(&release.old_version.to_string()).dim(),
             style(&release.new_version.to_string()).green(),
-            bump_style
+            bump_style,
+            origin,
+            skipped
         );
     }
 
+    if verbose {
+        print_inactive_groups(config, &release_plan);
+    }
+
     if !release_plan.warnings.is_empty() {
         println!();
         for warning in &release_plan.warnings {
@@ -86,5 +187,94 @@ pub fn run(verbose: bool, ecosystem: Option<Ecosystem>) -> Result<()> {
         }
     }
 
+    summary::print_partial_changelogs(&partial, "--filter");
+
+    println!(
+        "\n{} {}",
+        style("ℹ").blue().bold(),
+        summary::BumpTally::of(&release_plan.releases)
+    );
+
     Ok(())
 }
+
+/// Lists the configured groups without a releasing member, so it's visible that
+/// they were loaded.
+fn print_inactive_groups(config: &Config, release_plan: &ReleasePlan) {
+    let inactive = inactive_groups(config, release_plan);
+    if inactive.is_empty() {
+        return;
+    }
+
+    println!("\n{}", style("Inactive groups:").dim().underlined());
+    for group in inactive {
+        println!("  {} {}", style("•").dim(), style(group).dim());
+    }
+}
+
+fn inactive_groups(config: &Config, release_plan: &ReleasePlan) -> Vec<String> {
+    let releasing = |members: &[String]| {
+        members
+            .iter()
+            .any(|m| release_plan.releases.iter().any(|r| &r.name == m))
+    };
+
+    let fixed = config.fixed.iter().map(|g| ("fixed", &g.members));
+    let linked = config.linked.iter().map(|g| ("linked", &g.members));
+    let numbered = fixed
+        .enumerate()
+        .chain(linked.enumerate())
+        .map(|(i, (kind, members))| (kind, i + 1, members));
+
+    numbered
+        .filter(|(_, _, members)| !releasing(members))
+        .map(|(kind, n, members)| format!("{} group #{}: {}", kind, n, members.join(", ")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use changelogs::config::{FixedGroup, LinkedGroup};
+    use changelogs::plan::PackageRelease;
+    use semver::Version;
+
+    #[test]
+    fn test_inactive_groups() {
+        let config = Config {
+            fixed: vec![
+                FixedGroup {
+                    members: vec!["pkg-a".into(), "pkg-b".into()],
+                },
+                FixedGroup {
+                    members: vec!["cli".into(), "cli-core".into()],
+                },
+            ],
+            linked: vec![LinkedGroup {
+                members: vec!["sdk".into(), "sdk-macros".into()],
+            }],
+            ..Config::default()
+        };
+        let release_plan = ReleasePlan {
+            changelogs: vec![],
+            releases: vec![PackageRelease {
+                name: "pkg-a".into(),
+                bump: BumpType::Minor,
+                old_version: Version::new(1, 0, 0),
+                new_version: Version::new(1, 1, 0),
+                changelog_ids: vec![],
+                reasons: vec![],
+                updated_dependencies: vec![],
+            }],
+            warnings: vec![],
+        };
+
+        assert_eq!(
+            inactive_groups(&config, &release_plan),
+            [
+                "fixed group #2: cli, cli-core",
+                "linked group #1: sdk, sdk-macros"
+            ]
+        );
+    }
+}
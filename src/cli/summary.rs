@@ -0,0 +1,509 @@
+use anyhow::Result;
+use changelogs::config::Config;
+use changelogs::plan::{PackageRelease, ReleasePlan};
+use changelogs::{BumpReason, BumpType};
+use console::style;
+use std::io::Write;
+use std::path::Path;
+
+/// The number of releases per bump type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct BumpTally {
+    pub packages: usize,
+    pub major: usize,
+    pub minor: usize,
+    pub patch: usize,
+}
+
+impl BumpTally {
+    pub fn of(releases: &[PackageRelease]) -> Self {
+        let mut tally = BumpTally {
+            packages: releases.len(),
+            ..Default::default()
+        };
+        for release in releases {
+            match release.bump {
+                BumpType::Major => tally.major += 1,
+                BumpType::Minor => tally.minor += 1,
+                BumpType::Patch => tally.patch += 1,
+            }
+        }
+        tally
+    }
+}
+
+/// E.g. `3 packages: 1 major, 2 patch`, leaving out bump types without releases.
+impl std::fmt::Display for BumpTally {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let noun = if self.packages == 1 {
+            "package"
+        } else {
+            "packages"
+        };
+        write!(f, "{} {}", self.packages, noun)?;
+        let counts: Vec<String> = [
+            (self.major, "major"),
+            (self.minor, "minor"),
+            (self.patch, "patch"),
+        ]
+        .into_iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, bump)| format!("{} {}", count, bump))
+        .collect();
+        if !counts.is_empty() {
+            write!(f, ": {}", counts.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The plan as JSON: every release with its changelogs and reasons, the
+/// warnings, and a `summary` tallying the releases by bump type. This is also the
+/// `--export-plan` file, so any of them can be fed back to `--apply-plan`.
+pub fn plan_json(release_plan: &ReleasePlan) -> serde_json::Value {
+    let mut value = serde_json::to_value(release_plan).unwrap_or_default();
+    value["summary"] =
+        serde_json::to_value(BumpTally::of(&release_plan.releases)).unwrap_or_default();
+    value
+}
+
+/// Renders releases as a Markdown table. Releases in a fixed or linked group
+/// note the group next to their bump.
+pub fn releases_table(releases: &[PackageRelease]) -> String {
+    let mut out = String::new();
+    out.push_str("| Package | Current | Next | Bump |\n");
+    out.push_str("| --- | --- | --- | --- |\n");
+    for r in releases {
+        let bump = match group_origin(r) {
+            Some(origin) => format!("{} ({})", r.bump, origin),
+            None => r.bump.to_string(),
+        };
+        out.push_str(&format!(
+            "| `{}` | {} | {} | {} |\n",
+            r.name, r.old_version, r.new_version, bump
+        ));
+    }
+    out
+}
+
+/// Notes the changelogs a run releases only in part, which stay pending for the
+/// packages `outside` it (`--filter` or an imported plan).
+pub fn print_partial_changelogs(ids: &[String], outside: &str) {
+    if ids.is_empty() {
+        return;
+    }
+    println!();
+    for id in ids {
+        println!(
+            "  {} {} also releases packages outside {}, so it stays pending",
+            style("ℹ").blue().bold(),
+            style(id).cyan(),
+            outside
+        );
+    }
+}
+
+/// The groups a release belongs to, e.g. `fixed group #1 with pkg-a`.
+pub fn group_origin(release: &PackageRelease) -> Option<String> {
+    let origins: Vec<String> = release
+        .reasons
+        .iter()
+        .filter_map(|reason| reason_origin(reason, &release.name))
+        .collect();
+    (!origins.is_empty()).then(|| origins.join("; "))
+}
+
+/// Describes a group reason with the other members of the group. Changelog and
+/// dependency reasons aren't groups.
+fn reason_origin(reason: &BumpReason, package: &str) -> Option<String> {
+    let (label, members) = match reason {
+        BumpReason::Fixed {
+            members,
+            group: Some(n),
+            ..
+        } => (format!("fixed group #{}", n), members),
+        BumpReason::Fixed {
+            members,
+            group: None,
+            ..
+        } => ("root changelog group".to_string(), members),
+        BumpReason::Linked { members, group, .. } => (format!("linked group #{}", group), members),
+        BumpReason::Changelog { .. } | BumpReason::Dependent { .. } => return None,
+    };
+
+    let others: Vec<&str> = members
+        .iter()
+        .map(String::as_str)
+        .filter(|m| *m != package)
+        .collect();
+    if others.is_empty() {
+        Some(label)
+    } else {
+        Some(format!("{} with {}", label, others.join(", ")))
+    }
+}
+
+pub const DEFAULT_COMMIT_MESSAGE: &str = "chore: release {packages}";
+pub const DEFAULT_PR_TITLE: &str = "chore: release {packages}";
+pub const DEFAULT_PR_BODY: &str = "Releases {count} package(s):\n\n{table}";
+
+/// The release commit message and pull request text, from `[git] commit_message`
+/// and `[github] pr_title`/`pr_body_template` or their defaults.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ReleaseMessages {
+    pub commit_message: String,
+    pub pr_title: String,
+    pub pr_body: String,
+}
+
+impl ReleaseMessages {
+    pub fn new(config: &Config, releases: &[PackageRelease], date: &str) -> Self {
+        let render = |template: Option<&String>, default: &str| {
+            render_template(template.map_or(default, String::as_str), releases, date)
+        };
+        Self {
+            commit_message: render(config.git.commit_message.as_ref(), DEFAULT_COMMIT_MESSAGE),
+            pr_title: render(config.github.pr_title.as_ref(), DEFAULT_PR_TITLE),
+            pr_body: render(config.github.pr_body_template.as_ref(), DEFAULT_PR_BODY),
+        }
+    }
+}
+
+/// Replaces `{packages}`, `{count}`, `{date}` and `{table}` in `template`. Other
+/// braces are kept as written, and replaced text isn't scanned again.
+pub fn render_template(template: &str, releases: &[PackageRelease], date: &str) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        match token_value(&rest[1..end], releases, date) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn token_value(token: &str, releases: &[PackageRelease], date: &str) -> Option<String> {
+    let value = match token {
+        "packages" => releases
+            .iter()
+            .map(|r| format!("{}@{}", r.name, r.new_version))
+            .collect::<Vec<_>>()
+            .join(", "),
+        "count" => releases.len().to_string(),
+        "date" => date.to_string(),
+        "table" => releases_table(releases).trim_end().to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Appends the release plan to `$GITHUB_STEP_SUMMARY` when running in GitHub
+/// Actions. Does nothing elsewhere.
+pub fn append_step_summary(channel: Option<&str>, release_plan: &ReleasePlan) -> Result<()> {
+    match std::env::var_os("GITHUB_STEP_SUMMARY") {
+        Some(path) if !path.is_empty() => append_to(Path::new(&path), channel, release_plan),
+        _ => Ok(()),
+    }
+}
+
+fn append_to(path: &Path, channel: Option<&str>, release_plan: &ReleasePlan) -> Result<()> {
+    let mut out = match channel {
+        Some(name) => format!("### Release plan (`{}` channel)\n\n", name),
+        None => "### Release plan\n\n".to_string(),
+    };
+
+    if release_plan.releases.is_empty() {
+        out.push_str("No packages will be released.\n");
+    } else {
+        out.push_str(&releases_table(&release_plan.releases));
+    }
+
+    for warning in &release_plan.warnings {
+        out.push_str(&format!("\n> [!WARNING]\n> {}\n", warning));
+    }
+    out.push('\n');
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(out.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use semver::Version;
+    use tempfile::TempDir;
+
+    fn plan() -> ReleasePlan {
+        ReleasePlan {
+            changelogs: vec![],
+            releases: vec![PackageRelease {
+                name: "foo".to_string(),
+                bump: BumpType::Minor,
+                old_version: Version::new(1, 2, 3),
+                new_version: Version::new(1, 3, 0),
+                changelog_ids: vec![],
+                reasons: vec![],
+                updated_dependencies: vec![],
+            }],
+            warnings: vec![],
+        }
+    }
+
+    fn group_release(reasons: Vec<BumpReason>) -> PackageRelease {
+        PackageRelease {
+            name: "pkg-b".to_string(),
+            reasons,
+            ..plan().releases.remove(0)
+        }
+    }
+
+    #[test]
+    fn test_bump_tally() {
+        let release = |name: &str, bump| PackageRelease {
+            name: name.to_string(),
+            bump,
+            ..plan().releases.remove(0)
+        };
+        let mut mixed = plan();
+        mixed.releases = vec![
+            release("a", BumpType::Patch),
+            release("b", BumpType::Major),
+            release("c", BumpType::Patch),
+        ];
+
+        let tally = BumpTally::of(&mixed.releases);
+        assert_eq!(
+            tally,
+            BumpTally {
+                packages: 3,
+                major: 1,
+                minor: 0,
+                patch: 2
+            }
+        );
+        assert_eq!(tally.to_string(), "3 packages: 1 major, 2 patch");
+        assert_eq!(
+            BumpTally::of(&plan().releases).to_string(),
+            "1 package: 1 minor"
+        );
+        assert_eq!(BumpTally::default().to_string(), "0 packages");
+
+        let json = plan_json(&mixed);
+        assert_eq!(
+            json["summary"],
+            serde_json::json!({ "packages": 3, "major": 1, "minor": 0, "patch": 2 })
+        );
+    }
+
+    #[test]
+    fn test_render_template_tokens() {
+        let mut releases = plan().releases;
+        releases.push(PackageRelease {
+            name: "bar".to_string(),
+            bump: BumpType::Patch,
+            old_version: Version::new(0, 1, 0),
+            new_version: Version::new(0, 1, 1),
+            ..plan().releases.remove(0)
+        });
+        let render = |template| render_template(template, &releases, "2024-05-01");
+
+        assert_eq!(render("{packages}"), "foo@1.3.0, bar@0.1.1");
+        assert_eq!(render("{count}"), "2");
+        assert_eq!(render("{date}"), "2024-05-01");
+        assert_eq!(
+            render("{table}"),
+            "| Package | Current | Next | Bump |\n| --- | --- | --- | --- |\n\
+             | `foo` | 1.2.3 | 1.3.0 | minor |\n| `bar` | 0.1.0 | 0.1.1 | patch |"
+        );
+        assert_eq!(
+            render("{count} on {date}: {unknown} {} {packages"),
+            "2 on 2024-05-01: {unknown} {} {packages"
+        );
+    }
+
+    #[test]
+    fn test_release_messages_defaults() {
+        let messages = ReleaseMessages::new(&Config::default(), &plan().releases, "2024-05-01");
+
+        assert_eq!(
+            messages,
+            ReleaseMessages {
+                commit_message: "chore: release foo@1.3.0".to_string(),
+                pr_title: "chore: release foo@1.3.0".to_string(),
+                pr_body: "Releases 1 package(s):\n\n\
+                          | Package | Current | Next | Bump |\n| --- | --- | --- | --- |\n\
+                          | `foo` | 1.2.3 | 1.3.0 | minor |"
+                    .to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_release_messages_from_config() {
+        let config: Config = toml::from_str(
+            "[git]\ncommit_message = \"release {date}\"\n\n[github]\npr_title = \"Release {count}\"\n",
+        )
+        .unwrap();
+
+        let messages = ReleaseMessages::new(&config, &plan().releases, "2024-05-01");
+
+        assert_eq!(messages.commit_message, "release 2024-05-01");
+        assert_eq!(messages.pr_title, "Release 1");
+        assert!(messages.pr_body.starts_with("Releases 1 package(s):"));
+    }
+
+    #[test]
+    fn test_reason_origin() {
+        let members = vec!["pkg-a".to_string(), "pkg-b".to_string()];
+
+        assert_eq!(
+            reason_origin(
+                &BumpReason::Changelog {
+                    id: "brave-fox".into(),
+                    bump: BumpType::Minor
+                },
+                "pkg-b"
+            ),
+            None
+        );
+        assert_eq!(
+            reason_origin(
+                &BumpReason::Dependent {
+                    dependency: "pkg-a".into(),
+                    path: members.clone(),
+                    bump: BumpType::Patch
+                },
+                "pkg-b"
+            ),
+            None
+        );
+        assert_eq!(
+            reason_origin(
+                &BumpReason::Fixed {
+                    members: members.clone(),
+                    bump: BumpType::Minor,
+                    group: Some(1)
+                },
+                "pkg-b"
+            )
+            .as_deref(),
+            Some("fixed group #1 with pkg-a")
+        );
+        assert_eq!(
+            reason_origin(
+                &BumpReason::Fixed {
+                    members: members.clone(),
+                    bump: BumpType::Minor,
+                    group: None
+                },
+                "pkg-b"
+            )
+            .as_deref(),
+            Some("root changelog group with pkg-a")
+        );
+        assert_eq!(
+            reason_origin(
+                &BumpReason::Linked {
+                    members: vec!["pkg-b".into()],
+                    bump: BumpType::Minor,
+                    group: 2
+                },
+                "pkg-b"
+            )
+            .as_deref(),
+            Some("linked group #2")
+        );
+    }
+
+    #[test]
+    fn test_group_origin_joins_groups() {
+        let release = group_release(vec![
+            BumpReason::Changelog {
+                id: "brave-fox".into(),
+                bump: BumpType::Patch,
+            },
+            BumpReason::Fixed {
+                members: vec!["pkg-a".into(), "pkg-b".into()],
+                bump: BumpType::Minor,
+                group: Some(1),
+            },
+            BumpReason::Linked {
+                members: vec!["pkg-b".into(), "pkg-c".into(), "pkg-d".into()],
+                bump: BumpType::Minor,
+                group: 1,
+            },
+        ]);
+
+        assert_eq!(
+            group_origin(&release).as_deref(),
+            Some("fixed group #1 with pkg-a; linked group #1 with pkg-c, pkg-d")
+        );
+        assert_eq!(group_origin(&group_release(vec![])), None);
+        assert_eq!(
+            releases_table(&[release]).lines().last(),
+            Some(
+                "| `pkg-b` | 1.2.3 | 1.3.0 | minor (fixed group #1 with pkg-a; linked group #1 with pkg-c, pkg-d) |"
+            )
+        );
+    }
+
+    #[test]
+    fn test_append_step_summary_from_env() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("summary.md");
+        std::fs::write(&path, "earlier step\n").unwrap();
+
+        // SAFETY: test-only, no concurrent access to this env var
+        unsafe {
+            std::env::set_var("GITHUB_STEP_SUMMARY", &path);
+        }
+        let result = append_step_summary(None, &plan());
+        // SAFETY: test-only, no concurrent access to this env var
+        unsafe {
+            std::env::remove_var("GITHUB_STEP_SUMMARY");
+        }
+        result.unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "earlier step\n\
+             ### Release plan\n\n\
+             | Package | Current | Next | Bump |\n\
+             | --- | --- | --- | --- |\n\
+             | `foo` | 1.2.3 | 1.3.0 | minor |\n\n"
+        );
+    }
+
+    #[test]
+    fn test_append_to_channel_with_warnings() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("summary.md");
+        let plan = ReleasePlan {
+            releases: vec![],
+            warnings: vec!["changelog references unknown package 'bar'".to_string()],
+            ..plan()
+        };
+
+        append_to(&path, Some("beta"), &plan).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "### Release plan (`beta` channel)\n\n\
+             No packages will be released.\n\n\
+             > [!WARNING]\n\
+             > changelog references unknown package 'bar'\n\n"
+        );
+    }
+}
@@ -1,34 +1,126 @@
 use anyhow::{Context, Result};
-use changelogs::Ecosystem;
+use changelogs::Package;
 use changelogs::changelog_entry;
-use changelogs::changelog_writer;
-use changelogs::config::Config;
+use changelogs::changelog_writer::{self, OnExistingVersion};
+use changelogs::config::{Config, VersionTarget};
 use changelogs::error::Error;
-use changelogs::plan;
-use changelogs::workspace::Workspace;
+use changelogs::fs_util::atomic_write;
+use changelogs::git::{self, Git};
+use changelogs::github::AuthorResolver;
+use changelogs::plan::{self, ApplyOptions, ReleasePlan};
+use changelogs::workspace::{DiscoverOptions, Workspace};
 use console::style;
-use semver::Version;
-use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-pub fn run(dry_run: bool, ecosystem: Option<Ecosystem>) -> Result<()> {
-    let workspace = Workspace::discover_with_ecosystem(ecosystem).context(
-        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python>",
+use super::summary;
+
+/// Where `version` gets its release plan and what it does with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanMode {
+    /// Assemble the plan from the pending changelogs and apply it.
+    Apply,
+    /// Print the tags the assembled plan would create.
+    PrintTags,
+    /// Write the assembled plan to a JSON file for review or editing.
+    Export(PathBuf),
+    /// Apply a plan written by `Export` instead of assembling one.
+    Import(PathBuf),
+}
+
+/// Checks `version` makes around writing the release.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CheckOptions {
+    /// Run even if files the release modifies have uncommitted changes.
+    pub allow_dirty: bool,
+    /// Fail instead of warning when the lockfile can't be refreshed.
+    pub strict_lockfile: bool,
+}
+
+pub fn run(
+    dry_run: bool,
+    on_existing: OnExistingVersion,
+    checks: CheckOptions,
+    mode: PlanMode,
+    include_unchanged: bool,
+    channel: Option<String>,
+    discover: &DiscoverOptions,
+) -> Result<()> {
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
     )?;
 
     if !workspace.is_initialized() {
         return Err(Error::NotInitialized.into());
     }
 
-    let changelog_dir = workspace.changelog_dir();
-    let changelogs = changelog_entry::read_all(&changelog_dir)?;
+    let mut config = Config::load(&workspace.changelog_dir())?.for_channel(channel.as_deref())?;
+    config.changelog.include_unchanged |= include_unchanged;
+    super::apply_repo_url_env(&mut config);
+    let changelog_dir = workspace.channel_dir(channel.as_deref());
+    let mut changelogs = changelog_entry::read_all(&changelog_dir)?;
+    // Entries only partly in a --filter run release those packages but stay pending.
+    let mut partial = Vec::new();
+    if !discover.filter.is_empty() {
+        (changelogs, partial) = plan::retain_workspace_releases(&workspace, changelogs);
+    }
+
+    match &mode {
+        PlanMode::PrintTags => {
+            // Tags only, one per line, so the output can be piped straight into scripts.
+            let release_plan = plan::assemble(&workspace, changelogs, &config);
+            for tag in planned_tags(&workspace, &release_plan, &config) {
+                println!("{}", tag);
+            }
+            return Ok(());
+        }
+        PlanMode::Export(path) => {
+            let release_plan = plan::assemble(&workspace, changelogs, &config);
+            let json = serde_json::to_string_pretty(&summary::plan_json(&release_plan))?;
+            atomic_write(path, json + "\n")
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            println!(
+                "{} Wrote release plan for {} package(s) to {}",
+                style("✓").green().bold(),
+                release_plan.releases.len(),
+                path.display()
+            );
+            return Ok(());
+        }
+        PlanMode::Apply | PlanMode::Import(_) => {}
+    }
 
     if changelogs.is_empty() {
         println!("{} No changelogs found", style("ℹ").blue().bold());
         return Ok(());
     }
 
-    let config = Config::load(&changelog_dir)?;
-    let release_plan = plan::assemble(&workspace, changelogs.clone(), &config);
+    // Entries naming a package the imported plan dropped stay pending for it.
+    let mut dropped = Vec::new();
+    let release_plan = match &mode {
+        PlanMode::Import(path) => {
+            let json = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let exported: ReleasePlan = serde_json::from_str(&json)
+                .with_context(|| format!("invalid release plan in {}", path.display()))?;
+            let imported;
+            (imported, dropped) = plan::import(&workspace, exported, changelogs, &config)?;
+            imported
+        }
+        _ => plan::assemble(&workspace, changelogs, &config),
+    };
+
+    if config.strict {
+        let mut problems = release_plan.warnings.clone();
+        if let Err(issues) = config.validate(&workspace.all_package_names()) {
+            problems.extend(issues);
+        }
+        let mut seen = HashSet::new();
+        problems.retain(|problem| seen.insert(problem.clone()));
+        if !problems.is_empty() {
+            return Err(Error::StrictPlan(problems.join("\n")).into());
+        }
+    }
 
     if release_plan.releases.is_empty() {
         println!("{} No packages to release", style("ℹ").blue().bold());
@@ -46,6 +138,10 @@ pub fn run(dry_run: bool, ecosystem: Option<Ecosystem>) -> Result<()> {
         println!();
     }
 
+    if config.check_registry {
+        warn_ahead_on_registry(&workspace, &release_plan);
+    }
+
     println!("{} Updating versions...\n", style("→").blue().bold());
 
     println!("{} Release plan:\n", style("→").blue().bold());
@@ -60,7 +156,12 @@ pub fn run(dry_run: bool, ecosystem: Option<Ecosystem>) -> Result<()> {
         );
     }
 
+    summary::print_partial_changelogs(&partial, "--filter");
+    summary::print_partial_changelogs(&dropped, "the imported plan");
+
     if dry_run {
+        print_dependency_rewrites(&workspace, &release_plan)?;
+        print_version_files(&workspace, &release_plan, &config);
         println!(
             "\n{} {} package(s) would be updated (dry run — no files changed)",
             style("ℹ").blue().bold(),
@@ -69,22 +170,36 @@ pub fn run(dry_run: bool, ecosystem: Option<Ecosystem>) -> Result<()> {
         return Ok(());
     }
 
-    println!("\n{} Updating versions...\n", style("→").blue().bold());
-
-    let mut version_updates: HashMap<String, Version> = HashMap::new();
-    for release in &release_plan.releases {
-        workspace.update_version(&release.name, &release.new_version)?;
-        version_updates.insert(release.name.clone(), release.new_version.clone());
+    if !checks.allow_dirty {
+        let planned = planned_paths(&workspace, &release_plan, &changelog_dir, &config);
+        let dirty = dirty_planned_paths(git::open().as_ref(), &planned)?;
+        if !dirty.is_empty() {
+            let list: Vec<String> = dirty
+                .iter()
+                .map(|p| {
+                    let relative = p.strip_prefix(&workspace.root).unwrap_or(p);
+                    format!("  {}", relative.display())
+                })
+                .collect();
+            return Err(Error::DirtyFiles(list.join("\n")).into());
+        }
     }
-    workspace.update_dependency_versions(&version_updates)?;
 
-    println!("{} Updating changelogs...\n", style("→").blue().bold());
+    println!(
+        "\n{} Updating versions and changelogs...\n",
+        style("→").blue().bold()
+    );
 
-    changelog_writer::write_changelogs(
+    let applied = plan::apply(
         &workspace,
-        &release_plan.releases,
-        &changelogs,
-        config.changelog.format,
+        &release_plan,
+        &config,
+        &AuthorResolver::from_env(),
+        ApplyOptions {
+            on_existing,
+            channel: channel.as_deref(),
+            keep: &[partial, dropped].concat(),
+        },
     )?;
 
     for release in &release_plan.releases {
@@ -95,17 +210,26 @@ pub fn run(dry_run: bool, ecosystem: Option<Ecosystem>) -> Result<()> {
         );
     }
 
-    println!("\n{} Removing changelogs...\n", style("→").blue().bold());
+    for section in &applied.replaced {
+        println!(
+            "  {} Replaced existing {} section in {}",
+            style("!").yellow().bold(),
+            section.version,
+            style(section.path.display()).dim()
+        );
+    }
 
-    for cs in &changelogs {
-        changelog_entry::delete(&changelog_dir, &cs.id)?;
+    println!();
+    for path in &applied.deleted {
         println!(
             "  {} Deleted {}",
             style("✓").green(),
-            style(format!("{}.md", cs.id)).dim()
+            style(path.file_name().unwrap_or_default().to_string_lossy()).dim()
         );
     }
 
+    refresh_lockfile(workspace.update_lockfile(&config), checks.strict_lockfile)?;
+
     println!(
         "\n{} {} package(s) updated",
         style("✓").green().bold(),
@@ -114,3 +238,302 @@ pub fn run(dry_run: bool, ecosystem: Option<Ecosystem>) -> Result<()> {
 
     Ok(())
 }
+
+/// Lists the dependency requirements `version` would rewrite, by manifest.
+fn print_dependency_rewrites(workspace: &Workspace, release_plan: &ReleasePlan) -> Result<()> {
+    let rewrites = plan::dependency_rewrites(workspace, release_plan)?;
+    if rewrites.is_empty() {
+        return Ok(());
+    }
+
+    println!("\n{} Dependency updates:\n", style("→").blue().bold());
+    let mut manifest = None;
+    for rewrite in &rewrites {
+        if manifest != Some(&rewrite.manifest) {
+            let relative = rewrite
+                .manifest
+                .strip_prefix(&workspace.root)
+                .unwrap_or(&rewrite.manifest);
+            println!("  {}", style(relative.display()).dim());
+            manifest = Some(&rewrite.manifest);
+        }
+        println!(
+            "    {} {} → {}",
+            style("•").dim(),
+            style(&rewrite.dependency).cyan(),
+            style(&rewrite.version.to_string()).green()
+        );
+    }
+    Ok(())
+}
+
+/// The version files besides manifests that applying `release_plan` rewrites, with
+/// the value each gets.
+fn version_files<'a>(
+    workspace: &'a Workspace,
+    release_plan: &ReleasePlan,
+    config: &Config,
+) -> Vec<(&'a Package, VersionTarget, String)> {
+    let mut files = Vec::new();
+    for release in &release_plan.releases {
+        let Some(package) = workspace.get_package(&release.name) else {
+            continue;
+        };
+        let (targets, _) = workspace.version_targets(package, &release.new_version, config);
+        files.extend(
+            targets
+                .into_iter()
+                .map(|(target, value)| (package, target, value)),
+        );
+    }
+    files
+}
+
+fn print_version_files(workspace: &Workspace, release_plan: &ReleasePlan, config: &Config) {
+    let files = version_files(workspace, release_plan, config);
+    if files.is_empty() {
+        return;
+    }
+
+    println!("\n{} Version files:\n", style("→").blue().bold());
+    for (package, target, value) in &files {
+        let path = package.path.join(&target.path);
+        let relative = path.strip_prefix(&workspace.root).unwrap_or(&path);
+        println!(
+            "  {} {} → {}",
+            style("•").dim(),
+            style(relative.display()).dim(),
+            style(value).green()
+        );
+    }
+}
+
+/// Reports the lockfile refresh. The versions are already written by then, so a
+/// failure is only a warning unless `strict`.
+fn refresh_lockfile(result: changelogs::error::Result<Option<String>>, strict: bool) -> Result<()> {
+    match result {
+        Ok(Some(command)) => println!(
+            "  {} Refreshed lockfile ({})",
+            style("✓").green(),
+            style(command).dim()
+        ),
+        Ok(None) => {}
+        Err(e) if strict => return Err(e.into()),
+        Err(e) => eprintln!(
+            "  {} {}",
+            style("!").yellow().bold(),
+            style(format!(
+                "{} (the new versions are written; refresh it by hand)",
+                e
+            ))
+            .yellow()
+        ),
+    }
+    Ok(())
+}
+
+/// Warns about released packages whose registry is ahead of their manifest: the
+/// planned version may already be taken. The check is advisory, so registry errors
+/// (e.g. when offline) are ignored.
+fn warn_ahead_on_registry(workspace: &Workspace, release_plan: &ReleasePlan) {
+    let released: Vec<&Package> = release_plan
+        .releases
+        .iter()
+        .filter_map(|r| workspace.get_package(&r.name))
+        .collect();
+    let Ok(ahead) = workspace.ahead_on_registry(&released) else {
+        return;
+    };
+    for (pkg, published) in &ahead {
+        println!(
+            "  {} {}",
+            style("!").yellow().bold(),
+            style(format!(
+                "{} {} is published, but the manifest is at {} — the local repo is out of sync",
+                pkg.name, published, pkg.version
+            ))
+            .yellow()
+        );
+    }
+    if !ahead.is_empty() {
+        println!();
+    }
+}
+
+/// Returns the tags `publish` will create once the planned versions are released.
+fn planned_tags(workspace: &Workspace, release_plan: &ReleasePlan, config: &Config) -> Vec<String> {
+    let released: Vec<Package> = release_plan
+        .releases
+        .iter()
+        .filter_map(|release| {
+            let mut pkg = workspace.get_package(&release.name)?.clone();
+            pkg.version = release.new_version.clone();
+            Some(pkg)
+        })
+        .collect();
+    let released: Vec<&Package> = released.iter().collect();
+    workspace.release_tags(&released, config)
+}
+
+/// Every file `run` may write or delete: manifests and version files of released
+/// packages, manifests of packages depending on them, the root manifest,
+/// changelogs and consumed entries.
+fn planned_paths(
+    workspace: &Workspace,
+    release_plan: &ReleasePlan,
+    changelog_dir: &Path,
+    config: &Config,
+) -> Vec<PathBuf> {
+    let released: HashSet<&str> = release_plan
+        .releases
+        .iter()
+        .map(|r| r.name.as_str())
+        .collect();
+
+    let mut paths: Vec<PathBuf> = workspace
+        .packages
+        .iter()
+        .filter(|p| {
+            released.contains(p.name.as_str())
+                || p.dependencies
+                    .iter()
+                    .any(|d| released.contains(d.name.as_str()))
+        })
+        .map(|p| p.manifest_path.clone())
+        .collect();
+
+    paths.push(workspace.root_manifest_path());
+
+    for (package, target, _) in version_files(workspace, release_plan, config) {
+        paths.push(package.path.join(&target.path));
+    }
+    paths.extend(
+        changelog_writer::changelog_targets(workspace, &release_plan.releases, config)
+            .into_iter()
+            .map(|(path, _)| path),
+    );
+    paths.extend(
+        release_plan
+            .changelogs
+            .iter()
+            .map(|c| changelog_dir.join(format!("{}.md", c.id))),
+    );
+    paths
+}
+
+/// Returns the planned paths that have uncommitted changes in git.
+fn dirty_planned_paths(git: &dyn Git, planned: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let planned: HashSet<PathBuf> = planned.iter().map(|p| normalize(p)).collect();
+    let mut dirty: Vec<PathBuf> = git
+        .dirty_paths()?
+        .into_iter()
+        .filter(|p| planned.contains(&normalize(p)))
+        .collect();
+    dirty.sort();
+    dirty.dedup();
+    Ok(dirty)
+}
+
+/// Resolves symlinks (e.g. macOS `/var` → `/private/var`) so git's paths compare
+/// equal to the workspace's. Deleted files keep their path as is.
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use changelogs::BumpType;
+    use changelogs::Ecosystem;
+    use changelogs::changelog_entry::{Changelog, Release};
+    use changelogs::config::{ChangelogFormat, PackageConfig};
+    use semver::Version;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_refresh_lockfile_warns_unless_strict() {
+        let failed = || {
+            Err(Error::LockfileUpdateFailed(
+                "`uv lock --offline` failed".into(),
+            ))
+        };
+
+        assert!(refresh_lockfile(failed(), false).is_ok());
+        let err = refresh_lockfile(failed(), true).unwrap_err();
+        assert!(err.to_string().contains("uv lock --offline"), "{err}");
+        assert!(refresh_lockfile(Ok(None), true).is_ok());
+    }
+
+    fn package(name: &str, version: Version) -> Package {
+        Package {
+            name: name.to_string(),
+            version,
+            path: PathBuf::from(format!("/fake/{name}")),
+            manifest_path: PathBuf::from(format!("/fake/{name}/Cargo.toml")),
+            dependencies: vec![],
+        }
+    }
+
+    fn plan(workspace: &Workspace, config: &Config) -> ReleasePlan {
+        let changelog = Changelog {
+            id: "release".to_string(),
+            summary: "Changes".to_string(),
+            releases: vec![
+                Release {
+                    package: "core".to_string(),
+                    bump: BumpType::Minor,
+                },
+                Release {
+                    package: "utils".to_string(),
+                    bump: BumpType::Patch,
+                },
+            ],
+            commit: None,
+            package_summaries: HashMap::new(),
+        };
+        plan::assemble(workspace, vec![changelog], config)
+    }
+
+    #[test]
+    fn test_planned_tags() {
+        let workspace = Workspace::new(
+            PathBuf::from("/fake"),
+            vec![
+                package("core", Version::new(1, 0, 0)),
+                package("utils", Version::new(2, 0, 0)),
+                package("unchanged", Version::new(3, 0, 0)),
+            ],
+            Ecosystem::Rust,
+        );
+        let mut config = Config::default();
+
+        let tags = planned_tags(&workspace, &plan(&workspace, &config), &config);
+        assert_eq!(tags, ["core@1.1.0", "utils@2.0.1"]);
+
+        config.packages.push(PackageConfig {
+            name: "core".into(),
+            tag_template: Some("{name}-v{version}".into()),
+            changelog_path: None,
+            min_bump: None,
+            format: None,
+        });
+        let tags = planned_tags(&workspace, &plan(&workspace, &config), &config);
+        assert_eq!(tags, ["core-v1.1.0", "utils@2.0.1"]);
+
+        // Only `utils` writes the root changelog: it gets the `v` tag.
+        config.packages.push(PackageConfig {
+            name: "utils".into(),
+            tag_template: None,
+            changelog_path: None,
+            min_bump: None,
+            format: Some(ChangelogFormat::Root),
+        });
+        let tags = planned_tags(&workspace, &plan(&workspace, &config), &config);
+        assert_eq!(tags, ["v2.0.1", "core-v1.1.0"]);
+
+        config.changelog.format = ChangelogFormat::Root;
+        config.packages.pop();
+        let tags = planned_tags(&workspace, &plan(&workspace, &config), &config);
+        assert_eq!(tags, ["v1.1.0"]);
+    }
+}
@@ -0,0 +1,176 @@
+use anyhow::{Context, Result};
+use changelogs::BumpReason;
+use changelogs::changelog_entry;
+use changelogs::config::Config;
+use changelogs::error::Error;
+use changelogs::plan;
+use changelogs::workspace::{DiscoverOptions, Workspace};
+use console::style;
+
+pub fn run(package: String, json: bool, discover: &DiscoverOptions) -> Result<()> {
+    let workspace = Workspace::discover_with(discover).context(
+        "could not detect workspace — specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet>",
+    )?;
+
+    if !workspace.is_initialized() {
+        return Err(Error::NotInitialized.into());
+    }
+
+    let Some(pkg) = workspace.get_package(&package) else {
+        return Err(Error::PackageNotFound(package).into());
+    };
+
+    let changelogs = changelog_entry::read_all(&workspace.channel_dir(None))?;
+    let config = Config::load(&workspace.changelog_dir())?;
+    let release_plan = plan::assemble(&workspace, changelogs, &config);
+
+    let ignored = config.ignore.contains(&package);
+    let release = release_plan.releases.iter().find(|r| r.name == package);
+
+    let pending_dir = workspace.channel_dir(None);
+    let pending_dir = pending_dir
+        .strip_prefix(&workspace.root)
+        .unwrap_or(&pending_dir);
+    let changelog_path = |id: &str| {
+        pending_dir
+            .join(format!("{}.md", id))
+            .to_string_lossy()
+            .replace('\\', "/")
+    };
+    let first_line = |id: &str| {
+        release_plan
+            .changelogs
+            .iter()
+            .find(|c| c.id == id)
+            .and_then(|c| c.summary.lines().next())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    if json {
+        let reasons: Vec<serde_json::Value> = release
+            .map(|r| r.reasons.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|reason| {
+                let mut value = serde_json::to_value(reason).unwrap_or_default();
+                if let BumpReason::Changelog { id, .. } = reason {
+                    value["path"] = changelog_path(id).into();
+                    value["summary"] = first_line(id).into();
+                }
+                value
+            })
+            .collect();
+
+        let output = serde_json::json!({
+            "package": package,
+            "current_version": pkg.version.to_string(),
+            "new_version": release.map(|r| r.new_version.to_string()),
+            "bump": release.map(|r| r.bump),
+            "ignored": ignored,
+            "reasons": reasons,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    let Some(release) = release else {
+        let why = if ignored {
+            "it is listed in `ignore` in .changelog/config.toml"
+        } else {
+            "no pending changelogs reference it and no group or dependency pulls it in"
+        };
+        println!(
+            "{} {} {} is not being released: {}",
+            style("ℹ").blue().bold(),
+            style(&package).cyan(),
+            style(pkg.version.to_string()).dim(),
+            why
+        );
+        return Ok(());
+    };
+
+    println!(
+        "{} {} {} → {} ({})",
+        style("ℹ").blue().bold(),
+        style(&release.name).cyan(),
+        style(release.old_version.to_string()).dim(),
+        style(release.new_version.to_string()).green(),
+        style(release.bump.to_string()).yellow()
+    );
+
+    let changelog_reasons: Vec<_> = release
+        .reasons
+        .iter()
+        .filter(|r| matches!(r, BumpReason::Changelog { .. }))
+        .collect();
+    let group_reasons: Vec<_> = release
+        .reasons
+        .iter()
+        .filter(|r| matches!(r, BumpReason::Fixed { .. } | BumpReason::Linked { .. }))
+        .collect();
+    let dependent_reasons: Vec<_> = release
+        .reasons
+        .iter()
+        .filter(|r| matches!(r, BumpReason::Dependent { .. }))
+        .collect();
+
+    if !changelog_reasons.is_empty() {
+        println!("\n{}", style("Changelogs:").bold().underlined());
+        for reason in changelog_reasons {
+            if let BumpReason::Changelog { id, bump } = reason {
+                println!(
+                    "  {} {} ({}) {}",
+                    style("•").dim(),
+                    style(id).cyan(),
+                    style(bump.to_string()).yellow(),
+                    style(changelog_path(id)).dim()
+                );
+                let summary = first_line(id);
+                if !summary.is_empty() {
+                    println!("    {}", style(summary).dim());
+                }
+            }
+        }
+    }
+
+    if !group_reasons.is_empty() {
+        println!("\n{}", style("Groups:").bold().underlined());
+        for reason in group_reasons {
+            let (kind, members) = match reason {
+                BumpReason::Fixed { members, .. } => ("fixed", members),
+                BumpReason::Linked { members, .. } => ("linked", members),
+                _ => continue,
+            };
+            println!(
+                "  {} {}: {} ({})",
+                style("•").dim(),
+                kind,
+                members.join(", "),
+                style(reason.bump().to_string()).yellow()
+            );
+        }
+    }
+
+    if !dependent_reasons.is_empty() {
+        println!("\n{}", style("Dependencies:").bold().underlined());
+        for reason in dependent_reasons {
+            if let BumpReason::Dependent {
+                dependency,
+                path,
+                bump,
+            } = reason
+            {
+                println!(
+                    "  {} {} ({}) via {}",
+                    style("•").dim(),
+                    style(dependency).cyan(),
+                    style(bump.to_string()).yellow(),
+                    path.join(" → ")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,16 +1,53 @@
+use crate::BumpType;
 use crate::ecosystems::Ecosystem;
 use crate::error::{Error, Result};
+use crate::fs_util::atomic_write;
+use crate::versioning::ZeroMajor;
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
+use toml_edit::{DocumentMut, Item, Table, TableLike};
+use tracing::warn;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Schema version of this file. Newer than [`CONFIG_VERSION`] means it was
+    /// written for a later changelogs, whose keys this one may not understand.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+
     #[serde(default)]
     pub ecosystem: Option<Ecosystem>,
 
+    /// Versions of changelogs this repo works with, e.g. `>=0.4, <0.6`. Checked by
+    /// every command through [`check_required_version`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_version: Option<VersionReq>,
+
     #[serde(default = "default_dependent_bump")]
     pub dependent_bump: DependentBump,
 
+    /// Also bump packages that only use a released package as a dev or build
+    /// dependency.
+    #[serde(default)]
+    pub propagate_dev_dependencies: bool,
+
+    /// How bumps apply below `1.0.0`: as written, or shifted down a level the way
+    /// Cargo treats `0.x` versions.
+    #[serde(default)]
+    pub zero_major: ZeroMajor,
+
+    /// Make `version` fail instead of warning when the release plan has problems, or
+    /// when [`Config::validate`] finds any, such as a group naming a removed package.
+    #[serde(default)]
+    pub strict: bool,
+
+    /// Ask the registry whether it is ahead of the manifests, in `version` and
+    /// `doctor`. Off for offline or air-gapped work.
+    #[serde(default = "default_true")]
+    pub check_registry: bool,
+
     #[serde(default)]
     pub changelog: ChangelogConfig,
 
@@ -25,6 +62,327 @@ pub struct Config {
 
     #[serde(default)]
     pub ai: AiConfig,
+
+    /// Branch that `add --ai` diffs against when there is no `--ref` and the
+    /// working tree has no staged or unstaged changes (e.g. in CI).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+
+    #[serde(default)]
+    pub verify: VerifyConfig,
+
+    #[serde(default)]
+    pub rust: RustConfig,
+
+    #[serde(default)]
+    pub python: PythonConfig,
+
+    #[serde(default)]
+    pub go: GoConfig,
+
+    #[serde(default)]
+    pub dotnet: DotnetConfig,
+
+    #[serde(default)]
+    pub git: GitConfig,
+
+    #[serde(default)]
+    pub github: GitHubConfig,
+
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    #[serde(default)]
+    pub lint: LintConfig,
+
+    /// Release channels. Each one keeps its pending changelogs in `.changelog/<name>/`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<String>,
+
+    /// Per-channel overrides, keyed by channel name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub channel: BTreeMap<String, ChannelConfig>,
+
+    /// Per-package overrides, merged over the settings above for that package.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PackageConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageConfig {
+    pub name: String,
+
+    /// Release tag, with `{name}` and `{version}` replaced (default: the
+    /// ecosystem's tag, e.g. `{name}@{version}`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_template: Option<String>,
+
+    /// Changelog file, relative to the package directory (default: `CHANGELOG.md`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changelog_path: Option<String>,
+
+    /// Smallest bump the package is released with, e.g. `minor` for a package
+    /// whose every release should be a minor one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_bump: Option<BumpType>,
+
+    /// Replaces `changelog.format` for this package, e.g. `root` for an app whose
+    /// changes belong in the root CHANGELOG.md next to per-crate libraries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<ChangelogFormat>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ChannelConfig {
+    /// Replaces the top-level `dependent_bump` for this channel.
+    #[serde(default)]
+    pub dependent_bump: Option<DependentBump>,
+
+    /// Replaces `changelog.format` for this channel.
+    #[serde(default)]
+    pub format: Option<ChangelogFormat>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GitConfig {
+    /// Sign release tags with `git tag -s`. Falls back to git's `tag.gpgSign` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sign_tags: Option<bool>,
+
+    /// Release commit message; see [`GitHubConfig`] for the tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_message: Option<String>,
+}
+
+/// Release pull request text. Templates may use `{packages}` (`name@version`, comma
+/// separated), `{count}`, `{date}` (`YYYY-MM-DD`) and `{table}` (a Markdown table of
+/// the releases); any other `{...}` is left as written.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GitHubConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_title: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_body_template: Option<String>,
+}
+
+/// Shell commands `publish` runs around each package, from the package directory
+/// with `CHANGELOGS_PACKAGE_NAME` and `CHANGELOGS_PACKAGE_VERSION` set.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HooksConfig {
+    /// Runs before the package is published; a failure skips the package.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_publish: Option<String>,
+
+    /// Runs after the package was published.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_publish: Option<String>,
+}
+
+/// House-style checks on changelog entries. `add` runs them before writing an entry,
+/// `lint` and `doctor` on every pending one.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LintConfig {
+    /// Runs from the workspace root with the entry's markdown on stdin; a non-zero
+    /// exit rejects the entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+
+    /// Rules checked without a script, e.g. `["max-summary-lines=10", "require-issue-ref"]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub builtin: Vec<LintRule>,
+}
+
+/// A built-in lint rule, written as `name` or `name=limit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum LintRule {
+    /// At most this many non-blank lines per summary.
+    MaxSummaryLines(usize),
+    /// No summary line longer than this many characters.
+    MaxLineLength(usize),
+    /// No summary line ending with a period.
+    NoTrailingPeriod,
+    /// The entry mentions an issue or pull request (`#123` or a GitHub link).
+    RequireIssueRef,
+}
+
+impl std::str::FromStr for LintRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (name, limit) = match s.split_once('=') {
+            Some((name, limit)) => (name.trim(), Some(limit.trim())),
+            None => (s.trim(), None),
+        };
+        let number = |limit: Option<&str>| {
+            limit
+                .and_then(|l| l.parse::<usize>().ok())
+                .ok_or_else(|| format!("lint rule `{}` needs a number, e.g. `{}=10`", name, name))
+        };
+        match (name, limit) {
+            ("max-summary-lines", l) => Ok(LintRule::MaxSummaryLines(number(l)?)),
+            ("max-line-length", l) => Ok(LintRule::MaxLineLength(number(l)?)),
+            ("no-trailing-period", None) => Ok(LintRule::NoTrailingPeriod),
+            ("require-issue-ref", None) => Ok(LintRule::RequireIssueRef),
+            ("no-trailing-period" | "require-issue-ref", Some(_)) => {
+                Err(format!("lint rule `{}` takes no value", name))
+            }
+            _ => Err(format!(
+                "unknown lint rule `{}` (expected max-summary-lines, max-line-length, \
+                 no-trailing-period or require-issue-ref)",
+                name
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for LintRule {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl std::fmt::Display for LintRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintRule::MaxSummaryLines(n) => write!(f, "max-summary-lines={}", n),
+            LintRule::MaxLineLength(n) => write!(f, "max-line-length={}", n),
+            LintRule::NoTrailingPeriod => write!(f, "no-trailing-period"),
+            LintRule::RequireIssueRef => write!(f, "require-issue-ref"),
+        }
+    }
+}
+
+impl From<LintRule> for String {
+    fn from(rule: LintRule) -> Self {
+        rule.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RustConfig {
+    #[serde(default)]
+    pub release_members: ReleaseMembers,
+
+    /// Build crates before uploading them (`cargo publish` without `--no-verify`).
+    #[serde(default)]
+    pub verify: bool,
+
+    /// Pass `--allow-dirty` to `cargo publish`.
+    #[serde(default)]
+    pub allow_dirty: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PythonConfig {
+    /// After `version`, refresh `uv.lock` (`uv lock --offline`) or `poetry.lock`
+    /// (`poetry lock`, with `--no-update` before Poetry 2) so it pins the new versions.
+    #[serde(default)]
+    pub update_lockfile: bool,
+
+    /// Keep `version` and `release` in the package's Sphinx `docs/conf.py` in step
+    /// with the package version.
+    #[serde(default = "default_true")]
+    pub sync_docs: bool,
+}
+
+impl Default for PythonConfig {
+    fn default() -> Self {
+        Self {
+            update_lockfile: false,
+            sync_docs: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GoConfig {
+    #[serde(default)]
+    pub package_names: GoPackageNames,
+
+    /// Files inside a module that hold its version, rewritten on each release.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub version_targets: Vec<VersionTarget>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DotnetConfig {
+    /// Globs, relative to the workspace root, of the project files to release.
+    #[serde(default = "default_dotnet_projects")]
+    pub projects: Vec<String>,
+}
+
+impl Default for DotnetConfig {
+    fn default() -> Self {
+        Self {
+            projects: default_dotnet_projects(),
+        }
+    }
+}
+
+/// How Go module paths become package names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum GoPackageNames {
+    /// The last path segment, e.g. `service` for `github.com/acme/service`.
+    #[default]
+    LastSegment,
+    /// The full module path.
+    ModulePath,
+}
+
+/// A file whose version is rewritten by a regex, e.g. `internal/version.go`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionTarget {
+    /// Path relative to the module directory.
+    pub path: String,
+
+    /// Regex whose `version` group (or first group) is replaced with the new version.
+    #[serde(default = "default_version_pattern")]
+    pub pattern: String,
+}
+
+/// Which Cargo workspace members are managed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseMembers {
+    /// Every workspace member.
+    #[default]
+    All,
+    /// Only `workspace.default-members` (all members if it is not set).
+    Default,
+}
+
+/// Controls how changed files are mapped to packages.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct VerifyConfig {
+    /// Glob patterns for files that never require a changelog, e.g. `**/tests/**`.
+    #[serde(default)]
+    pub exclude_paths: Vec<String>,
+
+    /// Per-package overrides, keyed by package name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub packages: BTreeMap<String, PackageVerifyConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PackageVerifyConfig {
+    /// Replaces the top-level `exclude_paths` for this package.
+    #[serde(default)]
+    pub exclude_paths: Option<Vec<String>>,
+}
+
+impl VerifyConfig {
+    /// Returns the exclude patterns that apply to `package`.
+    pub fn exclude_paths_for(&self, package: &str) -> &[String] {
+        self.packages
+            .get(package)
+            .and_then(|p| p.exclude_paths.as_deref())
+            .unwrap_or(&self.exclude_paths)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -39,18 +397,151 @@ pub enum DependentBump {
     Patch,
     Minor,
     None,
+    /// Release only the packages changelogs name: no dependent bumps, and neither
+    /// `[[fixed]]` and `[[linked]]` groups nor the root-format group pull in or
+    /// raise other members.
+    #[serde(rename = "strict-explicit")]
+    StrictExplicit,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChangelogConfig {
     #[serde(default = "default_changelog_format")]
     pub format: ChangelogFormat,
+
+    /// Inline summary tags mapped to section headers, e.g. `breaking = "Breaking Changes"`
+    /// routes lines starting with `[breaking]` into a "Breaking Changes" section.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+
+    /// Emoji prepended to the bump section headers, e.g. `### ✨ Minor Changes`.
+    #[serde(default, skip_serializing_if = "SectionEmoji::is_disabled")]
+    pub section_emoji: SectionEmoji,
+
+    /// Per-crate changelog name for the root package of a non-virtual workspace, used
+    /// when the root `CHANGELOG.md` is a root-format changelog. `{name}` is replaced
+    /// with the package name.
+    #[serde(default = "default_root_package_file")]
+    pub root_package_file: String,
+
+    /// Root format only: end each release with a table of every workspace package
+    /// and its version, including packages that were not released.
+    #[serde(default)]
+    pub include_unchanged: bool,
+
+    /// Packages (names or globs) whose CHANGELOG.md is maintained elsewhere. They are
+    /// still versioned and their entries consumed, but no changelog is written for them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip: Vec<String>,
+
+    /// Root format: still include the changes of skipped packages in the root CHANGELOG.md.
+    #[serde(default)]
+    pub include_skipped: bool,
+
+    /// How release sections are structured: by bump type, or keepachangelog.com.
+    #[serde(default)]
+    pub style: ChangelogStyle,
+
+    /// Order of the bullets within a section.
+    #[serde(default)]
+    pub sort: ChangelogSort,
+
+    /// Repository links point to, e.g. `wevm/changelogs-rs`, instead of the `origin`
+    /// remote's. `CHANGELOGS_REPO_URL` overrides it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_url: Option<String>,
+
+    /// Where pending entries live, relative to the workspace root, e.g. `.changesets`.
+    /// `config.toml` itself stays in `.changelog/` (or `--changelog-dir`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    /// Text written at the top of every release entry, below its heading, e.g. a
+    /// migration guide link. `{version}` is replaced with the new version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_prefix: Option<String>,
+
+    /// Also read PR numbers from `PR-URL:` (Gitea) and `Reviewed-on:` (Gerrit)
+    /// trailers in the body of the commit that added a changelog.
+    #[serde(default)]
+    pub pr_trailers: bool,
+}
+
+impl ChangelogConfig {
+    /// Whether `package` matches an entry in `skip`.
+    pub fn is_skipped(&self, package: &str) -> bool {
+        self.skip.iter().any(|pattern| {
+            pattern == package
+                || globset::Glob::new(pattern).is_ok_and(|g| g.compile_matcher().is_match(package))
+        })
+    }
 }
 
 impl Default for ChangelogConfig {
     fn default() -> Self {
         Self {
             format: default_changelog_format(),
+            tags: BTreeMap::new(),
+            section_emoji: SectionEmoji::default(),
+            root_package_file: default_root_package_file(),
+            include_unchanged: false,
+            skip: Vec::new(),
+            include_skipped: false,
+            style: ChangelogStyle::default(),
+            sort: ChangelogSort::default(),
+            repo_url: None,
+            dir: None,
+            entry_prefix: None,
+            pr_trailers: false,
+        }
+    }
+}
+
+/// `section_emoji = true` uses 💥 for major, ✨ for minor and 🐛 for patch; a table
+/// like `{ major = "🚨", minor = "🚀" }` picks its own (unlisted bumps get none).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SectionEmoji {
+    Enabled(bool),
+    Custom(SectionEmojiMap),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SectionEmojiMap {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub major: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<String>,
+}
+
+impl Default for SectionEmoji {
+    fn default() -> Self {
+        SectionEmoji::Enabled(false)
+    }
+}
+
+impl SectionEmoji {
+    pub fn is_disabled(&self) -> bool {
+        *self == SectionEmoji::Enabled(false)
+    }
+
+    /// Returns the emoji for a bump section, if any.
+    pub fn for_bump(&self, bump: BumpType) -> Option<&str> {
+        match self {
+            SectionEmoji::Enabled(false) => None,
+            SectionEmoji::Enabled(true) => Some(match bump {
+                BumpType::Major => "💥",
+                BumpType::Minor => "✨",
+                BumpType::Patch => "🐛",
+            }),
+            SectionEmoji::Custom(map) => match bump {
+                BumpType::Major => map.major.as_deref(),
+                BumpType::Minor => map.minor.as_deref(),
+                BumpType::Patch => map.patch.as_deref(),
+            },
         }
     }
 }
@@ -63,6 +554,30 @@ pub enum ChangelogFormat {
     Root,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangelogStyle {
+    /// `## 1.2.0 (2025-01-15)` with Major/Minor/Patch Changes sections.
+    #[default]
+    BumpTypes,
+    /// `## [1.2.0] - 2025-01-15` with the Added/Changed/Deprecated/Removed/Fixed/Security
+    /// sections of keepachangelog.com, below an `## [Unreleased]` section.
+    KeepAChangelog,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangelogSort {
+    /// By changelog file name.
+    #[default]
+    Id,
+    /// By the commit that added the changelog file (its mtime when uncommitted),
+    /// oldest first.
+    DateAdded,
+    /// Alphabetically by summary.
+    Summary,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FixedGroup {
     pub members: Vec<String>,
@@ -81,21 +596,126 @@ fn default_changelog_format() -> ChangelogFormat {
     ChangelogFormat::PerCrate
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_dotnet_projects() -> Vec<String> {
+    vec!["**/*.csproj".to_string()]
+}
+
+/// The newest `version` of `config.toml` this build understands.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Checks `running` against `required_version` in the `config.toml` of
+/// `changelog_dir`. Only that key is read, so other problems in the config are left
+/// to the command to report.
+pub fn check_required_version(changelog_dir: &Path, running: &Version) -> Result<()> {
+    let Ok(content) = std::fs::read_to_string(changelog_dir.join("config.toml")) else {
+        return Ok(());
+    };
+    let Ok(table) = content.parse::<toml::Table>() else {
+        return Ok(());
+    };
+    let Some(required) = table.get("required_version") else {
+        return Ok(());
+    };
+    let required = required
+        .as_str()
+        .ok_or_else(|| Error::ConfigParse("required_version must be a string".into()))?;
+    let req: VersionReq = required.parse().map_err(|e| {
+        Error::ConfigParse(format!("invalid required_version \"{}\": {}", required, e).into())
+    })?;
+    if !req.matches(running) {
+        return Err(Error::UnsupportedVersion {
+            required: req.to_string(),
+            running: running.to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn default_version_pattern() -> String {
+    r#"Version\s*=\s*"(?P<version>[^"]*)""#.to_string()
+}
+
+fn default_root_package_file() -> String {
+    "CHANGELOG-{name}.md".to_string()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: None,
             ecosystem: None,
+            required_version: None,
             dependent_bump: default_dependent_bump(),
+            propagate_dev_dependencies: false,
+            zero_major: ZeroMajor::Literal,
+            strict: false,
+            check_registry: true,
             changelog: ChangelogConfig::default(),
             fixed: Vec::new(),
             linked: Vec::new(),
             ignore: Vec::new(),
             ai: AiConfig::default(),
+            base_branch: None,
+            verify: VerifyConfig::default(),
+            rust: RustConfig::default(),
+            python: PythonConfig::default(),
+            go: GoConfig::default(),
+            dotnet: DotnetConfig::default(),
+            git: GitConfig::default(),
+            github: GitHubConfig::default(),
+            hooks: HooksConfig::default(),
+            lint: LintConfig::default(),
+            channels: Vec::new(),
+            channel: BTreeMap::new(),
+            packages: Vec::new(),
         }
     }
 }
 
 impl Config {
+    /// Returns the `[[packages]]` overrides for `name`, if any.
+    pub fn package(&self, name: &str) -> Option<&PackageConfig> {
+        self.packages.iter().find(|p| p.name == name)
+    }
+
+    /// Returns the changelog format for `name`: its `[[packages]]` override, or
+    /// `changelog.format`.
+    ///
+    /// Root-format packages are released as one product: they share the root
+    /// CHANGELOG.md, one version (an implicit fixed group) and one `v{version}` tag.
+    pub fn changelog_format(&self, name: &str) -> ChangelogFormat {
+        self.package(name)
+            .and_then(|p| p.format)
+            .unwrap_or(self.changelog.format)
+    }
+
+    /// Returns the config to use for `channel`, with its overrides applied.
+    /// `None` is the default channel and returns the config unchanged.
+    pub fn for_channel(&self, channel: Option<&str>) -> Result<Config> {
+        let Some(name) = channel else {
+            return Ok(self.clone());
+        };
+
+        if !self.channels.iter().any(|c| c == name) {
+            return Err(Error::UnknownChannel(name.to_string()));
+        }
+
+        let mut config = self.clone();
+        if let Some(overrides) = self.channel.get(name) {
+            if let Some(dependent_bump) = overrides.dependent_bump {
+                config.dependent_bump = dependent_bump;
+            }
+            if let Some(format) = overrides.format {
+                config.changelog.format = format;
+            }
+        }
+        Ok(config)
+    }
+
     pub fn load(changelog_dir: &Path) -> Result<Self> {
         let config_path = changelog_dir.join("config.toml");
 
@@ -104,32 +724,245 @@ impl Config {
         }
 
         let content = std::fs::read_to_string(&config_path)?;
-        let config: Config =
-            toml::from_str(&content).map_err(|e| Error::ConfigParse(e.to_string()))?;
+        let config: Config = toml::from_str(&content).map_err(|e| Error::ConfigParse(e.into()))?;
+        config.validate_groups()?;
+        if let Some(warning) = config.version_warning() {
+            warn!("{}", warning);
+        }
 
         Ok(config)
     }
 
+    /// Warns about a config written for a newer schema than [`CONFIG_VERSION`].
+    pub fn version_warning(&self) -> Option<String> {
+        let version = self.version.filter(|v| *v > CONFIG_VERSION)?;
+        Some(format!(
+            "config.toml has version = {}, but this changelogs ({}) only supports version {}; \
+             newer keys may be ignored, so upgrade changelogs",
+            version,
+            env!("CARGO_PKG_VERSION"),
+            CONFIG_VERSION
+        ))
+    }
+
+    /// Rejects `[[fixed]]` and `[[linked]]` groups that list a package more than once.
+    pub fn validate_groups(&self) -> Result<()> {
+        let fixed = self
+            .fixed
+            .iter()
+            .enumerate()
+            .map(|(i, g)| ("fixed", i, &g.members));
+        let linked = self
+            .linked
+            .iter()
+            .enumerate()
+            .map(|(i, g)| ("linked", i, &g.members));
+        let mut problems = Vec::new();
+        for (kind, i, members) in fixed.chain(linked) {
+            let mut seen = HashSet::new();
+            for member in members {
+                if !seen.insert(member) {
+                    problems.push(format!(
+                        "{} group {} lists '{}' more than once",
+                        kind,
+                        i + 1,
+                        member
+                    ));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            return Ok(());
+        }
+        let list: Vec<String> = problems.iter().map(|p| format!("  {}", p)).collect();
+        Err(Error::InvalidGroups(list.join("\n")))
+    }
+
+    /// Checks the config against the workspace's `package_names` and returns every
+    /// issue: fixed or linked members, `ignore` entries and `[[packages]]` overrides
+    /// that aren't packages, and ignored packages in groups.
+    pub fn validate(&self, package_names: &[&str]) -> std::result::Result<(), Vec<String>> {
+        let unknown = |names: &[String]| -> Vec<String> {
+            names
+                .iter()
+                .filter(|n| !package_names.contains(&n.as_str()))
+                .cloned()
+                .collect()
+        };
+
+        let fixed = self
+            .fixed
+            .iter()
+            .enumerate()
+            .map(|(i, g)| ("fixed", i, &g.members));
+        let linked = self
+            .linked
+            .iter()
+            .enumerate()
+            .map(|(i, g)| ("linked", i, &g.members));
+        let mut issues = self.unknown_group_members(package_names);
+        for (kind, i, members) in fixed.chain(linked) {
+            for member in members.iter().filter(|m| self.ignore.contains(m)) {
+                issues.push(format!(
+                    "'{}' is ignored but listed in {} group {}",
+                    member,
+                    kind,
+                    i + 1
+                ));
+            }
+        }
+
+        let missing = unknown(&self.ignore);
+        if !missing.is_empty() {
+            issues.push(format!(
+                "ignore references unknown packages: {}",
+                missing.join(", ")
+            ));
+        }
+        let overrides: Vec<String> = self.packages.iter().map(|p| p.name.clone()).collect();
+        for name in unknown(&overrides) {
+            issues.push(format!("[[packages]] entry for unknown package '{}'", name));
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// One issue per fixed or linked group member that isn't in `package_names`,
+    /// shared by [`Config::validate`] and the release plan so both word it the same.
+    pub fn unknown_group_members(&self, package_names: &[&str]) -> Vec<String> {
+        let groups = self.fixed.iter().map(|g| ("fixed", &g.members));
+        let groups = groups.chain(self.linked.iter().map(|g| ("linked", &g.members)));
+        groups
+            .flat_map(|(kind, members)| members.iter().map(move |m| (kind, m)))
+            .filter(|(_, member)| !package_names.contains(&member.as_str()))
+            .map(|(kind, member)| format!("{} group contains unknown package '{}'", kind, member))
+            .collect()
+    }
+
+    /// Group definitions that are valid but probably unintended: empty groups, and
+    /// packages in both a fixed and a linked group (the fixed group wins, and the
+    /// linked group leaves the package out).
+    pub fn group_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for (i, group) in self.fixed.iter().enumerate() {
+            if group.members.is_empty() {
+                warnings.push(format!("fixed group {} is empty", i + 1));
+            }
+        }
+        for (i, group) in self.linked.iter().enumerate() {
+            if group.members.is_empty() {
+                warnings.push(format!("linked group {} is empty", i + 1));
+            }
+        }
+        for (i, fixed) in self.fixed.iter().enumerate() {
+            for (j, linked) in self.linked.iter().enumerate() {
+                for member in fixed.members.iter().filter(|m| linked.members.contains(m)) {
+                    warnings.push(format!(
+                        "'{}' is in fixed group {} [{}] and linked group {} [{}]; the fixed group takes precedence",
+                        member,
+                        i + 1,
+                        fixed.members.join(", "),
+                        j + 1,
+                        linked.members.join(", ")
+                    ));
+                }
+            }
+        }
+        warnings
+    }
+
     pub fn save(&self, changelog_dir: &Path) -> Result<()> {
         let config_path = changelog_dir.join("config.toml");
-        let content =
-            toml::to_string_pretty(self).map_err(|e| Error::ConfigParse(e.to_string()))?;
-        std::fs::write(config_path, content)?;
+        let content = toml::to_string_pretty(self).map_err(|e| Error::ConfigParse(e.into()))?;
+        atomic_write(&config_path, content)?;
+        Ok(())
+    }
+
+    /// Returns the config as a TOML value, defaults included. Unset options are omitted.
+    pub fn to_value(&self) -> Result<toml::Value> {
+        toml::Value::try_from(self).map_err(|e| Error::ConfigParse(e.into()))
+    }
+
+    /// Sets a dotted `key` (e.g. `changelog.format`, `fixed.0.members`) in
+    /// `config.toml`, keeping comments and formatting.
+    ///
+    /// `raw` is parsed as a TOML value, or taken as a string if it isn't one. Nothing
+    /// is written unless the result is still a valid config that uses `key`.
+    pub fn set_key(changelog_dir: &Path, key: &str, raw: &str) -> Result<()> {
+        let config_path = changelog_dir.join("config.toml");
+        let content = if config_path.exists() {
+            std::fs::read_to_string(&config_path)?
+        } else {
+            String::new()
+        };
+
+        let mut doc: DocumentMut = content.parse()?;
+        let value = raw
+            .parse::<toml_edit::Value>()
+            .unwrap_or_else(|_| toml_edit::Value::from(raw));
+        let is_empty = match &value {
+            toml_edit::Value::Array(a) => a.is_empty(),
+            toml_edit::Value::InlineTable(t) => t.is_empty(),
+            _ => false,
+        };
+
+        let segments: Vec<&str> = key.split('.').collect();
+        if segments.iter().any(|s| s.is_empty()) {
+            return Err(Error::UnknownConfigKey(key.to_string()));
+        }
+        set_in_table(doc.as_table_mut(), key, &segments, value)?;
+
+        let config: Config =
+            toml::from_str(&doc.to_string()).map_err(|e| Error::ConfigParse(e.into()))?;
+        config.validate_groups()?;
+        // Unknown keys deserialize fine, so check that the key survives a round trip.
+        // Empty collections are not serialized, so they can't be checked this way.
+        if !is_empty && lookup(&config.to_value()?, key).is_none() {
+            return Err(Error::UnknownConfigKey(key.to_string()));
+        }
+
+        atomic_write(&config_path, doc.to_string())?;
         Ok(())
     }
 
     pub fn default_toml() -> &'static str {
-        r#"# Ecosystem: "rust" | "python" (auto-detected if not specified)
+        r#"# Schema version of this file
+version = 1
+
+# Ecosystem: "rust" | "python" | "go" | "dotnet" (auto-detected if not specified)
 # ecosystem = "rust"
 
+# Versions of changelogs this repo works with; other versions refuse to run
+# required_version = ">=0.6, <0.7"
+
 # How to bump packages that depend on changed packages
-# "patch" | "minor" | "none"
+# "patch" | "minor" | "none", or "strict-explicit" to release only the packages
+# changelogs name, without fixed, linked or root-format groups either
 dependent_bump = "patch"
 
+# Also bump packages that only use a changed package as a dev or build dependency
+# propagate_dev_dependencies = false
+
+# Bumps below 1.0.0: "literal" applies them as written, "shift" follows Cargo, where
+# a major bump on 0.3.1 releases 0.4.0 and a minor one 0.3.2
+# zero_major = "literal"
+
+# Fail `version` on release plan warnings or config problems (e.g. a group naming a removed package)
+# strict = false
+
+# Warn when the registry has a newer version than a manifest (needs network access)
+# check_registry = true
+
 # Packages to ignore
 ignore = []
 
-# Fixed groups: all packages always share the same version
+# Fixed groups: all packages always share the same version. A package in several
+# groups ties them together: they all get the highest bump among them.
 # [[fixed]]
 # members = ["package-a", "package-b"]
 
@@ -142,6 +975,124 @@ ignore = []
 # "root" - Single CHANGELOG.md at workspace root
 format = "per-crate"
 
+# Prefix section headers with emoji (💥 major, ✨ minor, 🐛 patch)
+# section_emoji = true
+
+# Per-crate changelog for the root package of a non-virtual workspace when the
+# root CHANGELOG.md is already a root-format changelog
+# root_package_file = "CHANGELOG-{name}.md"
+
+# Root format: list every package's version after each release, changed or not
+# include_unchanged = true
+
+# Packages (or globs) whose CHANGELOG.md is maintained elsewhere: they are still
+# versioned, but their changelog is never written
+# skip = ["docs-site"]
+# Root format: still list skipped packages' changes in the root CHANGELOG.md
+# include_skipped = false
+
+# "bump-types" - Major/Minor/Patch Changes sections under `## 1.2.0 (date)`
+# "keep-a-changelog" - Added/Changed/Deprecated/Removed/Fixed/Security under
+#   `## [1.2.0] - date`, below an `## [Unreleased]` section
+# style = "bump-types"
+
+# Order of the bullets within a section: "id" (file name), "date-added" (the
+# commit that added the changelog, oldest first) or "summary"
+# sort = "id"
+
+# GitHub repository for PR and commit links, e.g. when `origin` is a fork or CI
+# checks out without a remote (CHANGELOGS_REPO_URL overrides it)
+# repo_url = "owner/repo"
+# Also take PR numbers from `PR-URL:` (Gitea) or `Reviewed-on:` (Gerrit) commit trailers
+# pr_trailers = false
+
+# Keep pending entries somewhere other than .changelog/, relative to the workspace
+# root; this file stays in .changelog/
+# dir = ".changesets"
+
+# Text at the top of every release entry, below its heading; {version} is the new version
+# entry_prefix = "See the [migration guide](MIGRATING.md) for {version}."
+
+# Inline tags: summary lines starting with `[tag]` go into their own section
+# [changelog.tags]
+# breaking = "Breaking Changes"
+
+# Files that never require a changelog when mapping changes to packages
+# [verify]
+# exclude_paths = ["**/tests/**", "**/benches/**", "**/*.md"]
+#
+# [verify.packages.my-crate]
+# exclude_paths = ["examples/**"]
+
+# Rust: release every workspace member ("all") or only default-members ("default")
+# [rust]
+# release_members = "all"
+# Build crates before publishing; without it, compile errors only show up on crates.io
+# verify = false
+# Publish with uncommitted changes in the working tree
+# allow_dirty = false
+
+# Python: refresh uv.lock or poetry.lock after `version` bumps the versions
+# [python]
+# update_lockfile = true
+# Leave `version`/`release` in docs/conf.py alone
+# sync_docs = false
+
+# Go: name packages by the module path's last segment or the full path
+# [go]
+# package_names = "last-segment"  # or "module-path"
+# Files holding the module version, rewritten with the regex's `version` group
+# [[go.version_targets]]
+# path = "internal/version.go"
+# pattern = 'Version\s*=\s*"(?P<version>[^"]*)"'
+
+# .NET: project files to release, relative to the workspace root
+# [dotnet]
+# projects = ["src/**/*.csproj"]
+
+# Sign release tags created by `publish` (defaults to git's tag.gpgSign)
+# [git]
+# sign_tags = true
+# Release commit message in `status --json`; tokens: {packages}, {count}, {date}, {table}
+# commit_message = "chore: release {packages}"
+
+# Release pull request title and body in `status --json`, with the same tokens
+# [github]
+# pr_title = "chore: release {packages}"
+# pr_body_template = "Releases {count} package(s):\n\n{table}"
+
+# Commands `publish` runs in each package's directory, with CHANGELOGS_PACKAGE_NAME
+# and CHANGELOGS_PACKAGE_VERSION set; a failing pre_publish skips the package
+# [hooks]
+# pre_publish = "wasm-pack build"
+# post_publish = "./scripts/announce.sh"
+
+# Checks on changelog entries, run by `add` before writing one and by `lint` and
+# `doctor`: a command that gets the entry's markdown on stdin (non-zero exit
+# rejects it) and built-in rules (max-summary-lines=N, max-line-length=N,
+# no-trailing-period, require-issue-ref)
+# [lint]
+# command = "./scripts/lint-changelog.sh"
+# builtin = ["max-summary-lines=10", "require-issue-ref"]
+
+# Release channels: `changelogs add --channel beta` writes to .changelog/beta/
+# channels = ["beta"]
+#
+# [channel.beta]
+# dependent_bump = "minor"
+# format = "root"
+
+# Per-package overrides
+# [[packages]]
+# name = "my-crate"
+# tag_template = "{name}-v{version}"
+# changelog_path = "docs/CHANGELOG.md"
+# min_bump = "minor"
+# format = "root"  # overrides changelog.format
+
+# Base branch for `changelogs add --ai` when nothing is staged or modified
+# base_branch = "origin/main"
+
 # AI-assisted changelog generation
 # [ai]
 # command = "amp ask"  # or "gh copilot suggest -t shell"
@@ -149,6 +1100,82 @@ format = "per-crate"
     }
 }
 
+/// Returns the value at a dotted `key`. Numeric segments index into arrays.
+pub fn lookup<'a>(value: &'a toml::Value, key: &str) -> Option<&'a toml::Value> {
+    key.split('.')
+        .try_fold(value, |value, segment| match value {
+            toml::Value::Table(table) => table.get(segment),
+            toml::Value::Array(array) => array.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+fn set_in_table(
+    table: &mut dyn TableLike,
+    key: &str,
+    segments: &[&str],
+    value: toml_edit::Value,
+) -> Result<()> {
+    let (first, rest) = segments
+        .split_first()
+        .ok_or_else(|| Error::UnknownConfigKey(key.to_string()))?;
+
+    if rest.is_empty() {
+        // Replace in place so comments around the key and value survive.
+        if let Some(existing) = table.get_mut(first).and_then(|i| i.as_value_mut()) {
+            let decor = existing.decor().clone();
+            *existing = value;
+            *existing.decor_mut() = decor;
+        } else {
+            table.insert(first, Item::Value(value));
+        }
+        return Ok(());
+    }
+
+    if table.get(first).is_none() {
+        let mut child = Table::new();
+        child.set_implicit(true);
+        table.insert(first, Item::Table(child));
+    }
+    let child = table
+        .get_mut(first)
+        .ok_or_else(|| Error::UnknownConfigKey(key.to_string()))?;
+    set_in_item(child, key, rest, value)
+}
+
+fn set_in_item(
+    item: &mut Item,
+    key: &str,
+    segments: &[&str],
+    value: toml_edit::Value,
+) -> Result<()> {
+    let Ok(index) = segments[0].parse::<usize>() else {
+        let table = item
+            .as_table_like_mut()
+            .ok_or_else(|| Error::UnknownConfigKey(key.to_string()))?;
+        return set_in_table(table, key, segments, value);
+    };
+
+    let rest = &segments[1..];
+    let missing = || Error::UnknownConfigKey(key.to_string());
+    match item {
+        Item::ArrayOfTables(array) if !rest.is_empty() => {
+            set_in_table(array.get_mut(index).ok_or_else(missing)?, key, rest, value)
+        }
+        Item::Value(toml_edit::Value::Array(array)) => {
+            let element = array.get_mut(index).ok_or_else(missing)?;
+            if rest.is_empty() {
+                *element = value;
+                Ok(())
+            } else {
+                let table = element.as_inline_table_mut().ok_or_else(missing)?;
+                set_in_table(table, key, rest, value)
+            }
+        }
+        _ => Err(missing()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,15 +1194,222 @@ mod tests {
         assert!(config.ignore.is_empty());
     }
 
+    #[test]
+    fn test_load_rejects_duplicate_group_members() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("config.toml"),
+            "[[fixed]]\nmembers = [\"a\", \"b\", \"a\"]\n\n[[linked]]\nmembers = [\"x\", \"x\"]\n",
+        )
+        .unwrap();
+
+        let err = Config::load(dir.path()).unwrap_err();
+
+        assert!(matches!(err, Error::InvalidGroups(_)), "{err}");
+        let message = err.to_string();
+        assert!(
+            message.contains("fixed group 1 lists 'a' more than once"),
+            "{message}"
+        );
+        assert!(
+            message.contains("linked group 1 lists 'x' more than once"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn test_load_allows_package_in_two_fixed_groups() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("config.toml"),
+            "[[fixed]]\nmembers = [\"a\", \"b\"]\n\n[[fixed]]\nmembers = [\"b\", \"c\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::load(dir.path()).unwrap();
+
+        assert_eq!(config.fixed.len(), 2);
+        assert!(config.group_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_set_key_rejects_duplicate_group_members() {
+        let dir = TempDir::new().unwrap();
+        let original = "[[fixed]]\nmembers = [\"a\", \"b\"]\n\n[[fixed]]\nmembers = [\"c\"]\n";
+        std::fs::write(dir.path().join("config.toml"), original).unwrap();
+
+        let err = Config::set_key(dir.path(), "fixed.1.members", "[\"c\", \"c\"]").unwrap_err();
+
+        assert!(matches!(err, Error::InvalidGroups(_)), "{err}");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("config.toml")).unwrap(),
+            original
+        );
+    }
+
+    #[test]
+    fn test_group_warnings() {
+        let config: Config = toml::from_str(
+            r#"
+[[fixed]]
+members = ["a", "b"]
+
+[[fixed]]
+members = []
+
+[[linked]]
+members = ["b", "x"]
+
+[[linked]]
+members = []
+"#,
+        )
+        .unwrap();
+
+        assert!(config.validate_groups().is_ok());
+        assert_eq!(
+            config.group_warnings(),
+            [
+                "fixed group 2 is empty",
+                "linked group 2 is empty",
+                "'b' is in fixed group 1 [a, b] and linked group 1 [b, x]; the fixed group takes precedence",
+            ]
+        );
+        assert!(Config::default().group_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_check_required_version() {
+        let dir = TempDir::new().unwrap();
+        let running = Version::new(0, 5, 2);
+        let check = |content: &str| {
+            std::fs::write(dir.path().join("config.toml"), content).unwrap();
+            check_required_version(dir.path(), &running)
+        };
+
+        assert!(check("").is_ok());
+        assert!(check("required_version = \">=0.4, <0.6\"\n").is_ok());
+        assert!(check("required_version = \"0.5\"\n").is_ok());
+        // Problems elsewhere in the config are left to the command.
+        assert!(check("dependent_bump = 3\nrequired_version = \"0.5\"\n").is_ok());
+
+        let err = check("required_version = \">=0.6\"\n").unwrap_err();
+        assert!(matches!(err, Error::UnsupportedVersion { .. }), "{err}");
+        assert!(err.to_string().contains(">=0.6"), "{err}");
+        assert!(err.to_string().contains("0.5.2"), "{err}");
+
+        for malformed in ["required_version = \"latest\"\n", "required_version = 5\n"] {
+            let err = check(malformed).unwrap_err();
+            assert!(
+                matches!(&err, Error::ConfigParse(cause) if cause.to_string().contains("required_version")),
+                "{malformed}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dependent_bump_strict_explicit() {
+        let config: Config = toml::from_str("dependent_bump = \"strict-explicit\"\n").unwrap();
+        assert_eq!(config.dependent_bump, DependentBump::StrictExplicit);
+    }
+
+    #[test]
+    fn test_version_warning() {
+        assert_eq!(Config::default().version_warning(), None);
+
+        let current: Config = toml::from_str("version = 1\n").unwrap();
+        assert_eq!(current.version, Some(CONFIG_VERSION));
+        assert_eq!(current.version_warning(), None);
+
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("config.toml"),
+            "version = 2\nfuture_key = true\n",
+        )
+        .unwrap();
+        let future = Config::load(dir.path()).unwrap();
+        let warning = future.version_warning().unwrap();
+        assert!(warning.contains("version = 2"), "{warning}");
+        assert!(warning.contains("upgrade changelogs"), "{warning}");
+    }
+
+    #[test]
+    fn test_validate() {
+        let packages = ["a", "b", "c"];
+        assert_eq!(Config::default().validate(&packages), Ok(()));
+
+        let config: Config = toml::from_str(
+            r#"
+ignore = ["c", "gone"]
+
+[[fixed]]
+members = ["a", "old"]
+
+[[linked]]
+members = ["b", "c"]
+
+[[linked]]
+members = []
+
+[[packages]]
+name = "renamed"
+min_bump = "minor"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.validate(&packages),
+            Err(vec![
+                "fixed group contains unknown package 'old'".to_string(),
+                "'c' is ignored but listed in linked group 1".to_string(),
+                "ignore references unknown packages: gone".to_string(),
+                "[[packages]] entry for unknown package 'renamed'".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_leaves_group_overlaps_to_warnings() {
+        let config: Config = toml::from_str(
+            "[[fixed]]\nmembers = [\"a\", \"b\"]\n\n[[linked]]\nmembers = [\"b\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.validate(&["a", "b"]), Ok(()));
+        assert_eq!(config.group_warnings().len(), 1);
+    }
+
     #[test]
     fn test_save_then_load_roundtrip() {
         let dir = TempDir::new().unwrap();
 
         let config = Config {
+            version: Some(1),
             ecosystem: None,
+            required_version: Some(">=0.4, <0.6".parse().unwrap()),
             dependent_bump: DependentBump::Minor,
+            propagate_dev_dependencies: true,
+            zero_major: ZeroMajor::Shift,
+            strict: true,
+            check_registry: false,
             changelog: ChangelogConfig {
                 format: ChangelogFormat::Root,
+                tags: BTreeMap::from([("breaking".into(), "Breaking Changes".into())]),
+                section_emoji: SectionEmoji::Custom(SectionEmojiMap {
+                    minor: Some("🚀".into()),
+                    ..SectionEmojiMap::default()
+                }),
+                root_package_file: "HISTORY-{name}.md".into(),
+                include_unchanged: true,
+                skip: vec!["docs-*".into()],
+                include_skipped: true,
+                style: ChangelogStyle::KeepAChangelog,
+                sort: ChangelogSort::DateAdded,
+                repo_url: Some("wevm/changelogs-rs".into()),
+                dir: Some(".changesets".into()),
+                entry_prefix: Some("See the [migration guide](MIGRATING.md#{version}).".into()),
+                pr_trailers: true,
             },
             fixed: vec![FixedGroup {
                 members: vec!["a".into(), "b".into()],
@@ -187,19 +1421,135 @@ mod tests {
             ai: AiConfig {
                 command: Some("test-cmd".into()),
             },
+            base_branch: Some("develop".into()),
+            verify: VerifyConfig::default(),
+            rust: RustConfig {
+                release_members: ReleaseMembers::Default,
+                verify: true,
+                allow_dirty: true,
+            },
+            go: GoConfig {
+                package_names: GoPackageNames::ModulePath,
+                version_targets: vec![VersionTarget {
+                    path: "internal/version.go".into(),
+                    pattern: default_version_pattern(),
+                }],
+            },
+            python: PythonConfig {
+                update_lockfile: true,
+                sync_docs: false,
+            },
+            dotnet: DotnetConfig {
+                projects: vec!["src/**/*.csproj".into()],
+            },
+            git: GitConfig {
+                sign_tags: Some(true),
+                commit_message: Some("release: {packages}".into()),
+            },
+            github: GitHubConfig {
+                pr_title: Some("Release {count} packages".into()),
+                pr_body_template: None,
+            },
+            hooks: HooksConfig {
+                pre_publish: Some("make wasm".into()),
+                post_publish: None,
+            },
+            lint: LintConfig {
+                command: Some("./lint.sh".into()),
+                builtin: vec![LintRule::MaxSummaryLines(10), LintRule::RequireIssueRef],
+            },
+            channels: vec!["beta".into()],
+            channel: BTreeMap::from([(
+                "beta".into(),
+                ChannelConfig {
+                    dependent_bump: Some(DependentBump::None),
+                    format: None,
+                },
+            )]),
+            packages: vec![PackageConfig {
+                name: "a".into(),
+                tag_template: Some("{name}-v{version}".into()),
+                changelog_path: None,
+                min_bump: Some(BumpType::Minor),
+                format: Some(ChangelogFormat::PerCrate),
+            }],
         };
 
         config.save(dir.path()).unwrap();
         let loaded = Config::load(dir.path()).unwrap();
 
         assert_eq!(loaded.dependent_bump, DependentBump::Minor);
+        assert!(loaded.propagate_dev_dependencies);
+        assert_eq!(loaded.zero_major, ZeroMajor::Shift);
+        assert!(!loaded.check_registry);
         assert_eq!(loaded.changelog.format, ChangelogFormat::Root);
+        assert_eq!(loaded.changelog.tags["breaking"], "Breaking Changes");
+        assert_eq!(
+            loaded.changelog.section_emoji.for_bump(BumpType::Minor),
+            Some("🚀")
+        );
+        assert_eq!(
+            loaded.changelog.section_emoji.for_bump(BumpType::Major),
+            None
+        );
+        assert_eq!(loaded.changelog.root_package_file, "HISTORY-{name}.md");
+        assert!(loaded.changelog.include_unchanged);
+        assert_eq!(loaded.changelog.skip, ["docs-*"]);
+        assert!(loaded.changelog.include_skipped);
+        assert_eq!(loaded.changelog.style, ChangelogStyle::KeepAChangelog);
+        assert_eq!(loaded.changelog.sort, ChangelogSort::DateAdded);
+        assert_eq!(loaded.changelog.dir.as_deref(), Some(".changesets"));
+        assert_eq!(loaded.changelog.entry_prefix, config.changelog.entry_prefix);
+        assert!(loaded.changelog.pr_trailers);
+        assert_eq!(
+            loaded.changelog.repo_url.as_deref(),
+            Some("wevm/changelogs-rs")
+        );
+        assert!(loaded.rust.verify);
+        assert!(loaded.rust.allow_dirty);
+        assert_eq!(loaded.go.package_names, GoPackageNames::ModulePath);
+        assert_eq!(loaded.go.version_targets, config.go.version_targets);
+        assert!(loaded.python.update_lockfile);
+        assert!(!loaded.python.sync_docs);
+        assert_eq!(loaded.dotnet.projects, ["src/**/*.csproj"]);
+        assert_eq!(
+            loaded.required_version.as_ref().unwrap().to_string(),
+            ">=0.4, <0.6"
+        );
+        assert!(loaded.strict);
+        assert_eq!(loaded.version, Some(1));
         assert_eq!(loaded.fixed.len(), 1);
         assert_eq!(loaded.fixed[0].members, vec!["a", "b"]);
         assert_eq!(loaded.linked.len(), 1);
         assert_eq!(loaded.linked[0].members, vec!["x", "y"]);
         assert_eq!(loaded.ignore, vec!["foo"]);
         assert_eq!(loaded.ai.command.as_deref(), Some("test-cmd"));
+        assert_eq!(loaded.base_branch.as_deref(), Some("develop"));
+        assert_eq!(loaded.rust.release_members, ReleaseMembers::Default);
+        assert_eq!(loaded.git.sign_tags, Some(true));
+        assert_eq!(
+            loaded.git.commit_message.as_deref(),
+            Some("release: {packages}")
+        );
+        assert_eq!(
+            loaded.github.pr_title.as_deref(),
+            Some("Release {count} packages")
+        );
+        assert_eq!(loaded.github.pr_body_template, None);
+        assert_eq!(loaded.hooks.pre_publish.as_deref(), Some("make wasm"));
+        assert_eq!(loaded.hooks.post_publish, None);
+        assert_eq!(loaded.lint.command.as_deref(), Some("./lint.sh"));
+        assert_eq!(loaded.lint.builtin, config.lint.builtin);
+        assert_eq!(loaded.channels, vec!["beta"]);
+        assert_eq!(
+            loaded.channel["beta"].dependent_bump,
+            Some(DependentBump::None)
+        );
+        assert_eq!(loaded.packages, config.packages);
+        assert_eq!(loaded.package("a").unwrap().min_bump, Some(BumpType::Minor));
+        assert_eq!(loaded.changelog_format("a"), ChangelogFormat::PerCrate);
+        assert_eq!(loaded.changelog_format("b"), ChangelogFormat::Root);
+        assert!(loaded.package("b").is_none());
     }
 
     #[test]
@@ -241,4 +1591,169 @@ mod tests {
         assert!(config.linked.is_empty());
         assert!(config.ignore.is_empty());
     }
+
+    #[test]
+    fn test_lint_rules() {
+        let config: Config = toml::from_str(
+            "[lint]\nbuiltin = [\"max-summary-lines=10\", \"max-line-length = 72\", \"no-trailing-period\", \"require-issue-ref\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.lint.builtin,
+            [
+                LintRule::MaxSummaryLines(10),
+                LintRule::MaxLineLength(72),
+                LintRule::NoTrailingPeriod,
+                LintRule::RequireIssueRef,
+            ]
+        );
+
+        for (rule, message) in [
+            ("max-summary-lines", "needs a number"),
+            ("max-line-length=long", "needs a number"),
+            ("require-issue-ref=1", "takes no value"),
+            ("imperative-mood", "unknown lint rule"),
+        ] {
+            let err = toml::from_str::<Config>(&format!("[lint]\nbuiltin = [\"{}\"]\n", rule))
+                .unwrap_err();
+            assert!(err.to_string().contains(message), "{rule}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_verify_exclude_paths_per_package_override() {
+        let config: Config = toml::from_str(
+            r#"
+[verify]
+exclude_paths = ["**/*.md"]
+
+[verify.packages.core]
+exclude_paths = ["benches/**"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.verify.exclude_paths_for("utils"), ["**/*.md"]);
+        assert_eq!(config.verify.exclude_paths_for("core"), ["benches/**"]);
+    }
+
+    #[test]
+    fn test_changelog_skip_globs() {
+        let config: Config = toml::from_str(
+            r#"
+[changelog]
+skip = ["crate-a", "docs-*"]
+"#,
+        )
+        .unwrap();
+
+        assert!(config.changelog.is_skipped("crate-a"));
+        assert!(config.changelog.is_skipped("docs-site"));
+        assert!(!config.changelog.is_skipped("crate-b"));
+    }
+
+    #[test]
+    fn test_for_channel_applies_overrides() {
+        let config: Config = toml::from_str(
+            r#"
+            dependent_bump = "patch"
+            channels = ["beta", "nightly"]
+
+            [channel.beta]
+            dependent_bump = "minor"
+            format = "root"
+            "#,
+        )
+        .unwrap();
+
+        let default = config.for_channel(None).unwrap();
+        assert_eq!(default.dependent_bump, DependentBump::Patch);
+        assert_eq!(default.changelog.format, ChangelogFormat::PerCrate);
+
+        let beta = config.for_channel(Some("beta")).unwrap();
+        assert_eq!(beta.dependent_bump, DependentBump::Minor);
+        assert_eq!(beta.changelog.format, ChangelogFormat::Root);
+
+        // Declared without overrides: inherits everything.
+        let nightly = config.for_channel(Some("nightly")).unwrap();
+        assert_eq!(nightly.dependent_bump, DependentBump::Patch);
+
+        assert!(matches!(
+            config.for_channel(Some("alpha")),
+            Err(Error::UnknownChannel(name)) if name == "alpha"
+        ));
+    }
+
+    #[test]
+    fn test_set_key_round_trip_keeps_comments() {
+        let dir = TempDir::new().unwrap();
+        let original = "# How to bump dependents\ndependent_bump = \"patch\"  # patch, minor, or none\n\n[changelog]\nformat = \"per-crate\"\n\n[[fixed]]\nmembers = [\"a\", \"b\"]\n";
+        std::fs::write(dir.path().join("config.toml"), original).unwrap();
+
+        Config::set_key(dir.path(), "dependent_bump", "minor").unwrap();
+        Config::set_key(dir.path(), "changelog.format", "root").unwrap();
+        Config::set_key(dir.path(), "fixed.0.members", "[\"a\", \"c\"]").unwrap();
+        Config::set_key(dir.path(), "git.sign_tags", "true").unwrap();
+
+        let content = std::fs::read_to_string(dir.path().join("config.toml")).unwrap();
+        assert_eq!(
+            content,
+            "# How to bump dependents\ndependent_bump = \"minor\"  # patch, minor, or none\n\n[changelog]\nformat = \"root\"\n\n[[fixed]]\nmembers = [\"a\", \"c\"]\n\n[git]\nsign_tags = true\n"
+        );
+
+        let value = Config::load(dir.path()).unwrap().to_value().unwrap();
+        assert_eq!(
+            lookup(&value, "dependent_bump").unwrap().as_str(),
+            Some("minor")
+        );
+        assert_eq!(
+            lookup(&value, "changelog.format").unwrap().as_str(),
+            Some("root")
+        );
+        assert_eq!(
+            lookup(&value, "fixed.0.members.1").unwrap().as_str(),
+            Some("c")
+        );
+        assert_eq!(
+            lookup(&value, "git.sign_tags").unwrap().as_bool(),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_set_key_rejects_invalid_values_and_keys() {
+        let dir = TempDir::new().unwrap();
+        let original = "dependent_bump = \"patch\"\n";
+        std::fs::write(dir.path().join("config.toml"), original).unwrap();
+
+        let err = Config::set_key(dir.path(), "dependent_bump", "sometimes").unwrap_err();
+        assert!(matches!(err, Error::ConfigParse(msg) if msg.to_string().contains("sometimes")));
+
+        let err = Config::set_key(dir.path(), "changelog.colour", "red").unwrap_err();
+        assert!(matches!(err, Error::UnknownConfigKey(key) if key == "changelog.colour"));
+
+        let err = Config::set_key(dir.path(), "fixed.3.members", "[]").unwrap_err();
+        assert!(matches!(err, Error::UnknownConfigKey(_)));
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("config.toml")).unwrap(),
+            original
+        );
+    }
+
+    #[test]
+    fn test_section_emoji() {
+        let config: Config = toml::from_str("[changelog]\nsection_emoji = true\n").unwrap();
+        assert_eq!(
+            config.changelog.section_emoji.for_bump(BumpType::Major),
+            Some("💥")
+        );
+        assert_eq!(
+            config.changelog.section_emoji.for_bump(BumpType::Patch),
+            Some("🐛")
+        );
+
+        assert!(Config::default().changelog.section_emoji.is_disabled());
+        assert!(toml::from_str::<Config>("[changelog.section_emoji]\nmajr = \"x\"\n").is_err());
+    }
 }
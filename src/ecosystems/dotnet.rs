@@ -0,0 +1,626 @@
+use crate::ecosystems::{
+    Dependency, Ecosystem, EcosystemAdapter, Package, PublishOptions, PublishResult, SkipReason,
+    command_line, http_agent,
+};
+use crate::error::{Error, Result};
+use crate::fs_util::{read_text, write_text};
+use globset::{GlobBuilder, GlobSetBuilder};
+use regex::Regex;
+use semver::Version;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use tracing::debug;
+
+const NUGET_FLAT_CONTAINER: &str = "https://api.nuget.org/v3-flatcontainer";
+const NUGET_SOURCE: &str = "https://api.nuget.org/v3/index.json";
+
+/// SDK-style .NET projects (`*.csproj`) published to NuGet.
+///
+/// A project's package id is its `<PackageId>`, else `<AssemblyName>`, else the file
+/// name. Projects without a `<Version>` or `<PackageVersion>` (test projects, apps)
+/// are not released. Manifests are edited as text so comments and formatting
+/// survive.
+pub struct DotnetAdapter;
+
+impl EcosystemAdapter for DotnetAdapter {
+    fn ecosystem() -> Ecosystem {
+        Ecosystem::Dotnet
+    }
+
+    fn discover(root: &Path) -> Result<Vec<Package>> {
+        Self::discover_projects(root, &["**/*.csproj".to_string()])
+    }
+
+    fn read_version(manifest_path: &Path) -> Result<Version> {
+        let (content, _) = read_text(manifest_path)?;
+        let version = property(&content, "Version")
+            .or_else(|| property(&content, "PackageVersion"))
+            .ok_or_else(|| Error::VersionNotFound(manifest_path.display().to_string()))?;
+        Ok(content[version].trim().parse()?)
+    }
+
+    /// Rewrites `<Version>` and, when present, `<PackageVersion>`.
+    fn write_version(manifest_path: &Path, version: &Version) -> Result<()> {
+        let (content, bom) = read_text(manifest_path)?;
+        let mut ranges: Vec<Range<usize>> = ["Version", "PackageVersion"]
+            .iter()
+            .filter_map(|name| property(&content, name))
+            .collect();
+        if ranges.is_empty() {
+            return Err(Error::VersionNotFound(manifest_path.display().to_string()));
+        }
+
+        ranges.sort_by_key(|r| std::cmp::Reverse(r.start));
+        let mut updated = content.clone();
+        for range in ranges {
+            updated.replace_range(range, &version.to_string());
+        }
+        write_text(manifest_path, &updated, bom)
+    }
+
+    /// Rewrites the version of the `<PackageReference>` to `dep_name`, if any.
+    fn update_dependency_version(
+        manifest_path: &Path,
+        dep_name: &str,
+        new_version: &Version,
+    ) -> Result<bool> {
+        let (content, bom) = read_text(manifest_path)?;
+        let Some(updated) = Self::rewrite_package_reference(&content, dep_name, new_version) else {
+            return Ok(false);
+        };
+        write_text(manifest_path, &updated, bom)?;
+        Ok(true)
+    }
+
+    fn is_published(name: &str, version: &Version) -> Result<bool> {
+        Ok(Self::fetch_index(name)?.is_some_and(|json| Self::index_contains(&json, version)))
+    }
+
+    fn latest_published(name: &str) -> Result<Option<Version>> {
+        Ok(Self::fetch_index(name)?.and_then(|json| Self::latest_in_index(&json)))
+    }
+
+    /// Packs the project into a temp directory and pushes the package with
+    /// `NUGET_API_KEY`, to `options.registry` or nuget.org.
+    fn publish(pkg: &Package, dry_run: bool, options: &PublishOptions) -> Result<PublishResult> {
+        if dry_run {
+            return Ok(PublishResult::Success);
+        }
+
+        let api_key = match std::env::var("NUGET_API_KEY") {
+            Ok(key) if !key.is_empty() => key,
+            _ => {
+                debug!(package = %pkg.name, "NUGET_API_KEY is not set, skipping");
+                return Ok(PublishResult::Skipped(SkipReason::NoToken));
+            }
+        };
+
+        let out_dir = tempfile::TempDir::new()?;
+        let mut pack = Command::new("dotnet");
+        pack.arg("pack")
+            .arg(&pkg.manifest_path)
+            .args(["--configuration", "Release", "--output"])
+            .arg(out_dir.path());
+        debug!(command = %command_line(&pack, &[]), "running");
+        let pack_output = pack
+            .output()
+            .map_err(|e| Error::PublishFailed(format!("failed to run 'dotnet pack': {}", e)))?;
+
+        if !pack_output.status.success() {
+            return Err(Error::PublishFailed(format!(
+                "dotnet pack failed (exit code {}):\nstdout: {}\nstderr: {}",
+                pack_output.status,
+                String::from_utf8_lossy(&pack_output.stdout).trim(),
+                String::from_utf8_lossy(&pack_output.stderr).trim(),
+            )));
+        }
+
+        let nupkg = out_dir
+            .path()
+            .join(format!("{}.{}.nupkg", pkg.name, pkg.version));
+        if !nupkg.exists() {
+            return Err(Error::PublishFailed(format!(
+                "dotnet pack did not produce {}",
+                nupkg.display()
+            )));
+        }
+
+        let mut push = Command::new("dotnet");
+        push.args(["nuget", "push"])
+            .arg(&nupkg)
+            .args(["--api-key", &api_key, "--skip-duplicate", "--source"])
+            .arg(options.registry.as_deref().unwrap_or(NUGET_SOURCE));
+        debug!(command = %command_line(&push, &[&api_key]), "running");
+        let push_output = push.output().map_err(|e| {
+            Error::PublishFailed(format!("failed to run 'dotnet nuget push': {}", e))
+        })?;
+
+        if push_output.status.success() {
+            return Ok(PublishResult::Success);
+        }
+
+        Err(Error::PublishFailed(format!(
+            "dotnet nuget push failed (exit code {}):\nstdout: {}\nstderr: {}",
+            push_output.status,
+            String::from_utf8_lossy(&push_output.stdout).trim(),
+            String::from_utf8_lossy(&push_output.stderr).trim(),
+        )))
+    }
+}
+
+impl DotnetAdapter {
+    /// Discovers the versioned projects under `root` whose path matches one of
+    /// `patterns`. `bin/`, `obj/` and hidden directories are skipped.
+    pub fn discover_projects(root: &Path, patterns: &[String]) -> Result<Vec<Package>> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let glob = GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| {
+                    Error::ConfigParse(format!("invalid dotnet.projects glob: {}", e).into())
+                })?;
+            builder.add(glob);
+        }
+        let globs = builder.build().map_err(|e| {
+            Error::ConfigParse(format!("invalid dotnet.projects glob: {}", e).into())
+        })?;
+
+        let mut manifests = Vec::new();
+        collect_files(root, &mut manifests)?;
+        manifests.retain(|path| {
+            path.extension().is_some_and(|ext| ext == "csproj")
+                && path.strip_prefix(root).is_ok_and(|rel| globs.is_match(rel))
+        });
+        manifests.sort();
+
+        if manifests.is_empty() {
+            return Err(Error::DotnetProjectNotFound(format!(
+                "No project matching {} under {}",
+                patterns.join(", "),
+                root.display()
+            )));
+        }
+
+        let mut projects = Vec::new();
+        for manifest_path in manifests {
+            let (content, _) = read_text(&manifest_path)?;
+            let Ok(version) = Self::read_version(&manifest_path) else {
+                continue;
+            };
+            projects.push((
+                Self::package_id(&manifest_path, &content),
+                version,
+                manifest_path,
+                content,
+            ));
+        }
+
+        let name_of_path: HashMap<&Path, &str> = projects
+            .iter()
+            .map(|(name, _, path, _)| (path.as_path(), name.as_str()))
+            .collect();
+
+        Ok(projects
+            .iter()
+            .map(|(name, version, manifest_path, content)| {
+                let dir = manifest_path.parent().unwrap_or(root);
+                let mut dependencies: Vec<Dependency> = Vec::new();
+                for reference in attribute_values(content, "ProjectReference", "Include") {
+                    let path = normalize(&dir.join(reference.replace('\\', "/")));
+                    if let Some(dep) = name_of_path.get(path.as_path()) {
+                        dependencies.push(Dependency::normal(*dep));
+                    }
+                }
+                for id in attribute_values(content, "PackageReference", "Include") {
+                    if let Some((dep, _, _, _)) = projects
+                        .iter()
+                        .find(|(other, _, _, _)| other.eq_ignore_ascii_case(&id))
+                    {
+                        dependencies.push(Dependency::normal(dep));
+                    }
+                }
+                dependencies.dedup();
+
+                Package {
+                    name: name.clone(),
+                    version: version.clone(),
+                    path: dir.to_path_buf(),
+                    manifest_path: manifest_path.clone(),
+                    dependencies,
+                }
+            })
+            .collect())
+    }
+
+    fn package_id(manifest_path: &Path, content: &str) -> String {
+        property(content, "PackageId")
+            .or_else(|| property(content, "AssemblyName"))
+            .map(|range| content[range].trim().to_string())
+            .unwrap_or_else(|| {
+                manifest_path
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string()
+            })
+    }
+
+    /// Fetches a package's flat container `index.json`. Returns `None` if the
+    /// package does not exist.
+    fn fetch_index(name: &str) -> Result<Option<serde_json::Value>> {
+        let url = format!(
+            "{}/{}/index.json",
+            NUGET_FLAT_CONTAINER,
+            name.to_lowercase()
+        );
+
+        let response = match http_agent().get(&url).call() {
+            Ok(resp) => resp,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(e) => return Err(Error::NugetCheckFailed(e.into())),
+        };
+
+        let json = response
+            .into_json()
+            .map_err(|e| Error::NugetCheckFailed(e.into()))?;
+
+        Ok(Some(json))
+    }
+
+    /// The highest version in a flat container index; four-part versions are skipped.
+    fn latest_in_index(json: &serde_json::Value) -> Option<Version> {
+        json.get("versions")?
+            .as_array()?
+            .iter()
+            .filter_map(|v| v.as_str()?.parse().ok())
+            .max()
+    }
+
+    fn index_contains(json: &serde_json::Value, version: &Version) -> bool {
+        let version = version.to_string().to_lowercase();
+        json.get("versions")
+            .and_then(|v| v.as_array())
+            .is_some_and(|versions| versions.iter().any(|v| v.as_str() == Some(&version)))
+    }
+
+    /// Rewrites the version of a `<PackageReference>` to `id`, given as a `Version`
+    /// attribute or a nested `<Version>` element. Ranges (`[1.0,2.0)`) and floating
+    /// versions (`1.*`) are left alone.
+    fn rewrite_package_reference(content: &str, id: &str, version: &Version) -> Option<String> {
+        let start_tag = Regex::new(r"(?s)<PackageReference\b([^>]*?)(/?)>").unwrap();
+        let version_attr = Regex::new(r#"\bVersion\s*=\s*"([^"]*)""#).unwrap();
+        let version_element = Regex::new(r"(?s)^\s*<Version>([^<]*)</Version>").unwrap();
+        let comments = comment_ranges(content);
+
+        let mut updated = content.to_string();
+        let mut edits: Vec<Range<usize>> = Vec::new();
+        for tag in start_tag.captures_iter(content) {
+            let whole = tag.get(0).unwrap();
+            if in_comment(&comments, whole.start()) {
+                continue;
+            }
+            let attrs = tag.get(1).unwrap();
+            if !attribute(attrs.as_str(), "Include").is_some_and(|i| i.eq_ignore_ascii_case(id)) {
+                continue;
+            }
+            if let Some(current) = version_attr.captures(attrs.as_str()).and_then(|c| c.get(1)) {
+                edits.push(attrs.start() + current.start()..attrs.start() + current.end());
+            } else if tag.get(2).unwrap().as_str().is_empty() {
+                if let Some(current) = version_element
+                    .captures(&content[whole.end()..])
+                    .and_then(|c| c.get(1))
+                {
+                    edits.push(whole.end() + current.start()..whole.end() + current.end());
+                }
+            }
+        }
+
+        edits.retain(|range| {
+            let current = content[range.clone()].trim();
+            !current.starts_with(['[', '(']) && !current.contains('*')
+        });
+        if edits.is_empty() {
+            return None;
+        }
+        for range in edits.into_iter().rev() {
+            updated.replace_range(range, &version.to_string());
+        }
+        (updated != content).then_some(updated)
+    }
+
+    /// Updates `<PackageReference>` versions of released packages in every project.
+    pub fn update_all_dependency_versions(
+        packages: &[Package],
+        _root_manifest: &Path,
+        updates: &HashMap<String, Version>,
+    ) -> Result<()> {
+        for package in packages {
+            let mut modified = false;
+            for (dep_name, new_version) in updates {
+                modified |=
+                    Self::update_dependency_version(&package.manifest_path, dep_name, new_version)?;
+            }
+            debug!(manifest = %package.manifest_path.display(), modified, "updated dependency requirements");
+        }
+        Ok(())
+    }
+}
+
+/// Recursively collects files under `dir`, skipping build output and hidden
+/// directories.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    collect_files_in(dir, files, &mut Vec::new())
+}
+
+/// Walks `dir`, following symlinked directories; `visited` holds the directories
+/// already walked, by canonical path, so a symlink loop ends.
+fn collect_files_in(
+    dir: &Path,
+    files: &mut Vec<PathBuf>,
+    visited: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let canonical = dir.canonicalize()?;
+    if visited.contains(&canonical) && dir.is_symlink() {
+        return Ok(());
+    }
+    visited.push(canonical);
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        // `is_dir` follows symlinks, unlike the entry's file type.
+        if path.is_dir() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') || matches!(name.as_ref(), "bin" | "obj" | "node_modules") {
+                continue;
+            }
+            collect_files_in(&path, files, visited)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Resolves `.` and `..` without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Byte ranges of `<!-- ... -->` comments.
+fn comment_ranges(content: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = content[offset..].find("<!--") {
+        let start = offset + start;
+        let end = content[start..]
+            .find("-->")
+            .map_or(content.len(), |end| start + end + 3);
+        ranges.push(start..end);
+        offset = end;
+    }
+    ranges
+}
+
+fn in_comment(comments: &[Range<usize>], position: usize) -> bool {
+    comments.iter().any(|c| c.contains(&position))
+}
+
+/// The text range of the first `<name>` property inside a `<PropertyGroup>`,
+/// ignoring commented-out elements.
+fn property(content: &str, name: &str) -> Option<Range<usize>> {
+    let group = Regex::new(r"(?s)<PropertyGroup\b[^>]*>(.*?)</PropertyGroup>").unwrap();
+    let element = Regex::new(&format!(r"<{0}>([^<]*)</{0}>", regex::escape(name))).unwrap();
+    let comments = comment_ranges(content);
+
+    group
+        .captures_iter(content)
+        .filter(|g| !in_comment(&comments, g.get(0).unwrap().start()))
+        .find_map(|g| {
+            let body = g.get(1).unwrap();
+            element
+                .captures_iter(body.as_str())
+                .map(|e| e.get(1).unwrap())
+                .map(|text| body.start() + text.start()..body.start() + text.end())
+                .find(|range| !in_comment(&comments, range.start))
+        })
+}
+
+/// Values of `attr` on every `<element>` outside comments.
+fn attribute_values(content: &str, element: &str, attr: &str) -> Vec<String> {
+    let tag = Regex::new(&format!(r"(?s)<{}\b([^>]*)>", regex::escape(element))).unwrap();
+    let comments = comment_ranges(content);
+    tag.captures_iter(content)
+        .filter(|t| !in_comment(&comments, t.get(0).unwrap().start()))
+        .filter_map(|t| attribute(t.get(1).unwrap().as_str(), attr))
+        .collect()
+}
+
+fn attribute(attrs: &str, name: &str) -> Option<String> {
+    let re = Regex::new(&format!(r#"\b{}\s*=\s*"([^"]*)""#, regex::escape(name))).unwrap();
+    re.captures(attrs).map(|c| c[1].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    const CORE: &str = r#"<Project Sdk="Microsoft.NET.Sdk">
+
+  <!-- Shared client library -->
+  <PropertyGroup>
+    <TargetFramework>net8.0</TargetFramework>
+    <PackageId>Acme.Core</PackageId>
+    <!-- <Version>0.0.1</Version> -->
+    <Version>1.2.0</Version>
+  </PropertyGroup>
+
+</Project>
+"#;
+
+    const CLIENT: &str = r#"<Project Sdk="Microsoft.NET.Sdk">
+  <PropertyGroup>
+    <TargetFramework>net8.0</TargetFramework>
+    <AssemblyName>Acme.Client</AssemblyName>
+    <Version>0.4.0</Version>
+    <PackageVersion>0.4.0</PackageVersion>
+  </PropertyGroup>
+
+  <ItemGroup>
+    <ProjectReference Include="..\Core\Core.csproj" />
+    <PackageReference Include="Newtonsoft.Json" Version="13.0.3" />
+    <PackageReference Version="1.2.0" Include="acme.core" />
+    <PackageReference Include="Acme.Core">
+      <Version>1.2.0</Version>
+    </PackageReference>
+  </ItemGroup>
+</Project>
+"#;
+
+    /// A solution with two projects, the client referencing the core library, and
+    /// an unversioned test project.
+    fn workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "Acme.sln", "");
+        write(dir.path(), "src/Core/Core.csproj", CORE);
+        write(dir.path(), "src/Client/Client.csproj", CLIENT);
+        write(dir.path(), "src/Client/obj/Stale.csproj", CORE);
+        write(
+            dir.path(),
+            "tests/Client.Tests/Client.Tests.csproj",
+            "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <ItemGroup>\n    <ProjectReference Include=\"..\\..\\src\\Client\\Client.csproj\" />\n  </ItemGroup>\n</Project>\n",
+        );
+        dir
+    }
+
+    #[test]
+    fn discover_projects_and_references() {
+        let dir = workspace();
+
+        let packages =
+            DotnetAdapter::discover_projects(dir.path(), &["**/*.csproj".to_string()]).unwrap();
+
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Acme.Client", "Acme.Core"]);
+        assert_eq!(packages[0].version, Version::new(0, 4, 0));
+        assert_eq!(packages[0].path, dir.path().join("src/Client"));
+        assert_eq!(packages[0].dependencies, [Dependency::normal("Acme.Core")]);
+        assert_eq!(packages[1].version, Version::new(1, 2, 0));
+        assert!(packages[1].dependencies.is_empty());
+
+        let only_core =
+            DotnetAdapter::discover_projects(dir.path(), &["src/Core/*.csproj".to_string()])
+                .unwrap();
+        assert_eq!(only_core.len(), 1);
+
+        let err = DotnetAdapter::discover_projects(dir.path(), &["lib/**/*.csproj".to_string()])
+            .unwrap_err();
+        assert!(matches!(err, Error::DotnetProjectNotFound(_)));
+    }
+
+    #[test]
+    fn detects_solution_root() {
+        let dir = workspace();
+
+        assert!(Ecosystem::Dotnet.has_workspace_file(dir.path()));
+        assert!(Ecosystem::Dotnet.has_manifest(&dir.path().join("src/Core")));
+        assert_eq!(
+            crate::ecosystems::detect_ecosystem(&dir.path().join("src/Core")),
+            Some(Ecosystem::Dotnet)
+        );
+    }
+
+    #[test]
+    fn write_version_preserves_comments() {
+        let dir = workspace();
+        let core = dir.path().join("src/Core/Core.csproj");
+        let client = dir.path().join("src/Client/Client.csproj");
+
+        DotnetAdapter::write_version(&core, &Version::new(1, 3, 0)).unwrap();
+        DotnetAdapter::write_version(&client, &Version::new(0, 5, 0)).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&core).unwrap(),
+            CORE.replace("<Version>1.2.0</Version>", "<Version>1.3.0</Version>")
+        );
+        assert_eq!(
+            DotnetAdapter::read_version(&core).unwrap(),
+            Version::new(1, 3, 0)
+        );
+        let client = std::fs::read_to_string(&client).unwrap();
+        assert!(client.contains("<Version>0.5.0</Version>"), "{client}");
+        assert!(
+            client.contains("<PackageVersion>0.5.0</PackageVersion>"),
+            "{client}"
+        );
+        assert!(client.contains("<Version>1.2.0</Version>"), "{client}");
+    }
+
+    #[test]
+    fn update_dependency_versions_rewrites_package_references() {
+        let dir = workspace();
+        let packages =
+            DotnetAdapter::discover_projects(dir.path(), &["**/*.csproj".to_string()]).unwrap();
+        let updates = HashMap::from([("Acme.Core".to_string(), Version::new(1, 3, 0))]);
+
+        DotnetAdapter::update_all_dependency_versions(&packages, Path::new(""), &updates).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src/Client/Client.csproj")).unwrap(),
+            CLIENT
+                .replace(
+                    r#"<PackageReference Version="1.2.0" Include="acme.core" />"#,
+                    r#"<PackageReference Version="1.3.0" Include="acme.core" />"#,
+                )
+                .replace(
+                    "<Version>1.2.0</Version>\n    </PackageReference>",
+                    "<Version>1.3.0</Version>\n    </PackageReference>",
+                )
+        );
+        // Ranges are the author's call.
+        let ranged = r#"<PackageReference Include="Acme.Core" Version="[1.0,2.0)" />"#;
+        assert_eq!(
+            DotnetAdapter::rewrite_package_reference(ranged, "Acme.Core", &Version::new(1, 3, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn nuget_index_lookup() {
+        let json = serde_json::json!({ "versions": ["1.0.0", "1.1.0-beta.1"] });
+        assert!(DotnetAdapter::index_contains(&json, &Version::new(1, 0, 0)));
+        assert!(DotnetAdapter::index_contains(
+            &json,
+            &"1.1.0-BETA.1".parse().unwrap()
+        ));
+        assert!(!DotnetAdapter::index_contains(
+            &json,
+            &Version::new(2, 0, 0)
+        ));
+        assert_eq!(
+            DotnetAdapter::latest_in_index(&json),
+            Some("1.1.0-beta.1".parse().unwrap())
+        );
+    }
+}
@@ -0,0 +1,654 @@
+use crate::changelog_reader;
+use crate::config::{Config, GoPackageNames};
+use crate::ecosystems::{
+    Dependency, Ecosystem, EcosystemAdapter, Package, PublishOptions, PublishResult, http_agent,
+};
+use crate::error::{Error, Result};
+use crate::fs_util::atomic_write;
+use crate::git::{CliGit, Git};
+use regex::Regex;
+use semver::Version;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+const GO_PROXY: &str = "https://proxy.golang.org";
+
+/// Go modules from a `go.mod`, or every module listed in a `go.work`.
+///
+/// Go keeps no version in `go.mod`: a module's version is its latest `vX.Y.Z` tag
+/// (`sub/dir/vX.Y.Z` for nested modules). Until `publish` creates that tag, a
+/// release made by `changelogs version` is only recorded in the module's
+/// changelog, so the newest version found there counts too.
+pub struct GoAdapter;
+
+impl EcosystemAdapter for GoAdapter {
+    fn ecosystem() -> Ecosystem {
+        Ecosystem::Go
+    }
+
+    fn discover(root: &Path) -> Result<Vec<Package>> {
+        Self::discover_modules(root, &Config::default(), &CliGit::in_dir(root).tags())
+    }
+
+    fn read_version(manifest_path: &Path) -> Result<Version> {
+        let dir = manifest_path.parent().unwrap_or(Path::new("."));
+        let module = Self::module_path(manifest_path)?;
+        Ok(Self::current_version(
+            dir,
+            &module,
+            &dir.join("CHANGELOG.md"),
+            &CliGit::in_dir(dir).tags(),
+        ))
+    }
+
+    /// Versions live in tags, so there is nothing to write to `go.mod`.
+    fn write_version(_manifest_path: &Path, _version: &Version) -> Result<()> {
+        Ok(())
+    }
+
+    /// `dep_name` is the required module path.
+    fn update_dependency_version(
+        manifest_path: &Path,
+        dep_name: &str,
+        new_version: &Version,
+    ) -> Result<bool> {
+        let content = std::fs::read_to_string(manifest_path)?;
+        let Some(updated) = Self::rewrite_require(&content, dep_name, new_version) else {
+            return Ok(false);
+        };
+        atomic_write(manifest_path, updated)?;
+        Ok(true)
+    }
+
+    /// `name` is the module path. Checks the module proxy's version list.
+    fn is_published(name: &str, version: &Version) -> Result<bool> {
+        Ok(Self::fetch_list(name)?.is_some_and(|body| Self::list_contains(&body, version)))
+    }
+
+    fn latest_published(name: &str) -> Result<Option<Version>> {
+        Ok(Self::fetch_list(name)?.and_then(|body| Self::latest_in_list(&body)))
+    }
+
+    /// Publishing a Go module is pushing its tag, which `publish` creates after this.
+    fn publish(_pkg: &Package, _dry_run: bool, _options: &PublishOptions) -> Result<PublishResult> {
+        Ok(PublishResult::Success)
+    }
+
+    fn tag_name(pkg: &Package) -> String {
+        let module = Self::module_path(&pkg.manifest_path).unwrap_or_default();
+        format!("{}v{}", Self::tag_prefix(&pkg.path, &module), pkg.version)
+    }
+}
+
+impl GoAdapter {
+    /// Discovers the modules under `root`, taking versions from `tags` and from each
+    /// module's changelog (`changelog_path` in `[[packages]]`, or `CHANGELOG.md`).
+    pub fn discover_modules(root: &Path, config: &Config, tags: &[String]) -> Result<Vec<Package>> {
+        let names = config.go.package_names;
+        let go_work = root.join("go.work");
+        let dirs: Vec<PathBuf> = if go_work.exists() {
+            let content = std::fs::read_to_string(&go_work)?;
+            directive_entries(&content, "use")
+                .iter()
+                .filter_map(|entry| first_token(entry))
+                .map(|dir| root.join(dir.trim_start_matches("./")))
+                .collect()
+        } else {
+            vec![root.to_path_buf()]
+        };
+
+        let mut modules = Vec::new();
+        for dir in dirs {
+            let manifest_path = dir.join("go.mod");
+            if !manifest_path.exists() {
+                return Err(Error::GoModuleNotFound(format!(
+                    "No go.mod found at {}",
+                    dir.display()
+                )));
+            }
+            let content = std::fs::read_to_string(&manifest_path)?;
+            let module = module_directive(&content).ok_or_else(|| {
+                Error::GoModuleNotFound(format!(
+                    "{} has no module directive",
+                    manifest_path.display()
+                ))
+            })?;
+            modules.push((dir, manifest_path, module, content));
+        }
+
+        let name_of: HashMap<&str, String> = modules
+            .iter()
+            .map(|(_, _, module, _)| (module.as_str(), Self::package_name(module, names)))
+            .collect();
+
+        Ok(modules
+            .iter()
+            .map(|(dir, manifest_path, module, content)| {
+                let name = &name_of[module.as_str()];
+                let changelog = dir.join(
+                    config
+                        .package(name)
+                        .and_then(|p| p.changelog_path.as_deref())
+                        .unwrap_or("CHANGELOG.md"),
+                );
+                let dependencies = directive_entries(content, "require")
+                    .iter()
+                    .filter_map(|entry| first_token(entry))
+                    .filter_map(|required| name_of.get(required))
+                    .map(Dependency::normal)
+                    .collect();
+
+                Package {
+                    name: name.clone(),
+                    version: Self::current_version(dir, module, &changelog, tags),
+                    path: dir.clone(),
+                    manifest_path: manifest_path.clone(),
+                    dependencies,
+                }
+            })
+            .collect())
+    }
+
+    /// Reads the module path from a `go.mod`.
+    pub fn module_path(manifest_path: &Path) -> Result<String> {
+        let content = std::fs::read_to_string(manifest_path)?;
+        module_directive(&content).ok_or_else(|| {
+            Error::GoModuleNotFound(format!(
+                "{} has no module directive",
+                manifest_path.display()
+            ))
+        })
+    }
+
+    /// The package name for `module`. The last segment skips a major version
+    /// suffix, so `example.com/sdk/v2` is `sdk`.
+    fn package_name(module: &str, names: GoPackageNames) -> String {
+        match names {
+            GoPackageNames::ModulePath => module.to_string(),
+            GoPackageNames::LastSegment => {
+                let mut segments = module.rsplit('/');
+                let last = segments.next().unwrap_or(module);
+                match segments.next() {
+                    Some(parent) if major_suffix(last).is_some() => parent.to_string(),
+                    _ => last.to_string(),
+                }
+            }
+        }
+    }
+
+    /// The newer of the latest tagged version and the newest section of the
+    /// `changelog` file, or `0.0.0` for a module with neither.
+    fn current_version(dir: &Path, module: &str, changelog: &Path, tags: &[String]) -> Version {
+        let prefix = Self::tag_prefix(dir, module);
+        let tagged = tags
+            .iter()
+            .filter_map(|tag| tag.strip_prefix(&prefix)?.strip_prefix('v'))
+            .filter_map(|v| Version::parse(v).ok())
+            .max();
+
+        let changelog = std::fs::read_to_string(changelog).unwrap_or_default();
+        let released = changelog_reader::parse(&changelog)
+            .sections
+            .into_iter()
+            .find_map(|s| s.version);
+
+        tagged
+            .into_iter()
+            .chain(released)
+            .max()
+            .unwrap_or_else(|| Version::new(0, 0, 0))
+    }
+
+    /// The tag prefix for the module in `dir`: its path from the repository root
+    /// with a trailing `/`, or nothing for a module at the root. A major version
+    /// subdirectory (`sdk/v2` for `example.com/sdk/v2`) is tagged as its parent.
+    fn tag_prefix(dir: &Path, module: &str) -> String {
+        let Some(repo_root) = dir.ancestors().find(|d| d.join(".git").exists()) else {
+            return String::new();
+        };
+        let mut relative = dir.strip_prefix(repo_root).unwrap_or(Path::new(""));
+
+        let dir_name = relative.file_name().and_then(|n| n.to_str());
+        if dir_name.is_some_and(|n| major_suffix(n).is_some() && module.ends_with(&format!("/{n}")))
+        {
+            relative = relative.parent().unwrap_or(Path::new(""));
+        }
+
+        let prefix: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", prefix.join("/"))
+        }
+    }
+
+    /// Escapes a module path for the proxy protocol: uppercase letters become `!`
+    /// followed by the lowercase letter.
+    fn escape_module_path(module: &str) -> String {
+        module
+            .chars()
+            .flat_map(|c| {
+                if c.is_ascii_uppercase() {
+                    vec!['!', c.to_ascii_lowercase()]
+                } else {
+                    vec![c]
+                }
+            })
+            .collect()
+    }
+
+    /// Fetches the proxy's `@v/list` for a module path. Returns `None` if the proxy
+    /// doesn't know the module.
+    fn fetch_list(module: &str) -> Result<Option<String>> {
+        let url = format!("{}/{}/@v/list", GO_PROXY, Self::escape_module_path(module));
+
+        let response = match http_agent().get(&url).call() {
+            Ok(resp) => resp,
+            Err(ureq::Error::Status(404 | 410, _)) => return Ok(None),
+            Err(e) => return Err(Error::GoProxyCheckFailed(e.into())),
+        };
+
+        let body = response
+            .into_string()
+            .map_err(|e| Error::GoProxyCheckFailed(e.into()))?;
+
+        Ok(Some(body))
+    }
+
+    /// The highest version in a proxy `@v/list` response.
+    fn latest_in_list(body: &str) -> Option<Version> {
+        body.lines()
+            .filter_map(|line| line.trim().strip_prefix('v')?.parse().ok())
+            .max()
+    }
+
+    /// True if a proxy `@v/list` response lists `version`.
+    fn list_contains(body: &str, version: &Version) -> bool {
+        let wanted = format!("v{}", version);
+        body.lines().any(|line| line.trim() == wanted)
+    }
+
+    /// Rewrites the required version of `module` in a `go.mod`, in both the
+    /// single-line and block forms of `require`. Other directives, such as a
+    /// `replace` pinning the same module, are left alone. Returns `None` if nothing
+    /// changed.
+    fn rewrite_require(content: &str, module: &str, version: &Version) -> Option<String> {
+        let pattern = format!(r"^(\s*(?:require\s+)?){}\s+v[^\s/]+", regex::escape(module));
+        let re = Regex::new(&pattern).ok()?;
+        let replacement = format!("${{1}}{} v{}", module, version);
+
+        // `Some(true)` inside a `require ( ... )` block, `Some(false)` inside any
+        // other block.
+        let mut block = None;
+        let mut updated = String::with_capacity(content.len());
+        for line in content.split_inclusive('\n') {
+            let code = line.split("//").next().unwrap_or_default().trim();
+            let in_require = match block {
+                Some(is_require) => {
+                    if code == ")" {
+                        block = None;
+                    }
+                    is_require
+                }
+                None => {
+                    let keyword = code
+                        .split(|c: char| c.is_whitespace() || c == '(')
+                        .next()
+                        .unwrap_or_default();
+                    if code.ends_with('(') {
+                        block = Some(keyword == "require");
+                        false
+                    } else {
+                        keyword == "require"
+                    }
+                }
+            };
+            if in_require {
+                updated.push_str(&re.replace(line, replacement.as_str()));
+            } else {
+                updated.push_str(line);
+            }
+        }
+        (updated != content).then_some(updated)
+    }
+
+    /// Rewrites the required versions of released modules in every module's
+    /// `go.mod`. `updates` is keyed by package name.
+    pub fn update_all_dependency_versions(
+        packages: &[Package],
+        _root_manifest: &Path,
+        updates: &HashMap<String, Version>,
+    ) -> Result<()> {
+        let mut modules = HashMap::new();
+        for package in packages {
+            modules.insert(
+                package.name.as_str(),
+                Self::module_path(&package.manifest_path)?,
+            );
+        }
+
+        for package in packages {
+            let mut modified = false;
+            for (name, new_version) in updates {
+                if let Some(module) = modules.get(name.as_str()) {
+                    modified |= Self::update_dependency_version(
+                        &package.manifest_path,
+                        module,
+                        new_version,
+                    )?;
+                }
+            }
+            debug!(manifest = %package.manifest_path.display(), modified, "updated dependency requirements");
+        }
+        Ok(())
+    }
+}
+
+/// Returns `N` for a major version path element `vN` with `N >= 2`.
+fn major_suffix(segment: &str) -> Option<u64> {
+    segment
+        .strip_prefix('v')?
+        .parse()
+        .ok()
+        .filter(|major| *major >= 2)
+}
+
+/// The module path from a `go.mod`'s `module` directive.
+fn module_directive(content: &str) -> Option<String> {
+    directive_entries(content, "module")
+        .first()
+        .and_then(|entry| first_token(entry))
+        .map(str::to_string)
+}
+
+/// Returns the entries of every `keyword` directive, in both the single-line
+/// (`use ./a`) and block (`use (\n ./a\n)`) forms, without comments.
+fn directive_entries(content: &str, keyword: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                entries.push(line.to_string());
+            }
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix(keyword) else {
+            continue;
+        };
+        if !rest.starts_with(char::is_whitespace) && !rest.starts_with('(') {
+            continue;
+        }
+        let rest = rest.trim();
+        if rest == "(" {
+            in_block = true;
+        } else if !rest.is_empty() {
+            entries.push(rest.to_string());
+        }
+    }
+
+    entries
+}
+
+/// The first whitespace-separated token of a directive entry, unquoted.
+fn first_token(entry: &str) -> Option<&str> {
+    entry
+        .split_whitespace()
+        .next()
+        .map(|token| token.trim_matches('"'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PackageConfig;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|t| t.to_string()).collect()
+    }
+
+    fn config(package_names: GoPackageNames) -> Config {
+        let mut config = Config::default();
+        config.go.package_names = package_names;
+        config
+    }
+
+    /// A repository with a `go.work` listing a root module and a nested one.
+    fn workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        write(
+            dir.path(),
+            "go.work",
+            "go 1.22\n\nuse (\n\t.\n\t./tools/cli // the CLI\n)\n",
+        );
+        write(
+            dir.path(),
+            "go.mod",
+            "module github.com/acme/service\n\ngo 1.22\n",
+        );
+        write(
+            dir.path(),
+            "tools/cli/go.mod",
+            "module github.com/acme/service/tools/cli\n\ngo 1.22\n\nrequire (\n\tgithub.com/acme/service v1.2.0\n\tgithub.com/spf13/cobra v1.8.0 // indirect\n)\n",
+        );
+        dir
+    }
+
+    #[test]
+    fn discover_single_module() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        write(dir.path(), "go.mod", "module \"example.com/widget\"\n");
+
+        let packages = GoAdapter::discover_modules(
+            dir.path(),
+            &config(GoPackageNames::LastSegment),
+            &tags(&[
+                "v0.9.0",
+                "v1.1.0",
+                "v1.0.0",
+                "other/v9.0.0",
+                "not-a-version",
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name, "widget");
+        assert_eq!(packages[0].version, Version::new(1, 1, 0));
+        assert_eq!(packages[0].manifest_path, dir.path().join("go.mod"));
+    }
+
+    #[test]
+    fn discover_go_work_modules() {
+        let dir = workspace();
+        let packages = GoAdapter::discover_modules(
+            dir.path(),
+            &config(GoPackageNames::LastSegment),
+            &tags(&["v1.2.0", "tools/cli/v0.3.1", "tools/cli/v0.3.0"]),
+        )
+        .unwrap();
+
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["service", "cli"]);
+        assert_eq!(packages[0].version, Version::new(1, 2, 0));
+        assert_eq!(packages[1].version, Version::new(0, 3, 1));
+        assert_eq!(packages[1].path, dir.path().join("tools/cli"));
+        // Only workspace modules count as dependencies.
+        assert_eq!(
+            packages[1].dependencies,
+            vec![Dependency::normal("service")]
+        );
+    }
+
+    #[test]
+    fn discover_module_path_names() {
+        let dir = workspace();
+        let packages =
+            GoAdapter::discover_modules(dir.path(), &config(GoPackageNames::ModulePath), &[])
+                .unwrap();
+
+        assert_eq!(packages[0].name, "github.com/acme/service");
+        assert_eq!(packages[0].version, Version::new(0, 0, 0));
+        assert_eq!(
+            packages[1].dependencies,
+            vec![Dependency::normal("github.com/acme/service")]
+        );
+    }
+
+    #[test]
+    fn discover_missing_module_errors() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "go.work", "use ./missing\n");
+
+        let result = GoAdapter::discover_modules(dir.path(), &Config::default(), &[]);
+        assert!(matches!(result, Err(Error::GoModuleNotFound(_))));
+    }
+
+    #[test]
+    fn version_from_changelog_when_ahead_of_tags() {
+        let dir = workspace();
+        write(
+            dir.path(),
+            "tools/cli/CHANGELOG.md",
+            "# cli\n\n## 0.4.0 (2025-01-15)\n\n- Added a flag\n\n## 0.3.1\n",
+        );
+
+        let packages = GoAdapter::discover_modules(
+            dir.path(),
+            &Config::default(),
+            &tags(&["tools/cli/v0.3.1"]),
+        )
+        .unwrap();
+        assert_eq!(packages[1].version, Version::new(0, 4, 0));
+
+        // With a `changelog_path` override, that file is read instead.
+        write(
+            dir.path(),
+            "tools/cli/docs/CHANGES.md",
+            "# cli\n\n## 0.5.0 (2025-02-01)\n\n- Added a command\n",
+        );
+        let mut config = Config::default();
+        config.packages.push(PackageConfig {
+            name: "cli".into(),
+            tag_template: None,
+            changelog_path: Some("docs/CHANGES.md".into()),
+            min_bump: None,
+            format: None,
+        });
+        let packages = GoAdapter::discover_modules(dir.path(), &config, &[]).unwrap();
+        assert_eq!(packages[1].version, Version::new(0, 5, 0));
+    }
+
+    #[test]
+    fn tag_names_follow_go_convention() {
+        let dir = workspace();
+        write(
+            dir.path(),
+            "sdk/v2/go.mod",
+            "module github.com/acme/sdk/v2\n",
+        );
+
+        let mut packages =
+            GoAdapter::discover_modules(dir.path(), &Config::default(), &[]).unwrap();
+        packages[0].version = Version::new(1, 3, 0);
+        packages[1].version = Version::new(0, 4, 0);
+        assert_eq!(GoAdapter::tag_name(&packages[0]), "v1.3.0");
+        assert_eq!(GoAdapter::tag_name(&packages[1]), "tools/cli/v0.4.0");
+
+        let sdk = Package {
+            name: "sdk".to_string(),
+            version: Version::new(2, 1, 0),
+            path: dir.path().join("sdk/v2"),
+            manifest_path: dir.path().join("sdk/v2/go.mod"),
+            dependencies: vec![],
+        };
+        assert_eq!(GoAdapter::tag_name(&sdk), "sdk/v2.1.0");
+        assert_eq!(
+            GoAdapter::package_name("github.com/acme/sdk/v2", GoPackageNames::LastSegment),
+            "sdk"
+        );
+    }
+
+    #[test]
+    fn proxy_helpers() {
+        assert_eq!(
+            GoAdapter::escape_module_path("github.com/BurntSushi/toml"),
+            "github.com/!burnt!sushi/toml"
+        );
+        let list = "v1.0.0\nv1.1.0\nv1.2.0-rc.1\n";
+        assert!(GoAdapter::list_contains(list, &Version::new(1, 1, 0)));
+        assert!(!GoAdapter::list_contains(list, &Version::new(1, 2, 0)));
+        assert_eq!(
+            GoAdapter::latest_in_list(list),
+            Some(Version::parse("1.2.0-rc.1").unwrap())
+        );
+    }
+
+    #[test]
+    fn update_dependency_versions_rewrites_require() {
+        let dir = workspace();
+        write(
+            dir.path(),
+            "go.mod",
+            "module github.com/acme/service\n\nrequire github.com/acme/service/tools/cli v0.3.1\n",
+        );
+        let packages = GoAdapter::discover_modules(dir.path(), &Config::default(), &[]).unwrap();
+
+        let updates = HashMap::from([
+            ("service".to_string(), Version::new(1, 3, 0)),
+            ("cli".to_string(), Version::new(0, 4, 0)),
+        ]);
+        GoAdapter::update_all_dependency_versions(&packages, Path::new("go.mod"), &updates)
+            .unwrap();
+
+        let root = std::fs::read_to_string(dir.path().join("go.mod")).unwrap();
+        assert!(root.contains("require github.com/acme/service/tools/cli v0.4.0\n"));
+        let cli = std::fs::read_to_string(dir.path().join("tools/cli/go.mod")).unwrap();
+        assert!(cli.contains("\tgithub.com/acme/service v1.3.0\n"));
+        // A module whose path extends a released one is left alone.
+        assert!(cli.contains("github.com/spf13/cobra v1.8.0 // indirect"));
+        assert!(!root.contains("service v1.3.0"));
+    }
+
+    #[test]
+    fn rewrite_require_leaves_replace_alone() {
+        let content = "module github.com/acme/app\n\nrequire github.com/acme/lib v1.0.0\n\nreplace (\n\tgithub.com/acme/lib v1.0.0 => ../lib\n)\n\nrequire (\n\tgithub.com/acme/lib v1.0.0 // indirect\n)\n";
+
+        let updated =
+            GoAdapter::rewrite_require(content, "github.com/acme/lib", &Version::new(1, 1, 0))
+                .unwrap();
+        assert_eq!(
+            updated,
+            "module github.com/acme/app\n\nrequire github.com/acme/lib v1.1.0\n\nreplace (\n\tgithub.com/acme/lib v1.0.0 => ../lib\n)\n\nrequire (\n\tgithub.com/acme/lib v1.1.0 // indirect\n)\n"
+        );
+
+        let replaced_only =
+            "module github.com/acme/app\n\nreplace github.com/acme/lib v1.0.0 => ../lib\n";
+        assert_eq!(
+            GoAdapter::rewrite_require(
+                replaced_only,
+                "github.com/acme/lib",
+                &Version::new(1, 1, 0)
+            ),
+            None
+        );
+    }
+}
@@ -1,26 +1,49 @@
+mod dotnet;
+mod go;
 mod python;
 mod rust;
 
+pub use dotnet::DotnetAdapter;
+pub use go::GoAdapter;
 pub use python::PythonAdapter;
 pub use rust::RustAdapter;
 
-use crate::error::Result;
+use crate::config::{Config, VersionTarget};
+use crate::error::{Error, Result};
+use crate::fs_util::atomic_write;
+use crate::git::{CliGit, Git};
+use regex::Regex;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::debug;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 #[serde(rename_all = "lowercase")]
 pub enum Ecosystem {
     #[default]
     Rust,
     Python,
+    Go,
+    Dotnet,
 }
 
 impl Ecosystem {
+    /// Every supported ecosystem, in detection order.
+    pub const ALL: [Ecosystem; 4] = [
+        Ecosystem::Rust,
+        Ecosystem::Python,
+        Ecosystem::Go,
+        Ecosystem::Dotnet,
+    ];
+
     const RUST_ALIASES: &[&str] = &["rust", "cargo"];
     const PYTHON_ALIASES: &[&str] = &["python", "pypi"];
+    const GO_ALIASES: &[&str] = &["go", "golang"];
+    const DOTNET_ALIASES: &[&str] = &["dotnet", "csharp", "nuget"];
 
     pub fn from_alias(s: &str) -> Option<Self> {
         let lower = s.to_lowercase();
@@ -28,14 +51,68 @@ impl Ecosystem {
             Some(Ecosystem::Rust)
         } else if Self::PYTHON_ALIASES.contains(&lower.as_str()) {
             Some(Ecosystem::Python)
+        } else if Self::GO_ALIASES.contains(&lower.as_str()) {
+            Some(Ecosystem::Go)
+        } else if Self::DOTNET_ALIASES.contains(&lower.as_str()) {
+            Some(Ecosystem::Dotnet)
         } else {
             None
         }
     }
+
+    /// File name of the manifest that marks a package or workspace root. .NET
+    /// projects are named after themselves, so this is the shared
+    /// `Directory.Build.props` instead.
+    pub fn manifest_name(self) -> &'static str {
+        match self {
+            Ecosystem::Rust => "Cargo.toml",
+            Ecosystem::Python => "pyproject.toml",
+            Ecosystem::Go => "go.mod",
+            Ecosystem::Dotnet => "Directory.Build.props",
+        }
+    }
+
+    /// The ecosystem of the package in `dir`, from its manifest alone. Unlike
+    /// `detect_ecosystem` this doesn't look at parent directories or workspace
+    /// files, so it tells what a single package is. With several manifests the
+    /// first in `ALL` wins.
+    pub fn detect_single(dir: &Path) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.has_manifest(dir))
+    }
+
+    /// Whether `dir` holds a package manifest (any `*.csproj` for .NET).
+    pub fn has_manifest(self, dir: &Path) -> bool {
+        match self {
+            Ecosystem::Dotnet => has_file_with_extension(dir, &["csproj"]),
+            _ => dir.join(self.manifest_name()).exists(),
+        }
+    }
+
+    /// Whether `dir` holds a workspace file that lists packages without being a
+    /// package manifest itself: `go.work`, or a .NET solution.
+    pub fn has_workspace_file(self, dir: &Path) -> bool {
+        match self {
+            Ecosystem::Go => dir.join("go.work").exists(),
+            Ecosystem::Dotnet => has_file_with_extension(dir, &["sln", "slnx"]),
+            Ecosystem::Rust | Ecosystem::Python => false,
+        }
+    }
 }
 
-/// Result of attempting to publish a package
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+fn has_file_with_extension(dir: &Path, extensions: &[&str]) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|entries| {
+        entries.filter_map(|e| e.ok()).any(|e| {
+            e.path()
+                .extension()
+                .is_some_and(|ext| extensions.iter().any(|x| ext == *x))
+        })
+    })
+}
+
+/// Result of attempting to publish a package. Serializes as `{"status": "skipped",
+/// "reason": "no-token"}`, without `reason` for the other statuses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "reason", rename_all = "lowercase")]
 pub enum PublishResult {
     /// Package was successfully published
     Success,
@@ -45,8 +122,20 @@ pub enum PublishResult {
     Failed,
 }
 
+/// How to publish a package.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublishOptions {
+    /// Registry to publish to instead of the ecosystem's default.
+    pub registry: Option<String>,
+    /// Build the package before uploading it (Rust: no `--no-verify`).
+    pub verify: bool,
+    /// Publish even if the working tree has uncommitted changes (Rust: `--allow-dirty`).
+    pub allow_dirty: bool,
+}
+
 /// Reason a package publish was skipped
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum SkipReason {
     /// No registry token configured
     NoToken,
@@ -54,11 +143,23 @@ pub enum SkipReason {
     NotPublishable,
 }
 
+impl std::fmt::Display for PublishResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PublishResult::Success => write!(f, "success"),
+            PublishResult::Skipped(_) => write!(f, "skipped"),
+            PublishResult::Failed => write!(f, "failed"),
+        }
+    }
+}
+
 impl std::fmt::Display for Ecosystem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Ecosystem::Rust => write!(f, "rust"),
             Ecosystem::Python => write!(f, "python"),
+            Ecosystem::Go => write!(f, "go"),
+            Ecosystem::Dotnet => write!(f, "dotnet"),
         }
     }
 }
@@ -77,7 +178,40 @@ pub struct Package {
     pub version: Version,
     pub path: PathBuf,
     pub manifest_path: PathBuf,
-    pub dependencies: Vec<String>,
+    pub dependencies: Vec<Dependency>,
+}
+
+/// A dependency on another workspace package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub name: String,
+    pub kind: DependencyKind,
+}
+
+impl Dependency {
+    pub fn normal(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind: DependencyKind::Normal,
+        }
+    }
+
+    pub fn dev(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind: DependencyKind::Dev,
+        }
+    }
+}
+
+/// How a package depends on another. `Normal` and `Build` dependencies are needed
+/// to build a package from the registry, `Dev` ones only for its tests; optional
+/// (feature or extra) dependencies count as `Normal`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    Normal,
+    Dev,
+    Build,
 }
 
 /// Trait defining ecosystem-specific operations for package management.
@@ -119,8 +253,14 @@ pub trait EcosystemAdapter {
     where
         Self: Sized;
 
+    /// Returns the highest version of a package on the registry, or `None` if it
+    /// was never published.
+    fn latest_published(name: &str) -> Result<Option<Version>>
+    where
+        Self: Sized;
+
     /// Publishes a package to the registry.
-    fn publish(pkg: &Package, dry_run: bool, registry: Option<&str>) -> Result<PublishResult>
+    fn publish(pkg: &Package, dry_run: bool, options: &PublishOptions) -> Result<PublishResult>
     where
         Self: Sized;
 
@@ -137,11 +277,11 @@ pub fn detect_ecosystem(start: &Path) -> Option<Ecosystem> {
     let mut current = start.to_path_buf();
 
     loop {
-        if current.join("Cargo.toml").exists() {
-            return Some(Ecosystem::Rust);
-        }
-        if current.join("pyproject.toml").exists() {
-            return Some(Ecosystem::Python);
+        if let Some(ecosystem) = Ecosystem::ALL
+            .into_iter()
+            .find(|e| e.has_manifest(&current) || e.has_workspace_file(&current))
+        {
+            return Some(ecosystem);
         }
 
         match current.parent() {
@@ -151,17 +291,54 @@ pub fn detect_ecosystem(start: &Path) -> Option<Ecosystem> {
     }
 }
 
-pub fn discover_packages(ecosystem: Ecosystem, root: &Path) -> Result<Vec<Package>> {
-    match ecosystem {
-        Ecosystem::Rust => RustAdapter::discover(root),
+pub fn discover_packages(
+    ecosystem: Ecosystem,
+    root: &Path,
+    config: &Config,
+) -> Result<Vec<Package>> {
+    let packages = match ecosystem {
+        Ecosystem::Rust => RustAdapter::discover_members(root, config.rust.release_members),
         Ecosystem::Python => PythonAdapter::discover(root),
+        Ecosystem::Go => GoAdapter::discover_modules(root, config, &CliGit::in_dir(root).tags()),
+        Ecosystem::Dotnet => DotnetAdapter::discover_projects(root, &config.dotnet.projects),
+    }?;
+    Ok(dedupe_symlinked(root, packages))
+}
+
+/// Lists each package directory once when it was also found through a symlink
+/// (e.g. `packages/core -> ../crates/core`). Of the copies, the one reached without
+/// going through a symlink under `root` is kept, otherwise the first.
+fn dedupe_symlinked(root: &Path, packages: Vec<Package>) -> Vec<Package> {
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let canonical_root = canonical(root);
+    let is_real = |pkg: &Package| {
+        pkg.path
+            .strip_prefix(root)
+            .is_ok_and(|rel| canonical(&pkg.path) == canonical_root.join(rel))
+    };
+
+    let mut kept: Vec<(PathBuf, Package)> = Vec::with_capacity(packages.len());
+    for pkg in packages {
+        let key = canonical(&pkg.path);
+        match kept.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => {
+                debug!(package = %pkg.name, path = %pkg.path.display(), same_as = %existing.path.display(), "skipping symlinked duplicate");
+                if !is_real(existing) && is_real(&pkg) {
+                    *existing = pkg;
+                }
+            }
+            None => kept.push((key, pkg)),
+        }
     }
+    kept.into_iter().map(|(_, pkg)| pkg).collect()
 }
 
 pub fn read_version(ecosystem: Ecosystem, manifest_path: &Path) -> Result<Version> {
     match ecosystem {
         Ecosystem::Rust => RustAdapter::read_version(manifest_path),
         Ecosystem::Python => PythonAdapter::read_version(manifest_path),
+        Ecosystem::Go => GoAdapter::read_version(manifest_path),
+        Ecosystem::Dotnet => DotnetAdapter::read_version(manifest_path),
     }
 }
 
@@ -169,25 +346,169 @@ pub fn write_version(ecosystem: Ecosystem, manifest_path: &Path, version: &Versi
     match ecosystem {
         Ecosystem::Rust => RustAdapter::write_version(manifest_path, version),
         Ecosystem::Python => PythonAdapter::write_version(manifest_path, version),
+        Ecosystem::Go => GoAdapter::write_version(manifest_path, version),
+        Ecosystem::Dotnet => DotnetAdapter::write_version(manifest_path, version),
+    }
+}
+
+/// Writes `version` into each `targets` file that exists under `dir`, replacing
+/// the pattern's `version` group (or its first group).
+pub fn write_version_targets(
+    dir: &Path,
+    version: &Version,
+    targets: &[VersionTarget],
+) -> Result<()> {
+    let version = version.to_string();
+    for target in targets {
+        write_version_target(dir, target, &version)?;
+    }
+    Ok(())
+}
+
+/// Writes `value` into `target`'s file under `dir` if it exists, replacing the
+/// pattern's `version` group (or its first group).
+pub fn write_version_target(dir: &Path, target: &VersionTarget, value: &str) -> Result<()> {
+    let path = dir.join(&target.path);
+    if !path.exists() {
+        return Ok(());
     }
+
+    let re = Regex::new(&target.pattern).map_err(|e| {
+        Error::ConfigParse(format!("invalid version pattern for {}: {}", target.path, e).into())
+    })?;
+    let content = std::fs::read_to_string(&path)?;
+    let captures = re
+        .captures(&content)
+        .ok_or_else(|| Error::VersionNotFound(path.display().to_string()))?;
+    let group = captures
+        .name("version")
+        .or_else(|| captures.get(1))
+        .ok_or_else(|| {
+            Error::ConfigParse(
+                format!("version pattern for {} has no capture group", target.path).into(),
+            )
+        })?;
+
+    let updated = format!(
+        "{}{}{}",
+        &content[..group.start()],
+        value,
+        &content[group.end()..]
+    );
+    atomic_write(&path, updated)
 }
 
 pub fn update_dependency_versions(
     ecosystem: Ecosystem,
     packages: &[Package],
-    root: &Path,
+    root_manifest: &Path,
     updates: &HashMap<String, Version>,
 ) -> Result<()> {
     match ecosystem {
-        Ecosystem::Rust => RustAdapter::update_all_dependency_versions(packages, root, updates),
-        Ecosystem::Python => PythonAdapter::update_all_dependency_versions(packages, root, updates),
+        Ecosystem::Rust => {
+            RustAdapter::update_all_dependency_versions(packages, root_manifest, updates)
+        }
+        Ecosystem::Python => {
+            PythonAdapter::update_all_dependency_versions(packages, root_manifest, updates)
+        }
+        Ecosystem::Go => {
+            GoAdapter::update_all_dependency_versions(packages, root_manifest, updates)
+        }
+        Ecosystem::Dotnet => {
+            DotnetAdapter::update_all_dependency_versions(packages, root_manifest, updates)
+        }
+    }
+}
+
+/// The requirements [`update_dependency_versions`] would rewrite, as `(manifest,
+/// dependency)` pairs. The updates run on scratch copies of the manifests, one
+/// dependency at a time, so requirements the adapter leaves alone (path-only,
+/// inherited, or ranges that still match) are left out here too.
+pub fn planned_dependency_updates(
+    ecosystem: Ecosystem,
+    packages: &[Package],
+    root_manifest: &Path,
+    updates: &HashMap<String, Version>,
+) -> Result<Vec<(PathBuf, String)>> {
+    let scratch = tempfile::TempDir::new()?;
+    // Each manifest keeps its file name in a directory of its own.
+    let mut copies: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let mut copy_of = |manifest: &Path| -> PathBuf {
+        if let Some((_, copy)) = copies.iter().find(|(original, _)| original == manifest) {
+            return copy.clone();
+        }
+        let copy = scratch
+            .path()
+            .join(copies.len().to_string())
+            .join(manifest.file_name().unwrap_or_default());
+        copies.push((manifest.to_path_buf(), copy.clone()));
+        copy
+    };
+    let scratch_packages: Vec<Package> = packages
+        .iter()
+        .map(|package| Package {
+            manifest_path: copy_of(&package.manifest_path),
+            ..package.clone()
+        })
+        .collect();
+    let scratch_root = if root_manifest.exists() {
+        copy_of(root_manifest)
+    } else {
+        scratch.path().join("missing").join("manifest")
+    };
+
+    let mut names: Vec<&String> = updates.keys().collect();
+    names.sort();
+    let mut planned = Vec::new();
+    for name in names {
+        let mut originals = Vec::new();
+        for (original, copy) in &copies {
+            let content = std::fs::read(original)?;
+            std::fs::create_dir_all(copy.parent().unwrap_or(scratch.path()))?;
+            std::fs::write(copy, &content)?;
+            originals.push(content);
+        }
+        let update = HashMap::from([(name.clone(), updates[name].clone())]);
+        update_dependency_versions(ecosystem, &scratch_packages, &scratch_root, &update)?;
+        for ((original, copy), content) in copies.iter().zip(&originals) {
+            if std::fs::read(copy)? != *content {
+                planned.push((original.clone(), name.clone()));
+            }
+        }
+    }
+    Ok(planned)
+}
+
+/// The HTTP client for registry and GitHub API lookups. Its short timeout makes an
+/// unreachable server fail fast rather than stall `version` or `doctor`.
+pub(crate) fn http_agent() -> &'static ureq::Agent {
+    static AGENT: std::sync::OnceLock<ureq::Agent> = std::sync::OnceLock::new();
+    AGENT.get_or_init(|| {
+        ureq::AgentBuilder::new()
+            .timeout_connect(std::time::Duration::from_secs(5))
+            .timeout(std::time::Duration::from_secs(15))
+            .build()
+    })
+}
+
+pub fn is_published(ecosystem: Ecosystem, pkg: &Package) -> Result<bool> {
+    match ecosystem {
+        Ecosystem::Rust => RustAdapter::is_published(&pkg.name, &pkg.version),
+        Ecosystem::Python => PythonAdapter::is_published(&pkg.name, &pkg.version),
+        // The proxy knows modules by path, which the package name may not be.
+        Ecosystem::Go => {
+            GoAdapter::is_published(&GoAdapter::module_path(&pkg.manifest_path)?, &pkg.version)
+        }
+        Ecosystem::Dotnet => DotnetAdapter::is_published(&pkg.name, &pkg.version),
     }
 }
 
-pub fn is_published(ecosystem: Ecosystem, name: &str, version: &Version) -> Result<bool> {
+pub fn latest_published(ecosystem: Ecosystem, pkg: &Package) -> Result<Option<Version>> {
     match ecosystem {
-        Ecosystem::Rust => RustAdapter::is_published(name, version),
-        Ecosystem::Python => PythonAdapter::is_published(name, version),
+        Ecosystem::Rust => RustAdapter::latest_published(&pkg.name),
+        Ecosystem::Python => PythonAdapter::latest_published(&pkg.name),
+        Ecosystem::Go => GoAdapter::latest_published(&GoAdapter::module_path(&pkg.manifest_path)?),
+        Ecosystem::Dotnet => DotnetAdapter::latest_published(&pkg.name),
     }
 }
 
@@ -195,17 +516,248 @@ pub fn publish(
     ecosystem: Ecosystem,
     pkg: &Package,
     dry_run: bool,
-    registry: Option<&str>,
+    options: &PublishOptions,
 ) -> Result<PublishResult> {
     match ecosystem {
-        Ecosystem::Rust => RustAdapter::publish(pkg, dry_run, registry),
-        Ecosystem::Python => PythonAdapter::publish(pkg, dry_run, registry),
+        Ecosystem::Rust => RustAdapter::publish(pkg, dry_run, options),
+        Ecosystem::Python => PythonAdapter::publish(pkg, dry_run, options),
+        Ecosystem::Go => GoAdapter::publish(pkg, dry_run, options),
+        Ecosystem::Dotnet => DotnetAdapter::publish(pkg, dry_run, options),
     }
 }
 
+/// Whether the package's manifest opts out of publishing. Only Cargo has such a
+/// setting (`publish = false`); other ecosystems' packages are never private.
+pub fn is_private(ecosystem: Ecosystem, pkg: &Package) -> Result<bool> {
+    match ecosystem {
+        Ecosystem::Rust => RustAdapter::is_private(&pkg.manifest_path),
+        Ecosystem::Python | Ecosystem::Go | Ecosystem::Dotnet => Ok(false),
+    }
+}
+
+/// The command that refreshes the workspace lockfile after `version`, when the
+/// ecosystem supports it, `update_lockfile` is set and a lockfile exists.
+pub fn lockfile_command(ecosystem: Ecosystem, root: &Path, config: &Config) -> Option<Command> {
+    match ecosystem {
+        Ecosystem::Python if config.python.update_lockfile => PythonAdapter::lockfile_command(root),
+        _ => None,
+    }
+}
+
+/// Runs a command from [`lockfile_command`] and returns its command line, failing
+/// with its output when it can't be started or exits non-zero.
+pub fn run_lockfile_command(mut cmd: Command) -> Result<String> {
+    let line = command_line(&cmd, &[]);
+    debug!(command = %line, "refreshing lockfile");
+    let output = cmd
+        .output()
+        .map_err(|e| Error::LockfileUpdateFailed(format!("could not run `{}`: {}", line, e)))?;
+    if !output.status.success() {
+        return Err(Error::LockfileUpdateFailed(format!(
+            "`{}` failed ({}):\n{}{}",
+            line,
+            output.status,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr).trim_end(),
+        )));
+    }
+    Ok(line)
+}
+
+/// Renders `cmd` as a shell-like line for logs, with every occurrence of a
+/// `secrets` value masked.
+pub(crate) fn command_line(cmd: &Command, secrets: &[&str]) -> String {
+    let mut line = std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+    for secret in secrets.iter().filter(|s| !s.is_empty()) {
+        line = line.replace(secret, "***");
+    }
+    line
+}
+
+/// A command that runs `command` through the platform shell: `sh -c` on Unix and
+/// `cmd /C` on Windows.
+pub fn shell_command(command: &str) -> Command {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    cmd.arg(command);
+    cmd
+}
+
 pub fn tag_name(ecosystem: Ecosystem, pkg: &Package) -> String {
     match ecosystem {
         Ecosystem::Rust => RustAdapter::tag_name(pkg),
         Ecosystem::Python => PythonAdapter::tag_name(pkg),
+        Ecosystem::Go => GoAdapter::tag_name(pkg),
+        Ecosystem::Dotnet => DotnetAdapter::tag_name(pkg),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_result_display_and_serde() {
+        let cases = [
+            (PublishResult::Success, "success", r#"{"status":"success"}"#),
+            (
+                PublishResult::Skipped(SkipReason::NoToken),
+                "skipped",
+                r#"{"status":"skipped","reason":"no-token"}"#,
+            ),
+            (
+                PublishResult::Skipped(SkipReason::NotPublishable),
+                "skipped",
+                r#"{"status":"skipped","reason":"not-publishable"}"#,
+            ),
+            (PublishResult::Failed, "failed", r#"{"status":"failed"}"#),
+        ];
+        for (result, display, json) in cases {
+            assert_eq!(result.to_string(), display);
+            assert_eq!(serde_json::to_string(&result).unwrap(), json);
+            assert_eq!(serde_json::from_str::<PublishResult>(json).unwrap(), result);
+        }
+    }
+
+    #[test]
+    fn test_detect_single() {
+        let cases = [
+            ("Cargo.toml", Ecosystem::Rust),
+            ("pyproject.toml", Ecosystem::Python),
+            ("go.mod", Ecosystem::Go),
+            ("Api.csproj", Ecosystem::Dotnet),
+        ];
+        for (manifest, ecosystem) in cases {
+            let dir = tempfile::TempDir::new().unwrap();
+            std::fs::write(dir.path().join(manifest), "").unwrap();
+            assert_eq!(Ecosystem::detect_single(dir.path()), Some(ecosystem));
+        }
+    }
+
+    #[test]
+    fn test_detect_single_ignores_parents_and_workspace_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        std::fs::write(dir.path().join("go.work"), "").unwrap();
+        std::fs::write(dir.path().join("App.sln"), "").unwrap();
+        let child = dir.path().join("docs");
+        std::fs::create_dir(&child).unwrap();
+
+        assert_eq!(Ecosystem::detect_single(&child), None);
+        assert_eq!(detect_ecosystem(&child), Some(Ecosystem::Rust));
+
+        std::fs::remove_file(dir.path().join("Cargo.toml")).unwrap();
+        assert_eq!(Ecosystem::detect_single(dir.path()), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_discover_packages_skips_symlinked_duplicates() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <PropertyGroup>\n    <Version>1.0.0</Version>\n  </PropertyGroup>\n</Project>\n";
+        std::fs::create_dir_all(dir.path().join("src/Core")).unwrap();
+        std::fs::write(dir.path().join("src/Core/Core.csproj"), project).unwrap();
+        std::fs::create_dir(dir.path().join("packages")).unwrap();
+        std::os::unix::fs::symlink("../src/Core", dir.path().join("packages/Core")).unwrap();
+        // A loop back to the root doesn't send the walk in circles.
+        std::os::unix::fs::symlink("..", dir.path().join("packages/root")).unwrap();
+
+        let packages =
+            discover_packages(Ecosystem::Dotnet, dir.path(), &Config::default()).unwrap();
+
+        assert_eq!(packages.len(), 1, "{packages:?}");
+        assert_eq!(packages[0].name, "Core");
+        assert_eq!(packages[0].path, dir.path().join("src/Core"));
+    }
+
+    #[test]
+    fn test_lockfile_command() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("uv.lock"), "").unwrap();
+        let mut config = Config::default();
+        assert!(lockfile_command(Ecosystem::Python, dir.path(), &config).is_none());
+
+        config.python.update_lockfile = true;
+        let cmd = lockfile_command(Ecosystem::Python, dir.path(), &config).unwrap();
+        assert_eq!(command_line(&cmd, &[]), "uv lock --offline");
+        assert!(lockfile_command(Ecosystem::Rust, dir.path(), &config).is_none());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_lockfile_command_failure() {
+        let cmd = shell_command("echo resolving; echo 'no solution found' >&2; exit 1");
+
+        let err = run_lockfile_command(cmd).unwrap_err();
+
+        let message = err.to_string();
+        assert!(matches!(err, Error::LockfileUpdateFailed(_)));
+        assert!(message.contains("resolving"), "{message}");
+        assert!(message.contains("no solution found"), "{message}");
+
+        let err = run_lockfile_command(Command::new("changelogs-no-such-tool")).unwrap_err();
+        assert!(err.to_string().contains("could not run"), "{err}");
+    }
+
+    #[test]
+    fn test_command_line_masks_secrets() {
+        let mut cmd = Command::new("dotnet");
+        cmd.args([
+            "nuget",
+            "push",
+            "--api-key",
+            "oy2abc",
+            "--source",
+            "oy2abc.example",
+        ]);
+
+        assert_eq!(
+            command_line(&cmd, &["oy2abc", ""]),
+            "dotnet nuget push --api-key *** --source ***.example"
+        );
+    }
+
+    #[test]
+    fn write_version_targets_replaces_version() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("internal")).unwrap();
+        std::fs::write(
+            dir.path().join("internal/version.go"),
+            "package internal\n\nconst Version = \"1.2.0\"\n",
+        )
+        .unwrap();
+        let targets = vec![
+            VersionTarget {
+                path: "internal/version.go".to_string(),
+                pattern: crate::config::default_version_pattern(),
+            },
+            VersionTarget {
+                path: "missing.go".to_string(),
+                pattern: crate::config::default_version_pattern(),
+            },
+        ];
+
+        write_version_targets(dir.path(), &Version::new(1, 3, 0), &targets).unwrap();
+
+        let content = std::fs::read_to_string(dir.path().join("internal/version.go")).unwrap();
+        assert_eq!(content, "package internal\n\nconst Version = \"1.3.0\"\n");
+
+        let bad = vec![VersionTarget {
+            path: "internal/version.go".to_string(),
+            pattern: r"Release = (\S+)".to_string(),
+        }];
+        let err = write_version_targets(dir.path(), &Version::new(1, 4, 0), &bad);
+        assert!(matches!(err, Err(Error::VersionNotFound(_))));
     }
 }
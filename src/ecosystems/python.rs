@@ -1,11 +1,18 @@
-use crate::ecosystems::{Ecosystem, EcosystemAdapter, Package, PublishResult, SkipReason};
+use crate::config::VersionTarget;
+use crate::ecosystems::{
+    Dependency, Ecosystem, EcosystemAdapter, Package, PublishOptions, PublishResult, SkipReason,
+    command_line, http_agent,
+};
 use crate::error::{Error, Result};
+use crate::fs_util::{Bom, read_text, write_text};
+use regex::Regex;
 use semver::Version;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
 use toml_edit::DocumentMut;
+use tracing::debug;
 
 pub struct PythonAdapter;
 
@@ -24,8 +31,7 @@ impl EcosystemAdapter for PythonAdapter {
             )));
         }
 
-        let content = std::fs::read_to_string(&pyproject_path)?;
-        let doc: DocumentMut = content.parse()?;
+        let (doc, _) = Self::load_manifest(&pyproject_path)?;
 
         // Try PEP 621 [project] first, then fall back to Poetry [tool.poetry]
         if let Some(pkg) = Self::try_pep621(&doc, root, &pyproject_path)? {
@@ -43,8 +49,7 @@ impl EcosystemAdapter for PythonAdapter {
     }
 
     fn read_version(manifest_path: &Path) -> Result<Version> {
-        let content = std::fs::read_to_string(manifest_path)?;
-        let doc: DocumentMut = content.parse()?;
+        let (doc, _) = Self::load_manifest(manifest_path)?;
 
         // Try PEP 621 first
         if let Some(version_str) = doc
@@ -69,14 +74,13 @@ impl EcosystemAdapter for PythonAdapter {
     }
 
     fn write_version(manifest_path: &Path, version: &Version) -> Result<()> {
-        let content = std::fs::read_to_string(manifest_path)?;
-        let mut doc: DocumentMut = content.parse()?;
+        let (mut doc, bom) = Self::load_manifest(manifest_path)?;
 
         // Try PEP 621 first
         if let Some(project) = doc.get_mut("project").and_then(|p| p.as_table_mut()) {
             if project.contains_key("version") {
                 project["version"] = toml_edit::value(version.to_string());
-                std::fs::write(manifest_path, doc.to_string())?;
+                write_text(manifest_path, &doc.to_string(), bom)?;
                 return Ok(());
             }
         }
@@ -86,7 +90,7 @@ impl EcosystemAdapter for PythonAdapter {
             if let Some(poetry) = tool.get_mut("poetry").and_then(|p| p.as_table_mut()) {
                 if poetry.contains_key("version") {
                     poetry["version"] = toml_edit::value(version.to_string());
-                    std::fs::write(manifest_path, doc.to_string())?;
+                    write_text(manifest_path, &doc.to_string(), bom)?;
                     return Ok(());
                 }
             }
@@ -103,8 +107,7 @@ impl EcosystemAdapter for PythonAdapter {
         dep_name: &str,
         new_version: &Version,
     ) -> Result<bool> {
-        let content = fs::read_to_string(manifest_path)?;
-        let mut doc: DocumentMut = content.parse()?;
+        let (mut doc, bom) = Self::load_manifest(manifest_path)?;
         let mut modified = false;
 
         let Some(project) = doc.get_mut("project") else {
@@ -130,26 +133,17 @@ impl EcosystemAdapter for PythonAdapter {
         }
 
         if modified {
-            fs::write(manifest_path, doc.to_string())?;
+            write_text(manifest_path, &doc.to_string(), bom)?;
         }
 
         Ok(modified)
     }
 
     fn is_published(name: &str, version: &Version) -> Result<bool> {
-        let normalized_name = Self::normalize_pep503(name);
-        let url = format!("https://pypi.org/pypi/{}/json", normalized_name);
-
-        let response = match ureq::get(&url).call() {
-            Ok(resp) => resp,
-            Err(ureq::Error::Status(404, _)) => return Ok(false),
-            Err(e) => return Err(Error::PypiCheckFailed(e.to_string())),
+        let Some(json) = Self::fetch_project(name)? else {
+            return Ok(false);
         };
 
-        let json: serde_json::Value = response
-            .into_json()
-            .map_err(|e| Error::PypiCheckFailed(format!("failed to parse JSON: {}", e)))?;
-
         if let Some(releases) = json.get("releases").and_then(|r| r.as_object()) {
             let version_str = version.to_string();
             return Ok(releases.contains_key(&version_str));
@@ -158,7 +152,11 @@ impl EcosystemAdapter for PythonAdapter {
         Ok(false)
     }
 
-    fn publish(pkg: &Package, dry_run: bool, registry: Option<&str>) -> Result<PublishResult> {
+    fn latest_published(name: &str) -> Result<Option<Version>> {
+        Ok(Self::fetch_project(name)?.and_then(|json| Self::latest_release(&json)))
+    }
+
+    fn publish(pkg: &Package, dry_run: bool, options: &PublishOptions) -> Result<PublishResult> {
         if dry_run {
             return Ok(PublishResult::Success);
         }
@@ -170,6 +168,7 @@ impl EcosystemAdapter for PythonAdapter {
             .map(|v| !v.is_empty())
             .unwrap_or(false);
         if !has_password && !has_username {
+            debug!(package = %pkg.name, "TWINE_USERNAME and TWINE_PASSWORD are not set, skipping");
             return Ok(PublishResult::Skipped(SkipReason::NoToken));
         }
 
@@ -190,9 +189,10 @@ impl EcosystemAdapter for PythonAdapter {
             fs::remove_dir_all(&canonical_dist)?;
         }
 
-        let build_output = Command::new("python")
-            .args(["-m", "build"])
-            .current_dir(&pkg_path)
+        let mut build = Command::new("python");
+        build.args(["-m", "build"]).current_dir(&pkg_path);
+        debug!(command = %command_line(&build, &[]), "running");
+        let build_output = build
             .output()
             .map_err(|e| Error::PublishFailed(format!("failed to run 'python -m build': {}", e)))?;
 
@@ -226,7 +226,7 @@ impl EcosystemAdapter for PythonAdapter {
         let mut cmd = Command::new("twine");
         cmd.arg("upload");
 
-        if let Some(reg) = registry {
+        if let Some(reg) = options.registry.as_deref() {
             match reg.to_lowercase().as_str() {
                 "testpypi" => {
                     cmd.args(["--repository", "testpypi"]);
@@ -245,6 +245,7 @@ impl EcosystemAdapter for PythonAdapter {
         }
         cmd.current_dir(&pkg_path);
 
+        debug!(command = %command_line(&cmd, &[]), "running");
         let upload_output = cmd
             .output()
             .map_err(|e| Error::PublishFailed(format!("failed to run 'twine upload': {}", e)))?;
@@ -268,7 +269,112 @@ impl EcosystemAdapter for PythonAdapter {
     }
 }
 
+/// `poetry lock` arguments for the Poetry that printed `version_output`. Before
+/// 2.0, `poetry lock` upgrades dependencies unless given `--no-update`; 2.0 made
+/// that the default and removed the flag. An unknown version is taken as current.
+fn poetry_lock_args(version_output: &str) -> Vec<&'static str> {
+    let major = version_output
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .find_map(|word| word.split('.').next()?.parse::<u64>().ok());
+    match major {
+        Some(major) if major < 2 => vec!["lock", "--no-update"],
+        _ => vec!["lock"],
+    }
+}
+
 impl PythonAdapter {
+    /// The command that refreshes the lockfile in `root`: `uv lock` for `uv.lock`,
+    /// otherwise `poetry lock` for `poetry.lock`. Neither upgrades other dependencies.
+    pub fn lockfile_command(root: &Path) -> Option<Command> {
+        let (program, args) = if root.join("uv.lock").is_file() {
+            ("uv", vec!["lock", "--offline"])
+        } else if root.join("poetry.lock").is_file() {
+            let version = Command::new("poetry")
+                .arg("--version")
+                .current_dir(root)
+                .output()
+                .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+                .unwrap_or_default();
+            ("poetry", poetry_lock_args(&version))
+        } else {
+            return None;
+        };
+        let mut cmd = Command::new(program);
+        cmd.args(args).current_dir(root);
+        Some(cmd)
+    }
+
+    /// Version targets for a Sphinx `docs/conf.py` under `package_dir`, each with
+    /// the value to write for `new`: `release` when it is assigned exactly once to
+    /// `current`, and `version` when it is assigned exactly once to `current` or to
+    /// its short X.Y form, which it keeps. Anything else is skipped with a warning
+    /// rather than guessed at.
+    pub fn docs_version_targets(
+        package_dir: &Path,
+        current: &Version,
+        new: &Version,
+    ) -> (Vec<(VersionTarget, String)>, Vec<String>) {
+        let conf = package_dir.join("docs/conf.py");
+        let Ok(content) = fs::read_to_string(&conf) else {
+            return (Vec::new(), Vec::new());
+        };
+
+        let short = |v: &Version| format!("{}.{}", v.major, v.minor);
+        let mut targets = Vec::new();
+        let mut warnings = Vec::new();
+        for var in ["version", "release"] {
+            let pattern = format!(r#"(?m)^{var}\s*=\s*["'](?P<version>[^"'\n]*)["']"#);
+            let re = Regex::new(&pattern).expect("valid docs version pattern");
+            let values: Vec<&str> = re
+                .captures_iter(&content)
+                .filter_map(|c| c.name("version"))
+                .map(|m| m.as_str())
+                .collect();
+            let value = match values.as_slice() {
+                [] => continue,
+                [value] if *value == current.to_string() => new.to_string(),
+                [value] if var == "version" && *value == short(current) => short(new),
+                [value] => {
+                    warnings.push(format!(
+                        "{}: {} = \"{}\" does not match {}, leaving it alone",
+                        conf.display(),
+                        var,
+                        value,
+                        current
+                    ));
+                    continue;
+                }
+                _ => {
+                    warnings.push(format!(
+                        "{}: {} is assigned {} times, leaving it alone",
+                        conf.display(),
+                        var,
+                        values.len()
+                    ));
+                    continue;
+                }
+            };
+            let target = VersionTarget {
+                path: "docs/conf.py".to_string(),
+                pattern,
+            };
+            targets.push((target, value));
+        }
+        (targets, warnings)
+    }
+
+    /// Reads and parses a pyproject.toml, along with whether it has a byte order
+    /// mark to write back. Parse errors keep the path and the line/column reported
+    /// by toml_edit.
+    fn load_manifest(path: &Path) -> Result<(DocumentMut, Bom)> {
+        let (content, bom) = read_text(path)?;
+        let doc = content.parse().map_err(|e| Error::ManifestLoad {
+            ecosystem: Ecosystem::Python,
+            details: format!("{}: {}", path.display(), e).trim_end().to_string(),
+        })?;
+        Ok((doc, bom))
+    }
+
     fn try_pep621(
         doc: &DocumentMut,
         root: &Path,
@@ -349,13 +455,13 @@ impl PythonAdapter {
         }))
     }
 
-    fn extract_poetry_dependencies(poetry: &toml_edit::Item) -> Vec<String> {
+    fn extract_poetry_dependencies(poetry: &toml_edit::Item) -> Vec<Dependency> {
         let mut deps = Vec::new();
 
         if let Some(dependencies) = poetry.get("dependencies").and_then(|d| d.as_table_like()) {
             for (name, _) in dependencies.iter() {
                 if name != "python" {
-                    deps.push(Self::normalize_pep503(name));
+                    deps.push(Dependency::normal(Self::normalize_pep503(name)));
                 }
             }
         }
@@ -365,7 +471,7 @@ impl PythonAdapter {
             .and_then(|d| d.as_table_like())
         {
             for (name, _) in dev_deps.iter() {
-                deps.push(Self::normalize_pep503(name));
+                deps.push(Dependency::dev(Self::normalize_pep503(name)));
             }
         }
 
@@ -376,7 +482,7 @@ impl PythonAdapter {
                     .and_then(|d| d.as_table_like())
                 {
                     for (name, _) in group_deps.iter() {
-                        deps.push(Self::normalize_pep503(name));
+                        deps.push(Dependency::dev(Self::normalize_pep503(name)));
                     }
                 }
             }
@@ -399,33 +505,93 @@ impl PythonAdapter {
                 continue;
             }
             if let Some(new_dep) = Self::rewrite_dependency(dep_str, new_version) {
-                arr.replace(i, new_dep);
+                // `replace` keeps the item's comments and indentation; keep its quoting too.
+                let literal = match arr.get(i) {
+                    Some(toml_edit::Value::String(s)) => s
+                        .as_repr()
+                        .and_then(|r| r.as_raw().as_str())
+                        .is_some_and(|raw| raw.starts_with('\'')),
+                    _ => false,
+                };
+                let value = match format!("'{}'", new_dep).parse::<toml_edit::Value>() {
+                    Ok(value) if literal && !new_dep.contains('\'') => value,
+                    _ => new_dep.into(),
+                };
+                arr.replace(i, value);
                 modified = true;
             }
         }
         modified
     }
 
-    fn extract_dependencies(doc: &DocumentMut) -> Vec<String> {
+    /// Reads `[project]` dependencies and optional extras as normal dependencies, and
+    /// PEP 735 `[dependency-groups]` as dev dependencies.
+    fn extract_dependencies(doc: &DocumentMut) -> Vec<Dependency> {
         let mut deps = Vec::new();
 
         if let Some(project) = doc.get("project") {
-            if let Some(dependencies) = project.get("dependencies") {
-                if let Some(arr) = dependencies.as_array() {
-                    for item in arr.iter() {
-                        if let Some(dep_str) = item.as_str() {
-                            if let Some(name) = Self::parse_dependency_name(dep_str) {
-                                deps.push(name);
-                            }
-                        }
+            if let Some(arr) = project.get("dependencies").and_then(|d| d.as_array()) {
+                deps.extend(Self::parse_dependency_names(arr).map(Dependency::normal));
+            }
+            if let Some(extras) = project
+                .get("optional-dependencies")
+                .and_then(|d| d.as_table_like())
+            {
+                for (_, extra) in extras.iter() {
+                    if let Some(arr) = extra.as_array() {
+                        deps.extend(Self::parse_dependency_names(arr).map(Dependency::normal));
                     }
                 }
             }
         }
 
+        if let Some(groups) = doc.get("dependency-groups").and_then(|g| g.as_table_like()) {
+            for (_, group) in groups.iter() {
+                if let Some(arr) = group.as_array() {
+                    deps.extend(Self::parse_dependency_names(arr).map(Dependency::dev));
+                }
+            }
+        }
+
         deps
     }
 
+    fn parse_dependency_names(arr: &toml_edit::Array) -> impl Iterator<Item = String> + '_ {
+        arr.iter()
+            .filter_map(|item| item.as_str())
+            .filter_map(Self::parse_dependency_name)
+    }
+
+    /// Fetches a project's JSON from PyPI. Returns `None` if the project does not exist.
+    fn fetch_project(name: &str) -> Result<Option<serde_json::Value>> {
+        let url = format!(
+            "https://pypi.org/pypi/{}/json",
+            Self::normalize_pep503(name)
+        );
+
+        let response = match http_agent().get(&url).call() {
+            Ok(resp) => resp,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(e) => return Err(Error::PypiCheckFailed(e.into())),
+        };
+
+        let json = response
+            .into_json()
+            .map_err(|e| Error::PypiCheckFailed(e.into()))?;
+
+        Ok(Some(json))
+    }
+
+    /// The highest release in a PyPI project JSON. Versions that aren't semver,
+    /// like `1.0rc1`, are skipped.
+    fn latest_release(json: &serde_json::Value) -> Option<Version> {
+        json.get("releases")?
+            .as_object()?
+            .keys()
+            .filter_map(|v| v.parse().ok())
+            .max()
+    }
+
     fn normalize_pep503(name: &str) -> String {
         let lower = name.to_ascii_lowercase();
         let mut out = String::with_capacity(lower.len());
@@ -527,13 +693,16 @@ impl PythonAdapter {
 
     pub fn update_all_dependency_versions(
         packages: &[Package],
-        _root: &Path,
+        _root_manifest: &Path,
         updates: &HashMap<String, Version>,
     ) -> Result<()> {
         for package in packages {
+            let mut modified = false;
             for (dep_name, new_version) in updates {
-                Self::update_dependency_version(&package.manifest_path, dep_name, new_version)?;
+                modified |=
+                    Self::update_dependency_version(&package.manifest_path, dep_name, new_version)?;
             }
+            debug!(manifest = %package.manifest_path.display(), modified, "updated dependency requirements");
         }
         Ok(())
     }
@@ -552,6 +721,107 @@ mod tests {
         path
     }
 
+    #[test]
+    fn docs_version_targets_update_version_and_release() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        std::fs::write(
+            dir.path().join("docs/conf.py"),
+            "project = \"demo\"\nversion = \"1.2.0\"\nrelease = '1.2.0'\nhtml_theme = \"alabaster\"\n",
+        )
+        .unwrap();
+
+        let write = |current: Version, new: Version| {
+            let (targets, warnings) =
+                PythonAdapter::docs_version_targets(dir.path(), &current, &new);
+            assert_eq!(targets.len(), 2);
+            assert!(warnings.is_empty());
+            for (target, value) in &targets {
+                crate::ecosystems::write_version_target(dir.path(), target, value).unwrap();
+            }
+            std::fs::read_to_string(dir.path().join("docs/conf.py")).unwrap()
+        };
+
+        assert_eq!(
+            write(Version::new(1, 2, 0), Version::new(1, 3, 0)),
+            "project = \"demo\"\nversion = \"1.3.0\"\nrelease = '1.3.0'\nhtml_theme = \"alabaster\"\n"
+        );
+
+        // Sphinx's `version` is usually the short X.Y, and stays that way.
+        std::fs::write(
+            dir.path().join("docs/conf.py"),
+            "version = \"1.3\"\nrelease = \"1.3.0\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            write(Version::new(1, 3, 0), Version::new(1, 3, 1)),
+            "version = \"1.3\"\nrelease = \"1.3.1\"\n"
+        );
+        assert_eq!(
+            write(Version::new(1, 3, 1), Version::new(2, 0, 0)),
+            "version = \"2.0\"\nrelease = \"2.0.0\"\n"
+        );
+    }
+
+    #[test]
+    fn docs_version_targets_skip_mismatched_values() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        std::fs::write(
+            dir.path().join("docs/conf.py"),
+            "release = \"0.9.0\"\n# the short X.Y version\nmy_version = \"1.2.0\"\n",
+        )
+        .unwrap();
+
+        let (targets, warnings) = PythonAdapter::docs_version_targets(
+            dir.path(),
+            &Version::new(1, 2, 0),
+            &Version::new(1, 3, 0),
+        );
+        assert!(targets.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].contains("release = \"0.9.0\""),
+            "{}",
+            warnings[0]
+        );
+
+        // No docs/ at all is not worth a warning.
+        let empty = TempDir::new().unwrap();
+        let (targets, warnings) = PythonAdapter::docs_version_targets(
+            empty.path(),
+            &Version::new(1, 2, 0),
+            &Version::new(1, 3, 0),
+        );
+        assert!(targets.is_empty() && warnings.is_empty());
+    }
+
+    #[test]
+    fn poetry_lock_args_per_version() {
+        assert_eq!(
+            poetry_lock_args("Poetry (version 1.8.3)\n"),
+            ["lock", "--no-update"]
+        );
+        assert_eq!(poetry_lock_args("Poetry (version 2.1.1)\n"), ["lock"]);
+        assert_eq!(poetry_lock_args(""), ["lock"]);
+    }
+
+    #[test]
+    fn lockfile_command_per_tool() {
+        let dir = TempDir::new().unwrap();
+        assert!(PythonAdapter::lockfile_command(dir.path()).is_none());
+
+        std::fs::write(dir.path().join("poetry.lock"), "").unwrap();
+        let cmd = PythonAdapter::lockfile_command(dir.path()).unwrap();
+        assert_eq!(cmd.get_program(), "poetry");
+        assert_eq!(cmd.get_current_dir(), Some(dir.path()));
+
+        // uv wins when both lockfiles exist.
+        std::fs::write(dir.path().join("uv.lock"), "").unwrap();
+        let cmd = PythonAdapter::lockfile_command(dir.path()).unwrap();
+        assert_eq!(command_line(&cmd, &[]), "uv lock --offline");
+    }
+
     #[test]
     fn discover_valid_pyproject() {
         let tmp = TempDir::new().unwrap();
@@ -569,7 +839,51 @@ dependencies = ["requests>=2.0"]
         assert_eq!(packages.len(), 1);
         assert_eq!(packages[0].name, "my-package");
         assert_eq!(packages[0].version.to_string(), "1.2.3");
-        assert_eq!(packages[0].dependencies, vec!["requests"]);
+        assert_eq!(
+            packages[0].dependencies,
+            vec![Dependency::normal("requests")]
+        );
+    }
+
+    #[test]
+    fn discover_requires_python_without_dependencies() {
+        let tmp = TempDir::new().unwrap();
+        let path = create_pyproject(
+            tmp.path(),
+            r#"
+[project]
+name = "my-package"
+version = "1.2.3"
+requires-python = ">=3.9"
+"#,
+        );
+
+        let packages = PythonAdapter::discover(tmp.path()).unwrap();
+        assert_eq!(packages.len(), 1);
+        assert!(packages[0].dependencies.is_empty());
+
+        let new_version: Version = "2.0.0".parse().unwrap();
+        let modified =
+            PythonAdapter::update_dependency_version(&path, "requests", &new_version).unwrap();
+        assert!(!modified);
+    }
+
+    #[test]
+    fn write_version_keeps_bom() {
+        let tmp = TempDir::new().unwrap();
+        let path = create_pyproject(
+            tmp.path(),
+            "\u{feff}[project]\nname = \"my-package\"\nversion = \"1.2.3\"\n",
+        );
+
+        let packages = PythonAdapter::discover(tmp.path()).unwrap();
+        assert_eq!(packages[0].name, "my-package");
+
+        PythonAdapter::write_version(&path, &Version::new(1, 3, 0)).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "\u{feff}[project]\nname = \"my-package\"\nversion = \"1.3.0\"\n"
+        );
     }
 
     #[test]
@@ -652,6 +966,18 @@ name = "my-package"
         assert_eq!(PythonAdapter::normalize_pep503("pkg_-_"), "pkg");
     }
 
+    #[test]
+    fn latest_release_skips_non_semver() {
+        let json = serde_json::json!({
+            "releases": { "1.0.0": [], "1.2.0": [], "1.10.0rc1": [], "1.1.0": [] }
+        });
+        assert_eq!(
+            PythonAdapter::latest_release(&json),
+            Some(Version::new(1, 2, 0))
+        );
+        assert_eq!(PythonAdapter::latest_release(&serde_json::json!({})), None);
+    }
+
     #[test]
     fn parse_dependency_name_simple() {
         assert_eq!(
@@ -760,6 +1086,46 @@ dependencies = [
         assert!(content.contains("click>=8.0"));
     }
 
+    #[test]
+    fn update_dependency_version_keeps_array_layout() {
+        let tmp = TempDir::new().unwrap();
+        let path = create_pyproject(
+            tmp.path(),
+            r#"[project]
+name = "my-package"
+version = "1.0.0"
+dependencies = [
+    # HTTP
+    "requests>=2.0",  # pinned by the release
+    'click>=8.0',
+]
+
+[project.optional-dependencies]
+cli = ['requests[socks]>=2.0; python_version >= "3.9"']
+"#,
+        );
+
+        let new_version: Version = "3.0.0".parse().unwrap();
+        assert!(PythonAdapter::update_dependency_version(&path, "requests", &new_version).unwrap());
+        assert!(PythonAdapter::update_dependency_version(&path, "click", &new_version).unwrap());
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            r#"[project]
+name = "my-package"
+version = "1.0.0"
+dependencies = [
+    # HTTP
+    "requests==3.0.0",  # pinned by the release
+    'click==3.0.0',
+]
+
+[project.optional-dependencies]
+cli = ['requests[socks]==3.0.0; python_version >= "3.9"']
+"#
+        );
+    }
+
     #[test]
     fn dependency_matches_normalized() {
         assert!(PythonAdapter::dependency_matches(
@@ -820,9 +1186,21 @@ click = "^8.0"
         assert_eq!(packages.len(), 1);
         assert_eq!(packages[0].name, "poetry-pkg");
         assert_eq!(packages[0].version.to_string(), "0.5.0");
-        assert!(packages[0].dependencies.contains(&"requests".to_string()));
-        assert!(packages[0].dependencies.contains(&"click".to_string()));
-        assert!(!packages[0].dependencies.contains(&"python".to_string()));
+        assert!(
+            packages[0]
+                .dependencies
+                .contains(&Dependency::normal("requests"))
+        );
+        assert!(
+            packages[0]
+                .dependencies
+                .contains(&Dependency::normal("click"))
+        );
+        assert!(
+            !packages[0]
+                .dependencies
+                .contains(&Dependency::normal("python"))
+        );
     }
 
     #[test]
@@ -847,9 +1225,17 @@ black = "^23.0"
 
         let packages = PythonAdapter::discover(tmp.path()).unwrap();
         assert_eq!(packages.len(), 1);
-        assert!(packages[0].dependencies.contains(&"requests".to_string()));
-        assert!(packages[0].dependencies.contains(&"pytest".to_string()));
-        assert!(packages[0].dependencies.contains(&"black".to_string()));
+        assert!(
+            packages[0]
+                .dependencies
+                .contains(&Dependency::normal("requests"))
+        );
+        assert!(
+            packages[0]
+                .dependencies
+                .contains(&Dependency::dev("pytest"))
+        );
+        assert!(packages[0].dependencies.contains(&Dependency::dev("black")));
     }
 
     #[test]
@@ -912,7 +1298,7 @@ version = "1.0.0"
 "#,
         );
         let pkg = &PythonAdapter::discover(tmp.path()).unwrap()[0];
-        let result = PythonAdapter::publish(pkg, true, None).unwrap();
+        let result = PythonAdapter::publish(pkg, true, &PublishOptions::default()).unwrap();
         assert_eq!(result, PublishResult::Success);
     }
 
@@ -934,7 +1320,7 @@ version = "1.0.0"
             std::env::remove_var("TWINE_PASSWORD");
             std::env::remove_var("TWINE_USERNAME");
         }
-        let result = PythonAdapter::publish(pkg, false, None).unwrap();
+        let result = PythonAdapter::publish(pkg, false, &PublishOptions::default()).unwrap();
         assert_eq!(result, PublishResult::Skipped(SkipReason::NoToken));
     }
 
@@ -1,70 +1,49 @@
-use crate::ecosystems::{Ecosystem, EcosystemAdapter, Package, PublishResult, SkipReason};
-use crate::error::Result;
+use crate::config::ReleaseMembers;
+use crate::ecosystems::{
+    Dependency, DependencyKind, Ecosystem, EcosystemAdapter, Package, PublishOptions,
+    PublishResult, SkipReason, command_line, http_agent,
+};
+use crate::error::{Error, Result};
+use crate::fs_util::{read_text, write_text};
 use cargo_metadata::MetadataCommand;
-use semver::Version;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::Path;
 use std::process::Command;
 use toml_edit::DocumentMut;
+use tracing::debug;
+
+const CRATES_IO_INDEX: &str = "https://index.crates.io";
 
 pub struct RustAdapter;
 
+/// A single version record from the crates.io sparse index.
+#[derive(Debug, Deserialize)]
+struct IndexEntry {
+    vers: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
 impl EcosystemAdapter for RustAdapter {
     fn ecosystem() -> Ecosystem {
         Ecosystem::Rust
     }
 
     fn discover(root: &Path) -> Result<Vec<Package>> {
-        let metadata = MetadataCommand::new().current_dir(root).exec()?;
-
-        let workspace_members: std::collections::HashSet<_> =
-            metadata.workspace_members.iter().collect();
-
-        let mut packages = Vec::new();
-
-        for package in &metadata.packages {
-            if !workspace_members.contains(&package.id) {
-                continue;
-            }
-
-            let deps: Vec<String> = package
-                .dependencies
-                .iter()
-                .filter_map(|dep| {
-                    metadata
-                        .packages
-                        .iter()
-                        .find(|p| p.name == dep.name && workspace_members.contains(&p.id))
-                        .map(|p| p.name.clone())
-                })
-                .collect();
-
-            packages.push(Package {
-                name: package.name.clone(),
-                version: package.version.clone(),
-                path: package
-                    .manifest_path
-                    .parent()
-                    .unwrap()
-                    .to_path_buf()
-                    .into_std_path_buf(),
-                manifest_path: package.manifest_path.clone().into_std_path_buf(),
-                dependencies: deps,
-            });
-        }
-
-        Ok(packages)
+        Self::discover_members(root, ReleaseMembers::All)
     }
 
     fn read_version(manifest_path: &Path) -> Result<Version> {
-        let content = std::fs::read_to_string(manifest_path)?;
+        let (content, _) = read_text(manifest_path)?;
         let doc: DocumentMut = content.parse()?;
 
         // Check if version uses workspace inheritance (version.workspace = true)
         if Self::is_workspace_inherited(&doc, "version") {
             let root = Self::find_workspace_root(manifest_path)?;
             let root_manifest = root.join("Cargo.toml");
-            let root_content = std::fs::read_to_string(&root_manifest)?;
+            let (root_content, _) = read_text(&root_manifest)?;
             let root_doc: DocumentMut = root_content.parse()?;
 
             let version_str = root_doc
@@ -79,34 +58,38 @@ impl EcosystemAdapter for RustAdapter {
             return Ok(version_str.parse()?);
         }
 
-        let version_str = doc["package"]["version"].as_str().ok_or_else(|| {
-            crate::error::Error::VersionNotFound(manifest_path.display().to_string())
-        })?;
+        let version_str = doc
+            .get("package")
+            .and_then(|p| p.get("version"))
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                crate::error::Error::VersionNotFound(manifest_path.display().to_string())
+            })?;
 
         Ok(version_str.parse()?)
     }
 
     fn write_version(manifest_path: &Path, version: &Version) -> Result<()> {
-        let content = std::fs::read_to_string(manifest_path)?;
+        let (content, bom) = read_text(manifest_path)?;
         let doc: DocumentMut = content.parse()?;
 
         // If version is inherited from workspace, write to root Cargo.toml instead
         if Self::is_workspace_inherited(&doc, "version") {
             let root = Self::find_workspace_root(manifest_path)?;
             let root_manifest = root.join("Cargo.toml");
-            let root_content = std::fs::read_to_string(&root_manifest)?;
+            let (root_content, root_bom) = read_text(&root_manifest)?;
             let mut root_doc: DocumentMut = root_content.parse()?;
 
             root_doc["workspace"]["package"]["version"] = toml_edit::value(version.to_string());
 
-            std::fs::write(&root_manifest, root_doc.to_string())?;
+            write_text(&root_manifest, &root_doc.to_string(), root_bom)?;
             return Ok(());
         }
 
         let mut doc: DocumentMut = content.parse()?;
         doc["package"]["version"] = toml_edit::value(version.to_string());
 
-        std::fs::write(manifest_path, doc.to_string())?;
+        write_text(manifest_path, &doc.to_string(), bom)?;
         Ok(())
     }
 
@@ -115,82 +98,51 @@ impl EcosystemAdapter for RustAdapter {
         dep_name: &str,
         new_version: &Version,
     ) -> Result<bool> {
-        let content = std::fs::read_to_string(manifest_path)?;
-        let mut doc: DocumentMut = content.parse()?;
-        let mut modified = false;
-
-        for section in &["dependencies", "dev-dependencies", "build-dependencies"] {
-            let Some(dep) = doc.get_mut(section).and_then(|d| d.get_mut(dep_name)) else {
-                continue;
-            };
-            modified |= Self::update_dep_version_in_item(dep, new_version);
-        }
-
-        if let Some(dep) = doc
-            .get_mut("workspace")
-            .and_then(|w| w.get_mut("dependencies"))
-            .and_then(|d| d.get_mut(dep_name))
-        {
-            modified |= Self::update_dep_version_in_item(dep, new_version);
-        }
-
-        if modified {
-            std::fs::write(manifest_path, doc.to_string())?;
-        }
-
-        Ok(modified)
+        let updates = HashMap::from([(dep_name.to_string(), new_version.clone())]);
+        Self::update_manifest_dependencies(manifest_path, &updates)
     }
 
     fn is_published(name: &str, version: &Version) -> Result<bool> {
-        let output = Command::new("cargo")
-            .args(["search", "--limit", "1", name])
-            .output()?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        // A yanked version still occupies its slot on crates.io and can never be
+        // re-uploaded, so it counts as published here.
+        let Some(body) = Self::fetch_index(name)? else {
+            return Ok(false);
+        };
 
-        let is_published_with_same_version = stdout
-            .lines()
-            .next()
-            .map(|line| line.contains(&format!("\"{}\"", version)))
-            .unwrap_or(false);
+        Ok(Self::lookup_index_entry(&body, version).is_some())
+    }
 
-        Ok(is_published_with_same_version)
+    fn latest_published(name: &str) -> Result<Option<Version>> {
+        Ok(Self::fetch_index(name)?.and_then(|body| Self::latest_in_index(&body)))
     }
 
-    fn publish(pkg: &Package, dry_run: bool, registry: Option<&str>) -> Result<PublishResult> {
+    fn publish(pkg: &Package, dry_run: bool, options: &PublishOptions) -> Result<PublishResult> {
         if dry_run {
             return Ok(PublishResult::Success);
         }
 
         // Skip crates that have `publish = false` in their Cargo.toml
-        let content = std::fs::read_to_string(&pkg.manifest_path)?;
-        let doc: DocumentMut = content.parse()?;
-        if let Some(publish) = doc
-            .get("package")
-            .and_then(|p| p.get("publish"))
-            .and_then(|v| v.as_bool())
-        {
-            if !publish {
-                return Ok(PublishResult::Skipped(SkipReason::NotPublishable));
-            }
+        if Self::is_private(&pkg.manifest_path)? {
+            debug!(package = %pkg.name, "publish = false, skipping");
+            return Ok(PublishResult::Skipped(SkipReason::NotPublishable));
         }
 
         match std::env::var("CARGO_REGISTRY_TOKEN") {
             Ok(token) if !token.is_empty() => {}
-            _ => return Ok(PublishResult::Skipped(SkipReason::NoToken)),
+            _ => {
+                debug!(package = %pkg.name, "CARGO_REGISTRY_TOKEN is not set, skipping");
+                return Ok(PublishResult::Skipped(SkipReason::NoToken));
+            }
         }
 
         let mut cmd = Command::new("cargo");
-        cmd.arg("publish")
-            .arg("--package")
-            .arg(&pkg.name)
-            .arg("--no-verify")
-            .arg("--allow-dirty");
+        cmd.args(Self::publish_args(&pkg.name, options));
 
-        if let Some(reg) = registry {
+        if let Some(reg) = options.registry.as_deref() {
             cmd.env("CARGO_REGISTRY_DEFAULT", reg);
         }
 
+        debug!(command = %command_line(&cmd, &[]), "running");
         let output = cmd.output().map_err(|e| {
             crate::error::Error::PublishFailed(format!("failed to run 'cargo publish': {}", e))
         })?;
@@ -215,6 +167,153 @@ impl EcosystemAdapter for RustAdapter {
 }
 
 impl RustAdapter {
+    /// Whether the crate opts out of publishing with `publish = false` (or `publish = []`,
+    /// no allowed registries).
+    pub fn is_private(manifest_path: &Path) -> Result<bool> {
+        let (content, _) = read_text(manifest_path)?;
+        let doc: DocumentMut = content.parse()?;
+        let publish = doc.get("package").and_then(|p| p.get("publish"));
+        Ok(publish.and_then(|v| v.as_bool()) == Some(false)
+            || publish
+                .and_then(|v| v.as_array())
+                .is_some_and(|registries| registries.is_empty()))
+    }
+
+    /// Arguments for `cargo publish`. Skipping verification is faster but lets build
+    /// errors through to crates.io, where the upload can't be taken back.
+    fn publish_args<'a>(name: &'a str, options: &PublishOptions) -> Vec<&'a str> {
+        let mut args = vec!["publish", "--package", name];
+        if !options.verify {
+            args.push("--no-verify");
+        }
+        if options.allow_dirty {
+            args.push("--allow-dirty");
+        }
+        args
+    }
+
+    /// Discovers workspace members, restricted to `default-members` when requested.
+    pub fn discover_members(root: &Path, members: ReleaseMembers) -> Result<Vec<Package>> {
+        let metadata = MetadataCommand::new()
+            .current_dir(root)
+            .exec()
+            .map_err(|e| metadata_error(e, root))?;
+
+        let workspace_members: std::collections::HashSet<_> = match members {
+            ReleaseMembers::All => metadata.workspace_members.iter().collect(),
+            ReleaseMembers::Default => {
+                if metadata.workspace_default_members.is_missing() {
+                    return Err(Error::ConfigParse(
+                        "rust.release_members = \"default\" requires cargo 1.71 or newer".into(),
+                    ));
+                }
+                metadata.workspace_default_members.iter().collect()
+            }
+        };
+
+        let mut packages = Vec::new();
+
+        for package in &metadata.packages {
+            if !workspace_members.contains(&package.id) {
+                continue;
+            }
+
+            let deps: Vec<Dependency> = package
+                .dependencies
+                .iter()
+                .filter_map(|dep| {
+                    let name = metadata
+                        .packages
+                        .iter()
+                        .find(|p| p.name == dep.name && workspace_members.contains(&p.id))
+                        .map(|p| p.name.clone())?;
+                    let kind = match dep.kind {
+                        cargo_metadata::DependencyKind::Development => DependencyKind::Dev,
+                        cargo_metadata::DependencyKind::Build => DependencyKind::Build,
+                        _ => DependencyKind::Normal,
+                    };
+                    Some(Dependency { name, kind })
+                })
+                .collect();
+
+            packages.push(Package {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                path: package
+                    .manifest_path
+                    .parent()
+                    .unwrap()
+                    .to_path_buf()
+                    .into_std_path_buf(),
+                manifest_path: package.manifest_path.clone().into_std_path_buf(),
+                dependencies: deps,
+            });
+        }
+
+        Ok(packages)
+    }
+
+    /// Checks whether a published version has been yanked from crates.io.
+    ///
+    /// Returns `false` for versions that were never published.
+    pub fn is_yanked(name: &str, version: &Version) -> Result<bool> {
+        let Some(body) = Self::fetch_index(name)? else {
+            return Ok(false);
+        };
+
+        Ok(Self::lookup_index_entry(&body, version).is_some_and(|entry| entry.yanked))
+    }
+
+    /// Fetches the sparse index file for a crate. Returns `None` if the crate does not exist.
+    fn fetch_index(name: &str) -> Result<Option<String>> {
+        let url = format!("{}/{}", CRATES_IO_INDEX, Self::index_path(name));
+
+        let response = match http_agent().get(&url).call() {
+            Ok(resp) => resp,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(e) => return Err(Error::CratesIoCheckFailed(e.into())),
+        };
+
+        let body = response
+            .into_string()
+            .map_err(|e| Error::CratesIoCheckFailed(e.into()))?;
+
+        Ok(Some(body))
+    }
+
+    /// Returns the path of a crate's file within the sparse index, following Cargo's layout.
+    fn index_path(name: &str) -> String {
+        let name = name.to_ascii_lowercase();
+        match name.len() {
+            1 => format!("1/{}", name),
+            2 => format!("2/{}", name),
+            3 => format!("3/{}/{}", &name[..1], name),
+            _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+        }
+    }
+
+    /// The highest version in a sparse index file, yanked ones included since their
+    /// slot is taken all the same.
+    fn latest_in_index(body: &str) -> Option<Version> {
+        body.lines()
+            .filter_map(|line| serde_json::from_str::<IndexEntry>(line).ok())
+            .filter_map(|entry| entry.vers.parse::<Version>().ok())
+            .max()
+    }
+
+    /// Finds the index entry for `version` in a sparse index file (one JSON object per line).
+    fn lookup_index_entry(body: &str, version: &Version) -> Option<IndexEntry> {
+        body.lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str::<IndexEntry>(line).ok())
+            .find(|entry| {
+                entry
+                    .vers
+                    .parse::<Version>()
+                    .is_ok_and(|vers| vers == *version)
+            })
+    }
+
     /// Check if a field in `[package]` uses workspace inheritance (e.g., `version.workspace = true`).
     fn is_workspace_inherited(doc: &DocumentMut, field: &str) -> bool {
         doc.get("package")
@@ -234,8 +333,9 @@ impl RustAdapter {
 
         loop {
             let candidate = current.join("Cargo.toml");
-            if candidate.exists() && candidate != manifest_path {
-                let content = std::fs::read_to_string(&candidate)?;
+            // The root package of a non-virtual workspace is its own workspace root.
+            if candidate.exists() {
+                let (content, _) = read_text(&candidate)?;
                 let doc: DocumentMut = content.parse()?;
                 if doc.get("workspace").is_some() {
                     return Ok(current);
@@ -255,12 +355,14 @@ impl RustAdapter {
 
     fn update_dep_version_in_item(dep: &mut toml_edit::Item, new_version: &Version) -> bool {
         if let Some(table) = dep.as_inline_table_mut() {
-            if table.contains_key("version") {
+            let current = table.get("version").and_then(|v| v.as_str());
+            if current.is_some_and(|req| Self::should_rewrite_requirement(req, new_version)) {
                 table.insert("version", new_version.to_string().into());
                 return true;
             }
         } else if let Some(table) = dep.as_table_mut() {
-            if table.contains_key("version") {
+            let current = table.get("version").and_then(|v| v.as_str());
+            if current.is_some_and(|req| Self::should_rewrite_requirement(req, new_version)) {
                 table["version"] = toml_edit::value(new_version.to_string());
                 return true;
             }
@@ -268,43 +370,112 @@ impl RustAdapter {
         false
     }
 
+    /// Pins (`1.0.0-rc.1`, `=1.0.0-rc.1`) always follow the new version. Explicit
+    /// ranges (`^1.0.0-rc`, `>=1.0, <2`) are left alone while they still match it.
+    fn should_rewrite_requirement(req: &str, new_version: &Version) -> bool {
+        let req = req.trim();
+        let is_range = req.starts_with(['^', '~', '>', '<']) || req.contains([',', '*']);
+        if !is_range {
+            return true;
+        }
+        VersionReq::parse(req).map_or(true, |r| !r.matches(new_version))
+    }
+
     pub fn update_all_dependency_versions(
         packages: &[Package],
-        root: &Path,
+        root_manifest: &Path,
         updates: &HashMap<String, Version>,
     ) -> Result<()> {
-        for package in packages {
-            for (dep_name, new_version) in updates {
-                Self::update_dependency_version(&package.manifest_path, dep_name, new_version)?;
-            }
+        // In a non-virtual workspace the root package's manifest is the root manifest;
+        // it gets a single pass covering both its own and `[workspace.dependencies]`.
+        let mut manifests: Vec<&Path> =
+            packages.iter().map(|p| p.manifest_path.as_path()).collect();
+        if root_manifest.exists() && !manifests.contains(&root_manifest) {
+            manifests.push(root_manifest);
         }
 
-        let root_manifest = root.join("Cargo.toml");
-        if !root_manifest.exists() {
-            return Ok(());
+        for manifest in manifests {
+            let modified = Self::update_manifest_dependencies(manifest, updates)?;
+            debug!(manifest = %manifest.display(), modified, "updated dependency requirements");
         }
+        Ok(())
+    }
+
+    /// Updates the entries of a dependency table that point at a package in
+    /// `updates`. A renamed dependency (`foo2 = { package = "foo", ... }`) is matched
+    /// by its `package` field, any other by its key.
+    fn update_dependency_table(
+        table: &mut toml_edit::Item,
+        updates: &HashMap<String, Version>,
+    ) -> bool {
+        let Some(table) = table.as_table_like_mut() else {
+            return false;
+        };
+
+        let mut modified = false;
+        for (key, dep) in table.iter_mut() {
+            let package = dep
+                .get("package")
+                .and_then(|p| p.as_str())
+                .unwrap_or(key.get());
+            if let Some(new_version) = updates.get(package) {
+                modified |= Self::update_dep_version_in_item(dep, new_version);
+            }
+        }
+        modified
+    }
 
-        let content = std::fs::read_to_string(&root_manifest)?;
+    /// Rewrites the requirements on every package in `updates` across the dependency
+    /// tables of one manifest, including `[workspace.dependencies]`, writing it at
+    /// most once. Returns true if the manifest changed.
+    fn update_manifest_dependencies(
+        manifest_path: &Path,
+        updates: &HashMap<String, Version>,
+    ) -> Result<bool> {
+        let (content, bom) = read_text(manifest_path)?;
         let mut doc: DocumentMut = content.parse()?;
         let mut modified = false;
 
-        if let Some(deps) = doc
+        for section in &["dependencies", "dev-dependencies", "build-dependencies"] {
+            if let Some(table) = doc.get_mut(section) {
+                modified |= Self::update_dependency_table(table, updates);
+            }
+        }
+
+        if let Some(table) = doc
             .get_mut("workspace")
             .and_then(|w| w.get_mut("dependencies"))
-            .and_then(|d| d.as_table_mut())
         {
-            for (dep_name, new_version) in updates {
-                if let Some(dep) = deps.get_mut(dep_name) {
-                    modified |= Self::update_dep_version_in_item(dep, new_version);
-                }
-            }
+            modified |= Self::update_dependency_table(table, updates);
         }
 
         if modified {
-            std::fs::write(&root_manifest, doc.to_string())?;
+            write_text(manifest_path, &doc.to_string(), bom)?;
         }
 
-        Ok(())
+        Ok(modified)
+    }
+}
+
+/// Maps a failed `cargo metadata` run to an error that says what to do about it.
+fn metadata_error(err: cargo_metadata::Error, root: &Path) -> Error {
+    match err {
+        cargo_metadata::Error::Io(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Error::CargoNotFound
+        }
+        // Keep cargo's own explanation (bad manifest, offline git deps, ...).
+        cargo_metadata::Error::CargoMetadata { stderr } => Error::ManifestLoad {
+            ecosystem: Ecosystem::Rust,
+            details: stderr.trim().to_string(),
+        },
+        other => Error::ManifestLoad {
+            ecosystem: Ecosystem::Rust,
+            details: format!(
+                "{}\nrun `cargo metadata --format-version 1` in {} to see the underlying error",
+                other,
+                root.display()
+            ),
+        },
     }
 }
 
@@ -328,13 +499,23 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "index not found")]
     fn test_read_version_missing_version() {
         let dir = TempDir::new().unwrap();
         let manifest = dir.path().join("Cargo.toml");
         std::fs::write(&manifest, "[package]\nname = \"test\"\n").unwrap();
 
-        let _ = RustAdapter::read_version(&manifest);
+        let result = RustAdapter::read_version(&manifest);
+        assert!(matches!(result, Err(Error::VersionNotFound(_))));
+    }
+
+    #[test]
+    fn test_read_version_virtual_manifest() {
+        let dir = TempDir::new().unwrap();
+        let manifest = dir.path().join("Cargo.toml");
+        std::fs::write(&manifest, "[workspace]\nmembers = [\"a\"]\n").unwrap();
+
+        let result = RustAdapter::read_version(&manifest);
+        assert!(matches!(result, Err(Error::VersionNotFound(_))));
     }
 
     #[test]
@@ -353,6 +534,29 @@ mod tests {
         assert_eq!(version, Version::new(2, 3, 4));
     }
 
+    #[test]
+    fn test_write_version_keeps_bom() {
+        let dir = TempDir::new().unwrap();
+        let manifest = dir.path().join("Cargo.toml");
+        std::fs::write(
+            &manifest,
+            "\u{feff}[package]\nname = \"test\"\nversion = \"1.0.0\"\n\n[dependencies]\ncore = { path = \"../core\", version = \"1.0.0\" }\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            RustAdapter::read_version(&manifest).unwrap(),
+            Version::new(1, 0, 0)
+        );
+        RustAdapter::write_version(&manifest, &Version::new(1, 1, 0)).unwrap();
+        RustAdapter::update_dependency_version(&manifest, "core", &Version::new(2, 0, 0)).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&manifest).unwrap(),
+            "\u{feff}[package]\nname = \"test\"\nversion = \"1.1.0\"\n\n[dependencies]\ncore = { path = \"../core\", version = \"2.0.0\" }\n"
+        );
+    }
+
     #[test]
     fn test_write_version_preserves_other_fields() {
         let dir = TempDir::new().unwrap();
@@ -399,6 +603,66 @@ my-dep = { version = \"1.0.0\", features = [\"serde\"] }\n";
         assert!(updated.contains("serde"));
     }
 
+    #[test]
+    fn test_update_dependency_version_prerelease_pin_vs_range() {
+        let dir = TempDir::new().unwrap();
+        let manifest = dir.path().join("Cargo.toml");
+        let content = "\
+[package]\n\
+name = \"test\"\n\
+version = \"1.0.0\"\n\
+\n\
+[dependencies]\n\
+pinned = { version = \"1.0.0-rc.1\" }\n\
+\n\
+[dev-dependencies]\n\
+ranged = { version = \"^1.0.0-rc\" }\n";
+        std::fs::write(&manifest, content).unwrap();
+        let rc2 = Version::parse("1.0.0-rc.2").unwrap();
+
+        assert!(RustAdapter::update_dependency_version(&manifest, "pinned", &rc2).unwrap());
+        assert!(!RustAdapter::update_dependency_version(&manifest, "ranged", &rc2).unwrap());
+
+        let updated = std::fs::read_to_string(&manifest).unwrap();
+        assert!(updated.contains("pinned = { version = \"1.0.0-rc.2\" }"));
+        assert!(updated.contains("ranged = { version = \"^1.0.0-rc\" }"));
+    }
+
+    #[test]
+    fn test_should_rewrite_requirement() {
+        let v = |s: &str| Version::parse(s).unwrap();
+
+        assert!(RustAdapter::should_rewrite_requirement(
+            "1.0.0-rc.1",
+            &v("1.0.0-rc.2")
+        ));
+        assert!(RustAdapter::should_rewrite_requirement(
+            "=1.0.0-rc.1",
+            &v("1.0.0-rc.2")
+        ));
+        assert!(!RustAdapter::should_rewrite_requirement(
+            "^1.0.0-rc",
+            &v("1.0.0-rc.2")
+        ));
+        assert!(!RustAdapter::should_rewrite_requirement(
+            "^1.0.0-rc",
+            &v("1.0.0")
+        ));
+        assert!(!RustAdapter::should_rewrite_requirement(
+            ">=1.0, <2",
+            &v("1.4.0")
+        ));
+        // A range the new version falls outside of still has to move.
+        assert!(RustAdapter::should_rewrite_requirement(
+            "^1.0.0-rc",
+            &v("2.0.0")
+        ));
+        assert!(RustAdapter::should_rewrite_requirement(
+            "~0.9",
+            &v("0.10.0")
+        ));
+    }
+
     #[test]
     fn test_update_dependency_version_not_found() {
         let dir = TempDir::new().unwrap();
@@ -462,6 +726,59 @@ my-dep = { version = \"1.0.0\" }\n";
         assert!(updated.contains("version = \"4.0.0\""));
     }
 
+    #[test]
+    fn test_update_renamed_dependencies() {
+        let dir = TempDir::new().unwrap();
+        let manifest = dir.path().join("Cargo.toml");
+        std::fs::write(
+            &manifest,
+            "\
+[package]
+name = \"test\"
+version = \"1.0.0\"
+
+[dependencies]
+foo2 = { package = \"foo\", path = \"../foo\", version = \"1.0.0\" }
+foo = { package = \"other\", version = \"1.0.0\" }
+
+[dev-dependencies.foo-dev]
+package = \"foo\"
+path = \"../foo\"
+version = \"1.0.0\"
+
+[workspace.dependencies]
+foo-ws = { package = \"foo\", path = \"foo\", version = \"1.0.0\" }
+",
+        )
+        .unwrap();
+
+        let modified =
+            RustAdapter::update_dependency_version(&manifest, "foo", &Version::new(2, 0, 0))
+                .unwrap();
+        assert!(modified);
+
+        assert_eq!(
+            std::fs::read_to_string(&manifest).unwrap(),
+            "\
+[package]
+name = \"test\"
+version = \"1.0.0\"
+
+[dependencies]
+foo2 = { package = \"foo\", path = \"../foo\", version = \"2.0.0\" }
+foo = { package = \"other\", version = \"1.0.0\" }
+
+[dev-dependencies.foo-dev]
+package = \"foo\"
+path = \"../foo\"
+version = \"2.0.0\"
+
+[workspace.dependencies]
+foo-ws = { package = \"foo\", path = \"foo\", version = \"2.0.0\" }
+"
+        );
+    }
+
     #[test]
     fn publish_dry_run_returns_success() {
         let dir = TempDir::new().unwrap();
@@ -480,10 +797,30 @@ my-dep = { version = \"1.0.0\" }\n";
             dependencies: vec![],
         };
 
-        let result = RustAdapter::publish(&pkg, true, None).unwrap();
+        let result = RustAdapter::publish(&pkg, true, &PublishOptions::default()).unwrap();
         assert_eq!(result, PublishResult::Success);
     }
 
+    #[test]
+    fn test_publish_args() {
+        let args = |verify, allow_dirty| {
+            let options = PublishOptions {
+                verify,
+                allow_dirty,
+                ..PublishOptions::default()
+            };
+            RustAdapter::publish_args("foo", &options).join(" ")
+        };
+
+        assert_eq!(
+            args(false, true),
+            "publish --package foo --no-verify --allow-dirty"
+        );
+        assert_eq!(args(true, true), "publish --package foo --allow-dirty");
+        assert_eq!(args(false, false), "publish --package foo --no-verify");
+        assert_eq!(args(true, false), "publish --package foo");
+    }
+
     #[test]
     fn publish_skipped_without_token() {
         let dir = TempDir::new().unwrap();
@@ -506,7 +843,7 @@ my-dep = { version = \"1.0.0\" }\n";
         unsafe {
             std::env::remove_var("CARGO_REGISTRY_TOKEN");
         }
-        let result = RustAdapter::publish(&pkg, false, None).unwrap();
+        let result = RustAdapter::publish(&pkg, false, &PublishOptions::default()).unwrap();
         assert_eq!(result, PublishResult::Skipped(SkipReason::NoToken));
     }
 
@@ -528,7 +865,7 @@ my-dep = { version = \"1.0.0\" }\n";
             dependencies: vec![],
         };
 
-        let result = RustAdapter::publish(&pkg, false, None).unwrap();
+        let result = RustAdapter::publish(&pkg, false, &PublishOptions::default()).unwrap();
         assert_eq!(result, PublishResult::Skipped(SkipReason::NotPublishable));
     }
 
@@ -589,6 +926,211 @@ my-dep = { version = \"1.0.0\" }\n";
         assert!(crate_content.contains("version.workspace = true"));
     }
 
+    #[test]
+    fn test_write_version_root_package_workspace_inherited() {
+        let dir = TempDir::new().unwrap();
+        let manifest = dir.path().join("Cargo.toml");
+        std::fs::write(
+            &manifest,
+            "[package]\nname = \"app\"\nversion.workspace = true\n\n[workspace]\nmembers = [\"core\"]\n\n[workspace.package]\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+
+        RustAdapter::write_version(&manifest, &Version::new(1, 1, 0)).unwrap();
+
+        let content = std::fs::read_to_string(&manifest).unwrap();
+        assert!(content.contains("version.workspace = true"));
+        assert!(content.contains("version = \"1.1.0\""));
+        assert_eq!(
+            RustAdapter::read_version(&manifest).unwrap(),
+            Version::new(1, 1, 0)
+        );
+    }
+
+    #[test]
+    fn test_update_all_dependency_versions_root_package() {
+        let dir = TempDir::new().unwrap();
+        let root_manifest = dir.path().join("Cargo.toml");
+        std::fs::write(
+            &root_manifest,
+            "[package]\nname = \"app\"\nversion = \"1.0.0\"\n\n[dependencies]\ncore = { version = \"1.0.0\", path = \"core\" }\n\n[workspace]\nmembers = [\"core\"]\n\n[workspace.dependencies]\ncore = { version = \"1.0.0\", path = \"core\" }\n",
+        )
+        .unwrap();
+        let core_dir = dir.path().join("core");
+        std::fs::create_dir_all(&core_dir).unwrap();
+        std::fs::write(
+            core_dir.join("Cargo.toml"),
+            "[package]\nname = \"core\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+
+        let package = |name: &str, path: std::path::PathBuf| Package {
+            name: name.to_string(),
+            version: Version::new(1, 0, 0),
+            manifest_path: path.join("Cargo.toml"),
+            path,
+            dependencies: vec![],
+        };
+        let packages = vec![
+            package("app", dir.path().to_path_buf()),
+            package("core", core_dir),
+        ];
+        let updates = HashMap::from([("core".to_string(), Version::new(2, 0, 0))]);
+
+        RustAdapter::update_all_dependency_versions(&packages, &root_manifest, &updates).unwrap();
+
+        let content = std::fs::read_to_string(&root_manifest).unwrap();
+        assert_eq!(
+            content
+                .matches("core = { version = \"2.0.0\", path = \"core\" }")
+                .count(),
+            2
+        );
+    }
+
+    const INDEX_BODY: &str = r#"{"name":"foo","vers":"0.1.0","deps":[],"cksum":"aa","features":{},"yanked":false}
+{"name":"foo","vers":"0.2.0","deps":[],"cksum":"bb","features":{},"yanked":true}
+{"name":"foo","vers":"0.3.0-rc.1","deps":[],"cksum":"cc","features":{},"yanked":false}
+"#;
+
+    fn default_members_workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\"app\", \"tools\"]\ndefault-members = [\"app\"]\nresolver = \"2\"\n",
+        )
+        .unwrap();
+        for name in ["app", "tools"] {
+            let crate_dir = dir.path().join(name);
+            std::fs::create_dir_all(crate_dir.join("src")).unwrap();
+            std::fs::write(
+                crate_dir.join("Cargo.toml"),
+                format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+            )
+            .unwrap();
+            std::fs::write(crate_dir.join("src/lib.rs"), "").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_metadata_error_cargo_not_found() {
+        let root = Path::new("/repo");
+        let missing = cargo_metadata::Error::Io(std::io::ErrorKind::NotFound.into());
+        assert!(matches!(
+            metadata_error(missing, root),
+            Error::CargoNotFound
+        ));
+
+        let denied = cargo_metadata::Error::Io(std::io::ErrorKind::PermissionDenied.into());
+        let message = metadata_error(denied, root).to_string();
+        assert!(message.contains("run `cargo metadata"), "{message}");
+        assert!(message.contains("/repo"), "{message}");
+    }
+
+    #[test]
+    fn test_discover_broken_manifest_keeps_cargo_stderr() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"broken\"\nversion = \"0.1.0\"\nedition = \"2021\"\n[dependencies\n",
+        )
+        .unwrap();
+
+        let err = RustAdapter::discover_members(dir.path(), ReleaseMembers::All).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::ManifestLoad {
+                ecosystem: Ecosystem::Rust,
+                ..
+            }
+        ));
+        let message = err.to_string();
+        assert!(message.starts_with("failed to load rust manifests:\n"));
+        // Cargo's exact wording varies between versions; it always names the manifest.
+        assert!(message.contains("error: "), "{message}");
+        assert!(message.contains("Cargo.toml"), "{message}");
+        assert!(message.contains("[dependencies"), "{message}");
+    }
+
+    #[test]
+    fn test_discover_all_members() {
+        let dir = default_members_workspace();
+        let packages = RustAdapter::discover_members(dir.path(), ReleaseMembers::All).unwrap();
+
+        let mut names: Vec<_> = packages.iter().map(|p| p.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["app", "tools"]);
+    }
+
+    #[test]
+    fn test_discover_default_members() {
+        let dir = default_members_workspace();
+        let packages = RustAdapter::discover_members(dir.path(), ReleaseMembers::Default).unwrap();
+
+        let names: Vec<_> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["app"]);
+    }
+
+    #[test]
+    fn test_discover_records_dependency_kinds() {
+        let dir = default_members_workspace();
+        std::fs::write(
+            dir.path().join("tools/Cargo.toml"),
+            "[package]\nname = \"tools\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+             [dev-dependencies]\napp = { path = \"../app\" }\n\n\
+             [build-dependencies]\napp = { path = \"../app\" }\n",
+        )
+        .unwrap();
+
+        let packages = RustAdapter::discover_members(dir.path(), ReleaseMembers::All).unwrap();
+        let tools = packages.iter().find(|p| p.name == "tools").unwrap();
+
+        let mut kinds: Vec<_> = tools.dependencies.iter().map(|d| d.kind).collect();
+        kinds.sort_by_key(|k| *k as u8);
+        assert_eq!(kinds, vec![DependencyKind::Dev, DependencyKind::Build]);
+        assert!(tools.dependencies.iter().all(|d| d.name == "app"));
+    }
+
+    #[test]
+    fn test_index_lookup_live_version() {
+        let entry = RustAdapter::lookup_index_entry(INDEX_BODY, &Version::new(0, 1, 0)).unwrap();
+        assert!(!entry.yanked);
+    }
+
+    #[test]
+    fn test_index_lookup_yanked_version_is_still_present() {
+        let entry = RustAdapter::lookup_index_entry(INDEX_BODY, &Version::new(0, 2, 0)).unwrap();
+        assert!(entry.yanked);
+    }
+
+    #[test]
+    fn test_index_lookup_prerelease_and_missing() {
+        let rc = Version::parse("0.3.0-rc.1").unwrap();
+        assert!(RustAdapter::lookup_index_entry(INDEX_BODY, &rc).is_some());
+        assert!(RustAdapter::lookup_index_entry(INDEX_BODY, &Version::new(0, 3, 0)).is_none());
+        assert!(RustAdapter::lookup_index_entry("", &Version::new(0, 1, 0)).is_none());
+    }
+
+    #[test]
+    fn test_latest_in_index() {
+        assert_eq!(
+            RustAdapter::latest_in_index(INDEX_BODY),
+            Some(Version::parse("0.3.0-rc.1").unwrap())
+        );
+        assert_eq!(RustAdapter::latest_in_index(""), None);
+    }
+
+    #[test]
+    fn test_index_path() {
+        assert_eq!(RustAdapter::index_path("a"), "1/a");
+        assert_eq!(RustAdapter::index_path("ab"), "2/ab");
+        assert_eq!(RustAdapter::index_path("abc"), "3/a/abc");
+        assert_eq!(RustAdapter::index_path("Serde"), "se/rd/serde");
+        assert_eq!(RustAdapter::index_path("changelogs"), "ch/an/changelogs");
+    }
+
     #[test]
     fn publish_failed_error_includes_context() {
         let err = crate::error::Error::PublishFailed(
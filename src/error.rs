@@ -1,15 +1,64 @@
+use crate::ecosystems::Ecosystem;
 use thiserror::Error;
 
+/// An underlying error kept as the `source()` of a variant.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Broad class of an [`Error`], e.g. for the CLI to pick an exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// `.changelog/config.toml` or a command line setting is invalid.
+    Config,
+    /// The workspace, its packages or pending changelogs are missing or inconsistent.
+    Workspace,
+    /// A package registry could not be queried.
+    Registry,
+    /// A git operation failed or the working tree is not in the required state.
+    Git,
+    /// Reading or writing a file failed.
+    Io,
+    /// A manifest, changelog or version could not be parsed.
+    Parse,
+    /// Publishing a package failed.
+    Publish,
+}
+
+impl ErrorCategory {
+    /// Lowercase name, as in `--json` output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::Config => "config",
+            ErrorCategory::Workspace => "workspace",
+            ErrorCategory::Registry => "registry",
+            ErrorCategory::Git => "git",
+            ErrorCategory::Io => "io",
+            ErrorCategory::Parse => "parse",
+            ErrorCategory::Publish => "publish",
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error(
-        "could not detect workspace. specify ecosystem with: changelogs --ecosystem <rust|python> init"
+        "could not detect workspace. specify ecosystem with: changelogs --ecosystem <rust|python|go|dotnet> init"
     )]
     NotInWorkspace,
 
-    #[error("changelogs already initialized")]
+    #[error("changelogs already initialized (pass --force to reinitialize)")]
     AlreadyInitialized,
 
+    #[error(
+        "found .changeset/ from changesets: pass --migrate to convert it, or --coexist to keep both"
+    )]
+    ChangesetsFound,
+
+    #[error("no .changeset/ directory to migrate")]
+    ChangesetsNotFound,
+
+    #[error("failed to migrate .changeset/: {0}")]
+    ChangesetsMigration(String),
+
     #[error("changelogs not initialized - run `changelogs init` first")]
     NotInitialized,
 
@@ -25,8 +74,62 @@ pub enum Error {
     #[error("failed to parse changelog {0}: {1}")]
     ChangelogParse(String, String),
 
-    #[error("failed to parse config: {0}")]
-    ConfigParse(String),
+    #[error("changelog not found: {0}")]
+    ChangelogNotFound(String),
+
+    #[error("{0} already has a section for version {1}")]
+    ChangelogVersionExists(String, String),
+
+    #[error("invalid package filter: {0}")]
+    InvalidFilter(String),
+
+    #[error("invalid release reference: {0} (expected <package>@<version> or YYYY-MM-DD)")]
+    InvalidSince(String),
+
+    #[error("no dated CHANGELOG.md section or release tag for {0}")]
+    ReleaseNotFound(String),
+
+    #[error(
+        "uncommitted changes to files that would be modified (commit or stash them, or pass --allow-dirty):\n{0}"
+    )]
+    DirtyFiles(String),
+
+    #[error(
+        "working tree has uncommitted changes, so release tags would point at an unclean state (commit or stash them, or pass --allow-dirty):\n{0}"
+    )]
+    DirtyWorkingTree(String),
+
+    #[error("changelog files are not writable (check their permissions):\n{0}")]
+    ChangelogNotWritable(String),
+
+    #[error("changelog rejected by [lint] checks:\n{0}")]
+    LintFailed(String),
+
+    #[error("failed to refresh the lockfile: {0}")]
+    LockfileUpdateFailed(String),
+
+    #[error("release plan does not match the workspace:\n{0}")]
+    InvalidPlan(String),
+
+    #[error("release plan has warnings and `strict` is set:\n{0}")]
+    StrictPlan(String),
+
+    #[error("invalid [[fixed]] or [[linked]] groups:\n{0}")]
+    InvalidGroups(String),
+
+    #[error(
+        "this repository requires changelogs {required} (`required_version` in .changelog/config.toml), but {running} is running; install a matching version or pass --ignore-version-check"
+    )]
+    UnsupportedVersion { required: String, running: String },
+
+    #[error("unknown config key: {0}")]
+    UnknownConfigKey(String),
+
+    #[error("failed to parse config")]
+    ConfigParse(#[source] BoxError),
+
+    #[error("unknown release channel: {0} (declare it in `channels` in .changelog/config.toml)")]
+    UnknownChannel(String),
 
     #[error("no packages selected")]
     NoPackagesSelected,
@@ -37,17 +140,41 @@ pub enum Error {
     #[error("failed to parse version: {0}")]
     VersionParse(String),
 
+    #[error("failed to load {ecosystem} manifests:\n{details}")]
+    ManifestLoad {
+        ecosystem: Ecosystem,
+        details: String,
+    },
+
     #[error("Python project not found: {0}")]
     PythonProjectNotFound(String),
 
     #[error("Python dynamic version: {0}")]
     PythonDynamicVersion(String),
 
+    #[error("Go module not found: {0}")]
+    GoModuleNotFound(String),
+
+    #[error(".NET project not found: {0}")]
+    DotnetProjectNotFound(String),
+
     #[error("publish failed: {0}")]
     PublishFailed(String),
 
-    #[error("failed to check PyPI: {0}")]
-    PypiCheckFailed(String),
+    #[error("failed to check PyPI")]
+    PypiCheckFailed(#[source] BoxError),
+
+    #[error("failed to check crates.io")]
+    CratesIoCheckFailed(#[source] BoxError),
+
+    #[error("failed to check the Go module proxy")]
+    GoProxyCheckFailed(#[source] BoxError),
+
+    #[error("failed to check NuGet")]
+    NugetCheckFailed(#[source] BoxError),
+
+    #[error("git error: {0}")]
+    Git(String),
 
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
@@ -61,6 +188,11 @@ pub enum Error {
     #[error("yaml parse error: {0}")]
     YamlParse(#[from] serde_yaml::Error),
 
+    #[error(
+        "cargo not found on PATH: install Rust (https://rustup.rs) or set CARGO to the cargo binary"
+    )]
+    CargoNotFound,
+
     #[error("cargo metadata error: {0}")]
     CargoMetadata(#[from] cargo_metadata::Error),
 
@@ -68,4 +200,175 @@ pub enum Error {
     SemverParse(#[from] semver::Error),
 }
 
+impl Error {
+    /// Stable machine-readable name of the error, used by `--json` output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::NotInWorkspace => "NotInWorkspace",
+            Error::AlreadyInitialized => "AlreadyInitialized",
+            Error::ChangesetsFound => "ChangesetsFound",
+            Error::ChangesetsNotFound => "ChangesetsNotFound",
+            Error::ChangesetsMigration(_) => "ChangesetsMigration",
+            Error::NotInitialized => "NotInitialized",
+            Error::InvalidBumpType(_) => "InvalidBumpType",
+            Error::InvalidEcosystem(_) => "InvalidEcosystem",
+            Error::PackageNotFound(_) => "PackageNotFound",
+            Error::ChangelogParse(_, _) => "ChangelogParse",
+            Error::ChangelogNotFound(_) => "ChangelogNotFound",
+            Error::ChangelogVersionExists(_, _) => "ChangelogVersionExists",
+            Error::InvalidFilter(_) => "InvalidFilter",
+            Error::InvalidSince(_) => "InvalidSince",
+            Error::ReleaseNotFound(_) => "ReleaseNotFound",
+            Error::DirtyFiles(_) => "DirtyFiles",
+            Error::DirtyWorkingTree(_) => "DirtyWorkingTree",
+            Error::ChangelogNotWritable(_) => "ChangelogNotWritable",
+            Error::LintFailed(_) => "LintFailed",
+            Error::LockfileUpdateFailed(_) => "LockfileUpdateFailed",
+            Error::InvalidPlan(_) => "InvalidPlan",
+            Error::StrictPlan(_) => "StrictPlan",
+            Error::InvalidGroups(_) => "InvalidGroups",
+            Error::UnsupportedVersion { .. } => "UnsupportedVersion",
+            Error::UnknownConfigKey(_) => "UnknownConfigKey",
+            Error::ConfigParse(_) => "ConfigParse",
+            Error::UnknownChannel(_) => "UnknownChannel",
+            Error::NoPackagesSelected => "NoPackagesSelected",
+            Error::VersionNotFound(_) => "VersionNotFound",
+            Error::VersionParse(_) => "VersionParse",
+            Error::ManifestLoad { .. } => "ManifestLoad",
+            Error::PythonProjectNotFound(_) => "PythonProjectNotFound",
+            Error::PythonDynamicVersion(_) => "PythonDynamicVersion",
+            Error::GoModuleNotFound(_) => "GoModuleNotFound",
+            Error::DotnetProjectNotFound(_) => "DotnetProjectNotFound",
+            Error::PublishFailed(_) => "PublishFailed",
+            Error::PypiCheckFailed(_) => "PypiCheckFailed",
+            Error::CratesIoCheckFailed(_) => "CratesIoCheckFailed",
+            Error::GoProxyCheckFailed(_) => "GoProxyCheckFailed",
+            Error::NugetCheckFailed(_) => "NugetCheckFailed",
+            Error::Git(_) => "Git",
+            Error::Io(_) => "Io",
+            Error::TomlParse(_) => "TomlParse",
+            Error::TomlEdit(_) => "TomlEdit",
+            Error::YamlParse(_) => "YamlParse",
+            Error::CargoNotFound => "CargoNotFound",
+            Error::CargoMetadata(_) => "CargoMetadata",
+            Error::SemverParse(_) => "SemverParse",
+        }
+    }
+}
+
+impl Error {
+    /// The category of the error, which stays the same when variants are added.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Error::UnknownConfigKey(_)
+            | Error::StrictPlan(_)
+            | Error::InvalidGroups(_)
+            | Error::UnsupportedVersion { .. }
+            | Error::ConfigParse(_)
+            | Error::UnknownChannel(_)
+            | Error::InvalidEcosystem(_)
+            | Error::ChangesetsMigration(_) => ErrorCategory::Config,
+            Error::NotInWorkspace
+            | Error::AlreadyInitialized
+            | Error::ChangesetsFound
+            | Error::ChangesetsNotFound
+            | Error::NotInitialized
+            | Error::PackageNotFound(_)
+            | Error::ChangelogNotFound(_)
+            | Error::ChangelogVersionExists(_, _)
+            | Error::ReleaseNotFound(_)
+            | Error::LintFailed(_)
+            | Error::InvalidPlan(_)
+            | Error::LockfileUpdateFailed(_)
+            | Error::NoPackagesSelected
+            | Error::ManifestLoad { .. }
+            | Error::PythonProjectNotFound(_)
+            | Error::PythonDynamicVersion(_)
+            | Error::GoModuleNotFound(_)
+            | Error::DotnetProjectNotFound(_)
+            | Error::CargoNotFound
+            | Error::CargoMetadata(_) => ErrorCategory::Workspace,
+            Error::PypiCheckFailed(_)
+            | Error::CratesIoCheckFailed(_)
+            | Error::GoProxyCheckFailed(_)
+            | Error::NugetCheckFailed(_) => ErrorCategory::Registry,
+            Error::Git(_) | Error::DirtyFiles(_) | Error::DirtyWorkingTree(_) => ErrorCategory::Git,
+            Error::Io(_) | Error::ChangelogNotWritable(_) => ErrorCategory::Io,
+            Error::InvalidBumpType(_)
+            | Error::ChangelogParse(_, _)
+            | Error::InvalidFilter(_)
+            | Error::InvalidSince(_)
+            | Error::VersionNotFound(_)
+            | Error::VersionParse(_)
+            | Error::TomlParse(_)
+            | Error::TomlEdit(_)
+            | Error::YamlParse(_)
+            | Error::SemverParse(_) => ErrorCategory::Parse,
+            Error::PublishFailed(_) => ErrorCategory::Publish,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_kind() {
+        assert_eq!(Error::NotInitialized.kind(), "NotInitialized");
+        assert_eq!(
+            Error::PackageNotFound("foo".into()).kind(),
+            "PackageNotFound"
+        );
+        assert_eq!(Error::Io(std::io::Error::other("boom")).kind(), "Io");
+    }
+
+    #[test]
+    fn test_error_category() {
+        assert_eq!(Error::NotInitialized.category(), ErrorCategory::Workspace);
+        assert_eq!(
+            Error::UnknownChannel("beta".into()).category(),
+            ErrorCategory::Config
+        );
+        assert_eq!(
+            Error::PypiCheckFailed("timed out".into()).category(),
+            ErrorCategory::Registry
+        );
+        assert_eq!(
+            Error::Io(std::io::Error::other("boom")).category(),
+            ErrorCategory::Io
+        );
+        assert_eq!(ErrorCategory::Registry.as_str(), "registry");
+    }
+
+    #[test]
+    fn test_source_chain() {
+        use std::error::Error as _;
+
+        let err = Error::from(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        let io = err.source().unwrap().downcast_ref::<std::io::Error>();
+        assert_eq!(
+            io.map(|e| e.kind()),
+            Some(std::io::ErrorKind::PermissionDenied)
+        );
+
+        let toml_err = toml::from_str::<toml::Value>("a = ").unwrap_err();
+        let err = Error::ConfigParse(toml_err.clone().into());
+        assert_eq!(err.to_string(), "failed to parse config");
+        assert!(err.source().unwrap().is::<toml::de::Error>());
+        // The cause is printed once, by the chain.
+        assert_eq!(
+            format!("{:#}", anyhow::Error::from(err)),
+            format!("failed to parse config: {}", toml_err)
+        );
+
+        // Through anyhow, the underlying error stays reachable from the chain.
+        let err = anyhow::Error::from(Error::CratesIoCheckFailed(Box::new(std::io::Error::from(
+            std::io::ErrorKind::TimedOut,
+        ))));
+        let io = err.chain().find_map(|e| e.downcast_ref::<std::io::Error>());
+        assert_eq!(io.map(|e| e.kind()), Some(std::io::ErrorKind::TimedOut));
+    }
+}
@@ -0,0 +1,307 @@
+use crate::error::Result;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Writes `contents` to `path` so that readers see either the old file or the new
+/// one, never a partial write.
+///
+/// The data goes to a temp file in the same directory, is fsynced, and is then
+/// renamed over the target. Permissions of an existing target are preserved. The
+/// temp file is removed if any step fails. A symlink at `path` is followed, so the
+/// link stays in place and its target gets the new contents.
+pub fn atomic_write(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let path = &resolve_symlinks(path);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+
+    let (mut file, temp) = create_temp_file(dir, &file_name.to_string_lossy())?;
+
+    file.write_all(contents.as_ref())?;
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.sync_all()?;
+    drop(file);
+
+    rename_over(&temp.path, path)?;
+    temp.keep();
+
+    sync_dir(dir);
+    Ok(())
+}
+
+/// Follows `path` through any chain of symlinks to the file it names, which need
+/// not exist. Returns `path` unchanged if it is not a symlink.
+pub fn resolve_symlinks(path: &Path) -> PathBuf {
+    let mut path = path.to_path_buf();
+    // Bounded, like the OS, so a link cycle can't loop forever.
+    for _ in 0..40 {
+        let Ok(target) = fs::read_link(&path) else {
+            break;
+        };
+        path = match path.parent() {
+            Some(dir) => dir.join(target),
+            None => target,
+        };
+    }
+    path
+}
+
+/// Whether `atomic_write` can be expected to succeed for `path`: the directory of
+/// the file (after following symlinks) is not read-only. A read-only file is fine,
+/// since `atomic_write` replaces it rather than writing to it.
+pub fn is_writable(path: &Path) -> bool {
+    let path = resolve_symlinks(path);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    !fs::metadata(dir).is_ok_and(|m| m.permissions().readonly())
+}
+
+/// Whether a text file started with a UTF-8 byte order mark, as Windows editors
+/// sometimes write. `read_text` splits it off before parsing and `write_text` puts
+/// it back, since `toml_edit` drops it when re-serializing a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Bom(bool);
+
+const BOM: &str = "\u{feff}";
+
+/// Reads `path` without its byte order mark, if any.
+pub fn read_text(path: &Path) -> Result<(String, Bom)> {
+    let content = fs::read_to_string(path)?;
+    Ok(match content.strip_prefix(BOM) {
+        Some(rest) => (rest.to_string(), Bom(true)),
+        None => (content, Bom(false)),
+    })
+}
+
+/// `atomic_write` with the byte order mark found by `read_text` restored.
+pub fn write_text(path: &Path, contents: &str, bom: Bom) -> Result<()> {
+    if bom.0 {
+        atomic_write(path, format!("{}{}", BOM, contents))
+    } else {
+        atomic_write(path, contents)
+    }
+}
+
+/// Removes the temp file on drop unless `keep` was called.
+struct TempPath {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl TempPath {
+    fn keep(mut self) {
+        self.keep = true;
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn create_temp_file(dir: &Path, file_name: &str) -> io::Result<(File, TempPath)> {
+    loop {
+        let path = dir.join(format!(".{}.{:08x}.tmp", file_name, rand::random::<u32>()));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((file, TempPath { path, keep: false })),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn rename_over(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        // Some Windows setups refuse to rename over an existing file.
+        Err(e) if cfg!(windows) && to.is_file() => {
+            fs::remove_file(to).map_err(|_| e)?;
+            fs::rename(from, to)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Persists the rename itself. Best effort: not every platform can open a directory.
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_atomic_write_new_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+
+        atomic_write(&path, "a = 1\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a = 1\n");
+        assert_eq!(entries(dir.path()), vec!["config.toml"]);
+    }
+
+    #[test]
+    fn test_atomic_write_overwrite() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+        fs::write(&path, "old content that is longer than the new one\n").unwrap();
+
+        atomic_write(&path, "new\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new\n");
+        assert_eq!(entries(dir.path()), vec!["CHANGELOG.md"]);
+    }
+
+    #[test]
+    fn test_atomic_write_is_never_seen_partially_written() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("Cargo.toml");
+        let old = "a".repeat(256 * 1024);
+        let new = "b".repeat(256 * 1024);
+        fs::write(&path, &old).unwrap();
+
+        let done = std::sync::atomic::AtomicBool::new(false);
+        std::thread::scope(|s| {
+            let reader = s.spawn(|| {
+                let mut reads = 0;
+                while !done.load(std::sync::atomic::Ordering::Relaxed) || reads == 0 {
+                    let content = fs::read_to_string(&path).unwrap();
+                    assert!(content == old || content == new, "partial read");
+                    reads += 1;
+                }
+            });
+            for i in 0..50 {
+                atomic_write(&path, if i % 2 == 0 { &new } else { &old }).unwrap();
+            }
+            done.store(true, std::sync::atomic::Ordering::Relaxed);
+            reader.join().unwrap();
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_atomic_write_preserves_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("Cargo.toml");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+        atomic_write(&path, "new").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+
+    #[test]
+    fn test_atomic_write_cleans_up_on_error() {
+        let dir = TempDir::new().unwrap();
+        // Renaming a file over a non-empty directory fails after the temp file exists.
+        let target = dir.path().join("target");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("keep"), "").unwrap();
+
+        assert!(atomic_write(&target, "data").is_err());
+
+        assert_eq!(entries(dir.path()), vec!["target"]);
+        assert_eq!(entries(&target), vec!["keep"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_atomic_write_through_symlink() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/changes.md"), "old").unwrap();
+        let link = dir.path().join("CHANGELOG.md");
+        std::os::unix::fs::symlink("docs/changes.md", &link).unwrap();
+
+        atomic_write(&link, "new").unwrap();
+
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("docs/changes.md")).unwrap(),
+            "new"
+        );
+        assert_eq!(entries(&dir.path().join("docs")), vec!["changes.md"]);
+    }
+
+    #[test]
+    fn test_is_writable() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+        assert!(is_writable(&path));
+
+        fs::write(&path, "").unwrap();
+        assert!(is_writable(&path));
+
+        let mut permissions = fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&path, permissions).unwrap();
+        assert!(is_writable(&path));
+        atomic_write(&path, "replaced").unwrap();
+
+        let mut permissions = fs::metadata(dir.path()).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(dir.path(), permissions.clone()).unwrap();
+        assert!(!is_writable(&path));
+
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        fs::set_permissions(dir.path(), permissions).unwrap();
+    }
+
+    #[test]
+    fn test_text_round_trip_keeps_bom() {
+        let dir = TempDir::new().unwrap();
+        let with_bom = dir.path().join("with.toml");
+        let without = dir.path().join("without.toml");
+        fs::write(&with_bom, "\u{feff}a = 1\n").unwrap();
+        fs::write(&without, "a = 1\n").unwrap();
+
+        let (content, bom) = read_text(&with_bom).unwrap();
+        assert_eq!(content, "a = 1\n");
+        write_text(&with_bom, "a = 2\n", bom).unwrap();
+        assert_eq!(fs::read(&with_bom).unwrap(), b"\xef\xbb\xbfa = 2\n");
+
+        let (content, bom) = read_text(&without).unwrap();
+        assert_eq!(content, "a = 1\n");
+        write_text(&without, "a = 2\n", bom).unwrap();
+        assert_eq!(fs::read_to_string(&without).unwrap(), "a = 2\n");
+    }
+}
@@ -0,0 +1,875 @@
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Which changes `Git::diff` should return.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffSpec<'a> {
+    /// Changes staged in the index.
+    Staged,
+    /// Changes in the working tree that are not yet staged.
+    Unstaged,
+    /// Changes between the merge base of a ref and HEAD (`<ref>...HEAD`).
+    Base(&'a str),
+}
+
+/// A commit author, with the commit they were read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    pub name: String,
+    pub email: String,
+    pub commit: String,
+}
+
+/// Git operations used across the crate.
+///
+/// Everything that needs repository information goes through this trait so that
+/// the backend can be swapped (subprocess or gix) and replaced with a fake in tests.
+pub trait Git {
+    /// Returns the URL of the named remote.
+    fn remote_url(&self, remote: &str) -> Option<String>;
+
+    /// Returns the SHA of the commit that first added `path`, following renames.
+    fn file_add_commit(&self, path: &str) -> Option<String>;
+
+    /// Returns the full message of a commit: the subject line, then the body.
+    fn commit_message(&self, rev: &str) -> Option<String>;
+
+    /// Returns the committer date of a commit as a Unix timestamp.
+    fn commit_timestamp(&self, rev: &str) -> Option<i64>;
+
+    /// Returns the authors of a commit.
+    ///
+    /// With a `path`, this covers every commit in `commit^..commit` that touched the file.
+    fn authors_for(&self, commit: &str, path: Option<&str>) -> Vec<Author>;
+
+    /// Returns `(sha, subject)` for merge commits on the ancestry path from `commit`
+    /// to HEAD, oldest first.
+    fn merges_containing(&self, commit: &str) -> Vec<(String, String)>;
+
+    /// Creates an annotated tag at HEAD, GPG-signed when `sign` is set.
+    ///
+    /// On failure the error carries git's stderr (e.g. why signing failed).
+    fn create_tag(&self, tag: &str, message: &str, sign: bool) -> Result<()>;
+
+    /// Deletes a local tag.
+    fn delete_tag(&self, tag: &str) -> Result<()>;
+
+    /// Returns true if the tag exists locally.
+    fn tag_exists(&self, tag: &str) -> bool;
+
+    /// Returns the names of all local tags.
+    fn tags(&self) -> Vec<String>;
+
+    /// Returns the unified diff for `spec`, or an empty string if there is none.
+    fn diff(&self, spec: DiffSpec<'_>) -> String;
+
+    /// Returns the paths changed between the merge base of `base` and HEAD.
+    fn diff_names(&self, base: &str) -> Vec<String>;
+
+    /// Resolves a revision to a full commit SHA.
+    fn rev_parse(&self, rev: &str) -> Option<String>;
+
+    /// Reads a boolean from git config (e.g. `tag.gpgSign`), or `None` if unset.
+    fn config_bool(&self, key: &str) -> Option<bool>;
+
+    /// Returns the absolute paths of tracked files with staged or unstaged changes.
+    /// Untracked files are not included, and outside a repository nothing is dirty.
+    /// Fails when the repository can't be queried, e.g. without a `git` binary.
+    fn dirty_paths(&self) -> Result<Vec<PathBuf>>;
+}
+
+/// Arguments for `git tag`: `-s` signs the tag, `-a` creates a plain annotated tag.
+pub fn tag_args<'a>(tag: &'a str, message: &'a str, sign: bool) -> [&'a str; 5] {
+    ["tag", if sign { "-s" } else { "-a" }, tag, "-m", message]
+}
+
+/// Returns the default git backend for the current directory.
+///
+/// Uses gix when built with the `gix` feature and the directory is a repository,
+/// otherwise spawns the `git` binary.
+pub fn open() -> Box<dyn Git> {
+    #[cfg(feature = "gix")]
+    if let Some(repo) = GixGit::discover(Path::new(".")) {
+        return Box::new(repo);
+    }
+
+    Box::new(CliGit::new())
+}
+
+/// Git backend that spawns the `git` binary.
+#[derive(Debug, Clone, Default)]
+pub struct CliGit {
+    dir: Option<PathBuf>,
+}
+
+impl CliGit {
+    /// Runs git in the current directory.
+    pub fn new() -> Self {
+        Self { dir: None }
+    }
+
+    /// Runs git in `dir` instead of the current directory.
+    pub fn in_dir(dir: &Path) -> Self {
+        Self {
+            dir: Some(dir.to_path_buf()),
+        }
+    }
+
+    fn command(&self) -> Command {
+        let mut cmd = Command::new("git");
+        if let Some(dir) = &self.dir {
+            cmd.current_dir(dir);
+        }
+        cmd
+    }
+
+    /// Runs git and returns trimmed stdout, or `None` if git could not be spawned or failed.
+    fn output(&self, args: &[&str]) -> Option<String> {
+        let output = self.command().args(args).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn non_empty(&self, args: &[&str]) -> Option<String> {
+        self.output(args).filter(|s| !s.is_empty())
+    }
+}
+
+impl Git for CliGit {
+    fn remote_url(&self, remote: &str) -> Option<String> {
+        self.non_empty(&["remote", "get-url", remote])
+    }
+
+    fn file_add_commit(&self, path: &str) -> Option<String> {
+        self.non_empty(&[
+            "log",
+            "--follow",
+            "--diff-filter=A",
+            "--format=%H",
+            "-1",
+            "--",
+            path,
+        ])
+    }
+
+    fn commit_message(&self, rev: &str) -> Option<String> {
+        self.output(&["log", "--format=%B", "-1", rev])
+    }
+
+    fn commit_timestamp(&self, rev: &str) -> Option<i64> {
+        self.output(&["log", "--format=%ct", "-1", rev])?
+            .parse()
+            .ok()
+    }
+
+    fn authors_for(&self, commit: &str, path: Option<&str>) -> Vec<Author> {
+        const FORMAT: &str = "--format=%H%x00%aN%x00%aE";
+        let stdout = match path {
+            Some(path) => self.output(&[
+                "log",
+                "--follow",
+                FORMAT,
+                &format!("{}^..{}", commit, commit),
+                "--",
+                path,
+            ]),
+            None => self.output(&["log", FORMAT, "-1", commit]),
+        };
+
+        stdout
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.trim().splitn(3, '\0');
+                let commit = fields.next()?.to_string();
+                let name = fields.next()?.trim().to_string();
+                let email = fields.next()?.trim().to_string();
+                (!name.is_empty()).then_some(Author {
+                    name,
+                    email,
+                    commit,
+                })
+            })
+            .collect()
+    }
+
+    fn merges_containing(&self, commit: &str) -> Vec<(String, String)> {
+        let stdout = self
+            .output(&[
+                "log",
+                "--merges",
+                "--ancestry-path",
+                "--reverse",
+                "--format=%H %s",
+                &format!("{}..HEAD", commit),
+            ])
+            .unwrap_or_default();
+
+        stdout
+            .lines()
+            .filter_map(|line| {
+                let (sha, subject) = line.trim().split_once(' ')?;
+                Some((sha.to_string(), subject.to_string()))
+            })
+            .collect()
+    }
+
+    fn create_tag(&self, tag: &str, message: &str, sign: bool) -> Result<()> {
+        let output = self
+            .command()
+            .args(tag_args(tag, message, sign))
+            .output()
+            .map_err(|e| Error::Git(format!("failed to run 'git tag': {}", e)))?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(Error::Git(stderr.trim().to_string()))
+        }
+    }
+
+    fn delete_tag(&self, tag: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["tag", "-d", tag])
+            .output()
+            .map_err(|e| Error::Git(format!("failed to run 'git tag -d': {}", e)))?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(Error::Git(stderr.trim().to_string()))
+        }
+    }
+
+    fn tag_exists(&self, tag: &str) -> bool {
+        self.output(&[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("refs/tags/{}", tag),
+        ])
+        .is_some()
+    }
+
+    fn tags(&self) -> Vec<String> {
+        self.output(&["tag", "--list"])
+            .unwrap_or_default()
+            .lines()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn diff(&self, spec: DiffSpec<'_>) -> String {
+        let range;
+        let args: Vec<&str> = match spec {
+            DiffSpec::Staged => vec!["diff", "--cached"],
+            DiffSpec::Unstaged => vec!["diff"],
+            DiffSpec::Base(base) => {
+                range = format!("{}...HEAD", base);
+                vec!["diff", &range]
+            }
+        };
+
+        // Keep the raw output: trailing whitespace is significant in a patch.
+        self.command()
+            .args(&args)
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+            .unwrap_or_default()
+    }
+
+    fn diff_names(&self, base: &str) -> Vec<String> {
+        self.output(&["diff", "--name-only", &format!("{}...HEAD", base)])
+            .unwrap_or_default()
+            .lines()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    fn rev_parse(&self, rev: &str) -> Option<String> {
+        self.non_empty(&[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", rev),
+        ])
+    }
+
+    fn config_bool(&self, key: &str) -> Option<bool> {
+        match self
+            .output(&["config", "--get", "--type=bool", key])?
+            .as_str()
+        {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        }
+    }
+
+    fn dirty_paths(&self) -> Result<Vec<PathBuf>> {
+        let dir = match &self.dir {
+            Some(dir) => dir.clone(),
+            None => std::env::current_dir()?,
+        };
+        if !dir.ancestors().any(|d| d.join(".git").exists()) {
+            return Ok(Vec::new());
+        }
+
+        let run = |args: &[&str]| -> Result<std::process::Output> {
+            let output = self
+                .command()
+                .args(args)
+                .output()
+                .map_err(|e| Error::Git(format!("could not run git {}: {}", args[0], e)))?;
+            if !output.status.success() {
+                return Err(Error::Git(format!(
+                    "git {} failed: {}",
+                    args[0],
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(output)
+        };
+        let root = run(&["rev-parse", "--show-toplevel"])?;
+        let root = String::from_utf8_lossy(&root.stdout).trim().to_string();
+        // Not `output`: trimming would eat the leading space of the first status code.
+        let output = run(&["status", "--porcelain", "-z", "--untracked-files=no"])?;
+
+        let status = String::from_utf8_lossy(&output.stdout);
+        let mut entries = status.split('\0').filter(|e| !e.is_empty());
+        let mut paths = Vec::new();
+        while let Some(entry) = entries.next() {
+            let Some((code, path)) = entry.split_at_checked(3) else {
+                continue;
+            };
+            // Renames and copies are followed by their source path.
+            if code.starts_with(['R', 'C']) {
+                entries.next();
+            }
+            paths.push(Path::new(&root).join(path));
+        }
+        Ok(paths)
+    }
+}
+
+/// Git backend that reads history in-process with gix.
+///
+/// Commit lookups, authors, merges, tags and config never spawn a process. Tag
+/// creation and deletion, diffs and the working tree status still go through the
+/// `git` binary, as does `authors_for` when the repository has a mailmap. Renames are
+/// only followed when the file moved without changes.
+#[cfg(feature = "gix")]
+pub struct GixGit {
+    repo: gix::Repository,
+    dir: PathBuf,
+    cli: CliGit,
+}
+
+#[cfg(feature = "gix")]
+impl GixGit {
+    /// Opens the repository containing `dir`.
+    pub fn discover(dir: &Path) -> Option<Self> {
+        let repo = gix::discover(dir).ok()?;
+        Some(Self {
+            repo,
+            dir: std::fs::canonicalize(dir).ok()?,
+            cli: CliGit::in_dir(dir),
+        })
+    }
+
+    fn commit(&self, rev: &str) -> Option<gix::Commit<'_>> {
+        let id = self.repo.rev_parse_single(rev).ok()?;
+        id.object().ok()?.peel_to_commit().ok()
+    }
+
+    /// `path`, relative to `dir` or absolute, as a path inside the repository.
+    fn repo_path(&self, path: &str) -> Option<PathBuf> {
+        let workdir = std::fs::canonicalize(self.repo.workdir()?).ok()?;
+        let path = self.dir.join(path);
+        // The file itself may be gone, e.g. a changelog that was already released.
+        let path = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => std::fs::canonicalize(parent).ok()?.join(name),
+            _ => path,
+        };
+        path.strip_prefix(&workdir).ok().map(Path::to_path_buf)
+    }
+
+    /// The blob at `path` in the tree of `commit`, if there is one.
+    fn blob_at(commit: &gix::Commit<'_>, path: &Path) -> Option<gix::ObjectId> {
+        let entry = commit.tree().ok()?.lookup_entry_by_path(path).ok()??;
+        Some(entry.object_id())
+    }
+
+    /// The parent of a commit, unless it has none or is a merge.
+    fn only_parent<'a>(commit: &gix::Commit<'a>) -> Option<Option<gix::Commit<'a>>> {
+        let mut parents = commit.parent_ids();
+        let parent = parents.next();
+        if parents.next().is_some() {
+            return None;
+        }
+        Some(parent.and_then(|id| id.object().ok()?.try_into_commit().ok()))
+    }
+
+    /// The path of a file in `parent` with the content `blob` that `commit` removed,
+    /// i.e. where a file added in `commit` was moved from.
+    fn renamed_from(
+        parent: &gix::Commit<'_>,
+        commit: &gix::Commit<'_>,
+        blob: gix::ObjectId,
+    ) -> Option<PathBuf> {
+        let files = parent.tree().ok()?.traverse().breadthfirst.files().ok()?;
+        files.into_iter().find_map(|file| {
+            let old = PathBuf::from(file.filepath.to_string());
+            (file.oid == blob && Self::blob_at(commit, &old).is_none()).then_some(old)
+        })
+    }
+
+    fn has_mailmap(&self) -> bool {
+        let config = self.repo.config_snapshot();
+        config.string("mailmap.file").is_some()
+            || config.string("mailmap.blob").is_some()
+            || self
+                .repo
+                .workdir()
+                .is_some_and(|dir| dir.join(".mailmap").exists())
+    }
+}
+
+#[cfg(feature = "gix")]
+impl Git for GixGit {
+    fn remote_url(&self, remote: &str) -> Option<String> {
+        let remote = self.repo.find_remote(remote).ok()?;
+        let url = remote.url(gix::remote::Direction::Fetch)?;
+        Some(url.to_bstring().to_string())
+    }
+
+    fn file_add_commit(&self, path: &str) -> Option<String> {
+        use gix::revision::walk::Sorting;
+        use gix::traverse::commit::simple::CommitTimeOrder;
+
+        let mut path = self.repo_path(path)?;
+        let head = self.repo.head_id().ok()?;
+        let walk = self
+            .repo
+            .rev_walk([head])
+            .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst))
+            .all()
+            .ok()?;
+        for info in walk {
+            let commit = info.ok()?.object().ok()?;
+            // Like `git log`, merges don't add files themselves.
+            let Some(parent) = Self::only_parent(&commit) else {
+                continue;
+            };
+            let Some(blob) = Self::blob_at(&commit, &path) else {
+                continue;
+            };
+            if parent
+                .as_ref()
+                .is_some_and(|p| Self::blob_at(p, &path).is_some())
+            {
+                continue;
+            }
+
+            match parent.and_then(|parent| Self::renamed_from(&parent, &commit, blob)) {
+                Some(old) => path = old,
+                None => return Some(commit.id.to_string()),
+            }
+        }
+        None
+    }
+
+    fn commit_message(&self, rev: &str) -> Option<String> {
+        let commit = self.commit(rev)?;
+        let message = commit.message_raw().ok()?;
+        Some(message.to_string().trim().to_string())
+    }
+
+    fn commit_timestamp(&self, rev: &str) -> Option<i64> {
+        Some(self.commit(rev)?.time().ok()?.seconds)
+    }
+
+    fn authors_for(&self, commit: &str, path: Option<&str>) -> Vec<Author> {
+        if self.has_mailmap() {
+            return self.cli.authors_for(commit, path);
+        }
+        let author = |commit: &gix::Commit<'_>| {
+            let signature = commit.author().ok()?;
+            let name = signature.name.to_string().trim().to_string();
+            (!name.is_empty()).then(|| Author {
+                name,
+                email: signature.email.to_string().trim().to_string(),
+                commit: commit.id.to_string(),
+            })
+        };
+        let Some(tip) = self.commit(commit) else {
+            return Vec::new();
+        };
+        let Some(path) = path else {
+            return author(&tip).into_iter().collect();
+        };
+
+        // The commits in `commit^..commit` that changed the file, newest first.
+        let authors = || -> Option<Vec<Author>> {
+            use gix::revision::walk::Sorting;
+            use gix::traverse::commit::simple::CommitTimeOrder;
+
+            let mut path = self.repo_path(path)?;
+            let first_parent = tip.parent_ids().next()?.detach();
+            let walk = self
+                .repo
+                .rev_walk([tip.id])
+                .with_hidden([first_parent])
+                .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst))
+                .all()
+                .ok()?;
+            let mut authors = Vec::new();
+            for info in walk {
+                let commit = info.ok()?.object().ok()?;
+                let Some(parent) = Self::only_parent(&commit) else {
+                    continue;
+                };
+                let after = Self::blob_at(&commit, &path);
+                let before = parent.as_ref().and_then(|p| Self::blob_at(p, &path));
+                if after == before {
+                    continue;
+                }
+                authors.extend(author(&commit));
+                if let (Some(blob), None, Some(parent)) = (after, before, parent) {
+                    path = Self::renamed_from(&parent, &commit, blob).unwrap_or(path);
+                }
+            }
+            Some(authors)
+        };
+        authors().unwrap_or_default()
+    }
+
+    fn merges_containing(&self, commit: &str) -> Vec<(String, String)> {
+        use gix::revision::walk::Sorting;
+        use gix::traverse::commit::simple::CommitTimeOrder;
+
+        let merges = || -> Option<Vec<(String, String)>> {
+            let base = self.commit(commit)?.id;
+            let head = self.repo.head_id().ok()?;
+            let walk = self
+                .repo
+                .rev_walk([head])
+                .with_hidden([base])
+                .sorting(Sorting::ByCommitTime(CommitTimeOrder::OldestFirst))
+                .all()
+                .ok()?;
+            let mut commits = Vec::new();
+            for info in walk {
+                let info = info.ok()?;
+                let parents: Vec<gix::ObjectId> = info.parent_ids().map(|id| id.detach()).collect();
+                commits.push((info.id, parents));
+            }
+
+            // The ancestry path: commits that descend from `base`. Oldest first, one
+            // pass usually settles it; another catches clock skew.
+            let mut descendants = std::collections::HashSet::from([base]);
+            loop {
+                let before = descendants.len();
+                for (id, parents) in &commits {
+                    if parents.iter().any(|p| descendants.contains(p)) {
+                        descendants.insert(*id);
+                    }
+                }
+                if descendants.len() == before {
+                    break;
+                }
+            }
+
+            let mut merges = Vec::new();
+            for (id, parents) in &commits {
+                if parents.len() > 1 && descendants.contains(id) {
+                    let commit = self.repo.find_commit(*id).ok()?;
+                    let subject = commit.message().ok()?.summary().to_string();
+                    merges.push((id.to_string(), subject));
+                }
+            }
+            Some(merges)
+        };
+        merges().unwrap_or_default()
+    }
+
+    fn create_tag(&self, tag: &str, message: &str, sign: bool) -> Result<()> {
+        self.cli.create_tag(tag, message, sign)
+    }
+
+    fn delete_tag(&self, tag: &str) -> Result<()> {
+        self.cli.delete_tag(tag)
+    }
+
+    fn tag_exists(&self, tag: &str) -> bool {
+        self.repo
+            .try_find_reference(format!("refs/tags/{}", tag).as_str())
+            .ok()
+            .flatten()
+            .is_some()
+    }
+
+    fn tags(&self) -> Vec<String> {
+        let Ok(references) = self.repo.references() else {
+            return Vec::new();
+        };
+        let Ok(tags) = references.tags() else {
+            return Vec::new();
+        };
+        let mut tags: Vec<String> = tags
+            .filter_map(|tag| Some(tag.ok()?.name().shorten().to_string()))
+            .collect();
+        tags.sort();
+        tags
+    }
+
+    fn diff(&self, spec: DiffSpec<'_>) -> String {
+        self.cli.diff(spec)
+    }
+
+    fn diff_names(&self, base: &str) -> Vec<String> {
+        self.cli.diff_names(base)
+    }
+
+    fn rev_parse(&self, rev: &str) -> Option<String> {
+        Some(self.commit(rev)?.id.to_string())
+    }
+
+    fn config_bool(&self, key: &str) -> Option<bool> {
+        self.repo.config_snapshot().boolean(key)
+    }
+
+    fn dirty_paths(&self) -> Result<Vec<PathBuf>> {
+        self.cli.dirty_paths()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fake;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn init_repo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["config", "user.name", "Jane Doe"]);
+        git(dir.path(), &["config", "user.email", "jane@example.com"]);
+        git(dir.path(), &["config", "tag.gpgSign", "false"]);
+        std::fs::write(dir.path().join("a.md"), "a").unwrap();
+        git(dir.path(), &["add", "a.md"]);
+        git(dir.path(), &["commit", "-q", "-m", "add a (#7)"]);
+        dir
+    }
+
+    #[test]
+    fn test_cli_git_history() {
+        let dir = init_repo();
+        let cli = CliGit::in_dir(dir.path());
+
+        let head = cli.rev_parse("HEAD").unwrap();
+        assert_eq!(head.len(), 40);
+        assert_eq!(cli.file_add_commit("a.md"), Some(head.clone()));
+        assert_eq!(cli.commit_message(&head).as_deref(), Some("add a (#7)"));
+        assert_eq!(
+            cli.authors_for(&head, None),
+            vec![Author {
+                name: "Jane Doe".into(),
+                email: "jane@example.com".into(),
+                commit: head.clone(),
+            }]
+        );
+        assert!(cli.merges_containing(&head).is_empty());
+        assert!(cli.remote_url("origin").is_none());
+        assert!(cli.file_add_commit("missing.md").is_none());
+    }
+
+    #[test]
+    fn test_cli_git_tags() {
+        let dir = init_repo();
+        let cli = CliGit::in_dir(dir.path());
+
+        assert!(!cli.tag_exists("v1.0.0"));
+        assert!(cli.tags().is_empty());
+        cli.create_tag("v1.0.0", "Release v1.0.0", false).unwrap();
+        assert!(cli.tag_exists("v1.0.0"));
+        assert_eq!(cli.tags(), vec!["v1.0.0".to_string()]);
+        assert_eq!(cli.rev_parse("v1.0.0"), cli.rev_parse("HEAD"));
+
+        let err = cli
+            .create_tag("v1.0.0", "Release v1.0.0", false)
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+
+        cli.delete_tag("v1.0.0").unwrap();
+        assert!(!cli.tag_exists("v1.0.0"));
+        assert!(cli.delete_tag("v1.0.0").is_err());
+    }
+
+    #[test]
+    fn test_tag_args() {
+        assert_eq!(
+            tag_args("v1.0.0", "Release v1.0.0", false),
+            ["tag", "-a", "v1.0.0", "-m", "Release v1.0.0"]
+        );
+        assert_eq!(
+            tag_args("v1.0.0", "Release v1.0.0", true),
+            ["tag", "-s", "v1.0.0", "-m", "Release v1.0.0"]
+        );
+    }
+
+    #[test]
+    fn test_cli_git_config_bool() {
+        let dir = init_repo();
+        let cli = CliGit::in_dir(dir.path());
+
+        assert_eq!(cli.config_bool("tag.gpgSign"), Some(false));
+        git(dir.path(), &["config", "tag.gpgSign", "yes"]);
+        assert_eq!(cli.config_bool("tag.gpgSign"), Some(true));
+        assert_eq!(cli.config_bool("changelogs.unset"), None);
+    }
+
+    #[test]
+    fn test_cli_git_diff() {
+        let dir = init_repo();
+        let cli = CliGit::in_dir(dir.path());
+
+        assert!(cli.diff(DiffSpec::Unstaged).is_empty());
+        std::fs::write(dir.path().join("a.md"), "b").unwrap();
+        assert!(cli.diff(DiffSpec::Unstaged).contains("+b"));
+        assert!(cli.diff(DiffSpec::Staged).is_empty());
+    }
+
+    #[test]
+    fn test_cli_git_dirty_paths() {
+        let dir = init_repo();
+        let cli = CliGit::in_dir(dir.path());
+        let root = PathBuf::from(cli.output(&["rev-parse", "--show-toplevel"]).unwrap());
+
+        assert!(cli.dirty_paths().unwrap().is_empty());
+        std::fs::write(dir.path().join("untracked.md"), "u").unwrap();
+        assert!(cli.dirty_paths().unwrap().is_empty());
+
+        std::fs::write(dir.path().join("a.md"), "b").unwrap();
+        git(dir.path(), &["mv", "a.md", "b.md"]);
+        assert_eq!(cli.dirty_paths().unwrap(), vec![root.join("b.md")]);
+
+        // Outside a repository nothing is tracked; a broken one can't be queried.
+        let plain = tempfile::TempDir::new().unwrap();
+        assert!(
+            CliGit::in_dir(plain.path())
+                .dirty_paths()
+                .unwrap()
+                .is_empty()
+        );
+        std::fs::write(plain.path().join(".git"), "gitdir: missing\n").unwrap();
+        assert!(CliGit::in_dir(plain.path()).dirty_paths().is_err());
+    }
+
+    #[cfg(feature = "gix")]
+    #[test]
+    fn test_gix_git_matches_cli() {
+        let dir = init_repo();
+        git(
+            dir.path(),
+            &[
+                "remote",
+                "add",
+                "origin",
+                "git@github.com:wevm/changelogs-rs.git",
+            ],
+        );
+        let cli = CliGit::in_dir(dir.path());
+        cli.create_tag("v1.0.0", "Release v1.0.0", false).unwrap();
+        let gix = GixGit::discover(dir.path()).unwrap();
+
+        assert_eq!(gix.rev_parse("HEAD"), cli.rev_parse("HEAD"));
+        assert_eq!(gix.rev_parse("v1.0.0"), cli.rev_parse("HEAD"));
+        assert_eq!(gix.commit_message("HEAD"), cli.commit_message("HEAD"));
+        assert_eq!(gix.remote_url("origin"), cli.remote_url("origin"));
+        assert!(gix.tag_exists("v1.0.0"));
+        assert!(!gix.tag_exists("v2.0.0"));
+        assert_eq!(gix.tags(), cli.tags());
+        assert_eq!(
+            gix.config_bool("tag.gpgSign"),
+            cli.config_bool("tag.gpgSign")
+        );
+        assert_eq!(gix.config_bool("changelogs.unset"), None);
+    }
+
+    #[cfg(feature = "gix")]
+    #[test]
+    fn test_gix_git_history_matches_cli() {
+        let dir = init_repo();
+        let path = dir.path();
+        git(path, &["checkout", "-q", "-b", "feature"]);
+        std::fs::create_dir(path.join(".changes")).unwrap();
+        std::fs::write(path.join(".changes/brave-fox.md"), "x").unwrap();
+        git(path, &["add", "."]);
+        git(path, &["commit", "-q", "-m", "add changelog"]);
+        let added = CliGit::in_dir(path).rev_parse("HEAD").unwrap();
+        git(
+            path,
+            &["mv", ".changes/brave-fox.md", ".changes/calm-owl.md"],
+        );
+        git(path, &["commit", "-q", "-m", "rename changelog"]);
+        git(path, &["checkout", "-q", "-"]);
+        std::fs::write(path.join("b.md"), "b").unwrap();
+        git(path, &["add", "b.md"]);
+        git(path, &["commit", "-q", "-m", "add b"]);
+        git(
+            path,
+            &[
+                "merge",
+                "-q",
+                "--no-ff",
+                "feature",
+                "-m",
+                "Merge feature (#9)",
+            ],
+        );
+
+        let cli = CliGit::in_dir(path);
+        let gix = GixGit::discover(path).unwrap();
+        for file in [".changes/calm-owl.md", "a.md", "b.md", "missing.md"] {
+            assert_eq!(
+                gix.file_add_commit(file),
+                cli.file_add_commit(file),
+                "{file}"
+            );
+        }
+        assert_eq!(
+            gix.file_add_commit(".changes/calm-owl.md"),
+            Some(added.clone())
+        );
+        assert_eq!(gix.commit_timestamp("HEAD"), cli.commit_timestamp("HEAD"));
+        assert_eq!(gix.merges_containing(&added), cli.merges_containing(&added));
+        assert_eq!(gix.merges_containing(&added).len(), 1);
+        for (commit, file) in [
+            ("HEAD", Some(".changes/calm-owl.md")),
+            ("HEAD", Some("b.md")),
+            ("HEAD", None),
+            (&added, None),
+        ] {
+            assert_eq!(gix.authors_for(commit, file), cli.authors_for(commit, file));
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! In-memory `Git` for unit tests.
+//!
+//! The library mounts this as `git::fake` and the CLI includes the same file, so it
+//! only refers to the library through `changelogs::` paths.
+
+use changelogs::error::{Error, Result};
+use changelogs::git::{Author, DiffSpec, Git};
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+/// A repository described by its fields. Revisions resolve to themselves, and every
+/// commit carries `message` and has the `merges` after it.
+#[derive(Default)]
+pub struct FakeGit {
+    /// URL of every remote.
+    pub remote_url: Option<&'static str>,
+    /// Commit that added every file; without it, each file is its own commit.
+    pub add_commit: Option<&'static str>,
+    pub message: &'static str,
+    /// Authors of the commits that touched a file. Without a file, a commit's only
+    /// author is `fallback`.
+    pub authors: Vec<&'static str>,
+    /// `(sha, subject)` of merge commits.
+    pub merges: Vec<(&'static str, &'static str)>,
+    /// Tags that already exist.
+    pub tags: Vec<&'static str>,
+    /// Tags that can't be signed.
+    pub fail_signing: Vec<&'static str>,
+    pub staged: &'static str,
+    pub unstaged: &'static str,
+    /// Diffs against each `DiffSpec::Base` ref.
+    pub base: Vec<(&'static str, &'static str)>,
+    /// Tracked files with uncommitted changes.
+    pub dirty: Vec<&'static str>,
+    /// Makes `dirty_paths` fail as if git couldn't be run.
+    pub unreadable: bool,
+    /// `(tag, message, signed)` of each tag created.
+    pub created: RefCell<Vec<(String, String, bool)>>,
+    /// History lookups, as `"method arg"`.
+    pub lookups: RefCell<Vec<String>>,
+}
+
+impl FakeGit {
+    fn record(&self, method: &str, arg: &str) {
+        self.lookups
+            .borrow_mut()
+            .push(format!("{} {}", method, arg));
+    }
+}
+
+impl Git for FakeGit {
+    fn remote_url(&self, remote: &str) -> Option<String> {
+        self.record("remote_url", remote);
+        self.remote_url.map(String::from)
+    }
+
+    fn file_add_commit(&self, path: &str) -> Option<String> {
+        self.record("file_add_commit", path);
+        Some(self.add_commit.unwrap_or(path).to_string())
+    }
+
+    fn commit_message(&self, rev: &str) -> Option<String> {
+        self.record("commit_message", rev);
+        Some(self.message.to_string())
+    }
+
+    fn commit_timestamp(&self, _rev: &str) -> Option<i64> {
+        None
+    }
+
+    fn authors_for(&self, commit: &str, path: Option<&str>) -> Vec<Author> {
+        let author = |name: &str| Author {
+            name: name.to_string(),
+            email: format!("{}@example.com", name),
+            commit: commit.to_string(),
+        };
+        match path {
+            Some(_) => self.authors.iter().map(|name| author(name)).collect(),
+            None => vec![author("fallback")],
+        }
+    }
+
+    fn merges_containing(&self, _commit: &str) -> Vec<(String, String)> {
+        self.merges
+            .iter()
+            .map(|(sha, subject)| (sha.to_string(), subject.to_string()))
+            .collect()
+    }
+
+    fn create_tag(&self, tag: &str, message: &str, sign: bool) -> Result<()> {
+        if sign && self.fail_signing.contains(&tag) {
+            return Err(Error::Git(
+                "error: gpg failed to sign the data\nerror: unable to sign the tag".into(),
+            ));
+        }
+        self.created
+            .borrow_mut()
+            .push((tag.to_string(), message.to_string(), sign));
+        Ok(())
+    }
+
+    fn delete_tag(&self, tag: &str) -> Result<()> {
+        if !self.tag_exists(tag) {
+            return Err(Error::Git(format!("tag '{}' not found.", tag)));
+        }
+        self.created
+            .borrow_mut()
+            .retain(|(created, _, _)| created != tag);
+        Ok(())
+    }
+
+    fn tag_exists(&self, tag: &str) -> bool {
+        self.tags.contains(&tag) || self.created.borrow().iter().any(|(t, _, _)| t == tag)
+    }
+
+    fn tags(&self) -> Vec<String> {
+        let created = self.created.borrow();
+        let created = created.iter().map(|(tag, _, _)| tag.clone());
+        self.tags
+            .iter()
+            .map(|t| t.to_string())
+            .chain(created)
+            .collect()
+    }
+
+    fn diff(&self, spec: DiffSpec<'_>) -> String {
+        match spec {
+            DiffSpec::Staged => self.staged.to_string(),
+            DiffSpec::Unstaged => self.unstaged.to_string(),
+            DiffSpec::Base(rev) => self
+                .base
+                .iter()
+                .find(|(r, _)| *r == rev)
+                .map(|(_, diff)| diff.to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn diff_names(&self, _base: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn rev_parse(&self, rev: &str) -> Option<String> {
+        Some(rev.to_string())
+    }
+
+    fn config_bool(&self, _key: &str) -> Option<bool> {
+        None
+    }
+
+    fn dirty_paths(&self) -> Result<Vec<PathBuf>> {
+        if self.unreadable {
+            return Err(Error::Git("git status failed".into()));
+        }
+        Ok(self.dirty.iter().map(PathBuf::from).collect())
+    }
+}
@@ -0,0 +1,181 @@
+use crate::git::Author;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// GitHub API calls used for attribution.
+///
+/// Lookups go through this trait so tests can replace the HTTP client with a fake.
+pub trait GitHub {
+    /// Returns the login of the GitHub user who authored `sha` in `repo` (`owner/name`).
+    fn commit_author_login(&self, repo: &str, sha: &str) -> Option<String>;
+}
+
+/// GitHub REST API client authenticated with a token.
+pub struct ApiClient {
+    token: String,
+}
+
+impl ApiClient {
+    pub fn new(token: String) -> Self {
+        Self { token }
+    }
+
+    /// Returns a client when `GITHUB_TOKEN` is set.
+    pub fn from_env() -> Option<Self> {
+        std::env::var("GITHUB_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty())
+            .map(Self::new)
+    }
+}
+
+impl GitHub for ApiClient {
+    fn commit_author_login(&self, repo: &str, sha: &str) -> Option<String> {
+        let url = format!("https://api.github.com/repos/{}/commits/{}", repo, sha);
+        let json: serde_json::Value = crate::ecosystems::http_agent()
+            .get(&url)
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/vnd.github+json")
+            .set("User-Agent", "changelogs")
+            .call()
+            .ok()?
+            .into_json()
+            .ok()?;
+
+        json.get("author")?
+            .get("login")?
+            .as_str()
+            .map(str::to_string)
+    }
+}
+
+/// Extracts the login from a GitHub noreply address, either
+/// `12345+login@users.noreply.github.com` or the older `login@users.noreply.github.com`.
+pub fn noreply_login(email: &str) -> Option<&str> {
+    let local = email.strip_suffix("@users.noreply.github.com")?;
+    let login = local.split_once('+').map_or(local, |(_, login)| login);
+    (!login.is_empty()).then_some(login)
+}
+
+/// Turns commit authors into changelog attributions, caching API lookups for the run.
+pub struct AuthorResolver {
+    client: Option<Box<dyn GitHub>>,
+    logins: RefCell<HashMap<String, Option<String>>>,
+}
+
+impl AuthorResolver {
+    pub fn new(client: Option<Box<dyn GitHub>>) -> Self {
+        Self {
+            client,
+            logins: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Uses the GitHub API when `GITHUB_TOKEN` is set.
+    pub fn from_env() -> Self {
+        Self::new(ApiClient::from_env().map(|client| Box::new(client) as Box<dyn GitHub>))
+    }
+
+    /// Returns `@login` when the author's GitHub login is known, otherwise their
+    /// plain name.
+    ///
+    /// The login comes from a noreply email address first, then from the API for
+    /// the author's commit in `repo` (`owner/name`).
+    pub fn attribute(&self, repo: &str, author: &Author) -> String {
+        match self.login(repo, author) {
+            Some(login) => format!("@{}", login),
+            None => author.name.clone(),
+        }
+    }
+
+    fn login(&self, repo: &str, author: &Author) -> Option<String> {
+        if let Some(login) = noreply_login(&author.email) {
+            return Some(login.to_string());
+        }
+
+        let client = self.client.as_ref()?;
+        let key = format!("{}@{}", repo, author.commit);
+        self.logins
+            .borrow_mut()
+            .entry(key)
+            .or_insert_with(|| client.commit_author_login(repo, &author.commit))
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    /// Answers from a fixed map of commit SHAs to logins, counting calls.
+    struct FakeGitHub {
+        logins: HashMap<&'static str, &'static str>,
+        calls: Rc<RefCell<usize>>,
+    }
+
+    impl GitHub for FakeGitHub {
+        fn commit_author_login(&self, _repo: &str, sha: &str) -> Option<String> {
+            *self.calls.borrow_mut() += 1;
+            self.logins.get(sha).map(|s| s.to_string())
+        }
+    }
+
+    fn author(name: &str, email: &str, commit: &str) -> Author {
+        Author {
+            name: name.to_string(),
+            email: email.to_string(),
+            commit: commit.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_noreply_login() {
+        assert_eq!(
+            noreply_login("12345+jsmith42@users.noreply.github.com"),
+            Some("jsmith42")
+        );
+        assert_eq!(
+            noreply_login("jsmith42@users.noreply.github.com"),
+            Some("jsmith42")
+        );
+        assert_eq!(noreply_login("john@example.com"), None);
+        assert_eq!(noreply_login("12345+@users.noreply.github.com"), None);
+    }
+
+    #[test]
+    fn test_attribute_fallback_chain() {
+        let calls = Rc::new(RefCell::new(0));
+        let resolver = AuthorResolver::new(Some(Box::new(FakeGitHub {
+            logins: HashMap::from([("abc1234", "octocat")]),
+            calls: calls.clone(),
+        })));
+
+        let noreply = author(
+            "John Smith",
+            "12345+jsmith42@users.noreply.github.com",
+            "abc1234",
+        );
+        assert_eq!(resolver.attribute("wevm/changelogs", &noreply), "@jsmith42");
+        assert_eq!(*calls.borrow(), 0);
+
+        let api = author("Mona Lisa", "mona@example.com", "abc1234");
+        assert_eq!(resolver.attribute("wevm/changelogs", &api), "@octocat");
+        assert_eq!(resolver.attribute("wevm/changelogs", &api), "@octocat");
+        assert_eq!(*calls.borrow(), 1);
+
+        let unknown = author("John Smith", "john@example.com", "def5678");
+        assert_eq!(
+            resolver.attribute("wevm/changelogs", &unknown),
+            "John Smith"
+        );
+    }
+
+    #[test]
+    fn test_attribute_without_client() {
+        let resolver = AuthorResolver::new(None);
+
+        let author = author("John Smith", "john@example.com", "abc1234");
+        assert_eq!(resolver.attribute("wevm/changelogs", &author), "John Smith");
+    }
+}
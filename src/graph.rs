@@ -1,3 +1,4 @@
+use crate::ecosystems::DependencyKind;
 use crate::workspace::Workspace;
 use petgraph::graph::{DiGraph, NodeIndex};
 use std::collections::HashMap;
@@ -8,7 +9,27 @@ pub struct DependencyGraph {
 }
 
 impl DependencyGraph {
+    /// Builds the graph from normal dependencies only, for bump propagation: a
+    /// release of a dev or build dependency doesn't change what a dependent's
+    /// consumers get.
     pub fn from_workspace(workspace: &Workspace) -> Self {
+        Self::build(workspace, |kind| kind == DependencyKind::Normal)
+    }
+
+    /// Builds the graph for publish ordering from normal and build dependencies.
+    /// Build dependencies are compiled on consumers' machines, so they must be on
+    /// the registry first. Dev dependencies are left out; cargo allows cycles
+    /// through them.
+    pub fn for_publish(workspace: &Workspace) -> Self {
+        Self::build(workspace, |kind| kind != DependencyKind::Dev)
+    }
+
+    /// Builds the graph from every dependency, including dev and build dependencies.
+    pub fn from_workspace_with_dev(workspace: &Workspace) -> Self {
+        Self::build(workspace, |_| true)
+    }
+
+    fn build(workspace: &Workspace, include: impl Fn(DependencyKind) -> bool) -> Self {
         let mut graph = DiGraph::new();
         let mut node_indices = HashMap::new();
 
@@ -20,8 +41,11 @@ impl DependencyGraph {
         for package in &workspace.packages {
             let from_idx = node_indices[&package.name];
             for dep in &package.dependencies {
-                if let Some(&to_idx) = node_indices.get(dep) {
-                    graph.add_edge(from_idx, to_idx, ());
+                if !include(dep.kind) {
+                    continue;
+                }
+                if let Some(&to_idx) = node_indices.get(&dep.name) {
+                    graph.update_edge(from_idx, to_idx, ());
                 }
             }
         }
@@ -67,6 +91,92 @@ impl DependencyGraph {
         result
     }
 
+    /// Returns the shortest dependency chain from `from` to `to`, both included.
+    pub fn path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let &from_idx = self.node_indices.get(from)?;
+        let &to_idx = self.node_indices.get(to)?;
+
+        let mut previous: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let mut queue = std::collections::VecDeque::from([from_idx]);
+
+        while let Some(idx) = queue.pop_front() {
+            if idx == to_idx {
+                let mut path = vec![self.graph[idx].clone()];
+                let mut current = idx;
+                while let Some(&prev) = previous.get(&current) {
+                    path.push(self.graph[prev].clone());
+                    current = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for neighbor in self
+                .graph
+                .neighbors_directed(idx, petgraph::Direction::Outgoing)
+            {
+                if neighbor != from_idx && !previous.contains_key(&neighbor) {
+                    previous.insert(neighbor, idx);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Splits `packages` into waves for publishing: every package comes after the
+    /// packages it depends on, and packages within a wave have no edges between
+    /// them, so they may be published concurrently. Input order is kept within a
+    /// wave. Packages caught in a dependency cycle go into one final wave.
+    pub fn publish_waves(&self, packages: &[String]) -> Vec<Vec<String>> {
+        let mut remaining: Vec<&String> = packages.iter().collect();
+        let mut waves = Vec::new();
+
+        while !remaining.is_empty() {
+            let (ready, blocked): (Vec<&String>, Vec<&String>) =
+                remaining.iter().copied().partition(|name| {
+                    self.dependencies(name)
+                        .iter()
+                        .all(|dep| dep == *name || !remaining.contains(&dep))
+                });
+
+            if ready.is_empty() {
+                waves.push(blocked.into_iter().cloned().collect());
+                break;
+            }
+
+            waves.push(ready.into_iter().cloned().collect());
+            remaining = blocked;
+        }
+
+        waves
+    }
+
+    /// Renders the graph in Graphviz DOT format, one `"dependent" -> "dependency"`
+    /// edge per line. Nodes and edges are sorted so the output is stable.
+    pub fn to_dot(&self) -> String {
+        let mut nodes: Vec<&String> = self.graph.node_weights().collect();
+        nodes.sort();
+        let mut edges: Vec<(&String, &String)> = self
+            .graph
+            .edge_indices()
+            .filter_map(|e| self.graph.edge_endpoints(e))
+            .map(|(from, to)| (&self.graph[from], &self.graph[to]))
+            .collect();
+        edges.sort();
+
+        let mut out = String::from("digraph dependencies {\n");
+        for node in nodes {
+            out.push_str(&format!("    {};\n", dot_id(node)));
+        }
+        for (from, to) in edges {
+            out.push_str(&format!("    {} -> {};\n", dot_id(from), dot_id(to)));
+        }
+        out.push_str("}\n");
+        out
+    }
+
     pub fn dependencies(&self, package: &str) -> Vec<String> {
         let Some(&pkg_idx) = self.node_indices.get(package) else {
             return Vec::new();
@@ -79,9 +189,26 @@ impl DependencyGraph {
     }
 }
 
+/// Returns `name` as a double-quoted DOT ID, with `"` and `\` escaped.
+fn dot_id(name: &str) -> String {
+    let mut id = String::with_capacity(name.len() + 2);
+    id.push('"');
+    for c in name.chars() {
+        if matches!(c, '"' | '\\') {
+            id.push('\\');
+        }
+        id.push(c);
+    }
+    id.push('"');
+    id
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ecosystems::{Dependency, Ecosystem, Package};
+    use semver::Version;
+    use std::path::PathBuf;
 
     #[test]
     fn test_dependents() {
@@ -193,6 +320,43 @@ mod tests {
         assert!(deps.is_empty());
     }
 
+    #[test]
+    fn test_path_shortest_chain() {
+        let mut graph = DiGraph::new();
+        let a = graph.add_node("a".to_string());
+        let b = graph.add_node("b".to_string());
+        let c = graph.add_node("c".to_string());
+        let d = graph.add_node("d".to_string());
+
+        // d -> c -> b -> a, plus a shortcut d -> b
+        graph.add_edge(b, a, ());
+        graph.add_edge(c, b, ());
+        graph.add_edge(d, c, ());
+        graph.add_edge(d, b, ());
+
+        let mut node_indices = HashMap::new();
+        node_indices.insert("a".to_string(), a);
+        node_indices.insert("b".to_string(), b);
+        node_indices.insert("c".to_string(), c);
+        node_indices.insert("d".to_string(), d);
+
+        let dep_graph = DependencyGraph {
+            graph,
+            node_indices,
+        };
+
+        assert_eq!(
+            dep_graph.path("d", "a"),
+            Some(vec!["d".to_string(), "b".to_string(), "a".to_string()])
+        );
+        assert_eq!(
+            dep_graph.path("c", "a"),
+            Some(vec!["c".to_string(), "b".to_string(), "a".to_string()])
+        );
+        assert_eq!(dep_graph.path("a", "d"), None);
+        assert_eq!(dep_graph.path("a", "missing"), None);
+    }
+
     #[test]
     fn test_nonexistent_package() {
         let mut graph = DiGraph::new();
@@ -230,4 +394,145 @@ mod tests {
 
         assert!(dep_graph.dependents("b").is_empty());
     }
+
+    #[test]
+    fn test_to_dot() {
+        let mut graph = DiGraph::new();
+        let core = graph.add_node("core".to_string());
+        let app = graph.add_node("app".to_string());
+        let cli = graph.add_node("cli".to_string());
+        graph.add_node("docs".to_string());
+
+        graph.add_edge(cli, core, ());
+        graph.add_edge(app, core, ());
+        graph.add_edge(cli, app, ());
+
+        let dep_graph = DependencyGraph {
+            graph,
+            node_indices: HashMap::new(),
+        };
+
+        assert_eq!(
+            dep_graph.to_dot(),
+            "digraph dependencies {\n    \"app\";\n    \"cli\";\n    \"core\";\n    \"docs\";\n    \
+             \"app\" -> \"core\";\n    \"cli\" -> \"app\";\n    \"cli\" -> \"core\";\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_dot_id_escapes_quotes_and_backslashes() {
+        assert_eq!(dot_id("core"), r#""core""#);
+        assert_eq!(dot_id("@scope/pkg"), r#""@scope/pkg""#);
+        assert_eq!(dot_id(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(dot_id(r"back\slash"), r#""back\\slash""#);
+        assert_eq!(dot_id("ünïcode"), r#""ünïcode""#);
+    }
+
+    #[test]
+    fn test_publish_waves() {
+        let mut graph = DiGraph::new();
+        let mut node_indices = HashMap::new();
+        for name in ["core", "macros", "utils", "app", "cli", "solo"] {
+            node_indices.insert(name.to_string(), graph.add_node(name.to_string()));
+        }
+        let edge = |graph: &mut DiGraph<String, ()>, from: &str, to: &str| {
+            graph.add_edge(node_indices[from], node_indices[to], ());
+        };
+        // app -> core, app -> macros -> core, cli -> app, utils -> core
+        edge(&mut graph, "app", "core");
+        edge(&mut graph, "app", "macros");
+        edge(&mut graph, "macros", "core");
+        edge(&mut graph, "cli", "app");
+        edge(&mut graph, "utils", "core");
+
+        let dep_graph = DependencyGraph {
+            graph,
+            node_indices,
+        };
+
+        let names: Vec<String> = ["cli", "app", "utils", "macros", "core", "solo"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            dep_graph.publish_waves(&names),
+            vec![
+                vec!["core", "solo"],
+                vec!["utils", "macros"],
+                vec!["app"],
+                vec!["cli"],
+            ]
+        );
+
+        // Dependencies outside the publish set are already on the registry.
+        let names = vec!["app".to_string(), "cli".to_string()];
+        assert_eq!(
+            dep_graph.publish_waves(&names),
+            vec![vec!["app"], vec!["cli"]]
+        );
+    }
+
+    #[test]
+    fn test_publish_waves_wait_for_build_dependencies() {
+        let package = |name: &str, dependencies: Vec<Dependency>| Package {
+            name: name.to_string(),
+            version: Version::new(1, 0, 0),
+            path: PathBuf::from(name),
+            manifest_path: PathBuf::from(name).join("Cargo.toml"),
+            dependencies,
+        };
+        let workspace = Workspace::new(
+            PathBuf::from("/tmp/proj"),
+            vec![
+                package(
+                    "app",
+                    vec![
+                        Dependency {
+                            name: "codegen".to_string(),
+                            kind: DependencyKind::Build,
+                        },
+                        Dependency::dev("testkit"),
+                    ],
+                ),
+                package("codegen", vec![]),
+                package("testkit", vec![Dependency::normal("app")]),
+            ],
+            Ecosystem::Rust,
+        );
+
+        let names: Vec<String> = ["app", "codegen", "testkit"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            DependencyGraph::for_publish(&workspace).publish_waves(&names),
+            vec![vec!["codegen"], vec!["app"], vec!["testkit"]]
+        );
+    }
+
+    #[test]
+    fn test_publish_waves_cycle() {
+        let mut graph = DiGraph::new();
+        let a = graph.add_node("a".to_string());
+        let b = graph.add_node("b".to_string());
+        let c = graph.add_node("c".to_string());
+        graph.add_edge(a, b, ());
+        graph.add_edge(b, a, ());
+
+        let mut node_indices = HashMap::new();
+        node_indices.insert("a".to_string(), a);
+        node_indices.insert("b".to_string(), b);
+        node_indices.insert("c".to_string(), c);
+
+        let dep_graph = DependencyGraph {
+            graph,
+            node_indices,
+        };
+
+        let names: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            dep_graph.publish_waves(&names),
+            vec![vec!["c"], vec!["a", "b"]]
+        );
+    }
 }
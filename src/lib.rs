@@ -1,18 +1,30 @@
+// Lets the shared test fake in `git/fake.rs` name this crate the way the CLI does.
+#[cfg(test)]
+extern crate self as changelogs;
+
 pub mod changelog_entry;
+pub mod changelog_reader;
 pub mod changelog_writer;
+pub mod changesets;
 pub mod config;
 pub mod ecosystems;
 pub mod error;
+pub mod fs_util;
+pub mod git;
+pub mod github;
 pub mod graph;
+pub mod lint;
 pub mod plan;
+pub mod release_notes;
+pub mod versioning;
 pub mod workspace;
 
 use serde::{Deserialize, Serialize};
 
 pub use changelog_entry::{Changelog, Release};
 pub use config::Config;
-pub use ecosystems::{Ecosystem, Package, PublishResult, SkipReason};
-pub use plan::{PackageRelease, ReleasePlan};
+pub use ecosystems::{Ecosystem, Package, PublishOptions, PublishResult, SkipReason};
+pub use plan::{BumpReason, PackageRelease, ReleasePlan};
 pub use workspace::Workspace;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
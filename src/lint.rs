@@ -0,0 +1,231 @@
+//! The `[lint]` checks on changelog entries: built-in rules on the summaries and an
+//! optional command that gets the entry's markdown on stdin.
+
+use crate::Changelog;
+use crate::config::{LintConfig, LintRule};
+use crate::ecosystems::shell_command;
+use regex::Regex;
+use std::io::Write;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::LazyLock;
+
+/// `#123`, `owner/repo#123` or a GitHub issue or pull request link.
+static ISSUE_REF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(^|[^\w&])#\d+\b|[\w.-]+/[\w.-]+#\d+\b|github\.com/[^/\s]+/[^/\s]+/(issues|pull)/\d+",
+    )
+    .unwrap()
+});
+
+/// Runs the configured rules and command on `changelog`, whose file content is
+/// `markdown`, and returns every problem found.
+pub fn check(lint: &LintConfig, root: &Path, changelog: &Changelog, markdown: &str) -> Vec<String> {
+    let mut problems = check_rules(&lint.builtin, changelog);
+    if let Some(command) = &lint.command {
+        problems.extend(run_command(command, root, &changelog.id, markdown).err());
+    }
+    problems
+}
+
+/// Checks the built-in rules against the shared summary and each package summary.
+pub fn check_rules(rules: &[LintRule], changelog: &Changelog) -> Vec<String> {
+    let mut package_summaries: Vec<_> = changelog.package_summaries.iter().collect();
+    package_summaries.sort();
+    let summaries: Vec<(String, &str)> =
+        std::iter::once(("summary".to_string(), changelog.summary.as_str()))
+            .chain(
+                package_summaries
+                    .into_iter()
+                    .map(|(package, summary)| (format!("{} summary", package), summary.as_str())),
+            )
+            .filter(|(_, summary)| !summary.trim().is_empty())
+            .collect();
+
+    let mut problems = Vec::new();
+    for rule in rules {
+        match *rule {
+            LintRule::MaxSummaryLines(max) => {
+                for (label, summary) in &summaries {
+                    let count = summary.lines().filter(|l| !l.trim().is_empty()).count();
+                    if count > max {
+                        problems.push(format!("{} has {} lines (max {})", label, count, max));
+                    }
+                }
+            }
+            LintRule::MaxLineLength(max) => {
+                for (label, summary) in &summaries {
+                    for (i, line) in summary.lines().enumerate() {
+                        let len = line.chars().count();
+                        if len > max {
+                            problems.push(format!(
+                                "{} line {} is {} characters long (max {})",
+                                label,
+                                i + 1,
+                                len,
+                                max
+                            ));
+                        }
+                    }
+                }
+            }
+            LintRule::NoTrailingPeriod => {
+                for (label, summary) in &summaries {
+                    for (i, line) in summary.lines().enumerate() {
+                        let line = line.trim_end();
+                        if line.ends_with('.') && !line.ends_with("...") {
+                            problems.push(format!("{} line {} ends with a period", label, i + 1));
+                        }
+                    }
+                }
+            }
+            LintRule::RequireIssueRef => {
+                if !summaries.iter().any(|(_, s)| ISSUE_REF.is_match(s)) {
+                    problems.push("no issue or pull request reference (e.g. #123)".to_string());
+                }
+            }
+        }
+    }
+    problems
+}
+
+/// Runs `command` through the shell from `root` with `markdown` on stdin and
+/// `CHANGELOGS_CHANGELOG_ID` set, failing with its output when it exits non-zero.
+fn run_command(
+    command: &str,
+    root: &Path,
+    id: &str,
+    markdown: &str,
+) -> std::result::Result<(), String> {
+    let mut child = shell_command(command)
+        .current_dir(root)
+        .env("CHANGELOGS_CHANGELOG_ID", id)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run lint command `{}`: {}", command, e))?;
+
+    // Commands that don't read their input close the pipe early; only the exit
+    // status counts.
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(markdown.as_bytes());
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("failed to run lint command `{}`: {}", command, e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "lint command `{}` failed ({}):\nstdout: {}\nstderr: {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stdout).trim(),
+            String::from_utf8_lossy(&output.stderr).trim(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn changelog(summary: &str) -> Changelog {
+        Changelog {
+            id: "brave-fox".into(),
+            summary: summary.into(),
+            releases: Vec::new(),
+            commit: None,
+            package_summaries: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_max_summary_lines() {
+        let rules = [LintRule::MaxSummaryLines(2)];
+        assert!(check_rules(&rules, &changelog("Added x\n\nAdded y")).is_empty());
+
+        let mut cs = changelog("Added x");
+        cs.package_summaries
+            .insert("core".into(), "One\nTwo\nThree".into());
+        assert_eq!(
+            check_rules(&rules, &cs),
+            ["core summary has 3 lines (max 2)"]
+        );
+    }
+
+    #[test]
+    fn test_max_line_length() {
+        let rules = [LintRule::MaxLineLength(10)];
+        assert!(check_rules(&rules, &changelog("Added x")).is_empty());
+        assert_eq!(
+            check_rules(&rules, &changelog("Added x\nAdded a longer line")),
+            ["summary line 2 is 19 characters long (max 10)"]
+        );
+    }
+
+    #[test]
+    fn test_no_trailing_period() {
+        let rules = [LintRule::NoTrailingPeriod];
+        assert!(check_rules(&rules, &changelog("Added x\nWaits for more...")).is_empty());
+        assert_eq!(
+            check_rules(&rules, &changelog("Added x.")),
+            ["summary line 1 ends with a period"]
+        );
+    }
+
+    #[test]
+    fn test_require_issue_ref() {
+        let rules = [LintRule::RequireIssueRef];
+        for summary in [
+            "Fixed x (#123)",
+            "Fixed x, see wevm/changelogs-rs#12",
+            "Fixed x: https://github.com/wevm/changelogs-rs/pull/4",
+        ] {
+            assert!(
+                check_rules(&rules, &changelog(summary)).is_empty(),
+                "{summary}"
+            );
+        }
+        for summary in ["Fixed x", "Fixed &#123; escaping", "Fixed the #1st thing"] {
+            assert_eq!(
+                check_rules(&rules, &changelog(summary)),
+                ["no issue or pull request reference (e.g. #123)"],
+                "{summary}"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_failing_command() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("lint.sh"),
+            "input=$(cat)\necho \"checked $CHANGELOGS_CHANGELOG_ID\"\n\
+             case \"$input\" in *TODO*) echo 'no TODOs' >&2; exit 1;; esac\n",
+        )
+        .unwrap();
+        let lint = LintConfig {
+            command: Some("sh lint.sh".into()),
+            builtin: vec![LintRule::NoTrailingPeriod],
+        };
+
+        assert!(check(&lint, dir.path(), &changelog("Added x"), "Added x").is_empty());
+
+        let problems = check(
+            &lint,
+            dir.path(),
+            &changelog("TODO."),
+            "---\ncore: patch\n---\n\nTODO.\n",
+        );
+        assert_eq!(problems.len(), 2, "{problems:?}");
+        assert_eq!(problems[0], "summary line 1 ends with a period");
+        assert!(problems[1].starts_with("lint command `sh lint.sh` failed"));
+        assert!(problems[1].contains("stdout: checked brave-fox"));
+        assert!(problems[1].contains("stderr: no TODOs"));
+    }
+}
@@ -1,20 +1,72 @@
 use anyhow::Result;
-use changelogs::Ecosystem;
-use clap::{Parser, Subcommand};
+use changelogs::changelog_writer::OnExistingVersion;
+use changelogs::error::ErrorCategory;
+use changelogs::workspace::DiscoverOptions;
+use changelogs::{BumpType, Ecosystem};
+use clap::{CommandFactory, Parser, Subcommand};
+use cli::init::ChangesetsAction;
+use cli::output::ColorChoice;
+use cli::preview::PreviewFormat;
+use cli::version::PlanMode;
+use console::style;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tracing_subscriber::EnvFilter;
 
 mod cli;
 
 #[derive(Parser)]
 #[command(name = "changelogs")]
 #[command(about = "Manage versioning and changelogs for workspaces")]
-#[command(version)]
+#[command(version, disable_version_flag = true)]
+#[command(after_help = "\
+Exit codes:
+  0  success
+  1  other error
+  2  invalid command line usage
+  3  config error
+  4  workspace error
+  5  registry error
+  6  git error
+  7  I/O error
+  8  parse error
+  9  publish error")]
 struct Cli {
+    /// Print the version; with --json, also the version of the changelog format
+    #[arg(short = 'V', long)]
+    version: bool,
+
+    /// Run even if `required_version` in .changelog/config.toml excludes this version
+    #[arg(long, global = true)]
+    ignore_version_check: bool,
+
     /// Ecosystem to use (rust, python). Auto-detected if not specified.
     #[arg(short = 'e', long, global = true)]
     ecosystem: Option<Ecosystem>,
 
+    /// Directory holding config.toml and pending changelogs, instead of
+    /// .changelog/ at the workspace root
+    #[arg(long, value_name = "PATH", global = true)]
+    changelog_dir: Option<PathBuf>,
+
+    /// Print errors (and `why`, `status` and `packages` output) as JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// When to color output (auto follows NO_COLOR, CLICOLOR and CLICOLOR_FORCE)
+    #[arg(long, value_enum, default_value_t, global = true)]
+    color: ColorChoice,
+
+    /// Same as `--color never`
+    #[arg(long, global = true, conflicts_with = "color")]
+    no_color: bool,
+
+    /// Log what is happening to stderr: -v for debug, -vv for trace (`RUST_LOG` overrides)
+    #[arg(short = 'v', action = clap::ArgAction::Count, global = true)]
+    log_level: u8,
+
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
@@ -33,16 +85,120 @@ enum Commands {
         #[arg(short = 'i', long)]
         instructions: Option<String>,
 
+        /// Ask to accept, edit, regenerate or abort the AI-generated changelog even
+        /// when stdin isn't a terminal, failing instead of waiting (terminals are
+        /// always asked)
+        #[arg(long, requires = "ai")]
+        review: bool,
+
         /// Base ref to diff against (e.g. origin/main)
         #[arg(short = 'r', long = "ref")]
         base_ref: Option<String>,
+
+        /// Release channel (defaults to the root .changelog directory)
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Edit a pending changelog in place instead of creating a new one
+        /// (prompts for which one when no ID is given)
+        #[arg(
+            long,
+            value_name = "ID",
+            num_args = 0..=1,
+            default_missing_value = "",
+            conflicts_with = "ai"
+        )]
+        amend: Option<String>,
+
+        /// Package to include (repeatable; skips the prompts, or with --amend adds it to the changelog)
+        #[arg(short = 'p', long = "package", conflicts_with = "ai")]
+        packages: Vec<String>,
+
+        /// Bump type for the packages given with --package
+        #[arg(short = 'b', long, requires = "packages")]
+        bump: Option<BumpType>,
+
+        /// Summary for the new changelog; `-` reads it from stdin, as does piping it
+        /// in with --package
+        #[arg(short = 's', long, conflicts_with_all = ["ai", "amend"])]
+        summary: Option<String>,
+
+        /// Write the summary in $VISUAL or $EDITOR (default vim) instead of the
+        /// line-by-line prompt
+        #[arg(long, conflicts_with_all = ["summary", "ai", "amend", "packages"])]
+        editor: bool,
+
+        /// Write one changelog per --package, with the summary read from FILE and
+        /// `{package}` replaced by the package name
+        #[arg(
+            long,
+            value_name = "FILE",
+            requires = "packages",
+            conflicts_with_all = ["summary", "ai", "amend", "empty"]
+        )]
+        from_template: Option<PathBuf>,
+    },
+
+    /// Inspect or edit .changelog/config.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
     },
 
     /// Validate workspace configuration
     Doctor,
 
     /// Initialize changelogs in this workspace
-    Init,
+    Init {
+        /// Rewrite the default config.toml and README.md even if already initialized
+        /// (pending changelogs are kept)
+        #[arg(long)]
+        force: bool,
+
+        /// Don't prompt; abort if .changeset/ is found unless --migrate or --coexist is given
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        /// Convert an existing .changeset/ directory (see `changelogs migrate`)
+        #[arg(long, conflicts_with = "coexist")]
+        migrate: bool,
+
+        /// Initialize next to an existing .changeset/ directory, leaving it untouched
+        #[arg(long)]
+        coexist: bool,
+    },
+
+    /// Check pending changelogs for problems without computing a release plan
+    Lint,
+
+    /// Convert a changesets .changeset/ directory: config.json becomes config.toml and
+    /// pending entries move to .changelog/
+    Migrate {
+        /// Overwrite config.toml even if already initialized (pending changelogs are kept)
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Combine every CHANGELOG.md section released since a tag or date into one document
+    Notes {
+        /// Reference point: a release like `core@1.4.0`, or a date like `2025-01-31`
+        #[arg(long)]
+        since: String,
+
+        /// Write the notes to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Show the release plan a single changelog file would produce on its own
+    Preview {
+        /// Path to the changelog entry (does not need to be inside .changelog)
+        path: PathBuf,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: PreviewFormat,
+    },
 
     /// Publish unpublished packages to crates.io
     Publish {
@@ -50,9 +206,50 @@ enum Commands {
         #[arg(long)]
         dry_run: bool,
 
-        /// Registry to publish to
+        /// Registry to publish to (`--tag` is accepted for compatibility)
+        #[arg(long, alias = "tag")]
+        registry: Option<String>,
+
+        /// Sign git tags (`git tag -s`); defaults to `[git] sign_tags`, then git's tag.gpgSign
+        #[arg(long)]
+        sign: bool,
+
+        /// Build crates before uploading them instead of passing `--no-verify` (also `[rust] verify = true`)
+        #[arg(long)]
+        verify: bool,
+
+        /// Number of packages to publish at once (dependents always wait for their dependencies)
+        #[arg(short = 'j', long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+        jobs: u16,
+
+        /// Treat every package as unpublished instead of asking the registry, for offline or
+        /// air-gapped runs
+        #[arg(long, visible_alias = "offline")]
+        skip_published_check: bool,
+
+        /// Only publish packages whose release tag doesn't exist yet (the ones the last
+        /// `version` run released), skipping registry lookups for the rest
+        #[arg(long)]
+        changed_only: bool,
+
+        /// Publish and tag even when tracked files have uncommitted changes
+        #[arg(long)]
+        allow_dirty: bool,
+
+        /// Move release tags that already point at a different commit to HEAD (deletes and
+        /// recreates them)
         #[arg(long)]
-        tag: Option<String>,
+        retag: bool,
+    },
+
+    /// Set an exact version on packages, bypassing changelog bumps
+    SetVersion {
+        /// Version to write (semver)
+        version: String,
+
+        /// Package to set the version on (repeatable)
+        #[arg(short = 'p', long = "package", required = true)]
+        packages: Vec<String>,
     },
 
     /// Show pending changelogs and releases
@@ -60,6 +257,14 @@ enum Commands {
         /// Show detailed changelog contents
         #[arg(long)]
         verbose: bool,
+
+        /// Release channel (defaults to the root .changelog directory)
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Only consider packages whose name matches this glob, e.g. 'platform-*' (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        filter: Vec<String>,
     },
 
     /// Update changelogs to the latest version
@@ -70,28 +275,476 @@ enum Commands {
         /// Compute and display the release plan without writing any files
         #[arg(long)]
         dry_run: bool,
+
+        /// Fail instead of replacing a changelog section that already exists for a version
+        #[arg(long)]
+        no_overwrite: bool,
+
+        /// Add a new changelog section even if one already exists for the version
+        #[arg(long, conflicts_with = "no_overwrite")]
+        force: bool,
+
+        /// Run even if files the release would modify have uncommitted changes
+        #[arg(long)]
+        allow_dirty: bool,
+
+        /// Fail if refreshing the lockfile (`[python] update_lockfile`) fails, instead of warning
+        #[arg(long)]
+        strict_lockfile: bool,
+
+        /// Print the tags the release would create, one per line, without changing anything
+        #[arg(long)]
+        print_tags: bool,
+
+        /// Write the release plan as JSON to this file, without changing anything, so it
+        /// can be reviewed or edited and applied with --apply-plan
+        #[arg(long, value_name = "FILE", conflicts_with = "print_tags")]
+        export_plan: Option<PathBuf>,
+
+        /// Apply the release plan in this file (from --export-plan) instead of computing
+        /// one; it must still match the manifests and pending changelogs
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["print_tags", "export_plan"])]
+        apply_plan: Option<PathBuf>,
+
+        /// Root format: list every package's version after the changes, including unreleased ones
+        /// (also `[changelog] include_unchanged = true`)
+        #[arg(long)]
+        include_unchanged: bool,
+
+        /// Release channel (defaults to the root .changelog directory)
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Only consider packages whose name matches this glob, e.g. 'platform-*' (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        filter: Vec<String>,
+    },
+
+    /// List the packages changelogs discovered, with their dependencies on each other
+    Packages {
+        /// Print the internal dependency graph in DOT format (for Graphviz) instead
+        #[arg(long)]
+        graph: bool,
+    },
+
+    /// Explain why a package is (or isn't) part of the release plan
+    Why {
+        /// Package to explain
+        package: String,
     },
 }
 
-fn main() -> Result<()> {
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print a value by dotted key (e.g. changelog.format, fixed.0.members)
+    Get { key: String },
+
+    /// Set a value by dotted key, keeping comments and formatting
+    Set { key: String, value: String },
+
+    /// Print the effective config, marking which values come from the file
+    List,
+}
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
+    let json = cli.json;
+    cli::output::init(if cli.no_color {
+        ColorChoice::Never
+    } else {
+        cli.color
+    });
+    init_logging(cli.log_level);
+
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            if json {
+                eprintln!("{}", json_error(&err));
+            } else if let Some(block) = manifest_error(&err) {
+                eprintln!("{}", block);
+            } else {
+                eprintln!("Error: {:?}", err);
+            }
+            ExitCode::from(exit_code(&err))
+        }
+    }
+}
+
+fn print_version(json: bool) {
+    let version = env!("CARGO_PKG_VERSION");
+    if json {
+        println!(
+            "{}",
+            serde_json::json!({
+                "version": version,
+                "format_version": changelogs::changelog_writer::FORMAT_VERSION,
+            })
+        );
+    } else {
+        println!("changelogs {}", version);
+    }
+}
+
+/// Checks `required_version` in the nearest `.changelog/config.toml` above the
+/// current directory, or the one in `changelog_dir`, before any command runs.
+fn check_required_version(changelog_dir: Option<&Path>) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let Some(changelog_dir) = changelog_dir.map(|dir| cwd.join(dir)).or_else(|| {
+        cwd.ancestors()
+            .map(|dir| dir.join(".changelog"))
+            .find(|dir| dir.is_dir())
+    }) else {
+        return Ok(());
+    };
+    let running = semver::Version::parse(env!("CARGO_PKG_VERSION"))?;
+    changelogs::config::check_required_version(&changelog_dir, &running)?;
+    Ok(())
+}
+
+/// Sends library logs to stderr. Only warnings show by default, so regular output
+/// is unchanged.
+fn init_logging(log_level: u8) {
+    let filter = match std::env::var("RUST_LOG") {
+        Ok(directives) if !directives.is_empty() => EnvFilter::new(directives),
+        _ => EnvFilter::new(match log_level {
+            0 => "warn",
+            1 => "changelogs=debug",
+            _ => "changelogs=trace",
+        }),
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .without_time()
+        .init();
+}
+
+/// The exit code for an error, by the category of the first `changelogs::error::Error`
+/// in the chain (see `--help`). 1 for anything else; 2 is left to clap for usage errors.
+fn exit_code(err: &anyhow::Error) -> u8 {
+    match library_error(err).map(|e| e.category()) {
+        Some(ErrorCategory::Config) => 3,
+        Some(ErrorCategory::Workspace) => 4,
+        Some(ErrorCategory::Registry) => 5,
+        Some(ErrorCategory::Git) => 6,
+        Some(ErrorCategory::Io) => 7,
+        Some(ErrorCategory::Parse) => 8,
+        Some(ErrorCategory::Publish) => 9,
+        None => 1,
+    }
+}
+
+fn library_error(err: &anyhow::Error) -> Option<&changelogs::error::Error> {
+    err.chain()
+        .find_map(|e| e.downcast_ref::<changelogs::error::Error>())
+}
+
+/// Renders an error as `{ "error": "...", "kind": "...", "category": "..." }`. The kind
+/// and category come from the first `changelogs::error::Error` in the chain, or are
+/// `Other` and `other`.
+fn json_error(err: &anyhow::Error) -> String {
+    let error = library_error(err);
+
+    serde_json::json!({
+        "error": format!("{:#}", err),
+        "kind": error.map(|e| e.kind()).unwrap_or("Other"),
+        "category": error.map(|e| e.category().as_str()).unwrap_or("other"),
+    })
+    .to_string()
+}
 
-    match cli.command {
+/// Formats a manifest load failure with the tool's own output (e.g. cargo's stderr)
+/// as a dimmed, indented block under the headline instead of burying it in the chain.
+fn manifest_error(err: &anyhow::Error) -> Option<String> {
+    let (ecosystem, details) = err.chain().find_map(|e| match e.downcast_ref() {
+        Some(changelogs::error::Error::ManifestLoad { ecosystem, details }) => {
+            Some((ecosystem, details))
+        }
+        _ => None,
+    })?;
+
+    let headline = format!("failed to load {} manifests:", ecosystem);
+    // `anyhow::Error::downcast_ref` sees through context, so inspect the outermost link.
+    let outermost = err.chain().next().and_then(|e| e.downcast_ref());
+    let mut out = match outermost {
+        Some(changelogs::error::Error::ManifestLoad { .. }) => format!("Error: {}\n", headline),
+        _ => format!("Error: {}\n  {}\n", err, headline),
+    };
+    for line in details.lines() {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            out.push_str(&format!("    {}\n", style(line).for_stderr().dim()));
+        }
+    }
+    Some(out.trim_end().to_string())
+}
+
+fn run(cli: Cli) -> Result<()> {
+    if cli.version {
+        print_version(cli.json);
+        return Ok(());
+    }
+    if !cli.ignore_version_check {
+        check_required_version(cli.changelog_dir.as_deref())?;
+    }
+    let discover = DiscoverOptions {
+        ecosystem: cli.ecosystem,
+        changelog_dir: cli.changelog_dir.clone(),
+        ..Default::default()
+    };
+
+    let Some(command) = cli.command else {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::MissingSubcommand,
+                "a subcommand is required",
+            )
+            .exit();
+    };
+    match command {
+        Commands::Add {
+            empty,
+            channel,
+            amend: Some(id),
+            packages,
+            bump,
+            ..
+        } => {
+            let id = (!id.is_empty()).then_some(id);
+            cli::add::amend(id, packages, bump, empty, channel, &discover)?
+        }
         Commands::Add {
             empty,
             ai,
             instructions,
+            review,
             base_ref,
-        } => cli::add::run(empty, ai, instructions, base_ref, cli.ecosystem)?,
-        Commands::Doctor => cli::doctor::run(cli.ecosystem)?,
-        Commands::Init => cli::init::run(cli.ecosystem)?,
-        Commands::Publish { dry_run, tag } => {
-            cli::publish::run_with_ecosystem(dry_run, tag, cli.ecosystem)?
+            channel,
+            amend: None,
+            packages,
+            bump,
+            summary,
+            editor,
+            from_template,
+        } => cli::add::run(
+            empty,
+            ai.map(|command| cli::add::AiOptions {
+                command,
+                instructions,
+                base_ref,
+                review,
+            }),
+            cli::add::Preset {
+                packages,
+                bump,
+                summary,
+                editor,
+                template: from_template,
+            },
+            channel,
+            &discover,
+        )?,
+        Commands::Config { action } => match action {
+            ConfigAction::Get { key } => cli::config::get(key, &discover)?,
+            ConfigAction::Set { key, value } => cli::config::set(key, value, &discover)?,
+            ConfigAction::List => cli::config::list(cli.json, &discover)?,
+        },
+        Commands::Doctor => cli::doctor::run(&discover)?,
+        Commands::Init {
+            force,
+            yes,
+            migrate,
+            coexist,
+        } => {
+            let action = if migrate {
+                Some(ChangesetsAction::Migrate)
+            } else if coexist {
+                Some(ChangesetsAction::Coexist)
+            } else {
+                None
+            };
+            cli::init::run(force, yes, action, &discover)?
+        }
+        Commands::Lint => cli::lint::run(&discover)?,
+        Commands::Migrate { force } => cli::init::migrate(force, &discover)?,
+        Commands::Notes { since, output } => cli::notes::run(since, output, &discover)?,
+        Commands::Preview { path, format } => {
+            // The global --json flag wins over --format.
+            let format = if cli.json {
+                PreviewFormat::Json
+            } else {
+                format
+            };
+            cli::preview::run(path, format, &discover)?
         }
-        Commands::Status { verbose } => cli::status::run(verbose, cli.ecosystem)?,
+        Commands::Publish {
+            dry_run,
+            registry,
+            sign,
+            verify,
+            jobs,
+            skip_published_check,
+            changed_only,
+            allow_dirty,
+            retag,
+        } => cli::publish::run_with_ecosystem(
+            dry_run,
+            registry,
+            cli::publish::TagOptions { sign, retag },
+            cli::publish::CheckOptions {
+                verify,
+                allow_dirty,
+            },
+            jobs.into(),
+            cli::publish::SelectOptions {
+                skip_published_check,
+                changed_only,
+            },
+            &discover,
+        )?,
+        Commands::SetVersion { version, packages } => {
+            cli::set_version::run(version, packages, &discover)?
+        }
+        Commands::Status {
+            verbose,
+            channel,
+            filter,
+        } => cli::status::run(
+            verbose,
+            channel,
+            cli.json,
+            &DiscoverOptions { filter, ..discover },
+        )?,
         Commands::Up => cli::up::run()?,
-        Commands::Version { dry_run } => cli::version::run(dry_run, cli.ecosystem)?,
+        Commands::Version {
+            dry_run,
+            no_overwrite,
+            force,
+            allow_dirty,
+            strict_lockfile,
+            print_tags,
+            export_plan,
+            apply_plan,
+            include_unchanged,
+            channel,
+            filter,
+        } => cli::version::run(
+            dry_run,
+            if no_overwrite {
+                OnExistingVersion::Fail
+            } else if force {
+                OnExistingVersion::Prepend
+            } else {
+                OnExistingVersion::Replace
+            },
+            cli::version::CheckOptions {
+                allow_dirty,
+                strict_lockfile,
+            },
+            match (print_tags, export_plan, apply_plan) {
+                (true, _, _) => PlanMode::PrintTags,
+                (_, Some(path), _) => PlanMode::Export(path),
+                (_, _, Some(path)) => PlanMode::Import(path),
+                _ => PlanMode::Apply,
+            },
+            include_unchanged,
+            channel,
+            &DiscoverOptions { filter, ..discover },
+        )?,
+        Commands::Packages { graph } => cli::packages::run(graph, cli.json, &discover)?,
+        Commands::Why { package } => cli::why::run(package, cli.json, &discover)?,
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use changelogs::error::Error;
+
+    #[test]
+    fn test_json_error_kind() {
+        let err = anyhow::Error::from(Error::NotInitialized);
+        let value: serde_json::Value = serde_json::from_str(&json_error(&err)).unwrap();
+        assert_eq!(value["kind"], "NotInitialized");
+        assert_eq!(value["category"], "workspace");
+        assert_eq!(
+            value["error"],
+            "changelogs not initialized - run `changelogs init` first"
+        );
+    }
+
+    #[test]
+    fn test_json_error_kind_through_context() {
+        let err = Err::<(), _>(Error::NotInWorkspace)
+            .context("could not detect workspace")
+            .unwrap_err();
+        let value: serde_json::Value = serde_json::from_str(&json_error(&err)).unwrap();
+        assert_eq!(value["kind"], "NotInWorkspace");
+        assert!(
+            value["error"]
+                .as_str()
+                .unwrap()
+                .starts_with("could not detect workspace: ")
+        );
+    }
+
+    #[test]
+    fn test_json_error_other() {
+        let err = anyhow::anyhow!("something else");
+        let value: serde_json::Value = serde_json::from_str(&json_error(&err)).unwrap();
+        assert_eq!(value["kind"], "Other");
+        assert_eq!(value["category"], "other");
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let code = |err: Error| exit_code(&anyhow::Error::from(err));
+        assert_eq!(code(Error::UnknownChannel("beta".into())), 3);
+        assert_eq!(code(Error::NotInitialized), 4);
+        assert_eq!(code(Error::CratesIoCheckFailed("timeout".into())), 5);
+        assert_eq!(code(Error::DirtyFiles("a".into())), 6);
+        assert_eq!(code(Error::Io(std::io::ErrorKind::NotFound.into())), 7);
+        assert_eq!(code(Error::InvalidBumpType("huge".into())), 8);
+        assert_eq!(code(Error::PublishFailed("core".into())), 9);
+        assert_eq!(exit_code(&anyhow::anyhow!("something else")), 1);
+
+        // Found through context, like `kind`.
+        let err = Err::<(), _>(Error::NotInWorkspace)
+            .context("could not detect workspace")
+            .unwrap_err();
+        assert_eq!(exit_code(&err), 4);
+    }
+
+    #[test]
+    fn test_publish_registry_accepts_tag_alias() {
+        for flag in ["--registry", "--tag"] {
+            let cli = Cli::try_parse_from(["changelogs", "publish", flag, "internal"]).unwrap();
+            let Some(Commands::Publish { registry, .. }) = cli.command else {
+                panic!("expected publish");
+            };
+            assert_eq!(registry.as_deref(), Some("internal"));
+        }
+    }
+
+    #[test]
+    fn test_manifest_error_block() {
+        let err = Err::<(), _>(Error::ManifestLoad {
+            ecosystem: changelogs::Ecosystem::Rust,
+            details: "error: failed to parse manifest\n\nCaused by:\n  bad key".into(),
+        })
+        .context("could not detect workspace")
+        .unwrap_err();
+
+        let block = console::strip_ansi_codes(&manifest_error(&err).unwrap()).to_string();
+        assert_eq!(
+            block,
+            "Error: could not detect workspace\n  failed to load rust manifests:\n    error: failed to parse manifest\n\n    Caused by:\n      bad key"
+        );
+        assert!(manifest_error(&anyhow::anyhow!("other")).is_none());
+    }
+}
@@ -1,44 +1,134 @@
 use crate::BumpType;
-use crate::changelog_entry::Changelog;
+use crate::changelog_entry::{self, Changelog};
+use crate::changelog_writer::{self, OnExistingVersion, ReplacedSection};
 use crate::config::{ChangelogFormat, Config, DependentBump};
+use crate::ecosystems::DependencyKind;
+use crate::error::{Error, Result};
+use crate::github::AuthorResolver;
 use crate::graph::DependencyGraph;
+use crate::versioning::{self, BumpPolicy};
 use crate::workspace::Workspace;
 use semver::Version;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use tracing::debug;
 
-#[derive(Debug, Clone)]
+/// The releases computed from the pending changelogs. Serializes without the
+/// changelogs themselves, which [`import`] reads back from the pending directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReleasePlan {
+    #[serde(skip)]
     pub changelogs: Vec<Changelog>,
     pub releases: Vec<PackageRelease>,
+    #[serde(default)]
     pub warnings: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PackageRelease {
     pub name: String,
     pub bump: BumpType,
     pub old_version: Version,
     pub new_version: Version,
+    /// Required when importing, so a plan can't bump without consuming its changelogs.
     pub changelog_ids: Vec<String>,
+    #[serde(default)]
+    pub reasons: Vec<BumpReason>,
+    /// Direct dependencies released in the same plan, with their new versions.
+    /// Derived from the other releases, so not serialized.
+    #[serde(skip)]
+    pub updated_dependencies: Vec<(String, Version)>,
 }
 
-pub fn bump_version(version: &Version, bump: BumpType) -> Version {
-    match bump {
-        BumpType::Major => Version::new(version.major + 1, 0, 0),
-        BumpType::Minor => Version::new(version.major, version.minor + 1, 0),
-        BumpType::Patch => Version::new(version.major, version.minor, version.patch + 1),
+/// Why a package is part of the release plan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum BumpReason {
+    /// A pending changelog lists the package directly.
+    Changelog { id: String, bump: BumpType },
+    /// The package shares a fixed group with a releasing package. `group` is the
+    /// 1-based position in `[[fixed]]`, or `None` for the implicit group of the
+    /// root changelog format.
+    Fixed {
+        members: Vec<String>,
+        bump: BumpType,
+        group: Option<usize>,
+    },
+    /// The package is released together with other members of a linked group.
+    /// `group` is the 1-based position in `[[linked]]`.
+    Linked {
+        members: Vec<String>,
+        bump: BumpType,
+        group: usize,
+    },
+    /// The package depends (possibly transitively) on a releasing package.
+    /// `path` runs from this package down to `dependency`.
+    Dependent {
+        dependency: String,
+        path: Vec<String>,
+        bump: BumpType,
+    },
+}
+
+impl BumpReason {
+    pub fn bump(&self) -> BumpType {
+        match self {
+            BumpReason::Changelog { bump, .. }
+            | BumpReason::Fixed { bump, .. }
+            | BumpReason::Linked { bump, .. }
+            | BumpReason::Dependent { bump, .. } => *bump,
+        }
+    }
+}
+
+impl std::fmt::Display for BumpReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BumpReason::Changelog { id, bump } => write!(f, "changelog {} ({})", id, bump),
+            BumpReason::Fixed { members, bump, .. } => {
+                write!(f, "fixed group [{}] ({})", members.join(", "), bump)
+            }
+            BumpReason::Linked { members, bump, .. } => {
+                write!(f, "linked group [{}] ({})", members.join(", "), bump)
+            }
+            BumpReason::Dependent {
+                dependency,
+                path,
+                bump,
+            } => write!(
+                f,
+                "depends on {} via {} ({})",
+                dependency,
+                path.join(" -> "),
+                bump
+            ),
+        }
     }
 }
 
+/// Bumps `version` with the default [`BumpPolicy`].
+#[deprecated(note = "use `versioning::bump`, which takes an explicit policy")]
+#[must_use]
+pub fn bump_version(version: &Version, bump: BumpType) -> Version {
+    versioning::bump(version, bump, BumpPolicy::default())
+}
+
 pub fn assemble(workspace: &Workspace, changelogs: Vec<Changelog>, config: &Config) -> ReleasePlan {
-    let graph = DependencyGraph::from_workspace(workspace);
+    let graph = if config.propagate_dev_dependencies {
+        DependencyGraph::from_workspace_with_dev(workspace)
+    } else {
+        DependencyGraph::from_workspace(workspace)
+    };
 
     let mut bump_map: HashMap<String, BumpType> = HashMap::new();
     let mut changelog_map: HashMap<String, Vec<String>> = HashMap::new();
+    let mut reason_map: HashMap<String, Vec<BumpReason>> = HashMap::new();
 
     for changelog in &changelogs {
         for release in &changelog.releases {
             if config.ignore.contains(&release.package) {
+                debug!(package = %release.package, changelog = %changelog.id, "ignored package, skipping entry");
                 continue;
             }
 
@@ -53,27 +143,85 @@ pub fn assemble(workspace: &Workspace, changelogs: Vec<Changelog>, config: &Conf
                 .entry(release.package.clone())
                 .or_default()
                 .push(changelog.id.clone());
+
+            reason_map
+                .entry(release.package.clone())
+                .or_default()
+                .push(BumpReason::Changelog {
+                    id: changelog.id.clone(),
+                    bump: release.bump,
+                });
         }
     }
 
+    // Raise bumps to package floors before they spread through groups.
+    for (name, bump) in bump_map.iter_mut() {
+        *bump = with_min_bump(config, name, *bump);
+    }
+
     // Build effective fixed groups: explicit ones plus, when using root format,
-    // an implicit group of all non-ignored workspace packages.
-    let mut fixed_groups: Vec<Vec<String>> =
-        config.fixed.iter().map(|g| g.members.clone()).collect();
+    // an implicit group of all non-ignored workspace packages. Strictly explicit
+    // releases use no groups at all, not even the implicit one.
+    let explicit = config.dependent_bump == DependentBump::StrictExplicit;
+    let explicit_groups: &[_] = if explicit { &[] } else { &config.fixed };
+    let mut fixed_groups: Vec<(Option<usize>, Vec<String>)> = explicit_groups
+        .iter()
+        .enumerate()
+        .map(|(i, g)| (Some(i + 1), g.members.clone()))
+        .collect();
 
-    if config.changelog.format == ChangelogFormat::Root {
+    if !explicit
+        && (config.changelog.format == ChangelogFormat::Root
+            || config
+                .packages
+                .iter()
+                .any(|p| p.format == Some(ChangelogFormat::Root)))
+    {
         let all_members: Vec<String> = workspace
             .package_names()
             .into_iter()
             .filter(|n| !config.ignore.contains(&n.to_string()))
+            .filter(|n| config.changelog_format(n) == ChangelogFormat::Root)
             .map(|n| n.to_string())
             .collect();
         if all_members.len() > 1 {
-            fixed_groups.push(all_members);
+            fixed_groups.push((None, all_members));
         }
     }
 
-    for members in &fixed_groups {
+    let known: HashSet<&str> = workspace.package_names().into_iter().collect();
+    let mut warnings = config.unknown_group_members(&workspace.all_package_names());
+
+    // A package in several fixed groups ties them together: raise bumps until every
+    // group agrees, so each member gets the highest bump across all the groups it is
+    // connected to, whatever order they are listed in. Bumps only go up, so this ends.
+    loop {
+        let mut raised = false;
+        for (_, members) in &fixed_groups {
+            let Some(bump) = members
+                .iter()
+                .filter_map(|m| bump_map.get(m))
+                .max()
+                .copied()
+            else {
+                continue;
+            };
+            for member in members {
+                if config.ignore.contains(member) || !known.contains(member.as_str()) {
+                    continue;
+                }
+                if bump_map.get(member) != Some(&bump) {
+                    bump_map.insert(member.clone(), bump);
+                    raised = true;
+                }
+            }
+        }
+        if !raised {
+            break;
+        }
+    }
+
+    for (group, members) in &fixed_groups {
         let max_bump = members
             .iter()
             .filter_map(|m| bump_map.get(m))
@@ -82,18 +230,37 @@ pub fn assemble(workspace: &Workspace, changelogs: Vec<Changelog>, config: &Conf
 
         if let Some(bump) = max_bump {
             for member in members {
-                if !config.ignore.contains(member) {
-                    bump_map.insert(member.clone(), bump);
+                if !config.ignore.contains(member) && known.contains(member.as_str()) {
+                    let reasons = reason_map.entry(member.clone()).or_default();
+                    // The implicit root-format group can repeat an explicit one.
+                    let repeated = reasons
+                        .iter()
+                        .any(|r| matches!(r, BumpReason::Fixed { members: m, .. } if m == members));
+                    if !repeated {
+                        reasons.push(BumpReason::Fixed {
+                            members: members.clone(),
+                            bump,
+                            group: *group,
+                        });
+                    }
                 }
             }
         }
     }
 
-    for group in &config.linked {
+    // Fixed groups take precedence: a package in one is left out of linked groups,
+    // which could otherwise raise it above the rest of its fixed group.
+    let in_fixed_group = |name: &str| {
+        fixed_groups
+            .iter()
+            .any(|(_, m)| m.iter().any(|m| m == name))
+    };
+    let linked_groups: &[_] = if explicit { &[] } else { &config.linked };
+    for (i, group) in linked_groups.iter().enumerate() {
         let releasing: Vec<_> = group
             .members
             .iter()
-            .filter(|m| bump_map.contains_key(*m))
+            .filter(|m| bump_map.contains_key(*m) && !in_fixed_group(m))
             .collect();
 
         if releasing.len() > 1 {
@@ -106,19 +273,31 @@ pub fn assemble(workspace: &Workspace, changelogs: Vec<Changelog>, config: &Conf
             if let Some(bump) = max_bump {
                 for member in releasing {
                     bump_map.insert(member.clone(), bump);
+                    reason_map
+                        .entry(member.clone())
+                        .or_default()
+                        .push(BumpReason::Linked {
+                            members: group.members.clone(),
+                            bump,
+                            group: i + 1,
+                        });
                 }
             }
         }
     }
 
-    if config.dependent_bump != DependentBump::None {
+    if !matches!(
+        config.dependent_bump,
+        DependentBump::None | DependentBump::StrictExplicit
+    ) {
         let dependent_bump_type = match config.dependent_bump {
             DependentBump::Patch => BumpType::Patch,
             DependentBump::Minor => BumpType::Minor,
-            DependentBump::None => unreachable!(),
+            DependentBump::None | DependentBump::StrictExplicit => unreachable!(),
         };
 
-        let changed_packages: Vec<String> = bump_map.keys().cloned().collect();
+        let mut changed_packages: Vec<String> = bump_map.keys().cloned().collect();
+        changed_packages.sort();
 
         for pkg in changed_packages {
             for dependent in graph.all_dependents(&pkg) {
@@ -126,6 +305,18 @@ pub fn assemble(workspace: &Workspace, changelogs: Vec<Changelog>, config: &Conf
                     continue;
                 }
 
+                // In a dependency cycle a package is its own dependent.
+                if dependent != pkg {
+                    reason_map
+                        .entry(dependent.clone())
+                        .or_default()
+                        .push(BumpReason::Dependent {
+                            dependency: pkg.clone(),
+                            path: graph.path(&dependent, &pkg).unwrap_or_default(),
+                            bump: dependent_bump_type,
+                        });
+                }
+
                 let current = bump_map.get(&dependent).copied();
                 match current {
                     Some(existing) if existing >= dependent_bump_type => {}
@@ -137,18 +328,24 @@ pub fn assemble(workspace: &Workspace, changelogs: Vec<Changelog>, config: &Conf
         }
     }
 
-    let mut warnings: Vec<String> = Vec::new();
     let mut releases: Vec<PackageRelease> = Vec::new();
 
     for (name, bump) in bump_map {
+        // Dependents can be bumped below their floor.
+        let bump = with_min_bump(config, &name, bump);
         if let Some(package) = workspace.get_package(&name) {
-            let new_version = bump_version(&package.version, bump);
+            let policy = BumpPolicy {
+                zero_major: config.zero_major,
+            };
+            let new_version = versioning::bump(&package.version, bump, policy);
             releases.push(PackageRelease {
                 name: name.clone(),
                 bump,
                 old_version: package.version.clone(),
                 new_version,
                 changelog_ids: changelog_map.remove(&name).unwrap_or_default(),
+                reasons: reason_map.remove(&name).unwrap_or_default(),
+                updated_dependencies: Vec::new(),
             });
         } else {
             warnings.push(format!("changelog references unknown package '{}'", name));
@@ -157,6 +354,21 @@ pub fn assemble(workspace: &Workspace, changelogs: Vec<Changelog>, config: &Conf
 
     releases.sort_by(|a, b| a.name.cmp(&b.name));
     warnings.sort();
+    warnings.dedup();
+
+    for release in &releases {
+        let reasons: Vec<String> = release.reasons.iter().map(|r| r.to_string()).collect();
+        debug!(
+            package = %release.name,
+            bump = %release.bump,
+            from = %release.old_version,
+            to = %release.new_version,
+            reasons = %reasons.join("; "),
+            "planned release"
+        );
+    }
+
+    link_updated_dependencies(workspace, config, &mut releases);
 
     ReleasePlan {
         changelogs,
@@ -165,10 +377,291 @@ pub fn assemble(workspace: &Workspace, changelogs: Vec<Changelog>, config: &Conf
     }
 }
 
+/// Fills in each release's `updated_dependencies` from the other releases.
+fn link_updated_dependencies(
+    workspace: &Workspace,
+    config: &Config,
+    releases: &mut [PackageRelease],
+) {
+    let new_versions: HashMap<String, Version> = releases
+        .iter()
+        .map(|r| (r.name.clone(), r.new_version.clone()))
+        .collect();
+    for release in releases.iter_mut() {
+        let Some(package) = workspace.get_package(&release.name) else {
+            continue;
+        };
+        release.updated_dependencies = package
+            .dependencies
+            .iter()
+            .filter(|d| config.propagate_dev_dependencies || d.kind == DependencyKind::Normal)
+            .filter(|d| d.name != release.name)
+            .filter_map(|d| Some((d.name.clone(), new_versions.get(&d.name)?.clone())))
+            .collect();
+        release.updated_dependencies.sort();
+        release.updated_dependencies.dedup();
+    }
+}
+
+/// Raises `bump` to the package's `min_bump` from `[[packages]]`, if any.
+fn with_min_bump(config: &Config, name: &str, bump: BumpType) -> BumpType {
+    config
+        .package(name)
+        .and_then(|p| p.min_bump)
+        .map_or(bump, |floor| bump.max(floor))
+}
+
+/// A dependency requirement [`apply`] would rewrite: `manifest` depends on
+/// `dependency`, which is released as `version`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyRewrite {
+    pub manifest: PathBuf,
+    pub dependency: String,
+    pub version: Version,
+}
+
+/// The dependency requirements applying `plan` would rewrite, sorted by manifest.
+/// Found by running the ecosystem's own rewrite on scratch copies of the
+/// manifests, so it covers every dependency section and workspace-level tables,
+/// and skips requirements that would be left as they are.
+pub fn dependency_rewrites(
+    workspace: &Workspace,
+    plan: &ReleasePlan,
+) -> Result<Vec<DependencyRewrite>> {
+    let updates: HashMap<String, Version> = plan
+        .releases
+        .iter()
+        .map(|r| (r.name.clone(), r.new_version.clone()))
+        .collect();
+    let mut rewrites: Vec<DependencyRewrite> = workspace
+        .planned_dependency_updates(&updates)?
+        .into_iter()
+        .map(|(manifest, dependency)| DependencyRewrite {
+            version: updates[&dependency].clone(),
+            manifest,
+            dependency,
+        })
+        .collect();
+    rewrites.sort_by(|a, b| (&a.manifest, &a.dependency).cmp(&(&b.manifest, &b.dependency)));
+    Ok(rewrites)
+}
+
+/// Narrows `changelogs` to releases of packages in `workspace`, for a run over a
+/// filtered package subset. Entries that release none of them are dropped. Entries
+/// that also release other packages keep only the matching releases, and their ids
+/// are returned so they can stay pending for the other packages.
+pub fn retain_workspace_releases(
+    workspace: &Workspace,
+    changelogs: Vec<Changelog>,
+) -> (Vec<Changelog>, Vec<String>) {
+    let mut retained = Vec::new();
+    let mut partial = Vec::new();
+    for mut changelog in changelogs {
+        let total = changelog.releases.len();
+        changelog
+            .releases
+            .retain(|r| workspace.get_package(&r.package).is_some());
+        if changelog.releases.is_empty() {
+            debug!(changelog = %changelog.id, "no filtered package, skipping entry");
+            continue;
+        }
+        if changelog.releases.len() < total {
+            partial.push(changelog.id.clone());
+        }
+        retained.push(changelog);
+    }
+    (retained, partial)
+}
+
+/// Assembles the plan that would result if `entry` were the only pending changelog.
+/// Fixed, linked and dependent rules still apply; unknown packages end up in `warnings`.
+pub fn preview_entry(workspace: &Workspace, config: &Config, entry: &Changelog) -> ReleasePlan {
+    assemble(workspace, vec![entry.clone()], config)
+}
+
+/// Checks an exported (and possibly hand-edited) plan against the workspace and the
+/// `pending` changelogs, and returns it ready for [`apply`]: every package must
+/// exist at its `old_version`, move to a greater `new_version` and list only pending
+/// changelog ids. All problems are reported together.
+///
+/// Entries that also name a package the plan no longer releases (e.g. one dropped
+/// while editing it) have their ids returned too, so they can stay pending for that
+/// package, as with [`retain_workspace_releases`].
+pub fn import(
+    workspace: &Workspace,
+    mut plan: ReleasePlan,
+    pending: Vec<Changelog>,
+    config: &Config,
+) -> Result<(ReleasePlan, Vec<String>)> {
+    let mut problems = Vec::new();
+    let mut seen = HashSet::new();
+    for release in &plan.releases {
+        let name = &release.name;
+        if !seen.insert(name.as_str()) {
+            problems.push(format!("{} is listed more than once", name));
+        }
+        match workspace.get_package(name) {
+            None => problems.push(format!("unknown package '{}'", name)),
+            Some(package) if package.version != release.old_version => problems.push(format!(
+                "{}: old_version {} does not match the manifest ({})",
+                name, release.old_version, package.version
+            )),
+            Some(_) => {}
+        }
+        if release.new_version <= release.old_version {
+            problems.push(format!(
+                "{}: new_version {} is not greater than {}",
+                name, release.new_version, release.old_version
+            ));
+        }
+        for id in &release.changelog_ids {
+            if !pending.iter().any(|c| &c.id == id) {
+                problems.push(format!("{}: changelog '{}' is not pending", name, id));
+            }
+        }
+    }
+    if !problems.is_empty() {
+        let list: Vec<String> = problems.iter().map(|p| format!("  {}", p)).collect();
+        return Err(Error::InvalidPlan(list.join("\n")));
+    }
+
+    let ids: HashSet<&str> = plan
+        .releases
+        .iter()
+        .flat_map(|r| r.changelog_ids.iter().map(String::as_str))
+        .collect();
+    plan.changelogs = pending
+        .into_iter()
+        .filter(|c| ids.contains(c.id.as_str()))
+        .collect();
+    let partial = plan
+        .changelogs
+        .iter()
+        .filter(|c| {
+            c.releases.iter().any(|r| {
+                !plan.releases.iter().any(|release| {
+                    release.name == r.package && release.changelog_ids.contains(&c.id)
+                })
+            })
+        })
+        .map(|c| c.id.clone())
+        .collect();
+    plan.warnings.clear();
+    link_updated_dependencies(workspace, config, &mut plan.releases);
+    Ok((plan, partial))
+}
+
+/// How [`apply`] writes a plan.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApplyOptions<'a> {
+    /// What to do when a changelog already has a section for a planned version.
+    pub on_existing: OnExistingVersion,
+    /// The channel whose pending changelogs the plan consumes.
+    pub channel: Option<&'a str>,
+    /// Changelog ids to leave pending instead of deleting, such as entries a
+    /// filtered run only partly released.
+    pub keep: &'a [String],
+}
+
+/// What [`apply`] changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppliedReleases {
+    /// The new version of each released package.
+    pub versions: BTreeMap<String, Version>,
+    /// The changelogs written.
+    pub changelogs: Vec<PathBuf>,
+    /// Existing sections that were replaced with `OnExistingVersion::Replace`.
+    pub replaced: Vec<ReplacedSection>,
+    /// The consumed changelog entries, now deleted.
+    pub deleted: Vec<PathBuf>,
+}
+
+/// Applies `plan` to the workspace: writes the new versions to the manifests,
+/// updates dependents' requirements, writes the changelogs and deletes the consumed
+/// entries. Unwritable changelogs and, with `OnExistingVersion::Fail`, existing
+/// sections are reported before any file is touched. `authors` credits each entry's
+/// authors in the changelogs.
+pub fn apply(
+    workspace: &Workspace,
+    plan: &ReleasePlan,
+    config: &Config,
+    authors: &AuthorResolver,
+    options: ApplyOptions,
+) -> Result<AppliedReleases> {
+    let relative = |paths: &[PathBuf]| {
+        paths
+            .iter()
+            .map(|p| {
+                format!(
+                    "  {}",
+                    p.strip_prefix(&workspace.root).unwrap_or(p).display()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let unwritable = changelog_writer::unwritable_targets(workspace, &plan.releases, config);
+    if !unwritable.is_empty() {
+        return Err(Error::ChangelogNotWritable(relative(&unwritable)));
+    }
+    if options.on_existing == OnExistingVersion::Fail {
+        let existing = changelog_writer::existing_sections(workspace, &plan.releases, config)?;
+        if let Some((path, version)) = existing.into_iter().next() {
+            return Err(Error::ChangelogVersionExists(
+                path.display().to_string(),
+                version.to_string(),
+            ));
+        }
+    }
+
+    let mut updates = HashMap::new();
+    for release in &plan.releases {
+        workspace.update_version_with_config(&release.name, &release.new_version, config)?;
+        updates.insert(release.name.clone(), release.new_version.clone());
+    }
+    workspace.update_dependency_versions(&updates)?;
+
+    let replaced = changelog_writer::write_changelogs(
+        workspace,
+        &plan.releases,
+        &plan.changelogs,
+        config,
+        options.on_existing,
+        options.channel,
+        authors,
+    )?;
+    let mut changelogs: Vec<PathBuf> =
+        changelog_writer::changelog_targets(workspace, &plan.releases, config)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+    changelogs.dedup();
+
+    let changelog_dir = workspace.channel_dir(options.channel);
+    let mut deleted = Vec::new();
+    for changelog in &plan.changelogs {
+        if options.keep.contains(&changelog.id) {
+            continue;
+        }
+        changelog_entry::delete(&changelog_dir, &changelog.id)?;
+        deleted.push(changelog_dir.join(format!("{}.md", changelog.id)));
+    }
+
+    Ok(AppliedReleases {
+        versions: updates.into_iter().collect(),
+        changelogs,
+        replaced,
+        deleted,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Release;
+    use crate::ecosystems::Dependency;
+    use crate::versioning::ZeroMajor;
     use semver::Version;
 
     fn mock_package(name: &str, version: &str, deps: Vec<&str>) -> crate::ecosystems::Package {
@@ -177,13 +670,13 @@ mod tests {
             version: Version::parse(version).unwrap(),
             path: std::path::PathBuf::from(format!("crates/{}", name)),
             manifest_path: std::path::PathBuf::from(format!("crates/{}/Cargo.toml", name)),
-            dependencies: deps.into_iter().map(String::from).collect(),
+            dependencies: deps.into_iter().map(Dependency::normal).collect(),
         }
     }
 
     #[test]
     fn test_highest_bump_wins() {
-        let changelogs = vec![
+        let changelogs = [
             Changelog {
                 id: "a".to_string(),
                 summary: "patch change".to_string(),
@@ -192,6 +685,7 @@ mod tests {
                     bump: BumpType::Patch,
                 }],
                 commit: None,
+                package_summaries: HashMap::new(),
             },
             Changelog {
                 id: "b".to_string(),
@@ -201,6 +695,7 @@ mod tests {
                     bump: BumpType::Minor,
                 }],
                 commit: None,
+                package_summaries: HashMap::new(),
             },
         ];
 
@@ -223,12 +718,11 @@ mod tests {
     }
 
     fn mock_workspace(packages: Vec<crate::ecosystems::Package>) -> Workspace {
-        Workspace {
-            root: std::path::PathBuf::from("/tmp/test"),
-            changelog_dir: std::path::PathBuf::from("/tmp/test/.changelog"),
+        Workspace::new(
+            std::path::PathBuf::from("/tmp/test"),
             packages,
-            ecosystem: crate::ecosystems::Ecosystem::Rust,
-        }
+            crate::ecosystems::Ecosystem::Rust,
+        )
     }
 
     fn make_changelog(id: &str, releases: Vec<Release>) -> Changelog {
@@ -237,9 +731,142 @@ mod tests {
             summary: format!("changelog {}", id),
             releases,
             commit: None,
+            package_summaries: HashMap::new(),
         }
     }
 
+    #[test]
+    fn test_dependency_rewrites() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n\n\
+             [workspace.dependencies]\na = { path = \"crates/a\", version = \"1.0.0\" }\n",
+        );
+        let manifest = |name: &str, deps: &str| {
+            format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n\n{deps}")
+        };
+        write("crates/a/Cargo.toml", &manifest("a", ""));
+        write(
+            "crates/b/Cargo.toml",
+            &manifest(
+                "b",
+                "[dependencies]\na = { path = \"../a\", version = \"1.0.0\" }\n",
+            ),
+        );
+        // Path-only, inherited and still-matching requirements stay as they are.
+        write(
+            "crates/c/Cargo.toml",
+            &manifest("c", "[dependencies]\na = { path = \"../a\" }\n"),
+        );
+        write(
+            "crates/d/Cargo.toml",
+            &manifest("d", "[dependencies]\na = { workspace = true }\n"),
+        );
+        write(
+            "crates/e/Cargo.toml",
+            &manifest(
+                "e",
+                "[dependencies]\na = { path = \"../a\", version = \"^1.0\" }\n",
+            ),
+        );
+        write(
+            "crates/tests/Cargo.toml",
+            &manifest(
+                "tests",
+                "[dev-dependencies]\na = { path = \"../a\", version = \"1.0.0\" }\n",
+            ),
+        );
+
+        let packages = ["a", "b", "c", "d", "e", "tests"]
+            .map(|name| crate::ecosystems::Package {
+                path: dir.path().join("crates").join(name),
+                manifest_path: dir.path().join("crates").join(name).join("Cargo.toml"),
+                ..mock_package(name, "1.0.0", vec![])
+            })
+            .to_vec();
+        let ws = Workspace::new(
+            dir.path().to_path_buf(),
+            packages,
+            crate::ecosystems::Ecosystem::Rust,
+        );
+        let changelogs = vec![make_changelog(
+            "cl1",
+            vec![Release {
+                package: "a".to_string(),
+                bump: BumpType::Minor,
+            }],
+        )];
+        let config = Config {
+            dependent_bump: DependentBump::None,
+            ..Default::default()
+        };
+        let plan = assemble(&ws, changelogs, &config);
+
+        let rewrites: Vec<(String, String, String)> = dependency_rewrites(&ws, &plan)
+            .unwrap()
+            .into_iter()
+            .map(|r| {
+                (
+                    r.manifest
+                        .strip_prefix(dir.path())
+                        .unwrap()
+                        .display()
+                        .to_string(),
+                    r.dependency,
+                    r.version.to_string(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            rewrites,
+            [
+                ("Cargo.toml".into(), "a".into(), "1.1.0".into()),
+                ("crates/b/Cargo.toml".into(), "a".into(), "1.1.0".into()),
+                ("crates/tests/Cargo.toml".into(), "a".into(), "1.1.0".into()),
+            ]
+        );
+        // Nothing was written.
+        let b = std::fs::read_to_string(dir.path().join("crates/b/Cargo.toml")).unwrap();
+        assert!(b.contains("version = \"1.0.0\""), "{b}");
+    }
+
+    #[test]
+    fn test_retain_workspace_releases() {
+        // The workspace after filtering to `platform-*`; `web` depends on `platform-a`.
+        let ws = mock_workspace(vec![
+            mock_package("platform-a", "1.0.0", vec![]),
+            mock_package("platform-b", "1.0.0", vec!["platform-a"]),
+        ]);
+        let release = |package: &str| Release {
+            package: package.to_string(),
+            bump: BumpType::Minor,
+        };
+        let changelogs = vec![
+            make_changelog("both", vec![release("platform-a"), release("web")]),
+            make_changelog("web-only", vec![release("web")]),
+            make_changelog("platform", vec![release("platform-b")]),
+        ];
+
+        let (retained, partial) = retain_workspace_releases(&ws, changelogs);
+
+        let ids: Vec<&str> = retained.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["both", "platform"]);
+        assert_eq!(retained[0].releases.len(), 1);
+        assert_eq!(partial, ["both"]);
+
+        let plan = assemble(&ws, retained, &Config::default());
+        assert!(plan.warnings.is_empty(), "{:?}", plan.warnings);
+        let names: Vec<&str> = plan.releases.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["platform-a", "platform-b"]);
+    }
+
     #[test]
     fn test_assemble_simple_bump() {
         let ws = mock_workspace(vec![mock_package("foo", "1.0.0", vec![])]);
@@ -336,6 +963,153 @@ mod tests {
         assert_eq!(plan.releases[0].name, "a");
     }
 
+    fn dev_only_workspace() -> Workspace {
+        let mut b = mock_package("b", "2.0.0", vec![]);
+        b.dependencies = vec![Dependency::dev("a")];
+        let mut c = mock_package("c", "3.0.0", vec![]);
+        c.dependencies = vec![Dependency {
+            name: "a".to_string(),
+            kind: crate::ecosystems::DependencyKind::Build,
+        }];
+        mock_workspace(vec![mock_package("a", "1.0.0", vec![]), b, c])
+    }
+
+    #[test]
+    fn test_assemble_dev_only_dependent_not_bumped() {
+        let changelogs = vec![make_changelog(
+            "cl1",
+            vec![Release {
+                package: "a".to_string(),
+                bump: BumpType::Minor,
+            }],
+        )];
+
+        let plan = assemble(&dev_only_workspace(), changelogs, &Config::default());
+
+        assert_eq!(plan.releases.len(), 1);
+        assert_eq!(plan.releases[0].name, "a");
+    }
+
+    #[test]
+    fn test_assemble_zero_major_shift() {
+        let changelogs = vec![make_changelog(
+            "cl1",
+            vec![Release {
+                package: "a".to_string(),
+                bump: BumpType::Major,
+            }],
+        )];
+        let workspace = mock_workspace(vec![mock_package("a", "0.3.1", vec![])]);
+
+        let plan = assemble(&workspace, changelogs.clone(), &Config::default());
+        assert_eq!(plan.releases[0].new_version, Version::new(1, 0, 0));
+
+        let config = Config {
+            zero_major: ZeroMajor::Shift,
+            ..Config::default()
+        };
+        let plan = assemble(&workspace, changelogs, &config);
+        assert_eq!(plan.releases[0].new_version, Version::new(0, 4, 0));
+    }
+
+    #[test]
+    fn test_assemble_propagate_dev_dependencies() {
+        let changelogs = vec![make_changelog(
+            "cl1",
+            vec![Release {
+                package: "a".to_string(),
+                bump: BumpType::Minor,
+            }],
+        )];
+        let config = Config {
+            propagate_dev_dependencies: true,
+            ..Config::default()
+        };
+
+        let plan = assemble(&dev_only_workspace(), changelogs, &config);
+
+        let mut names: Vec<_> = plan.releases.iter().map(|r| r.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["a", "b", "c"]);
+        assert_eq!(
+            plan.releases[1].updated_dependencies,
+            vec![("a".to_string(), Version::new(1, 1, 0))]
+        );
+    }
+
+    #[test]
+    fn test_assemble_updated_dependencies_skip_dev_dependencies() {
+        let changelogs = vec![make_changelog(
+            "cl1",
+            vec![
+                Release {
+                    package: "a".to_string(),
+                    bump: BumpType::Minor,
+                },
+                Release {
+                    package: "b".to_string(),
+                    bump: BumpType::Patch,
+                },
+            ],
+        )];
+
+        let plan = assemble(&dev_only_workspace(), changelogs, &Config::default());
+
+        assert_eq!(plan.releases[1].name, "b");
+        assert!(plan.releases[1].updated_dependencies.is_empty());
+    }
+
+    #[test]
+    fn test_assemble_package_min_bump() {
+        let ws = mock_workspace(vec![
+            mock_package("x", "1.0.0", vec![]),
+            mock_package("y", "1.0.0", vec!["x"]),
+            mock_package("z", "1.0.0", vec![]),
+        ]);
+        let changelogs = vec![make_changelog(
+            "cl1",
+            vec![
+                Release {
+                    package: "x".to_string(),
+                    bump: BumpType::Patch,
+                },
+                Release {
+                    package: "z".to_string(),
+                    bump: BumpType::Major,
+                },
+            ],
+        )];
+        let floor = |name: &str| crate::config::PackageConfig {
+            name: name.to_string(),
+            tag_template: None,
+            changelog_path: None,
+            min_bump: Some(BumpType::Minor),
+            format: None,
+        };
+        let config = Config {
+            packages: vec![floor("x"), floor("y"), floor("z")],
+            ..Config::default()
+        };
+
+        let plan = assemble(&ws, changelogs, &config);
+
+        let bumps: Vec<(&str, BumpType)> = plan
+            .releases
+            .iter()
+            .map(|r| (r.name.as_str(), r.bump))
+            .collect();
+        // A floor raises changelog and dependent bumps but never lowers one.
+        assert_eq!(
+            bumps,
+            [
+                ("x", BumpType::Minor),
+                ("y", BumpType::Minor),
+                ("z", BumpType::Major)
+            ]
+        );
+        assert_eq!(plan.releases[0].new_version, Version::new(1, 1, 0));
+    }
+
     #[test]
     fn test_assemble_fixed_group() {
         let ws = mock_workspace(vec![
@@ -366,6 +1140,236 @@ mod tests {
         assert_eq!(y.bump, BumpType::Minor);
     }
 
+    #[test]
+    fn test_assemble_warns_about_unknown_group_members() {
+        let ws = mock_workspace(vec![
+            mock_package("x", "1.0.0", vec![]),
+            mock_package("y", "1.0.0", vec![]),
+        ]);
+        let changelogs = vec![make_changelog(
+            "cl1",
+            vec![Release {
+                package: "x".to_string(),
+                bump: BumpType::Minor,
+            }],
+        )];
+        let config = Config {
+            dependent_bump: DependentBump::None,
+            fixed: vec![crate::config::FixedGroup {
+                members: vec!["x".to_string(), "old-crate".to_string()],
+            }],
+            linked: vec![crate::config::LinkedGroup {
+                members: vec!["y".to_string(), "gone".to_string()],
+            }],
+            ..Config::default()
+        };
+
+        let plan = assemble(&ws, changelogs, &config);
+
+        assert_eq!(
+            plan.warnings,
+            [
+                "fixed group contains unknown package 'old-crate'",
+                "linked group contains unknown package 'gone'",
+            ]
+        );
+        let names: Vec<_> = plan.releases.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["x"]);
+    }
+
+    #[test]
+    fn test_assemble_package_in_several_fixed_groups() {
+        let ws = mock_workspace(vec![
+            mock_package("a", "1.0.0", vec![]),
+            mock_package("b", "1.0.0", vec![]),
+            mock_package("c", "1.0.0", vec![]),
+            mock_package("d", "1.0.0", vec![]),
+        ]);
+        let changelogs = || {
+            vec![
+                make_changelog(
+                    "cl1",
+                    vec![Release {
+                        package: "a".to_string(),
+                        bump: BumpType::Patch,
+                    }],
+                ),
+                make_changelog(
+                    "cl2",
+                    vec![Release {
+                        package: "c".to_string(),
+                        bump: BumpType::Major,
+                    }],
+                ),
+            ]
+        };
+        let group = |members: &[&str]| crate::config::FixedGroup {
+            members: members.iter().map(|m| m.to_string()).collect(),
+        };
+
+        // `b` ties [a, b] to [b, c]; `d` is in neither.
+        for fixed in [
+            vec![group(&["a", "b"]), group(&["b", "c"])],
+            vec![group(&["b", "c"]), group(&["a", "b"])],
+        ] {
+            let config = Config {
+                dependent_bump: DependentBump::None,
+                fixed,
+                ..Config::default()
+            };
+
+            let plan = assemble(&ws, changelogs(), &config);
+
+            let bumps: Vec<_> = plan
+                .releases
+                .iter()
+                .map(|r| (r.name.as_str(), r.bump))
+                .collect();
+            assert_eq!(
+                bumps,
+                [
+                    ("a", BumpType::Major),
+                    ("b", BumpType::Major),
+                    ("c", BumpType::Major),
+                ]
+            );
+            assert!(plan.warnings.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_assemble_fixed_group_wins_over_linked() {
+        let ws = mock_workspace(vec![
+            mock_package("a", "1.0.0", vec![]),
+            mock_package("b", "1.0.0", vec![]),
+            mock_package("x", "1.0.0", vec![]),
+        ]);
+        let changelogs = vec![
+            make_changelog(
+                "cl1",
+                vec![Release {
+                    package: "a".to_string(),
+                    bump: BumpType::Patch,
+                }],
+            ),
+            make_changelog(
+                "cl2",
+                vec![Release {
+                    package: "x".to_string(),
+                    bump: BumpType::Major,
+                }],
+            ),
+        ];
+        let config = Config {
+            dependent_bump: DependentBump::None,
+            fixed: vec![crate::config::FixedGroup {
+                members: vec!["a".to_string(), "b".to_string()],
+            }],
+            linked: vec![crate::config::LinkedGroup {
+                members: vec!["a".to_string(), "x".to_string()],
+            }],
+            ..Config::default()
+        };
+
+        let plan = assemble(&ws, changelogs, &config);
+
+        let bumps: Vec<_> = plan
+            .releases
+            .iter()
+            .map(|r| (r.name.as_str(), r.bump))
+            .collect();
+        assert_eq!(
+            bumps,
+            [
+                ("a", BumpType::Patch),
+                ("b", BumpType::Patch),
+                ("x", BumpType::Major),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_assemble_strict_explicit_releases_only_named_packages() {
+        let ws = mock_workspace(vec![
+            mock_package("core", "1.0.0", vec![]),
+            mock_package("app", "1.0.0", vec!["core"]),
+            mock_package("macros", "1.0.0", vec![]),
+            mock_package("sdk", "1.0.0", vec![]),
+            mock_package("cli", "1.0.0", vec![]),
+        ]);
+        let changelogs = vec![make_changelog(
+            "cl1",
+            vec![
+                Release {
+                    package: "core".to_string(),
+                    bump: BumpType::Major,
+                },
+                Release {
+                    package: "sdk".to_string(),
+                    bump: BumpType::Patch,
+                },
+            ],
+        )];
+        let config = Config {
+            dependent_bump: DependentBump::StrictExplicit,
+            fixed: vec![crate::config::FixedGroup {
+                members: vec!["core".to_string(), "macros".to_string()],
+            }],
+            linked: vec![crate::config::LinkedGroup {
+                members: vec!["sdk".to_string(), "cli".to_string(), "core".to_string()],
+            }],
+            ..Config::default()
+        };
+
+        let plan = assemble(&ws, changelogs, &config);
+
+        let bumps: Vec<_> = plan
+            .releases
+            .iter()
+            .map(|r| (r.name.as_str(), r.bump))
+            .collect();
+        assert_eq!(bumps, [("core", BumpType::Major), ("sdk", BumpType::Patch)]);
+        for release in &plan.releases {
+            assert!(
+                release
+                    .reasons
+                    .iter()
+                    .all(|r| matches!(r, BumpReason::Changelog { .. })),
+                "{:?}",
+                release.reasons
+            );
+        }
+    }
+
+    #[test]
+    fn test_assemble_strict_explicit_ignores_root_format_group() {
+        let ws = mock_workspace(vec![
+            mock_package("core", "1.0.0", vec![]),
+            mock_package("cli", "1.0.0", vec![]),
+        ]);
+        let changelogs = vec![make_changelog(
+            "cl1",
+            vec![Release {
+                package: "core".to_string(),
+                bump: BumpType::Minor,
+            }],
+        )];
+        let mut config = Config {
+            dependent_bump: DependentBump::StrictExplicit,
+            ..Config::default()
+        };
+        config.changelog.format = ChangelogFormat::Root;
+
+        let plan = assemble(&ws, changelogs, &config);
+
+        let bumps: Vec<_> = plan
+            .releases
+            .iter()
+            .map(|r| (r.name.as_str(), r.bump))
+            .collect();
+        assert_eq!(bumps, [("core", BumpType::Minor)]);
+    }
+
     #[test]
     fn test_assemble_linked_group_both_releasing() {
         let ws = mock_workspace(vec![
@@ -488,4 +1492,42 @@ mod tests {
         assert_eq!(plan.releases[0].name, "a");
         assert!(plan.releases.iter().all(|r| r.name != "b"));
     }
+
+    #[test]
+    fn test_preview_entry_applies_dependents() {
+        let workspace = mock_workspace(vec![
+            mock_package("core", "0.5.0", vec![]),
+            mock_package("app", "1.0.0", vec!["core"]),
+        ]);
+        let entry = make_changelog(
+            "pr-bot",
+            vec![
+                Release {
+                    package: "core".to_string(),
+                    bump: BumpType::Minor,
+                },
+                Release {
+                    package: "missing".to_string(),
+                    bump: BumpType::Patch,
+                },
+            ],
+        );
+
+        let plan = preview_entry(&workspace, &Config::default(), &entry);
+
+        assert_eq!(plan.changelogs.len(), 1);
+        let versions: Vec<_> = plan
+            .releases
+            .iter()
+            .map(|r| (r.name.as_str(), r.new_version.to_string()))
+            .collect();
+        assert_eq!(
+            versions,
+            vec![("app", "1.0.1".to_string()), ("core", "0.6.0".to_string())]
+        );
+        assert_eq!(
+            plan.warnings,
+            vec!["changelog references unknown package 'missing'"]
+        );
+    }
 }
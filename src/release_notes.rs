@@ -0,0 +1,325 @@
+//! Aggregates released sections from the committed CHANGELOG.md files, e.g. for a
+//! newsletter covering everything since a given release.
+
+use crate::changelog_reader;
+use crate::changelog_writer::package_changelog_path;
+use crate::config::{ChangelogFormat, Config};
+use crate::error::{Error, Result};
+use crate::git::Git;
+use crate::workspace::Workspace;
+use chrono::{DateTime, NaiveDate};
+use semver::Version;
+use std::path::{Path, PathBuf};
+
+/// The reference point sections must be newer than.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Since {
+    /// A release such as `core@1.4.0`. Sections of that package's changelog are
+    /// compared by version; other changelogs by date against that release's date.
+    /// A section is dated by its heading, or else by its release tag.
+    Release { package: String, version: Version },
+    /// Sections released on or after this date.
+    Date(NaiveDate),
+}
+
+impl std::str::FromStr for Since {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Ok(Since::Date(date));
+        }
+        let (package, version) = s
+            .rsplit_once('@')
+            .ok_or_else(|| Error::InvalidSince(s.to_string()))?;
+        let version = Version::parse(version.strip_prefix('v').unwrap_or(version))
+            .map_err(|_| Error::InvalidSince(s.to_string()))?;
+        if package.is_empty() {
+            return Err(Error::InvalidSince(s.to_string()));
+        }
+        Ok(Since::Release {
+            package: package.to_string(),
+            version,
+        })
+    }
+}
+
+impl std::fmt::Display for Since {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Since::Release { package, version } => write!(f, "{}@{}", package, version),
+            Since::Date(date) => write!(f, "{}", date),
+        }
+    }
+}
+
+/// The new sections of one changelog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageNotes {
+    pub name: String,
+    /// The changelog, relative to the workspace root.
+    pub path: PathBuf,
+    pub sections: Vec<NoteSection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteSection {
+    /// The heading without its `## ` marker, e.g. `1.5.0 (2025-02-01)`.
+    pub title: String,
+    /// Everything below the heading.
+    pub body: String,
+}
+
+/// Threshold a single changelog's sections are compared against.
+#[derive(Clone, Copy)]
+enum Cutoff<'a> {
+    After(&'a Version),
+    AfterDate(NaiveDate),
+    OnOrAfter(NaiveDate),
+}
+
+/// Collects the sections newer than `since` from every package's CHANGELOG.md (or
+/// the root CHANGELOG.md for the root format). Changelogs without new sections are
+/// left out. Per-crate headings carry no date, so their sections are dated by
+/// their release tag in `git`.
+pub fn collect(
+    workspace: &Workspace,
+    config: &Config,
+    since: &Since,
+    git: &dyn Git,
+) -> Result<Vec<PackageNotes>> {
+    let changelogs = changelog_files(workspace, config);
+    let date = |names: &[String], section: &changelog_reader::Section| {
+        section_date(workspace, config, git, names, section)
+    };
+
+    // Changelogs other than the named package's are compared by date.
+    let by_date = match since {
+        Since::Release { package, version } => {
+            let (names, path) = changelogs
+                .iter()
+                .find(|(names, _)| names.contains(package))
+                .ok_or_else(|| Error::PackageNotFound(package.clone()))?;
+            let content = std::fs::read_to_string(path).unwrap_or_default();
+            let parsed = changelog_reader::parse(&content);
+            let date = parsed
+                .find_version(version)
+                .and_then(|idx| date(names, &parsed.sections[idx]))
+                .ok_or_else(|| Error::ReleaseNotFound(since.to_string()))?;
+            Cutoff::AfterDate(date)
+        }
+        Since::Date(date) => Cutoff::OnOrAfter(*date),
+    };
+
+    let mut notes = Vec::new();
+    for (names, path) in &changelogs {
+        let cutoff = match since {
+            Since::Release { package, version } if names.contains(package) => {
+                Cutoff::After(version)
+            }
+            _ => by_date,
+        };
+
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        let sections: Vec<NoteSection> = changelog_reader::parse(&content)
+            .sections
+            .iter()
+            .filter(|section| match cutoff {
+                Cutoff::After(version) => section.version.as_ref().is_some_and(|v| v > version),
+                Cutoff::AfterDate(cutoff) => date(names, section).is_some_and(|d| d > cutoff),
+                Cutoff::OnOrAfter(cutoff) => date(names, section).is_some_and(|d| d >= cutoff),
+            })
+            .map(|section| NoteSection {
+                title: section.heading.trim_start_matches("## ").trim().to_string(),
+                body: section.text[section.heading.len()..].trim().to_string(),
+            })
+            .collect();
+
+        if sections.is_empty() {
+            continue;
+        }
+        notes.push(PackageNotes {
+            name: names.join(", "),
+            path: path
+                .strip_prefix(&workspace.root)
+                .unwrap_or(path)
+                .to_path_buf(),
+            sections,
+        });
+    }
+
+    Ok(notes)
+}
+
+/// When a section of the changelog covering `names` was released: the date in its
+/// heading, or else the commit date of the tag `version` would create for it.
+fn section_date(
+    workspace: &Workspace,
+    config: &Config,
+    git: &dyn Git,
+    names: &[String],
+    section: &changelog_reader::Section,
+) -> Option<NaiveDate> {
+    if let Some(date) = changelog_reader::heading_date(section.heading) {
+        return Some(date);
+    }
+    let mut package = workspace.get_package(names.first()?)?.clone();
+    package.version = section.version.clone()?;
+    let tag = workspace.release_tags(&[&package], config).pop()?;
+    let timestamp = git.commit_timestamp(&tag)?;
+    Some(DateTime::from_timestamp(timestamp, 0)?.date_naive())
+}
+
+/// The changelogs to read, with the packages each one covers: a single root
+/// CHANGELOG.md for every package with the root format, and one per other package.
+fn changelog_files(workspace: &Workspace, config: &Config) -> Vec<(Vec<String>, PathBuf)> {
+    let mut packages: Vec<_> = workspace.packages.iter().collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    let (root, per_crate): (Vec<_>, Vec<_>) = packages
+        .into_iter()
+        .partition(|p| config.changelog_format(&p.name) == ChangelogFormat::Root);
+
+    let mut files = Vec::new();
+    if !root.is_empty() {
+        let names = root.iter().map(|p| p.name.clone()).collect();
+        files.push((names, workspace.root.join("CHANGELOG.md")));
+    }
+    files.extend(per_crate.into_iter().map(|p| {
+        (
+            vec![p.name.clone()],
+            package_changelog_path(workspace, p, config),
+        )
+    }));
+    files
+}
+
+/// Renders the notes as one markdown document, a `##` section per changelog with
+/// each release heading linking to its section in that changelog.
+pub fn render_markdown(notes: &[PackageNotes], since: &Since, github_url: Option<&str>) -> String {
+    let mut out = format!("# Release notes since {}\n\n", since);
+
+    if notes.is_empty() {
+        out.push_str("No releases.\n");
+        return out;
+    }
+
+    for package in notes {
+        out.push_str(&format!("## {}\n\n", package.name));
+        for section in &package.sections {
+            let link = section_link(&package.path, &section.title, github_url);
+            out.push_str(&format!("### [{}]({})\n\n", section.title, link));
+            if !section.body.is_empty() {
+                out.push_str(&demote_headings(&section.body));
+                out.push_str("\n\n");
+            }
+        }
+    }
+
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+fn section_link(path: &Path, title: &str, github_url: Option<&str>) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    match github_url {
+        Some(url) => format!("{}/blob/HEAD/{}#{}", url, path, anchor(title)),
+        None => format!("{}#{}", path, anchor(title)),
+    }
+}
+
+/// GitHub's anchor for a heading: lowercase, punctuation dropped, spaces as `-`.
+fn anchor(title: &str) -> String {
+    title
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c.to_ascii_lowercase()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Nests a section's own headings one level deeper, outside code blocks.
+fn demote_headings(body: &str) -> String {
+    let mut in_code_block = false;
+    body.lines()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            }
+            if !in_code_block && line.starts_with('#') {
+                format!("#{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(
+            "core@1.4.0".parse::<Since>().unwrap(),
+            Since::Release {
+                package: "core".into(),
+                version: Version::new(1, 4, 0)
+            }
+        );
+        assert_eq!(
+            "@scope/pkg@v2.0.0".parse::<Since>().unwrap(),
+            Since::Release {
+                package: "@scope/pkg".into(),
+                version: Version::new(2, 0, 0)
+            }
+        );
+        assert_eq!(
+            "2025-01-15".parse::<Since>().unwrap(),
+            Since::Date(NaiveDate::from_ymd_opt(2025, 1, 15).unwrap())
+        );
+        assert!("core".parse::<Since>().is_err());
+        assert!("core@latest".parse::<Since>().is_err());
+        assert!("@1.0.0".parse::<Since>().is_err());
+    }
+
+    #[test]
+    fn test_anchor() {
+        assert_eq!(anchor("1.5.0 (2025-02-01)"), "150-2025-02-01");
+        assert_eq!(anchor("`core@1.5.0`"), "core150");
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let notes = vec![PackageNotes {
+            name: "core".into(),
+            path: PathBuf::from("core/CHANGELOG.md"),
+            sections: vec![NoteSection {
+                title: "1.5.0 (2025-02-01)".into(),
+                body: "### Minor Changes\n\n- Added x".into(),
+            }],
+        }];
+        let since = Since::Date(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
+
+        assert_eq!(
+            render_markdown(
+                &notes,
+                &since,
+                Some("https://github.com/wevm/changelogs-rs")
+            ),
+            "# Release notes since 2025-01-01\n\n## core\n\n\
+             ### [1.5.0 (2025-02-01)](https://github.com/wevm/changelogs-rs/blob/HEAD/core/CHANGELOG.md#150-2025-02-01)\n\n\
+             #### Minor Changes\n\n- Added x\n"
+        );
+        assert_eq!(
+            render_markdown(&[], &since, None),
+            "# Release notes since 2025-01-01\n\nNo releases.\n"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Version arithmetic for releases.
+//!
+//! Every new version in a release plan comes from [`bump`], so the CLI and library
+//! users get the same answer for the same input.
+
+use crate::BumpType;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+/// How bumps apply to versions below `1.0.0`, set by `zero_major` in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZeroMajor {
+    /// Bumps apply as written, so a major bump on `0.3.1` releases `1.0.0`.
+    #[default]
+    Literal,
+    /// Cargo's compatibility rules: below `1.0.0` the minor version is the breaking
+    /// one, so a major bump on `0.3.1` releases `0.4.0` and a minor bump `0.3.2`.
+    /// Patch bumps stay patch bumps.
+    Shift,
+}
+
+/// Rules for [`bump`] beyond the bump type itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BumpPolicy {
+    pub zero_major: ZeroMajor,
+}
+
+/// Returns the version to release when `version` gets a `bump`.
+///
+/// - The result never carries pre-release or build metadata.
+/// - A pre-release bumps like its release: `1.0.0-rc.1` becomes `1.0.1` for a
+///   patch bump and `1.1.0` for a minor one.
+/// - Build metadata is ignored: `1.0.0+abc` bumps like `1.0.0`.
+/// - Below `1.0.0`, `policy.zero_major` decides whether bumps shift down a level.
+///
+/// The result is always greater than `version`.
+#[must_use]
+pub fn bump(version: &Version, bump: BumpType, policy: BumpPolicy) -> Version {
+    match effective_bump(version, bump, policy) {
+        BumpType::Major => Version::new(version.major + 1, 0, 0),
+        BumpType::Minor => Version::new(version.major, version.minor + 1, 0),
+        BumpType::Patch => Version::new(version.major, version.minor, version.patch + 1),
+    }
+}
+
+/// Returns the bump that actually applies to `version` under `policy`.
+#[must_use]
+pub fn effective_bump(version: &Version, bump: BumpType, policy: BumpPolicy) -> BumpType {
+    if version.major > 0 || policy.zero_major == ZeroMajor::Literal {
+        return bump;
+    }
+    match bump {
+        BumpType::Major => BumpType::Minor,
+        BumpType::Minor | BumpType::Patch => BumpType::Patch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const SHIFT: BumpPolicy = BumpPolicy {
+        zero_major: ZeroMajor::Shift,
+    };
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    fn bumped(version: &str, bump_type: BumpType, policy: BumpPolicy) -> String {
+        bump(&v(version), bump_type, policy).to_string()
+    }
+
+    #[test]
+    fn test_bump_release() {
+        let policy = BumpPolicy::default();
+        assert_eq!(bumped("1.2.3", BumpType::Patch, policy), "1.2.4");
+        assert_eq!(bumped("1.2.3", BumpType::Minor, policy), "1.3.0");
+        assert_eq!(bumped("1.2.3", BumpType::Major, policy), "2.0.0");
+    }
+
+    #[test]
+    fn test_bump_zero_major_literal() {
+        let policy = BumpPolicy::default();
+        assert_eq!(bumped("0.3.1", BumpType::Patch, policy), "0.3.2");
+        assert_eq!(bumped("0.3.1", BumpType::Minor, policy), "0.4.0");
+        assert_eq!(bumped("0.3.1", BumpType::Major, policy), "1.0.0");
+        assert_eq!(bumped("0.0.1", BumpType::Major, policy), "1.0.0");
+    }
+
+    #[test]
+    fn test_bump_zero_major_shift() {
+        assert_eq!(bumped("0.3.1", BumpType::Patch, SHIFT), "0.3.2");
+        assert_eq!(bumped("0.3.1", BumpType::Minor, SHIFT), "0.3.2");
+        assert_eq!(bumped("0.3.1", BumpType::Major, SHIFT), "0.4.0");
+        assert_eq!(bumped("0.0.1", BumpType::Major, SHIFT), "0.1.0");
+        // Shifting only applies below 1.0.0.
+        assert_eq!(bumped("1.3.1", BumpType::Major, SHIFT), "2.0.0");
+    }
+
+    #[test]
+    fn test_bump_prerelease() {
+        let policy = BumpPolicy::default();
+        assert_eq!(bumped("1.0.0-rc.1", BumpType::Patch, policy), "1.0.1");
+        assert_eq!(bumped("1.0.0-rc.1", BumpType::Minor, policy), "1.1.0");
+        assert_eq!(bumped("1.0.0-rc.1", BumpType::Major, policy), "2.0.0");
+        assert_eq!(bumped("1.2.3-alpha", BumpType::Patch, policy), "1.2.4");
+        assert_eq!(bumped("0.4.0-rc.1", BumpType::Major, SHIFT), "0.5.0");
+    }
+
+    #[test]
+    fn test_bump_drops_build_metadata() {
+        let policy = BumpPolicy::default();
+        assert_eq!(bumped("1.2.3+build.5", BumpType::Patch, policy), "1.2.4");
+        assert_eq!(bumped("1.0.0-rc.1+abc", BumpType::Patch, policy), "1.0.1");
+    }
+
+    fn version() -> impl Strategy<Value = Version> {
+        let pre = prop_oneof![
+            Just(""),
+            Just("alpha"),
+            Just("alpha.1"),
+            Just("beta.2"),
+            Just("rc.1"),
+        ];
+        let build = prop_oneof![Just(""), Just("build.1"), Just("abc")];
+        (0u64..4, 0u64..4, 0u64..4, pre, build).prop_map(|(major, minor, patch, pre, build)| {
+            let mut version = Version::new(major, minor, patch);
+            version.pre = semver::Prerelease::new(pre).unwrap();
+            version.build = semver::BuildMetadata::new(build).unwrap();
+            version
+        })
+    }
+
+    fn bump_type() -> impl Strategy<Value = BumpType> {
+        prop_oneof![
+            Just(BumpType::Patch),
+            Just(BumpType::Minor),
+            Just(BumpType::Major)
+        ]
+    }
+
+    fn policy() -> impl Strategy<Value = BumpPolicy> {
+        prop_oneof![Just(BumpPolicy::default()), Just(SHIFT)]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_bump_is_greater(version in version(), bump_type in bump_type(), policy in policy()) {
+            let next = bump(&version, bump_type, policy);
+            prop_assert!(next.cmp_precedence(&version).is_gt());
+            prop_assert!(next.pre.is_empty() && next.build.is_empty());
+        }
+
+        #[test]
+        fn prop_bump_preserves_order(
+            a in version(),
+            b in version(),
+            bump_type in bump_type(),
+            policy in policy(),
+        ) {
+            let (low, high) = if a.cmp_precedence(&b).is_le() { (a, b) } else { (b, a) };
+            prop_assert!(bump(&low, bump_type, policy) <= bump(&high, bump_type, policy));
+        }
+
+        #[test]
+        fn prop_larger_bumps_go_further(version in version(), policy in policy()) {
+            let patch = bump(&version, BumpType::Patch, policy);
+            let minor = bump(&version, BumpType::Minor, policy);
+            let major = bump(&version, BumpType::Major, policy);
+            prop_assert!(patch <= minor && minor <= major);
+        }
+
+        #[test]
+        fn prop_patch_after_patch_increments_by_one(version in version(), policy in policy()) {
+            let first = bump(&version, BumpType::Patch, policy);
+            let second = bump(&first, BumpType::Patch, policy);
+            prop_assert_eq!(
+                second,
+                Version::new(first.major, first.minor, first.patch + 1)
+            );
+        }
+    }
+}
@@ -1,92 +1,251 @@
-use crate::ecosystems::{self, Ecosystem, Package, PublishResult};
+use crate::config::{ChangelogFormat, Config, DependentBump, VersionTarget};
+use crate::ecosystems::{self, Ecosystem, Package, PublishOptions, PublishResult, PythonAdapter};
 use crate::error::{Error, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use semver::Version;
-use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use toml_edit::DocumentMut;
+use tracing::{debug, warn};
 
 #[derive(Debug, Clone)]
 pub struct Workspace {
     pub root: PathBuf,
+    /// Holds `config.toml`: `.changelog/` unless `--changelog-dir` moves it.
     pub changelog_dir: PathBuf,
+    /// Holds the pending entries: the changelog directory unless `[changelog] dir`
+    /// moves them.
+    pub pending_dir: PathBuf,
     pub packages: Vec<Package>,
     pub ecosystem: Ecosystem,
+    /// Registry lookups already made by this run.
+    published: PublishedCache,
+    /// Packages discovered but left out by `--filter`.
+    filtered_out: Vec<String>,
 }
 
 pub type WorkspacePackage = Package;
 
+/// Whether each `(name, version)` is on the registry, so repeated
+/// [`Workspace::is_published`] calls in one run only ask the registry once.
+/// Failed lookups are not remembered.
+#[derive(Debug, Default)]
+struct PublishedCache(Mutex<HashMap<(String, Version), bool>>);
+
+impl PublishedCache {
+    fn get_or_fetch(
+        &self,
+        pkg: &Package,
+        fetch: impl FnOnce(&Package) -> Result<bool>,
+    ) -> Result<bool> {
+        let key = (pkg.name.clone(), pkg.version.clone());
+        if let Some(&published) = self.lock().get(&key) {
+            debug!(package = %pkg.name, version = %pkg.version, published, "registry lookup cached");
+            return Ok(published);
+        }
+        // Not held across the lookup, so packages can be checked in parallel.
+        let published = fetch(pkg)?;
+        self.lock().insert(key, published);
+        Ok(published)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(String, Version), bool>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clone for PublishedCache {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.lock().clone()))
+    }
+}
+
+/// Changed files that fall inside one package.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageChanges {
+    /// Every changed file inside the package.
+    pub files: Vec<PathBuf>,
+    /// The subset of `files` matched by an exclude pattern.
+    pub excluded: Vec<PathBuf>,
+}
+
+impl PackageChanges {
+    /// True if every changed file was excluded, so the package needs no changelog.
+    pub fn all_excluded(&self) -> bool {
+        self.files.len() == self.excluded.len()
+    }
+}
+
+/// Overrides for [`Workspace::discover_with`]; the defaults detect everything.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoverOptions {
+    pub ecosystem: Option<Ecosystem>,
+    /// Replaces `.changelog/`, relative to the current directory.
+    pub changelog_dir: Option<PathBuf>,
+    /// Package names or globs; when set, only matching packages are kept.
+    pub filter: Vec<String>,
+}
+
 impl Workspace {
+    /// A workspace of `packages` under `root`, with its changelog directory and
+    /// pending entries in `root/.changelog`.
+    pub fn new(root: PathBuf, packages: Vec<Package>, ecosystem: Ecosystem) -> Self {
+        let changelog_dir = root.join(".changelog");
+        Workspace {
+            root,
+            pending_dir: changelog_dir.clone(),
+            changelog_dir,
+            packages,
+            ecosystem,
+            published: PublishedCache::default(),
+            filtered_out: Vec::new(),
+        }
+    }
+
+    /// Moves the changelog directory, and the pending entries with it, to `dir`.
+    pub fn with_changelog_dir(mut self, dir: PathBuf) -> Self {
+        self.pending_dir = dir.clone();
+        self.changelog_dir = dir;
+        self
+    }
+
     pub fn discover() -> Result<Self> {
-        Self::discover_with_ecosystem(None)
+        Self::discover_with(&DiscoverOptions::default())
     }
 
     pub fn discover_with_ecosystem(ecosystem: Option<Ecosystem>) -> Result<Self> {
+        Self::discover_with(&DiscoverOptions {
+            ecosystem,
+            ..Default::default()
+        })
+    }
+
+    pub fn discover_with(options: &DiscoverOptions) -> Result<Self> {
         let cwd = std::env::current_dir()?;
 
-        let ecosystem = ecosystem
+        let ecosystem = options
+            .ecosystem
             .or_else(|| ecosystems::detect_ecosystem(&cwd))
             .ok_or(Error::NotInWorkspace)?;
 
         let root = Self::find_root(&cwd, ecosystem)?;
-        let packages = ecosystems::discover_packages(ecosystem, &root)?;
+        let changelog_dir = match &options.changelog_dir {
+            Some(dir) => cwd.join(dir),
+            None => root.join(".changelog"),
+        };
+        let config = Config::load(&changelog_dir)?;
+        let packages = ecosystems::discover_packages(ecosystem, &root, &config)?;
+        debug!(%ecosystem, root = %root.display(), count = packages.len(), "discovered packages");
+        for package in &packages {
+            debug!(
+                package = %package.name,
+                version = %package.version,
+                manifest = %package.manifest_path.display(),
+                "found package"
+            );
+        }
 
         if packages.is_empty() {
             return Err(Error::NotInWorkspace);
         }
+        let (packages, filtered_out) = filter_packages(packages, &options.filter, &config)?;
 
-        let changelog_dir = root.join(".changelog");
+        let pending_dir = match &config.changelog.dir {
+            Some(dir) => root.join(dir),
+            None => changelog_dir.clone(),
+        };
 
         Ok(Workspace {
-            root,
-            changelog_dir,
-            packages,
-            ecosystem,
+            pending_dir,
+            filtered_out,
+            ..Workspace::new(root, packages, ecosystem).with_changelog_dir(changelog_dir)
         })
     }
 
     fn find_root(start: &Path, ecosystem: Ecosystem) -> Result<PathBuf> {
-        let manifest_name = match ecosystem {
-            Ecosystem::Rust => "Cargo.toml",
-            Ecosystem::Python => "pyproject.toml",
-        };
+        // A workspace file (go.work, a .NET solution) marks the root even without a
+        // manifest beside it.
+        if let Some(root) = start.ancestors().find(|d| ecosystem.has_workspace_file(d)) {
+            return Ok(root.to_path_buf());
+        }
 
-        let mut current = start.to_path_buf();
+        let package_dir = start
+            .ancestors()
+            .find(|dir| ecosystem.has_manifest(dir))
+            .ok_or(Error::NotInWorkspace)?;
 
-        loop {
-            let manifest = current.join(manifest_name);
-            if manifest.exists() {
-                if ecosystem == Ecosystem::Rust {
-                    let content = std::fs::read_to_string(&manifest)?;
-                    if content.contains("[workspace]") {
-                        return Ok(current);
-                    }
-                }
+        if ecosystem == Ecosystem::Rust {
+            if let Some(root) = Self::find_cargo_workspace(package_dir)? {
+                return Ok(root);
+            }
+        }
 
-                let parent = current.parent();
-                if parent.is_none() {
-                    return Ok(current);
-                }
+        Ok(package_dir.to_path_buf())
+    }
 
-                let parent_manifest = parent.unwrap().join(manifest_name);
-                if !parent_manifest.exists() {
-                    return Ok(current);
-                }
+    /// Returns the nearest enclosing Cargo workspace that `package_dir` belongs to:
+    /// the one named by `package.workspace`, else the closest ancestor (or the
+    /// directory itself) whose `[workspace]` lists it in `members` and not in
+    /// `exclude`.
+    fn find_cargo_workspace(package_dir: &Path) -> Result<Option<PathBuf>> {
+        let manifest = std::fs::read_to_string(package_dir.join("Cargo.toml"))?;
+        let doc: DocumentMut = manifest.parse()?;
 
-                if ecosystem == Ecosystem::Rust {
-                    let content = std::fs::read_to_string(&parent_manifest)?;
-                    if content.contains("[workspace]") {
-                        current = parent.unwrap().to_path_buf();
-                        continue;
+        if let Some(path) = doc
+            .get("package")
+            .and_then(|p| p.get("workspace"))
+            .and_then(|w| w.as_str())
+        {
+            let mut root = package_dir.to_path_buf();
+            for component in Path::new(path).components() {
+                match component {
+                    Component::ParentDir => {
+                        root.pop();
                     }
+                    Component::CurDir => {}
+                    other => root.push(other),
                 }
-
-                return Ok(current);
             }
+            return Ok(Some(root));
+        }
+        if doc.get("workspace").is_some_and(|w| w.is_table_like()) {
+            return Ok(Some(package_dir.to_path_buf()));
+        }
 
-            match current.parent() {
-                Some(parent) => current = parent.to_path_buf(),
-                None => return Err(Error::NotInWorkspace),
+        for dir in package_dir.ancestors().skip(1) {
+            // Unrelated or broken manifests further up are not our concern.
+            let Ok(content) = std::fs::read_to_string(dir.join("Cargo.toml")) else {
+                continue;
+            };
+            let Ok(doc) = content.parse::<DocumentMut>() else {
+                continue;
+            };
+            let Some(workspace) = doc.get("workspace").and_then(|w| w.as_table_like()) else {
+                continue;
+            };
+
+            let relative = package_dir.strip_prefix(dir).unwrap_or(package_dir);
+            let patterns = |key: &str| -> Vec<String> {
+                workspace
+                    .get(key)
+                    .and_then(|v| v.as_array())
+                    .map(|a| {
+                        a.iter()
+                            .filter_map(|v| v.as_str())
+                            .map(normalize_member)
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            };
+            if build_glob_set(&patterns("members"))?.is_match(relative)
+                && !build_glob_set(&patterns("exclude"))?.is_match(relative)
+            {
+                return Ok(Some(dir.to_path_buf()));
             }
         }
+
+        Ok(None)
     }
 
     pub fn load() -> Result<Self> {
@@ -97,15 +256,50 @@ impl Workspace {
         Self::discover_with_ecosystem(ecosystem)
     }
 
+    /// Returns the ecosystem's manifest at the workspace root (`Cargo.toml` or
+    /// `pyproject.toml`). It may not exist, e.g. for a Python monorepo.
+    pub fn root_manifest_path(&self) -> PathBuf {
+        self.root.join(self.ecosystem.manifest_name())
+    }
+
+    /// Returns true for the package whose manifest is the root manifest, i.e. the
+    /// root package of a non-virtual Rust workspace or a single Python project.
+    pub fn is_root_package(&self, pkg: &Package) -> bool {
+        pkg.manifest_path == self.root_manifest_path()
+    }
+
     pub fn changelog_dir(&self) -> PathBuf {
-        self.root.join(".changelog")
+        self.changelog_dir.clone()
     }
 
+    /// Pending changelogs for `channel` live in `.changelog/<channel>/`; the default
+    /// channel uses `.changelog/` itself. `[changelog] dir` replaces `.changelog/`.
+    pub fn channel_dir(&self, channel: Option<&str>) -> PathBuf {
+        match channel {
+            Some(name) => self.pending_dir.join(name),
+            None => self.pending_dir.clone(),
+        }
+    }
+
+    /// Packages whose current version is not on the registry yet.
     pub fn get_publishable_packages(&self) -> Result<Vec<&Package>> {
+        self.get_publishable_packages_with(false)
+    }
+
+    /// Like [`Workspace::get_publishable_packages`]. With `skip_published_check` the
+    /// registry is not asked and every package is returned, for offline runs.
+    pub fn get_publishable_packages_with(
+        &self,
+        skip_published_check: bool,
+    ) -> Result<Vec<&Package>> {
+        if skip_published_check {
+            return Ok(self.packages.iter().collect());
+        }
+
         let mut publishable = Vec::new();
 
         for pkg in &self.packages {
-            let is_published = ecosystems::is_published(self.ecosystem, &pkg.name, &pkg.version)?;
+            let is_published = self.is_published(pkg)?;
 
             if !is_published {
                 publishable.push(pkg);
@@ -115,6 +309,35 @@ impl Workspace {
         Ok(publishable)
     }
 
+    /// Those of `packages` whose registry has a newer version than their manifest,
+    /// e.g. after a manual publish, with that version. Private packages are skipped.
+    pub fn ahead_on_registry<'a>(
+        &self,
+        packages: &[&'a Package],
+    ) -> Result<Vec<(&'a Package, Version)>> {
+        self.ahead_on_registry_with(packages, |pkg| {
+            ecosystems::latest_published(self.ecosystem, pkg)
+        })
+    }
+
+    /// [`Workspace::ahead_on_registry`] with `latest` in place of the registry.
+    pub fn ahead_on_registry_with<'a>(
+        &self,
+        packages: &[&'a Package],
+        latest: impl Fn(&Package) -> Result<Option<Version>>,
+    ) -> Result<Vec<(&'a Package, Version)>> {
+        let mut ahead = Vec::new();
+        for &pkg in packages {
+            if self.is_private(pkg)? {
+                continue;
+            }
+            if let Some(published) = latest(pkg)?.filter(|v| *v > pkg.version) {
+                ahead.push((pkg, published));
+            }
+        }
+        Ok(ahead)
+    }
+
     pub fn is_initialized(&self) -> bool {
         self.changelog_dir().exists()
     }
@@ -127,35 +350,378 @@ impl Workspace {
         self.packages.iter().map(|p| p.name.as_str()).collect()
     }
 
+    /// Every discovered package, including those left out by `--filter`.
+    pub fn all_package_names(&self) -> Vec<&str> {
+        let filtered_out = self.filtered_out.iter().map(String::as_str);
+        self.package_names()
+            .into_iter()
+            .chain(filtered_out)
+            .collect()
+    }
+
+    /// Maps changed paths to the packages that contain them.
+    ///
+    /// Paths may be absolute or relative to the workspace root. A file belongs to the
+    /// innermost package whose directory contains it; files outside every package are
+    /// dropped. Exclude patterns from `[verify]` match the path relative to either the
+    /// workspace root or the package directory.
+    pub fn changed_packages<P: AsRef<Path>>(
+        &self,
+        paths: &[P],
+        config: &Config,
+    ) -> Result<HashMap<String, PackageChanges>> {
+        let mut changes: HashMap<String, PackageChanges> = HashMap::new();
+        let mut exclude_sets: HashMap<&str, GlobSet> = HashMap::new();
+
+        for path in paths {
+            let path = path.as_ref();
+            let abs = if path.is_absolute() {
+                path.to_path_buf()
+            } else {
+                self.root.join(path)
+            };
+
+            let Some(package) = self
+                .packages
+                .iter()
+                .filter(|p| abs.starts_with(&p.path))
+                .max_by_key(|p| p.path.components().count())
+            else {
+                continue;
+            };
+
+            let excludes = match exclude_sets.get(package.name.as_str()) {
+                Some(set) => set,
+                None => {
+                    let set = build_glob_set(config.verify.exclude_paths_for(&package.name))?;
+                    exclude_sets.entry(&package.name).or_insert(set)
+                }
+            };
+
+            let from_root = abs.strip_prefix(&self.root).unwrap_or(path);
+            let from_package = abs.strip_prefix(&package.path).unwrap_or(path);
+
+            let entry = changes.entry(package.name.clone()).or_default();
+            if excludes.is_match(from_root) || excludes.is_match(from_package) {
+                entry.excluded.push(path.to_path_buf());
+            }
+            entry.files.push(path.to_path_buf());
+        }
+
+        Ok(changes)
+    }
+
+    /// Returns the packages with at least one changed file that is not excluded,
+    /// along with those files.
+    pub fn affected_packages<P: AsRef<Path>>(
+        &self,
+        paths: &[P],
+        config: &Config,
+    ) -> Result<HashMap<String, Vec<PathBuf>>> {
+        Ok(self
+            .changed_packages(paths, config)?
+            .into_iter()
+            .filter(|(_, changes)| !changes.all_excluded())
+            .map(|(name, changes)| {
+                let files = changes
+                    .files
+                    .into_iter()
+                    .filter(|f| !changes.excluded.contains(f))
+                    .collect();
+                (name, files)
+            })
+            .collect())
+    }
+
+    /// Like [`Workspace::update_version_with_config`], loading the config from the
+    /// changelog directory.
     pub fn update_version(&self, package_name: &str, new_version: &Version) -> Result<()> {
+        let config = Config::load(&self.changelog_dir())?;
+        self.update_version_with_config(package_name, new_version, &config)
+    }
+
+    /// Writes `new_version` to the package's manifest and to its
+    /// [`Workspace::version_targets`].
+    pub fn update_version_with_config(
+        &self,
+        package_name: &str,
+        new_version: &Version,
+        config: &Config,
+    ) -> Result<()> {
         let package = self
             .get_package(package_name)
             .ok_or_else(|| Error::PackageNotFound(package_name.to_string()))?;
 
-        ecosystems::write_version(self.ecosystem, &package.manifest_path, new_version)
+        ecosystems::write_version(self.ecosystem, &package.manifest_path, new_version)?;
+
+        let (targets, warnings) = self.version_targets(package, new_version, config);
+        for warning in warnings {
+            warn!("{}", warning);
+        }
+        for (target, value) in &targets {
+            ecosystems::write_version_target(&package.path, target, value)?;
+        }
+        Ok(())
+    }
+
+    /// The files besides the manifest that carry `package`'s version, each with the
+    /// value to write for `new_version`: Go's `version_targets`, and for Python the
+    /// Sphinx `docs/conf.py` entries that agree with the manifest. Warnings name the
+    /// docs entries left alone.
+    pub fn version_targets(
+        &self,
+        package: &Package,
+        new_version: &Version,
+        config: &Config,
+    ) -> (Vec<(VersionTarget, String)>, Vec<String>) {
+        match self.ecosystem {
+            Ecosystem::Go => {
+                let targets = config
+                    .go
+                    .version_targets
+                    .iter()
+                    .filter(|t| package.path.join(&t.path).exists())
+                    .map(|t| (t.clone(), new_version.to_string()))
+                    .collect();
+                (targets, Vec::new())
+            }
+            Ecosystem::Python if config.python.sync_docs => {
+                PythonAdapter::docs_version_targets(&package.path, &package.version, new_version)
+            }
+            _ => (Vec::new(), Vec::new()),
+        }
     }
 
     pub fn update_dependency_versions(&self, updates: &HashMap<String, Version>) -> Result<()> {
-        ecosystems::update_dependency_versions(self.ecosystem, &self.packages, &self.root, updates)
+        ecosystems::update_dependency_versions(
+            self.ecosystem,
+            &self.packages,
+            &self.root_manifest_path(),
+            updates,
+        )
+    }
+
+    /// The `(manifest, dependency)` requirements [`Workspace::update_dependency_versions`]
+    /// would rewrite for `updates`, without changing any file.
+    pub fn planned_dependency_updates(
+        &self,
+        updates: &HashMap<String, Version>,
+    ) -> Result<Vec<(PathBuf, String)>> {
+        ecosystems::planned_dependency_updates(
+            self.ecosystem,
+            &self.packages,
+            &self.root_manifest_path(),
+            updates,
+        )
+    }
+
+    /// Refreshes the lockfile with the ecosystem's tool when `update_lockfile` is set.
+    /// Returns the command that ran, or `None` when there was nothing to refresh.
+    pub fn update_lockfile(&self, config: &Config) -> Result<Option<String>> {
+        ecosystems::lockfile_command(self.ecosystem, &self.root, config)
+            .map(ecosystems::run_lockfile_command)
+            .transpose()
+    }
+
+    /// Writes `version` to every named package and rewrites dependency requirements
+    /// on them, bypassing the bump logic. All names are checked before anything is written.
+    pub fn set_versions(&self, package_names: &[String], version: &Version) -> Result<()> {
+        if package_names.is_empty() {
+            return Err(Error::NoPackagesSelected);
+        }
+
+        if let Some(missing) = package_names.iter().find(|n| self.get_package(n).is_none()) {
+            return Err(Error::PackageNotFound(missing.clone()));
+        }
+
+        let config = Config::load(&self.changelog_dir())?;
+        let mut updates = HashMap::new();
+        for name in package_names {
+            self.update_version_with_config(name, version, &config)?;
+            updates.insert(name.clone(), version.clone());
+        }
+
+        self.update_dependency_versions(&updates)
     }
 
     pub fn publish_package(
         &self,
         pkg: &Package,
         dry_run: bool,
-        registry: Option<&str>,
+        options: &PublishOptions,
     ) -> Result<PublishResult> {
-        ecosystems::publish(self.ecosystem, pkg, dry_run, registry)
+        ecosystems::publish(self.ecosystem, pkg, dry_run, options)
+    }
+
+    /// Whether `pkg`'s current version is on the registry. Each version is looked up
+    /// at most once per workspace.
+    pub fn is_published(&self, pkg: &Package) -> Result<bool> {
+        self.is_published_with(pkg, |pkg| ecosystems::is_published(self.ecosystem, pkg))
+    }
+
+    /// [`Workspace::is_published`] with `fetch` in place of the registry.
+    pub fn is_published_with(
+        &self,
+        pkg: &Package,
+        fetch: impl FnOnce(&Package) -> Result<bool>,
+    ) -> Result<bool> {
+        self.published.get_or_fetch(pkg, fetch)
+    }
+
+    /// Whether the package's manifest opts out of publishing, e.g. `publish = false`.
+    pub fn is_private(&self, pkg: &Package) -> Result<bool> {
+        ecosystems::is_private(self.ecosystem, pkg)
     }
 
     pub fn tag_name(&self, pkg: &Package) -> String {
         ecosystems::tag_name(self.ecosystem, pkg)
     }
+
+    /// Returns the tags for releasing `packages` at their current versions. The
+    /// root-format packages are a single product and get one `v{version}` tag;
+    /// every other package gets its `tag_template` from `[[packages]]` or the
+    /// ecosystem's tag.
+    pub fn release_tags(&self, packages: &[&Package], config: &Config) -> Vec<String> {
+        let (root, per_crate): (Vec<&Package>, Vec<&Package>) = packages
+            .iter()
+            .partition(|pkg| config.changelog_format(&pkg.name) == ChangelogFormat::Root);
+
+        let mut tags: Vec<String> = root
+            .first()
+            .map(|first| format!("v{}", first.version))
+            .into_iter()
+            .collect();
+        tags.extend(per_crate.into_iter().map(|pkg| {
+            match config
+                .package(&pkg.name)
+                .and_then(|p| p.tag_template.as_deref())
+            {
+                Some(template) => template
+                    .replace("{name}", &pkg.name)
+                    .replace("{version}", &pkg.version.to_string()),
+                None => self.tag_name(pkg),
+            }
+        }));
+        tags
+    }
+}
+
+/// Strips a leading `./` and trailing `/` from a `members` or `exclude` entry.
+fn normalize_member(pattern: &str) -> String {
+    pattern
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Keeps the packages whose name is, or matches a glob in, any of `patterns`,
+/// plus every package sharing a version group with one of them, so a filter
+/// cannot split a group. Returns the kept packages and the names of the rest.
+/// No patterns keeps everything.
+fn filter_packages(
+    packages: Vec<Package>,
+    patterns: &[String],
+    config: &Config,
+) -> Result<(Vec<Package>, Vec<String>)> {
+    if patterns.is_empty() {
+        return Ok((packages, Vec::new()));
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .build()
+            .map_err(|e| Error::InvalidFilter(format!("{}: {}", pattern, e)))?;
+        builder.add(glob);
+    }
+    let set = builder
+        .build()
+        .map_err(|e| Error::InvalidFilter(e.to_string()))?;
+
+    let mut selected: HashSet<String> = packages
+        .iter()
+        .filter(|p| set.is_match(&p.name))
+        .map(|p| p.name.clone())
+        .collect();
+    if selected.is_empty() {
+        return Err(Error::NoPackagesSelected);
+    }
+
+    let groups = version_groups(&packages, config);
+    loop {
+        let mut added = false;
+        for group in &groups {
+            let Some(member) = group.iter().find(|m| selected.contains(*m)) else {
+                continue;
+            };
+            for other in group {
+                if selected.insert(other.clone()) {
+                    warn!(
+                        "--filter includes '{}': it shares a version group with '{}'",
+                        other, member
+                    );
+                    added = true;
+                }
+            }
+        }
+        if !added {
+            break;
+        }
+    }
+
+    let (kept, dropped): (Vec<Package>, Vec<Package>) = packages
+        .into_iter()
+        .partition(|p| selected.contains(&p.name));
+    debug!(count = kept.len(), "filtered packages");
+    Ok((kept, dropped.into_iter().map(|p| p.name).collect()))
+}
+
+/// The fixed and linked groups among `packages`, plus the implicit group of
+/// root-format packages, as `plan::assemble` applies them.
+fn version_groups(packages: &[Package], config: &Config) -> Vec<Vec<String>> {
+    if config.dependent_bump == DependentBump::StrictExplicit {
+        return Vec::new();
+    }
+    let known: HashSet<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let explicit = config.fixed.iter().map(|g| &g.members);
+    for members in explicit.chain(config.linked.iter().map(|g| &g.members)) {
+        groups.push(
+            members
+                .iter()
+                .filter(|m| known.contains(m.as_str()))
+                .cloned()
+                .collect(),
+        );
+    }
+    groups.push(
+        packages
+            .iter()
+            .map(|p| p.name.clone())
+            .filter(|n| !config.ignore.contains(n))
+            .filter(|n| config.changelog_format(n) == ChangelogFormat::Root)
+            .collect(),
+    );
+    groups
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| Error::ConfigParse(format!("invalid exclude pattern: {}", e).into()))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|e| Error::ConfigParse(format!("invalid exclude pattern: {}", e).into()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{FixedGroup, LinkedGroup};
     use crate::ecosystems::Package;
     use tempfile::TempDir;
 
@@ -170,13 +736,7 @@ mod tests {
     }
 
     fn make_workspace(root: PathBuf, packages: Vec<Package>) -> Workspace {
-        let changelog_dir = root.join(".changelog");
-        Workspace {
-            root,
-            changelog_dir,
-            packages,
-            ecosystem: Ecosystem::Rust,
-        }
+        Workspace::new(root, packages, Ecosystem::Rust)
     }
 
     #[test]
@@ -192,6 +752,82 @@ mod tests {
         assert!(ws.get_package("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_publishable_packages_without_published_check() {
+        let ws = make_workspace(
+            PathBuf::from("/tmp/proj"),
+            vec![make_package("foo"), make_package("bar")],
+        );
+
+        let names: Vec<&str> = ws
+            .get_publishable_packages_with(true)
+            .unwrap()
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, ["foo", "bar"]);
+    }
+
+    #[test]
+    fn test_ahead_on_registry() {
+        let mut ws = make_workspace(
+            PathBuf::from("/tmp/proj"),
+            vec![
+                make_package("ahead"),
+                make_package("current"),
+                make_package("unpublished"),
+            ],
+        );
+        // Python packages are never private, so no manifest has to exist.
+        ws.ecosystem = Ecosystem::Python;
+
+        let packages: Vec<&Package> = ws.packages.iter().collect();
+        let ahead = ws
+            .ahead_on_registry_with(&packages, |pkg| {
+                Ok(match pkg.name.as_str() {
+                    "ahead" => Some(Version::new(1, 1, 0)),
+                    "current" => Some(Version::new(1, 0, 0)),
+                    _ => None,
+                })
+            })
+            .unwrap();
+
+        assert_eq!(ahead.len(), 1);
+        assert_eq!(ahead[0].0.name, "ahead");
+        assert_eq!(ahead[0].1, Version::new(1, 1, 0));
+    }
+
+    #[test]
+    fn test_is_published_looks_up_each_version_once() {
+        let ws = make_workspace(
+            PathBuf::from("/tmp/proj"),
+            vec![make_package("foo"), make_package("bar")],
+        );
+        let lookups = std::cell::Cell::new(0);
+        let fetch = |pkg: &Package| {
+            lookups.set(lookups.get() + 1);
+            Ok(pkg.name == "foo")
+        };
+
+        assert!(ws.is_published_with(&ws.packages[0], fetch).unwrap());
+        assert!(ws.is_published_with(&ws.packages[0], fetch).unwrap());
+        assert!(!ws.is_published_with(&ws.packages[1], fetch).unwrap());
+        assert_eq!(lookups.get(), 2);
+
+        // A new version is a new lookup.
+        let mut bumped = make_package("foo");
+        bumped.version = Version::new(1, 1, 0);
+        ws.is_published_with(&bumped, fetch).unwrap();
+        assert_eq!(lookups.get(), 3);
+
+        // Failures are retried.
+        let failing = make_package("baz");
+        let error = |_: &Package| Err(Error::PypiCheckFailed("timed out".into()));
+        assert!(ws.is_published_with(&failing, error).is_err());
+        assert!(!ws.is_published_with(&failing, fetch).unwrap());
+        assert_eq!(lookups.get(), 4);
+    }
+
     #[test]
     fn test_package_names() {
         let ws = make_workspace(
@@ -207,6 +843,62 @@ mod tests {
         assert_eq!(names, vec!["alpha", "beta", "gamma"]);
     }
 
+    #[test]
+    fn test_filter_packages() {
+        let packages = || {
+            ["platform-api", "platform-db", "web", "cli"]
+                .map(make_package)
+                .to_vec()
+        };
+        let mut config = Config::default();
+        let names = |patterns: &[&str], config: &Config| -> Result<Vec<String>> {
+            let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+            Ok(filter_packages(packages(), &patterns, config)?
+                .0
+                .into_iter()
+                .map(|p| p.name)
+                .collect())
+        };
+
+        assert_eq!(names(&[], &config).unwrap().len(), 4);
+        assert_eq!(
+            names(&["platform-*"], &config).unwrap(),
+            ["platform-api", "platform-db"]
+        );
+        assert_eq!(
+            names(&["platform-db", "cli"], &config).unwrap(),
+            ["platform-db", "cli"]
+        );
+        assert!(matches!(
+            names(&["mobile-*"], &config),
+            Err(Error::NoPackagesSelected)
+        ));
+        assert!(matches!(
+            names(&["platform-["], &config),
+            Err(Error::InvalidFilter(_))
+        ));
+
+        // A filter pulls in the rest of each group it touches, transitively.
+        config.fixed = vec![FixedGroup {
+            members: vec!["platform-api".into(), "web".into()],
+        }];
+        config.linked = vec![LinkedGroup {
+            members: vec!["web".into(), "cli".into()],
+        }];
+        assert_eq!(
+            names(&["platform-api"], &config).unwrap(),
+            ["platform-api", "web", "cli"]
+        );
+        assert_eq!(names(&["platform-db"], &config).unwrap(), ["platform-db"]);
+        let (_, filtered_out) =
+            filter_packages(packages(), &["platform-db".into()], &config).unwrap();
+        assert_eq!(filtered_out, ["platform-api", "web", "cli"]);
+
+        // Strictly explicit releases do not apply the groups.
+        config.dependent_bump = DependentBump::StrictExplicit;
+        assert_eq!(names(&["platform-api"], &config).unwrap(), ["platform-api"]);
+    }
+
     #[test]
     fn test_is_initialized_true() {
         let dir = TempDir::new().unwrap();
@@ -233,6 +925,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_channel_dir() {
+        let mut ws = make_workspace(PathBuf::from("/tmp/myproject"), vec![]);
+        assert_eq!(ws.channel_dir(None), ws.changelog_dir());
+        assert_eq!(
+            ws.channel_dir(Some("beta")),
+            PathBuf::from("/tmp/myproject/.changelog/beta")
+        );
+
+        ws.pending_dir = PathBuf::from("/tmp/myproject/.changesets");
+        assert_eq!(
+            ws.channel_dir(Some("beta")),
+            PathBuf::from("/tmp/myproject/.changesets/beta")
+        );
+        assert_eq!(
+            ws.changelog_dir(),
+            PathBuf::from("/tmp/myproject/.changelog")
+        );
+    }
+
     #[test]
     fn test_find_root_rust_workspace() {
         let dir = TempDir::new().unwrap();
@@ -256,6 +968,86 @@ mod tests {
         assert_eq!(found, root);
     }
 
+    fn write_manifest(dir: &Path, content: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), content).unwrap();
+    }
+
+    fn package_manifest(name: &str) -> String {
+        format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n")
+    }
+
+    #[test]
+    fn test_find_root_rust_glob_members() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write_manifest(
+            root,
+            "[workspace]\nmembers = [\"crates/*\", \"./tools/cli/\"]\nexclude = [\"crates/skipped\"]\n",
+        );
+        // Mentioning `[workspace]` doesn't make a package a workspace root.
+        write_manifest(
+            &root.join("crates/foo"),
+            &format!(
+                "{}description = \"part of a [workspace]\"\n",
+                package_manifest("foo")
+            ),
+        );
+        write_manifest(&root.join("tools/cli"), &package_manifest("cli"));
+        write_manifest(&root.join("crates/skipped"), &package_manifest("skipped"));
+        write_manifest(&root.join("other/bar"), &package_manifest("bar"));
+
+        let find = |path: &str| Workspace::find_root(&root.join(path), Ecosystem::Rust).unwrap();
+        assert_eq!(find("crates/foo"), root);
+        // Subdirectories of a member resolve through the member's manifest.
+        std::fs::create_dir_all(root.join("crates/foo/src")).unwrap();
+        assert_eq!(find("crates/foo/src"), root);
+        assert_eq!(find("tools/cli"), root);
+        assert_eq!(find("crates/skipped"), root.join("crates/skipped"));
+        assert_eq!(find("other/bar"), root.join("other/bar"));
+    }
+
+    #[test]
+    fn test_find_root_rust_nested_workspaces() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        // A virtual outer workspace with a nested workspace that is not one of its members.
+        write_manifest(
+            root,
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/inner\"]\n",
+        );
+        write_manifest(&root.join("crates/outer"), &package_manifest("outer"));
+        write_manifest(
+            &root.join("crates/inner"),
+            "[workspace]\nmembers = [\"sub\"]\n",
+        );
+        write_manifest(&root.join("crates/inner/sub"), &package_manifest("sub"));
+        write_manifest(&root.join("crates/inner/stray"), &package_manifest("stray"));
+
+        let find = |path: &str| Workspace::find_root(&root.join(path), Ecosystem::Rust).unwrap();
+        assert_eq!(find("crates/outer"), root);
+        assert_eq!(find("crates/inner"), root.join("crates/inner"));
+        assert_eq!(find("crates/inner/sub"), root.join("crates/inner"));
+        // Not listed by the inner workspace, and the outer one doesn't match
+        // `crates/*` for a nested path either.
+        assert_eq!(find("crates/inner/stray"), root.join("crates/inner/stray"));
+        assert_eq!(find(""), root);
+    }
+
+    #[test]
+    fn test_find_root_rust_package_workspace_key() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write_manifest(root, "[workspace]\nmembers = []\n");
+        write_manifest(
+            &root.join("deep/nested/pkg"),
+            "[package]\nname = \"pkg\"\nversion = \"0.1.0\"\nworkspace = \"../../..\"\n",
+        );
+
+        let found = Workspace::find_root(&root.join("deep/nested/pkg"), Ecosystem::Rust).unwrap();
+        assert_eq!(found, root);
+    }
+
     #[test]
     fn test_find_root_rust_no_workspace() {
         let dir = TempDir::new().unwrap();
@@ -288,4 +1080,217 @@ mod tests {
         let result = Workspace::find_root(dir.path(), Ecosystem::Rust);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_set_versions() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"core\", \"utils\", \"app\"]\n",
+        )
+        .unwrap();
+
+        let manifests = [
+            ("core", "[package]\nname = \"core\"\nversion = \"2.1.0\"\n"),
+            (
+                "utils",
+                "[package]\nname = \"utils\"\nversion = \"2.4.0\"\n",
+            ),
+            (
+                "app",
+                "[package]\nname = \"app\"\nversion = \"0.3.0\"\n\n[dependencies]\ncore = { path = \"../core\", version = \"2.1.0\" }\nutils = { path = \"../utils\", version = \"2.4.0\" }\n",
+            ),
+        ];
+        let mut packages = Vec::new();
+        for (name, manifest) in manifests {
+            let pkg_dir = root.join(name);
+            std::fs::create_dir_all(&pkg_dir).unwrap();
+            std::fs::write(pkg_dir.join("Cargo.toml"), manifest).unwrap();
+            packages.push(Package {
+                name: name.to_string(),
+                version: Version::new(0, 0, 0),
+                path: pkg_dir.clone(),
+                manifest_path: pkg_dir.join("Cargo.toml"),
+                dependencies: vec![],
+            });
+        }
+        let ws = make_workspace(root.to_path_buf(), packages);
+
+        ws.set_versions(&["core".into(), "utils".into()], &Version::new(3, 0, 0))
+            .unwrap();
+
+        let read =
+            |name: &str| std::fs::read_to_string(root.join(name).join("Cargo.toml")).unwrap();
+        assert!(read("core").contains("version = \"3.0.0\""));
+        assert!(read("utils").contains("version = \"3.0.0\""));
+        let app = read("app");
+        assert!(app.contains("version = \"0.3.0\""));
+        assert!(app.contains("core = { path = \"../core\", version = \"3.0.0\" }"));
+        assert!(app.contains("utils = { path = \"../utils\", version = \"3.0.0\" }"));
+    }
+
+    #[test]
+    fn test_set_versions_unknown_package_writes_nothing() {
+        let dir = TempDir::new().unwrap();
+        let pkg_dir = dir.path().join("core");
+        std::fs::create_dir_all(&pkg_dir).unwrap();
+        let manifest = "[package]\nname = \"core\"\nversion = \"2.1.0\"\n";
+        std::fs::write(pkg_dir.join("Cargo.toml"), manifest).unwrap();
+        let mut core = make_package("core");
+        core.manifest_path = pkg_dir.join("Cargo.toml");
+        let ws = make_workspace(dir.path().to_path_buf(), vec![core]);
+
+        let err = ws
+            .set_versions(&["core".into(), "nope".into()], &Version::new(3, 0, 0))
+            .unwrap_err();
+
+        assert!(matches!(err, Error::PackageNotFound(name) if name == "nope"));
+        assert_eq!(
+            std::fs::read_to_string(pkg_dir.join("Cargo.toml")).unwrap(),
+            manifest
+        );
+    }
+
+    #[test]
+    fn test_root_manifest_path() {
+        let mut ws = make_workspace(PathBuf::from("/repo"), vec![]);
+        assert_eq!(ws.root_manifest_path(), PathBuf::from("/repo/Cargo.toml"));
+
+        ws.ecosystem = Ecosystem::Python;
+        assert_eq!(
+            ws.root_manifest_path(),
+            PathBuf::from("/repo/pyproject.toml")
+        );
+    }
+
+    fn package_at(name: &str, path: &Path) -> Package {
+        Package {
+            name: name.to_string(),
+            version: Version::new(1, 0, 0),
+            path: path.to_path_buf(),
+            manifest_path: path.join("Cargo.toml"),
+            dependencies: vec![],
+        }
+    }
+
+    fn nested_workspace() -> Workspace {
+        let root = PathBuf::from("/repo");
+        make_workspace(
+            root.clone(),
+            vec![
+                package_at("core", &root.join("crates/core")),
+                package_at("core-macros", &root.join("crates/core/macros")),
+                package_at("utils", &root.join("crates/utils")),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_changed_packages_nested() {
+        let ws = nested_workspace();
+        let paths = [
+            "crates/core/src/lib.rs",
+            "crates/core/macros/src/lib.rs",
+            "/repo/crates/utils/src/lib.rs",
+            "README.md",
+        ];
+
+        let changes = ws.changed_packages(&paths, &Config::default()).unwrap();
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(
+            changes["core"].files,
+            vec![PathBuf::from("crates/core/src/lib.rs")]
+        );
+        assert_eq!(
+            changes["core-macros"].files,
+            vec![PathBuf::from("crates/core/macros/src/lib.rs")]
+        );
+        assert_eq!(
+            changes["utils"].files,
+            vec![PathBuf::from("/repo/crates/utils/src/lib.rs")]
+        );
+    }
+
+    #[test]
+    fn test_changed_packages_exclude_paths() {
+        let ws = nested_workspace();
+        let config: Config = toml::from_str(
+            r#"
+[verify]
+exclude_paths = ["**/tests/**", "**/*.md"]
+
+[verify.packages.core-macros]
+exclude_paths = ["src/**"]
+"#,
+        )
+        .unwrap();
+        let paths = [
+            "crates/core/src/lib.rs",
+            "crates/core/CHANGELOG.md",
+            "crates/utils/tests/it.rs",
+            "crates/utils/README.md",
+            "crates/core/macros/src/lib.rs",
+            "crates/core/macros/README.md",
+        ];
+
+        let changes = ws.changed_packages(&paths, &config).unwrap();
+
+        assert!(!changes["core"].all_excluded());
+        assert_eq!(
+            changes["core"].excluded,
+            vec![PathBuf::from("crates/core/CHANGELOG.md")]
+        );
+        assert!(changes["utils"].all_excluded());
+        // The override replaces the global patterns, so README.md counts again.
+        assert_eq!(
+            changes["core-macros"].excluded,
+            vec![PathBuf::from("crates/core/macros/src/lib.rs")]
+        );
+
+        let affected = ws.affected_packages(&paths, &config).unwrap();
+        assert_eq!(affected.len(), 2);
+        assert_eq!(
+            affected["core"],
+            vec![PathBuf::from("crates/core/src/lib.rs")]
+        );
+        assert_eq!(
+            affected["core-macros"],
+            vec![PathBuf::from("crates/core/macros/README.md")]
+        );
+    }
+
+    #[test]
+    fn test_affected_packages_docs_only_diff() {
+        let ws = nested_workspace();
+        let config: Config = toml::from_str(
+            r#"
+[verify]
+exclude_paths = ["**/*.md", "**/docs/**", "**/tests/**"]
+"#,
+        )
+        .unwrap();
+        let paths = [
+            "README.md",
+            "crates/core/README.md",
+            "crates/core/docs/guide.md",
+            "crates/utils/tests/it.rs",
+        ];
+
+        let affected = ws.affected_packages(&paths, &config).unwrap();
+
+        assert!(affected.is_empty(), "{affected:?}");
+        assert_eq!(ws.changed_packages(&paths, &config).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_changed_packages_invalid_pattern() {
+        let ws = nested_workspace();
+        let mut config = Config::default();
+        config.verify.exclude_paths = vec!["[".to_string()];
+
+        let result = ws.changed_packages(&["crates/core/src/lib.rs"], &config);
+        assert!(matches!(result, Err(Error::ConfigParse(_))));
+    }
 }
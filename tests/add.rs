@@ -0,0 +1,279 @@
+mod common;
+
+use common::{cargo_workspace, command, write};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Output, Stdio};
+use tempfile::TempDir;
+
+/// Runs `changelogs` with `stdin` piped in.
+fn changelogs(dir: &Path, args: &[&str], stdin: &str) -> Output {
+    let mut child = command(dir, "rust")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// A two-crate workspace with no pending changelogs.
+fn setup() -> TempDir {
+    let dir = cargo_workspace(&[("core", "0.1.0", &[]), ("cli", "0.1.0", &[])]);
+    write(dir.path(), ".changelog/config.toml", "");
+    dir
+}
+
+/// The contents of the only pending changelog.
+fn pending(dir: &Path) -> Vec<String> {
+    std::fs::read_dir(dir.join(".changelog"))
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
+        .map(|e| std::fs::read_to_string(e.path()).unwrap())
+        .collect()
+}
+
+#[test]
+fn add_reads_summary_from_stdin() {
+    let dir = setup();
+
+    let output = changelogs(
+        dir.path(),
+        &["add", "-p", "core", "-b", "minor", "--summary", "-"],
+        "Added a parser.\n\nIt handles nested tables.\n",
+    );
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let pending = pending(dir.path());
+    assert_eq!(pending.len(), 1);
+    assert_eq!(
+        pending[0],
+        "---\ncore: minor\n---\n\nAdded a parser.\n\nIt handles nested tables.\n"
+    );
+}
+
+#[test]
+fn add_reads_piped_summary_without_flag() {
+    let dir = setup();
+
+    let output = changelogs(
+        dir.path(),
+        &["add", "-p", "core", "-p", "cli"],
+        "Fixed a leak.",
+    );
+
+    assert!(output.status.success());
+    let pending = pending(dir.path());
+    assert!(pending[0].contains("core: patch"), "{}", pending[0]);
+    assert!(pending[0].contains("cli: patch"), "{}", pending[0]);
+    assert!(pending[0].contains("Fixed a leak."), "{}", pending[0]);
+}
+
+#[test]
+fn add_rejects_empty_summary_and_unknown_package() {
+    let dir = setup();
+
+    let output = changelogs(dir.path(), &["add", "-p", "core"], "  \n");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Empty summary"), "{stderr}");
+
+    let output = changelogs(dir.path(), &["add", "-p", "nope", "-s", "Hi"], "");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("package not found: nope"), "{stderr}");
+    assert!(pending(dir.path()).is_empty());
+}
+
+#[test]
+#[cfg(unix)]
+fn add_runs_lint_checks_before_writing() {
+    let dir = setup();
+    write(
+        dir.path(),
+        ".changelog/config.toml",
+        "[lint]\ncommand = \"sh scripts/lint.sh\"\nbuiltin = [\"require-issue-ref\"]\n",
+    );
+    write(
+        dir.path(),
+        "scripts/lint.sh",
+        "grep -q 'core: minor' || { echo 'expected the rendered entry on stdin' >&2; exit 1; }\n\
+         echo 'looks fine'\n",
+    );
+
+    let output = changelogs(
+        dir.path(),
+        &[
+            "add",
+            "-p",
+            "core",
+            "-b",
+            "patch",
+            "--summary",
+            "Fixed a bug (#12)",
+        ],
+        "",
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("changelog rejected by [lint] checks"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("expected the rendered entry on stdin"),
+        "{stderr}"
+    );
+    assert!(
+        !stderr.contains("no issue or pull request reference"),
+        "{stderr}"
+    );
+    assert!(pending(dir.path()).is_empty());
+
+    let output = changelogs(
+        dir.path(),
+        &[
+            "add",
+            "-p",
+            "core",
+            "-b",
+            "minor",
+            "--summary",
+            "Added a parser",
+        ],
+        "",
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("no issue or pull request reference"),
+        "{stderr}"
+    );
+    assert!(pending(dir.path()).is_empty());
+
+    let output = changelogs(
+        dir.path(),
+        &[
+            "add",
+            "-p",
+            "core",
+            "-b",
+            "minor",
+            "--summary",
+            "Added a parser (#12)",
+        ],
+        "",
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(pending(dir.path()).len(), 1);
+}
+
+#[test]
+fn add_from_template_writes_one_changelog_per_package() {
+    let dir = setup();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"core\", \"cli\", \"util\"]\nresolver = \"2\"\n",
+    );
+    write(
+        dir.path(),
+        "util/Cargo.toml",
+        "[package]\nname = \"util\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write(dir.path(), "util/src/lib.rs", "");
+    write(
+        dir.path(),
+        "template.md",
+        "Raised the MSRV of `{package}` to 1.85.\n",
+    );
+
+    let output = changelogs(
+        dir.path(),
+        &[
+            "add",
+            "--from-template",
+            "template.md",
+            "-p",
+            "core",
+            "-p",
+            "cli",
+            "-p",
+            "util",
+            "-b",
+            "minor",
+        ],
+        "",
+    );
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let mut entries = pending(dir.path());
+    entries.sort();
+    assert_eq!(
+        entries,
+        [
+            "---\ncli: minor\n---\n\nRaised the MSRV of `cli` to 1.85.\n",
+            "---\ncore: minor\n---\n\nRaised the MSRV of `core` to 1.85.\n",
+            "---\nutil: minor\n---\n\nRaised the MSRV of `util` to 1.85.\n",
+        ]
+    );
+
+    // An unknown package writes nothing.
+    let output = changelogs(
+        dir.path(),
+        &[
+            "add",
+            "--from-template",
+            "template.md",
+            "-p",
+            "core",
+            "-p",
+            "nope",
+        ],
+        "",
+    );
+    assert!(!output.status.success());
+    assert_eq!(pending(dir.path()).len(), 3);
+
+    // The template is the summary, so it can't be combined with AI generation.
+    let output = changelogs(
+        dir.path(),
+        &[
+            "add",
+            "--from-template",
+            "template.md",
+            "-p",
+            "core",
+            "--ai",
+            "claude -p",
+        ],
+        "",
+    );
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("cannot be used with"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(pending(dir.path()).len(), 3);
+}
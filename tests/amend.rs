@@ -0,0 +1,98 @@
+mod common;
+
+use common::{cargo_workspace, changelogs, write};
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A two-crate workspace with one pending changelog for `core`.
+fn setup() -> TempDir {
+    let dir = cargo_workspace(&[("core", "0.1.0", &[]), ("cli", "0.1.0", &[])]);
+    write(dir.path(), ".changelog/config.toml", "");
+    write(
+        dir.path(),
+        ".changelog/brave-cats-dance.md",
+        "---\ncore: minor\n---\n\nAdded a parser\n",
+    );
+    dir
+}
+
+fn entry(dir: &Path) -> String {
+    std::fs::read_to_string(dir.join(".changelog/brave-cats-dance.md")).unwrap()
+}
+
+#[test]
+fn amend_adds_package_in_place() {
+    let dir = setup();
+
+    let output = changelogs(
+        dir.path(),
+        &[
+            "add",
+            "--amend",
+            "brave-cats-dance",
+            "-p",
+            "cli",
+            "-b",
+            "patch",
+        ],
+    );
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let content = entry(dir.path());
+    assert!(content.contains("core: minor"), "{content}");
+    assert!(content.contains("cli: patch"), "{content}");
+    assert!(content.contains("Added a parser"), "{content}");
+
+    let pending: Vec<_> = std::fs::read_dir(dir.path().join(".changelog"))
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    assert_eq!(pending.len(), 1);
+}
+
+#[test]
+fn amend_changes_existing_bump() {
+    let dir = setup();
+
+    let output = changelogs(
+        dir.path(),
+        &[
+            "add",
+            "--amend",
+            "brave-cats-dance",
+            "-p",
+            "core",
+            "-b",
+            "major",
+        ],
+    );
+
+    assert!(output.status.success());
+    let content = entry(dir.path());
+    assert!(content.contains("core: major"), "{content}");
+    assert!(!content.contains("core: minor"), "{content}");
+}
+
+#[test]
+fn amend_rejects_unknown_entry_and_package() {
+    let dir = setup();
+
+    let output = changelogs(dir.path(), &["add", "--amend", "missing", "-p", "cli"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("changelog not found: missing"), "{stderr}");
+
+    let output = changelogs(
+        dir.path(),
+        &["add", "--amend", "brave-cats-dance", "-p", "nope"],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("package not found: nope"), "{stderr}");
+    assert!(!entry(dir.path()).contains("nope"));
+}
@@ -0,0 +1,263 @@
+mod common;
+
+use changelogs::Ecosystem;
+use changelogs::changelog_entry;
+use changelogs::changelog_writer::OnExistingVersion;
+use changelogs::config::Config;
+use changelogs::ecosystems;
+use changelogs::error::Error;
+use changelogs::github::AuthorResolver;
+use changelogs::plan::{self, ApplyOptions};
+use changelogs::workspace::Workspace;
+use common::{changelogs, core_and_app, write};
+use semver::Version;
+use std::path::Path;
+
+fn load(dir: &Path) -> (Workspace, Config, plan::ReleasePlan) {
+    let config = Config::load(&dir.join(".changelog")).unwrap();
+    let workspace = Workspace::new(
+        dir.to_path_buf(),
+        ecosystems::discover_packages(Ecosystem::Rust, dir, &config).unwrap(),
+        Ecosystem::Rust,
+    );
+    let changelogs = changelog_entry::read_all(&workspace.changelog_dir()).unwrap();
+    let release_plan = plan::assemble(&workspace, changelogs, &config);
+    (workspace, config, release_plan)
+}
+
+#[test]
+fn apply_writes_versions_changelogs_and_deletes_entries() {
+    let dir = core_and_app();
+    let (workspace, config, release_plan) = load(dir.path());
+
+    let applied = plan::apply(
+        &workspace,
+        &release_plan,
+        &config,
+        &AuthorResolver::new(None),
+        ApplyOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        applied.versions.into_iter().collect::<Vec<_>>(),
+        [
+            ("app".to_string(), Version::new(0, 3, 1)),
+            ("core".to_string(), Version::new(1, 1, 0)),
+        ]
+    );
+    assert!(applied.replaced.is_empty());
+    assert_eq!(
+        applied.deleted,
+        [workspace.changelog_dir().join("brave-fox.md")]
+    );
+    assert!(!dir.path().join(".changelog/brave-fox.md").exists());
+
+    let core = std::fs::read_to_string(dir.path().join("core/Cargo.toml")).unwrap();
+    assert!(core.contains("version = \"1.1.0\""), "{core}");
+    let app = std::fs::read_to_string(dir.path().join("app/Cargo.toml")).unwrap();
+    assert!(app.contains("version = \"0.3.1\""), "{app}");
+    assert!(app.contains("version = \"1.1.0\" }"), "{app}");
+
+    for path in &applied.changelogs {
+        let changelog = std::fs::read_to_string(path).unwrap();
+        assert!(changelog.contains("## "), "{}: {changelog}", path.display());
+    }
+    let core_changelog = std::fs::read_to_string(dir.path().join("core/CHANGELOG.md")).unwrap();
+    assert!(core_changelog.contains("Added a thing"));
+}
+
+#[test]
+fn apply_fails_on_existing_section_before_touching_files() {
+    let dir = core_and_app();
+    write(
+        dir.path(),
+        "core/CHANGELOG.md",
+        "# core\n\n## 1.1.0 (2025-01-01)\n\n- Already released\n",
+    );
+    let (workspace, config, release_plan) = load(dir.path());
+
+    let err = plan::apply(
+        &workspace,
+        &release_plan,
+        &config,
+        &AuthorResolver::new(None),
+        ApplyOptions {
+            on_existing: OnExistingVersion::Fail,
+            channel: None,
+            keep: &[],
+        },
+    )
+    .unwrap_err();
+
+    assert!(matches!(err, Error::ChangelogVersionExists(_, _)), "{err}");
+    let core = std::fs::read_to_string(dir.path().join("core/Cargo.toml")).unwrap();
+    assert!(core.contains("version = \"1.0.0\""));
+    assert!(dir.path().join(".changelog/brave-fox.md").exists());
+}
+
+#[test]
+fn exported_plan_can_be_edited_and_applied() {
+    let dir = core_and_app();
+    let (workspace, config, release_plan) = load(dir.path());
+
+    let json = serde_json::to_string(&release_plan).unwrap();
+    let mut exported: plan::ReleasePlan = serde_json::from_str(&json).unwrap();
+    assert_eq!(exported.releases.len(), 2);
+    assert_eq!(
+        exported.releases[1].reasons,
+        release_plan.releases[1].reasons
+    );
+
+    // Hold `app` back and make `core` a major release.
+    exported.releases.retain(|r| r.name != "app");
+    exported.releases[0].new_version = Version::new(2, 0, 0);
+
+    let pending = changelog_entry::read_all(&workspace.changelog_dir()).unwrap();
+    let (imported, partial) = plan::import(&workspace, exported, pending, &config).unwrap();
+    assert_eq!(imported.changelogs.len(), 1);
+    assert!(partial.is_empty());
+    let applied = plan::apply(
+        &workspace,
+        &imported,
+        &config,
+        &AuthorResolver::new(None),
+        ApplyOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        applied.versions.into_iter().collect::<Vec<_>>(),
+        [("core".to_string(), Version::new(2, 0, 0))]
+    );
+    let app = std::fs::read_to_string(dir.path().join("app/Cargo.toml")).unwrap();
+    assert!(app.contains("version = \"0.3.0\""), "{app}");
+    let core_changelog = std::fs::read_to_string(dir.path().join("core/CHANGELOG.md")).unwrap();
+    assert!(core_changelog.contains("core@2.0.0"), "{core_changelog}");
+    assert!(!dir.path().join(".changelog/brave-fox.md").exists());
+}
+
+#[test]
+fn import_keeps_entries_of_dropped_packages_pending() {
+    let dir = core_and_app();
+    write(
+        dir.path(),
+        ".changelog/shared.md",
+        "---\ncore: patch\napp: minor\n---\n\nReworked the config\n",
+    );
+    let (workspace, config, release_plan) = load(dir.path());
+
+    let mut exported = release_plan.clone();
+    exported.releases.retain(|r| r.name != "app");
+    let pending = changelog_entry::read_all(&workspace.changelog_dir()).unwrap();
+    let (imported, partial) = plan::import(&workspace, exported, pending, &config).unwrap();
+    assert_eq!(partial, ["shared"]);
+
+    plan::apply(
+        &workspace,
+        &imported,
+        &config,
+        &AuthorResolver::new(None),
+        ApplyOptions {
+            keep: &partial,
+            ..ApplyOptions::default()
+        },
+    )
+    .unwrap();
+
+    let core_changelog = std::fs::read_to_string(dir.path().join("core/CHANGELOG.md")).unwrap();
+    assert!(
+        core_changelog.contains("Reworked the config"),
+        "{core_changelog}"
+    );
+    assert!(!dir.path().join(".changelog/brave-fox.md").exists());
+    // Still pending for `app`.
+    assert!(dir.path().join(".changelog/shared.md").exists());
+}
+
+#[test]
+fn import_reports_every_problem() {
+    let dir = core_and_app();
+    let (workspace, config, release_plan) = load(dir.path());
+
+    let mut exported = release_plan.clone();
+    let app = exported
+        .releases
+        .iter_mut()
+        .find(|r| r.name == "app")
+        .unwrap();
+    app.new_version = Version::new(0, 2, 0);
+    let core = exported
+        .releases
+        .iter_mut()
+        .find(|r| r.name == "core")
+        .unwrap();
+    core.old_version = Version::new(0, 9, 0);
+    core.changelog_ids.push("missing".to_string());
+    let mut gone = core.clone();
+    gone.name = "gone".to_string();
+    gone.changelog_ids.clear();
+    exported.releases.push(gone);
+
+    let pending = changelog_entry::read_all(&workspace.changelog_dir()).unwrap();
+    let err = plan::import(&workspace, exported, pending, &config).unwrap_err();
+
+    let Error::InvalidPlan(problems) = &err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(
+        problems.lines().map(str::trim).collect::<Vec<_>>(),
+        [
+            "app: new_version 0.2.0 is not greater than 0.3.0",
+            "core: old_version 0.9.0 does not match the manifest (1.0.0)",
+            "core: changelog 'missing' is not pending",
+            "unknown package 'gone'",
+        ]
+    );
+    let core = std::fs::read_to_string(dir.path().join("core/Cargo.toml")).unwrap();
+    assert!(core.contains("version = \"1.0.0\""));
+}
+
+#[test]
+fn status_json_applies_as_a_plan() {
+    let dir = core_and_app();
+
+    let status = changelogs(dir.path(), &["--json", "status"]);
+    assert!(status.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&status.stdout).unwrap();
+    let core = json["releases"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["name"] == "core")
+        .unwrap();
+    assert_eq!(core["changelog_ids"], serde_json::json!(["brave-fox"]));
+
+    // A plan without changelog ids would bump without consuming the entries.
+    let mut stripped = json.clone();
+    for release in stripped["releases"].as_array_mut().unwrap() {
+        release.as_object_mut().unwrap().remove("changelog_ids");
+    }
+    write(dir.path(), "stripped.json", &stripped.to_string());
+    let output = changelogs(
+        dir.path(),
+        &["version", "--apply-plan", "stripped.json", "--allow-dirty"],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("changelog_ids"), "{stderr}");
+
+    write(dir.path(), "plan.json", &json.to_string());
+    let output = changelogs(
+        dir.path(),
+        &["version", "--apply-plan", "plan.json", "--allow-dirty"],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let core = std::fs::read_to_string(dir.path().join("core/Cargo.toml")).unwrap();
+    assert!(core.contains("version = \"1.1.0\""), "{core}");
+    assert!(!dir.path().join(".changelog/brave-fox.md").exists());
+}
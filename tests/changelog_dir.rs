@@ -0,0 +1,132 @@
+mod common;
+
+use common::{PYPROJECT, changelogs_python, git, stdout};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn entries(dir: &Path) -> Vec<String> {
+    let Ok(read) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = read
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".md") && name != "README.md")
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn changelog_dir_flag_moves_config_and_entries() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("pyproject.toml"), PYPROJECT).unwrap();
+    std::fs::create_dir(dir.path().join(".changesets")).unwrap();
+    std::fs::write(
+        dir.path().join(".changesets/config.toml"),
+        "[changelog]\nsection_emoji = true\n",
+    )
+    .unwrap();
+    let flag = ["--changelog-dir", ".changesets"];
+
+    stdout(&changelogs_python(
+        dir.path(),
+        &[
+            &flag[..],
+            &["add", "-p", "my-package", "-b", "minor", "-s", "Added x"],
+        ]
+        .concat(),
+    ));
+    assert_eq!(entries(&dir.path().join(".changesets")).len(), 1);
+    assert!(!dir.path().join(".changelog").exists());
+
+    let status = stdout(&changelogs_python(
+        dir.path(),
+        &[&flag[..], &["status"]].concat(),
+    ));
+    assert!(status.contains("1 changelog(s) found"), "{status}");
+
+    // Without the flag there is no .changelog/ to find.
+    assert!(!changelogs_python(dir.path(), &["status"]).status.success());
+
+    stdout(&changelogs_python(
+        dir.path(),
+        &[&flag[..], &["version"]].concat(),
+    ));
+    assert!(entries(&dir.path().join(".changesets")).is_empty());
+    let pyproject = std::fs::read_to_string(dir.path().join("pyproject.toml")).unwrap();
+    assert!(pyproject.contains("version = \"1.1.0\""), "{pyproject}");
+    // The config in .changesets/ was used.
+    let changelog = std::fs::read_to_string(dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.contains("✨"), "{changelog}");
+}
+
+#[test]
+fn config_dir_moves_pending_entries() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("pyproject.toml"), PYPROJECT).unwrap();
+    std::fs::create_dir(dir.path().join(".changelog")).unwrap();
+    std::fs::write(
+        dir.path().join(".changelog/config.toml"),
+        "[changelog]\ndir = \"changes\"\n",
+    )
+    .unwrap();
+
+    stdout(&changelogs_python(
+        dir.path(),
+        &["add", "-p", "my-package", "-b", "patch", "-s", "Fixed y"],
+    ));
+    assert!(entries(&dir.path().join(".changelog")).is_empty());
+    assert_eq!(entries(&dir.path().join("changes")).len(), 1);
+
+    let status = stdout(&changelogs_python(dir.path(), &["status"]));
+    assert!(status.contains("1 changelog(s) found"), "{status}");
+
+    let why = stdout(&changelogs_python(
+        dir.path(),
+        &["--json", "why", "my-package"],
+    ));
+    let entry = &entries(&dir.path().join("changes"))[0];
+    assert!(why.contains(&format!("\"changes/{entry}\"")), "{why}");
+
+    stdout(&changelogs_python(dir.path(), &["version"]));
+    assert!(entries(&dir.path().join("changes")).is_empty());
+    let pyproject = std::fs::read_to_string(dir.path().join("pyproject.toml")).unwrap();
+    assert!(pyproject.contains("version = \"1.0.1\""), "{pyproject}");
+}
+
+#[test]
+fn config_dir_entries_link_their_pull_request() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("pyproject.toml"), PYPROJECT).unwrap();
+    std::fs::create_dir(dir.path().join(".changelog")).unwrap();
+    std::fs::write(
+        dir.path().join(".changelog/config.toml"),
+        "[changelog]\ndir = \".changes\"\n",
+    )
+    .unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/acme/my-package",
+        ],
+    );
+    stdout(&changelogs_python(
+        dir.path(),
+        &["add", "-p", "my-package", "-b", "patch", "-s", "Fixed y"],
+    ));
+    git(dir.path(), &["add", "-A"]);
+    git(dir.path(), &["commit", "-q", "-m", "Fix y (#12)"]);
+
+    stdout(&changelogs_python(dir.path(), &["version"]));
+    let changelog = std::fs::read_to_string(dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(
+        changelog.contains("[#12](https://github.com/acme/my-package/pull/12)"),
+        "{changelog}"
+    );
+    assert!(changelog.contains("Alice"), "{changelog}");
+}
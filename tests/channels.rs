@@ -0,0 +1,162 @@
+mod common;
+
+use common::{PYPROJECT, changelogs_python, git, stdout};
+use std::path::Path;
+use tempfile::TempDir;
+
+const CONFIG: &str = r#"channels = ["beta"]
+
+[channel.beta]
+format = "root"
+"#;
+
+fn setup() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("pyproject.toml"), PYPROJECT).unwrap();
+    std::fs::create_dir_all(dir.path().join(".changelog/beta")).unwrap();
+    std::fs::write(dir.path().join(".changelog/config.toml"), CONFIG).unwrap();
+    std::fs::write(
+        dir.path().join(".changelog/stable-fix.md"),
+        "---\nmy-package: patch\n---\n\nFixed a stable bug\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join(".changelog/beta/beta-feature.md"),
+        "---\nmy-package: minor\n---\n\nAdded a beta feature\n",
+    )
+    .unwrap();
+    dir
+}
+
+fn pending(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = walk(dir)
+        .into_iter()
+        .filter(|p| p.extension().is_some_and(|e| e == "md"))
+        .map(|p| {
+            p.strip_prefix(dir)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+fn walk(dir: &Path) -> Vec<std::path::PathBuf> {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .flat_map(|e| {
+            let path = e.unwrap().path();
+            if path.is_dir() {
+                walk(&path)
+            } else {
+                vec![path]
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn add_writes_into_channel_directory() {
+    let dir = setup();
+
+    stdout(&changelogs_python(
+        dir.path(),
+        &["add", "--empty", "--channel", "beta"],
+    ));
+
+    let changelog_dir = dir.path().join(".changelog");
+    let beta = pending(&changelog_dir.join("beta"));
+    assert_eq!(beta.len(), 2);
+    assert_eq!(pending(&changelog_dir).len(), 3);
+}
+
+#[test]
+fn add_rejects_undeclared_channel() {
+    let dir = setup();
+
+    let output = changelogs_python(dir.path(), &["add", "--empty", "--channel", "nightly"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown release channel: nightly"));
+    assert!(!dir.path().join(".changelog/nightly").exists());
+}
+
+#[test]
+fn status_shows_each_channel() {
+    let dir = setup();
+
+    let all = stdout(&changelogs_python(dir.path(), &["status"]));
+    assert!(all.contains("Channel: default"));
+    assert!(all.contains("Channel: beta"));
+    assert!(all.contains("1.0.1"));
+    assert!(all.contains("1.1.0"));
+
+    let beta = stdout(&changelogs_python(
+        dir.path(),
+        &["status", "--channel", "beta"],
+    ));
+    assert!(!beta.contains("Channel:"));
+    assert!(beta.contains("1.1.0"));
+    assert!(!beta.contains("1.0.1"));
+}
+
+#[test]
+fn version_consumes_only_its_channel() {
+    let dir = setup();
+
+    stdout(&changelogs_python(
+        dir.path(),
+        &["version", "--channel", "beta"],
+    ));
+
+    // The beta entry is consumed with the beta overrides (root changelog format).
+    assert_eq!(
+        pending(&dir.path().join(".changelog")),
+        vec!["stable-fix.md"]
+    );
+    let pyproject = std::fs::read_to_string(dir.path().join("pyproject.toml")).unwrap();
+    assert!(pyproject.contains("version = \"1.1.0\""));
+    let changelog = std::fs::read_to_string(dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.contains("Added a beta feature"));
+    assert!(!changelog.contains("Fixed a stable bug"));
+
+    stdout(&changelogs_python(dir.path(), &["version"]));
+
+    assert!(pending(&dir.path().join(".changelog")).is_empty());
+    let changelog = std::fs::read_to_string(dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.contains("Fixed a stable bug"));
+}
+
+#[test]
+fn version_links_channel_entries_to_their_pull_request() {
+    let dir = setup();
+    git(dir.path(), &["init", "-q"]);
+    git(
+        dir.path(),
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/acme/my-package",
+        ],
+    );
+    git(dir.path(), &["add", "-A"]);
+    git(
+        dir.path(),
+        &["commit", "-q", "-m", "Add a beta feature (#34)"],
+    );
+
+    stdout(&changelogs_python(
+        dir.path(),
+        &["version", "--channel", "beta"],
+    ));
+
+    let changelog = std::fs::read_to_string(dir.path().join("CHANGELOG.md")).unwrap();
+    assert!(
+        changelog.contains("[#34](https://github.com/acme/my-package/pull/34)"),
+        "{changelog}"
+    );
+    assert!(changelog.contains("Alice"), "{changelog}");
+}
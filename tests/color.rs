@@ -0,0 +1,79 @@
+mod common;
+
+use common::{command, single_crate, write};
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+
+/// A one-crate workspace with a pending changelog.
+fn setup() -> TempDir {
+    let dir = single_crate();
+    write(dir.path(), ".changelog/config.toml", "");
+    write(
+        dir.path(),
+        ".changelog/brave-fox.md",
+        "---\ncore: minor\n---\n\nAdded a thing\n",
+    );
+    dir
+}
+
+/// Runs `changelogs` with `CLICOLOR_FORCE` set, so colors are on unless disabled.
+fn changelogs(dir: &Path, args: &[&str], env: &[(&str, &str)]) -> Output {
+    command(dir, "rust")
+        .args(args)
+        .env_remove("NO_COLOR")
+        .env("CLICOLOR_FORCE", "1")
+        .envs(env.iter().copied())
+        .output()
+        .unwrap()
+}
+
+fn has_escapes(output: &Output) -> bool {
+    output.stdout.contains(&0x1b) || output.stderr.contains(&0x1b)
+}
+
+#[test]
+fn color_never_and_no_color_strip_escape_sequences() {
+    let dir = setup();
+
+    for command in [
+        &["status"][..],
+        &["doctor"],
+        &["lint"],
+        &["version", "--dry-run"],
+    ] {
+        let colored = changelogs(dir.path(), command, &[]);
+        assert!(has_escapes(&colored), "{command:?} isn't colored at all");
+
+        for (args, env) in [
+            (&["--color", "never"][..], &[][..]),
+            (&["--no-color"], &[]),
+            (&[], &[("NO_COLOR", "1")]),
+        ] {
+            let args: Vec<&str> = args.iter().chain(command).copied().collect();
+            let output = changelogs(dir.path(), &args, env);
+            assert!(
+                !has_escapes(&output),
+                "{args:?} {env:?}:\n{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+    }
+}
+
+#[test]
+fn errors_are_not_colored_with_color_never() {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[package]\nname = \"core\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write(dir.path(), "src/lib.rs", "");
+
+    let output = changelogs(dir.path(), &["--color", "never", "status"], &[]);
+    assert!(!output.status.success());
+    assert!(!has_escapes(&output));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not initialized"));
+}
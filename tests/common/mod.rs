@@ -0,0 +1,139 @@
+//! Scaffolding shared by the integration tests: running the binary, writing
+//! projects into temporary directories and driving git.
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+/// A single-package pyproject.toml: `my-package` 1.0.0.
+pub const PYPROJECT: &str = r#"[project]
+name = "my-package"
+version = "1.0.0"
+"#;
+
+/// The `changelogs` binary, to run in `dir` with `--ecosystem <ecosystem>`.
+pub fn command(dir: &Path, ecosystem: &str) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_changelogs"));
+    cmd.args(["--ecosystem", ecosystem]).current_dir(dir);
+    cmd
+}
+
+/// Runs `changelogs` on the Rust project in `dir`.
+pub fn changelogs(dir: &Path, args: &[&str]) -> Output {
+    command(dir, "rust").args(args).output().unwrap()
+}
+
+/// Runs `changelogs` on the Python project in `dir`.
+pub fn changelogs_python(dir: &Path, args: &[&str]) -> Output {
+    command(dir, "python").args(args).output().unwrap()
+}
+
+/// The stdout of a command that must have succeeded.
+pub fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "command failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// Writes `content` to `path` under `dir`, creating parent directories.
+pub fn write(dir: &Path, path: &str, content: &str) {
+    let path = dir.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// Runs git in `dir` as Alice.
+pub fn git(dir: &Path, args: &[&str]) {
+    git_at(dir, None, args);
+}
+
+/// Runs git in `dir` as Alice, with author and committer dates set to `date`.
+pub fn git_at(dir: &Path, date: Option<&str>, args: &[&str]) {
+    let mut cmd = Command::new("git");
+    cmd.args([
+        "-c",
+        "user.name=Alice",
+        "-c",
+        "user.email=alice@example.com",
+    ])
+    .args(args)
+    .current_dir(dir);
+    if let Some(date) = date {
+        cmd.env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date);
+    }
+    let output = cmd.output().unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// A virtual Cargo workspace, not yet initialized. Members are `(name, version,
+/// dependencies)`, where each dependency is a path dependency on another member at
+/// its version.
+pub fn cargo_workspace(members: &[(&str, &str, &[&str])]) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    let names: Vec<String> = members
+        .iter()
+        .map(|(name, ..)| format!("\"{name}\""))
+        .collect();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        &format!(
+            "[workspace]\nmembers = [{}]\nresolver = \"2\"\n",
+            names.join(", ")
+        ),
+    );
+    for (name, version, dependencies) in members {
+        let mut manifest =
+            format!("[package]\nname = \"{name}\"\nversion = \"{version}\"\nedition = \"2021\"\n");
+        if !dependencies.is_empty() {
+            manifest.push_str("\n[dependencies]\n");
+        }
+        for dependency in *dependencies {
+            let (_, dependency_version, _) = members
+                .iter()
+                .find(|(member, ..)| member == dependency)
+                .unwrap();
+            manifest.push_str(&format!(
+                "{dependency} = {{ path = \"../{dependency}\", version = \"{dependency_version}\" }}\n"
+            ));
+        }
+        write(dir.path(), &format!("{name}/Cargo.toml"), &manifest);
+        write(dir.path(), &format!("{name}/src/lib.rs"), "");
+    }
+    dir
+}
+
+/// A single crate, `core` 0.1.0, not yet initialized.
+pub fn single_crate() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[package]\nname = \"core\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write(dir.path(), "src/lib.rs", "");
+    dir
+}
+
+/// A cargo workspace where `app` depends on `core`, with a pending minor change to
+/// `core`.
+pub fn core_and_app() -> TempDir {
+    let dir = cargo_workspace(&[("core", "1.0.0", &[]), ("app", "0.3.0", &["core"])]);
+    write(dir.path(), ".changelog/config.toml", "");
+    write(
+        dir.path(),
+        ".changelog/brave-fox.md",
+        "---\ncore: minor\n---\n\nAdded a thing\n",
+    );
+    dir
+}
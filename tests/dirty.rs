@@ -0,0 +1,119 @@
+mod common;
+
+use common::{PYPROJECT, changelogs_python, git};
+use tempfile::TempDir;
+
+/// A committed python project with one pending patch changelog.
+fn setup() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("pyproject.toml"), PYPROJECT).unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "notes\n").unwrap();
+    std::fs::create_dir_all(dir.path().join(".changelog")).unwrap();
+    std::fs::write(dir.path().join(".changelog/config.toml"), "").unwrap();
+    std::fs::write(
+        dir.path().join(".changelog/fix.md"),
+        "---\nmy-package: patch\n---\n\nFixed a bug\n",
+    )
+    .unwrap();
+
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "init"]);
+    dir
+}
+
+#[test]
+fn version_refuses_dirty_manifest() {
+    let dir = setup();
+    std::fs::write(
+        dir.path().join("pyproject.toml"),
+        format!("{PYPROJECT}description = \"wip\"\n"),
+    )
+    .unwrap();
+
+    let output = changelogs_python(dir.path(), &["version"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("uncommitted changes"), "{stderr}");
+    assert!(stderr.contains("pyproject.toml"), "{stderr}");
+    assert!(dir.path().join(".changelog/fix.md").exists());
+
+    let output = changelogs_python(dir.path(), &["version", "--allow-dirty"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let pyproject = std::fs::read_to_string(dir.path().join("pyproject.toml")).unwrap();
+    assert!(pyproject.contains("version = \"1.0.1\""));
+    assert!(pyproject.contains("description = \"wip\""));
+}
+
+#[test]
+fn version_ignores_untracked_and_unrelated_changes() {
+    let dir = setup();
+    std::fs::write(dir.path().join("notes.txt"), "edited\n").unwrap();
+    std::fs::write(dir.path().join("CHANGELOG.md"), "untracked\n").unwrap();
+
+    let output = changelogs_python(dir.path(), &["version"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!dir.path().join(".changelog/fix.md").exists());
+}
+
+#[test]
+fn version_refuses_read_only_changelog_dir_before_any_change() {
+    let dir = setup();
+    std::fs::write(dir.path().join("CHANGELOG.md"), "# Changelog\n").unwrap();
+    let mut permissions = std::fs::metadata(dir.path()).unwrap().permissions();
+    permissions.set_readonly(true);
+    std::fs::set_permissions(dir.path(), permissions.clone()).unwrap();
+
+    let output = changelogs_python(dir.path(), &["version", "--allow-dirty"]);
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    std::fs::set_permissions(dir.path(), permissions).unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not writable"), "{stderr}");
+    assert!(stderr.contains("  CHANGELOG.md"), "{stderr}");
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("pyproject.toml")).unwrap(),
+        PYPROJECT
+    );
+    assert!(dir.path().join(".changelog/fix.md").exists());
+}
+
+#[test]
+fn version_checks_sphinx_conf_it_would_rewrite() {
+    let dir = setup();
+    std::fs::create_dir(dir.path().join("docs")).unwrap();
+    let conf = "project = \"demo\"\nversion = \"1.0\"\nrelease = \"1.0.0\"\n";
+    std::fs::write(dir.path().join("docs/conf.py"), conf).unwrap();
+    git(dir.path(), &["add", "."]);
+    git(dir.path(), &["commit", "-q", "-m", "docs"]);
+
+    let output = changelogs_python(dir.path(), &["version", "--dry-run"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("docs/conf.py → 1.0\n"), "{stdout}");
+    assert!(stdout.contains("docs/conf.py → 1.0.1\n"), "{stdout}");
+
+    std::fs::write(
+        dir.path().join("docs/conf.py"),
+        format!("{conf}html_theme = \"alabaster\"\n"),
+    )
+    .unwrap();
+    let output = changelogs_python(dir.path(), &["version"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("docs/conf.py"), "{stderr}");
+    assert!(dir.path().join(".changelog/fix.md").exists());
+}
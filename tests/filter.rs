@@ -0,0 +1,140 @@
+mod common;
+
+use common::{cargo_workspace, changelogs, stdout, write};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn version_of(dir: &Path, name: &str) -> String {
+    let manifest = std::fs::read_to_string(dir.join(name).join("Cargo.toml")).unwrap();
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("version = "))
+        .unwrap()
+        .trim_matches('"')
+        .to_string()
+}
+
+/// Two platform crates and a web crate, with one entry touching a platform crate
+/// and the web crate, and one touching only the web crate.
+fn setup() -> TempDir {
+    let dir = cargo_workspace(&[
+        ("platform-api", "0.1.0", &[]),
+        ("platform-db", "0.1.0", &[]),
+        ("web", "0.1.0", &[]),
+    ]);
+    write(dir.path(), ".changelog/config.toml", "");
+    write(
+        dir.path(),
+        ".changelog/shared-auth.md",
+        "---\nplatform-api: minor\nweb: minor\n---\n\nAdded token refresh.\n",
+    );
+    write(
+        dir.path(),
+        ".changelog/web-theme.md",
+        "---\nweb: patch\n---\n\nFixed the dark theme.\n",
+    );
+    dir
+}
+
+#[test]
+fn status_filter_ignores_other_packages() {
+    let dir = setup();
+
+    let status = stdout(&changelogs(
+        dir.path(),
+        &["status", "--filter", "platform-*"],
+    ));
+    assert!(status.contains("1 changelog(s) found"), "{status}");
+    assert!(status.contains("platform-api"), "{status}");
+    assert!(!status.contains("web"), "{status}");
+    assert!(
+        status.contains("shared-auth also releases packages outside --filter"),
+        "{status}"
+    );
+
+    // Filters union, and a filter that matches nothing is an error.
+    let status = stdout(&changelogs(
+        dir.path(),
+        &["status", "--filter", "platform-api", "--filter", "web"],
+    ));
+    assert!(status.contains("2 changelog(s) found"), "{status}");
+    assert!(
+        !changelogs(dir.path(), &["status", "--filter", "mobile-*"])
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn version_filter_releases_only_matching_packages() {
+    let dir = setup();
+
+    let output = stdout(&changelogs(
+        dir.path(),
+        &["version", "--filter", "platform-*", "--allow-dirty"],
+    ));
+    assert!(output.contains("stays pending"), "{output}");
+
+    assert_eq!(version_of(dir.path(), "platform-api"), "0.2.0");
+    assert_eq!(version_of(dir.path(), "platform-db"), "0.1.0");
+    assert_eq!(version_of(dir.path(), "web"), "0.1.0");
+    let changelog = std::fs::read_to_string(dir.path().join("platform-api/CHANGELOG.md")).unwrap();
+    assert!(changelog.contains("Added token refresh."), "{changelog}");
+    assert!(!dir.path().join("web/CHANGELOG.md").exists());
+
+    // The shared entry stays for `web`, and the web-only entry was never touched.
+    assert!(dir.path().join(".changelog/shared-auth.md").exists());
+    assert!(dir.path().join(".changelog/web-theme.md").exists());
+}
+
+#[test]
+fn version_filter_keeps_fixed_groups_together() {
+    let dir = setup();
+    write(
+        dir.path(),
+        ".changelog/config.toml",
+        "strict = true\n\n[[fixed]]\nmembers = [\"platform-db\", \"web\"]\n",
+    );
+    write(
+        dir.path(),
+        ".changelog/db-index.md",
+        "---\nplatform-db: minor\n---\n\nAdded an index.\n",
+    );
+    std::fs::remove_file(dir.path().join(".changelog/web-theme.md")).unwrap();
+
+    let output = changelogs(
+        dir.path(),
+        &["version", "--filter", "platform-db", "--allow-dirty"],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--filter includes 'web'"), "{stderr}");
+    stdout(&output);
+
+    assert_eq!(version_of(dir.path(), "platform-db"), "0.2.0");
+    assert_eq!(version_of(dir.path(), "web"), "0.2.0");
+    assert_eq!(version_of(dir.path(), "platform-api"), "0.1.0");
+    assert!(!dir.path().join(".changelog/db-index.md").exists());
+    // Released for `web` too, but `platform-api` is still to come.
+    assert!(dir.path().join(".changelog/shared-auth.md").exists());
+}
+
+#[test]
+fn version_strict_reports_unknown_group_members_once() {
+    let dir = setup();
+    write(
+        dir.path(),
+        ".changelog/config.toml",
+        "strict = true\n\n[[fixed]]\nmembers = [\"web\", \"mobile\"]\n",
+    );
+
+    let output = changelogs(dir.path(), &["version", "--allow-dirty"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stderr
+            .matches("fixed group contains unknown package 'mobile'")
+            .count(),
+        1,
+        "{stderr}"
+    );
+}
@@ -1,7 +1,9 @@
 use changelogs::changelog_entry;
-use changelogs::changelog_writer;
+use changelogs::changelog_writer::{self, OnExistingVersion, WriteOptions};
 use changelogs::config::Config;
-use changelogs::ecosystems::{Ecosystem, Package};
+use changelogs::ecosystems::{Dependency, Ecosystem, Package};
+use changelogs::git::CliGit;
+use changelogs::github::AuthorResolver;
 use changelogs::plan;
 use changelogs::workspace::Workspace;
 use semver::Version;
@@ -27,6 +29,8 @@ struct PackageDef {
     version: String,
     #[serde(default)]
     deps: Vec<String>,
+    /// Directory relative to the workspace root; `"."` is the root package.
+    path: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -47,33 +51,12 @@ fn run_golden_test(fixture_name: &str) {
         panic!("fixture {fixture_name} missing packages.toml");
     }
 
-    let manifest: PackagesManifest =
-        toml::from_str(&std::fs::read_to_string(&packages_toml).unwrap()).unwrap();
-
     let tmp = TempDir::new().unwrap();
-
-    let packages: Vec<Package> = manifest
-        .packages
-        .iter()
-        .map(|p| {
-            let pkg_dir = tmp.path().join(&p.name);
-            std::fs::create_dir_all(&pkg_dir).unwrap();
-            Package {
-                name: p.name.clone(),
-                version: Version::parse(&p.version).unwrap(),
-                path: pkg_dir.clone(),
-                manifest_path: pkg_dir.join("Cargo.toml"),
-                dependencies: p.deps.clone(),
-            }
-        })
-        .collect();
-
-    let workspace = Workspace {
-        root: tmp.path().to_path_buf(),
-        changelog_dir: changelog_dir.clone(),
-        packages,
-        ecosystem: Ecosystem::Rust,
-    };
+    let workspace = fixture_workspace(&packages_toml, tmp.path(), &changelog_dir);
+    let existing = fixture.join("existing");
+    if existing.exists() {
+        copy_dir(&existing, tmp.path());
+    }
 
     let changelogs = changelog_entry::read_all(&changelog_dir).unwrap();
 
@@ -97,6 +80,18 @@ fn run_golden_test(fixture_name: &str) {
         }
     }
 
+    // Check why.txt
+    let why_golden = expected_dir.join("why.txt");
+    if why_golden.exists() || should_bless() {
+        let actual = format_reasons(&release_plan.releases);
+        if should_bless() && !release_plan.releases.is_empty() {
+            std::fs::write(&why_golden, &actual).unwrap();
+        } else if why_golden.exists() {
+            let expected = std::fs::read_to_string(&why_golden).unwrap();
+            assert_eq!(actual, expected, "[{fixture_name}] why.txt mismatch");
+        }
+    }
+
     // Check CHANGELOG golden files
     let has_changelog_golden = std::fs::read_dir(&expected_dir)
         .unwrap()
@@ -117,8 +112,15 @@ fn run_golden_test(fixture_name: &str) {
         &workspace,
         &release_plan.releases,
         &changelogs,
-        config.changelog.format,
-        TEST_DATE,
+        &config,
+        WriteOptions {
+            on_existing: OnExistingVersion::Replace,
+            channel: None,
+            // The temp workspace is not a repository, so no commit links are generated.
+            git: &CliGit::in_dir(&workspace.root),
+            authors: &AuthorResolver::new(None),
+            date: TEST_DATE,
+        },
     )
     .unwrap();
 
@@ -146,6 +148,59 @@ fn run_golden_test(fixture_name: &str) {
 
         assert_eq!(actual, expected, "[{fixture_name}] {filename} mismatch");
     }
+
+    // Per-crate changelogs written without a golden file are unexpected output.
+    if config.changelog.format == changelogs::config::ChangelogFormat::PerCrate
+        && workspace.packages.len() > 1
+    {
+        for pkg in &workspace.packages {
+            let golden = expected_dir.join(format!("{}-CHANGELOG.md", pkg.name));
+            assert!(
+                golden.exists() || !pkg.path.join("CHANGELOG.md").exists(),
+                "[{fixture_name}] unexpected output file: {}/CHANGELOG.md",
+                pkg.name
+            );
+        }
+    }
+}
+
+/// Builds a workspace under `root` with one directory per package in `packages.toml`.
+fn fixture_workspace(packages_toml: &Path, root: &Path, changelog_dir: &Path) -> Workspace {
+    let manifest: PackagesManifest =
+        toml::from_str(&std::fs::read_to_string(packages_toml).unwrap()).unwrap();
+
+    let packages: Vec<Package> = manifest
+        .packages
+        .iter()
+        .map(|p| {
+            let pkg_dir = root.join(p.path.as_deref().unwrap_or(&p.name));
+            std::fs::create_dir_all(&pkg_dir).unwrap();
+            Package {
+                name: p.name.clone(),
+                version: Version::parse(&p.version).unwrap(),
+                path: pkg_dir.clone(),
+                manifest_path: pkg_dir.join("Cargo.toml"),
+                dependencies: p.deps.iter().map(Dependency::normal).collect(),
+            }
+        })
+        .collect();
+
+    Workspace::new(root.to_path_buf(), packages, Ecosystem::Rust)
+        .with_changelog_dir(changelog_dir.to_path_buf())
+}
+
+/// Copies the files under `src` into `dest`, keeping their relative paths.
+fn copy_dir(src: &Path, dest: &Path) {
+    for entry in std::fs::read_dir(src).unwrap() {
+        let entry = entry.unwrap();
+        let target = dest.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            std::fs::create_dir_all(&target).unwrap();
+            copy_dir(&entry.path(), &target);
+        } else {
+            std::fs::copy(entry.path(), target).unwrap();
+        }
+    }
 }
 
 fn bless_changelogs(workspace: &Workspace, config: &Config, expected_dir: &Path) {
@@ -172,6 +227,11 @@ fn bless_changelogs(workspace: &Workspace, config: &Config, expected_dir: &Path)
                         std::fs::copy(&src, expected_dir.join(golden_name)).unwrap();
                     }
                 }
+                // Packages with `format = "root"` write the root changelog.
+                let root = workspace.root.join("CHANGELOG.md");
+                if root.exists() {
+                    std::fs::copy(&root, expected_dir.join("CHANGELOG.md")).unwrap();
+                }
             }
         }
     }
@@ -223,6 +283,19 @@ fn format_releases(releases: &[plan::PackageRelease]) -> String {
         + "\n"
 }
 
+fn format_reasons(releases: &[plan::PackageRelease]) -> String {
+    releases
+        .iter()
+        .flat_map(|r| {
+            r.reasons
+                .iter()
+                .map(move |reason| format!("{}: {}", r.name, reason))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
 // ── Table-driven tests ──────────────────────────────────────────────
 
 #[test]
@@ -260,11 +333,61 @@ fn golden_root_changelog() {
     run_golden_test("root-changelog");
 }
 
+#[test]
+fn golden_root_package_versions() {
+    run_golden_test("root-package-versions");
+}
+
+#[test]
+fn golden_changelog_skip() {
+    run_golden_test("changelog-skip");
+}
+
 #[test]
 fn golden_multiple_changelogs_per_crate() {
     run_golden_test("multiple-changelogs-per-crate");
 }
 
+#[test]
+fn golden_package_summaries() {
+    run_golden_test("package-summaries");
+}
+
+#[test]
+fn golden_section_emoji() {
+    run_golden_test("section-emoji");
+}
+
+#[test]
+fn golden_entry_prefix() {
+    run_golden_test("entry-prefix");
+}
+
+#[test]
+fn golden_non_virtual_workspace() {
+    run_golden_test("non-virtual-workspace");
+}
+
+#[test]
+fn golden_non_virtual_workspace_root() {
+    run_golden_test("non-virtual-workspace-root");
+}
+
+#[test]
+fn golden_updated_dependencies() {
+    run_golden_test("updated-dependencies");
+}
+
+#[test]
+fn golden_mixed_formats() {
+    run_golden_test("mixed-formats");
+}
+
+#[test]
+fn golden_keep_a_changelog() {
+    run_golden_test("keep-a-changelog");
+}
+
 // ── Edge-case tests ─────────────────────────────────────────────────
 
 #[test]
@@ -307,6 +430,75 @@ fn golden_root_fixed_group() {
     run_golden_test("root-fixed-group");
 }
 
+#[test]
+fn preview_out_of_tree_entry() {
+    let fixture = fixtures_root().join("dependent-bump");
+    let tmp = TempDir::new().unwrap();
+    let workspace = fixture_workspace(
+        &fixture.join("packages.toml"),
+        tmp.path(),
+        &fixture.join("changelog"),
+    );
+
+    // A changelog added in a PR, outside the fixture's pending set.
+    let entry_path = tmp.path().join("incoming.md");
+    std::fs::write(
+        &entry_path,
+        "---\ncore: major\nunknown: patch\n---\n\nBreaking change\n",
+    )
+    .unwrap();
+    let entry =
+        changelog_entry::parse("incoming", &std::fs::read_to_string(&entry_path).unwrap()).unwrap();
+
+    let preview = plan::preview_entry(&workspace, &load_config(&fixture), &entry);
+
+    assert_eq!(
+        format_releases(&preview.releases),
+        "app: 1.0.0 -> 1.0.1 (patch)\ncore: 0.5.0 -> 1.0.0 (major)\n"
+    );
+    assert_eq!(
+        preview.warnings,
+        vec!["changelog references unknown package 'unknown'"]
+    );
+}
+
+#[test]
+fn changelog_order_is_independent_of_read_order() {
+    let fixture = fixtures_root().join("multiple-changelogs-per-crate");
+    let config = load_config(&fixture);
+    let expected = std::fs::read_to_string(fixture.join("expected").join("CHANGELOG.md")).unwrap();
+
+    let mut changelogs = changelog_entry::read_all(&fixture.join("changelog")).unwrap();
+    changelogs.reverse();
+
+    let tmp = TempDir::new().unwrap();
+    let workspace = fixture_workspace(
+        &fixture.join("packages.toml"),
+        tmp.path(),
+        &fixture.join("changelog"),
+    );
+    let release_plan = plan::assemble(&workspace, changelogs.clone(), &config);
+    changelog_writer::write_changelogs_with_date(
+        &workspace,
+        &release_plan.releases,
+        &changelogs,
+        &config,
+        WriteOptions {
+            on_existing: OnExistingVersion::Replace,
+            channel: None,
+            git: &CliGit::in_dir(&workspace.root),
+            authors: &AuthorResolver::new(None),
+            date: TEST_DATE,
+        },
+    )
+    .unwrap();
+
+    let actual =
+        std::fs::read_to_string(resolve_changelog_path(&workspace, "CHANGELOG.md", &config))
+            .unwrap();
+    assert_eq!(actual, expected);
+}
+
 #[test]
 fn golden_invalid_frontmatter() {
     let fixture = fixtures_root().join("invalid-frontmatter");
@@ -0,0 +1,167 @@
+mod common;
+
+use common::{changelogs, single_crate, write};
+use tempfile::TempDir;
+
+/// An initialized single-crate workspace with a customized config and one pending
+/// changelog.
+fn setup() -> TempDir {
+    let dir = single_crate();
+    write(
+        dir.path(),
+        ".changelog/config.toml",
+        "dependent_bump = \"minor\"\n",
+    );
+    write(
+        dir.path(),
+        ".changelog/brave-cats-dance.md",
+        "---\ncore: minor\n---\n\nAdded a parser\n",
+    );
+    dir
+}
+
+#[test]
+fn init_refuses_when_initialized() {
+    let dir = setup();
+
+    let output = changelogs(dir.path(), &["init"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--force"), "{stderr}");
+    let config = std::fs::read_to_string(dir.path().join(".changelog/config.toml")).unwrap();
+    assert_eq!(config, "dependent_bump = \"minor\"\n");
+}
+
+#[test]
+fn init_force_refreshes_config_and_keeps_changelogs() {
+    let dir = setup();
+
+    let output = changelogs(dir.path(), &["init", "--force"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Overwriting customized"), "{stderr}");
+
+    let changelog_dir = dir.path().join(".changelog");
+    let config = std::fs::read_to_string(changelog_dir.join("config.toml")).unwrap();
+    assert!(config.contains("dependent_bump = \"patch\""), "{config}");
+    assert_eq!(
+        std::fs::read_to_string(changelog_dir.join("config.toml.bak")).unwrap(),
+        "dependent_bump = \"minor\"\n"
+    );
+    assert!(changelog_dir.join("README.md").exists());
+    assert_eq!(
+        std::fs::read_to_string(changelog_dir.join("brave-cats-dance.md")).unwrap(),
+        "---\ncore: minor\n---\n\nAdded a parser\n"
+    );
+
+    // A default config is replaced without a warning.
+    let output = changelogs(dir.path(), &["init", "--force"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("Overwriting"), "{stderr}");
+}
+
+/// An uninitialized single-crate workspace that uses changesets, with one pending entry.
+fn setup_changesets() -> TempDir {
+    let dir = single_crate();
+    write(
+        dir.path(),
+        ".changeset/config.json",
+        r#"{ "access": "public", "ignore": ["docs"], "baseBranch": "develop" }"#,
+    );
+    write(dir.path(), ".changeset/README.md", "# Changesets\n");
+    write(
+        dir.path(),
+        ".changeset/quiet-owls-sing.md",
+        "---\n\"core\": patch\n---\n\nFixed a crash\n",
+    );
+    dir
+}
+
+#[test]
+fn init_aborts_on_changesets_without_a_choice() {
+    let dir = setup_changesets();
+
+    let output = changelogs(dir.path(), &["init", "--yes"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--migrate"), "{stderr}");
+    assert!(!dir.path().join(".changelog").exists());
+    assert!(dir.path().join(".changeset/quiet-owls-sing.md").exists());
+}
+
+#[test]
+fn init_coexists_with_changesets() {
+    let dir = setup_changesets();
+
+    let output = changelogs(dir.path(), &["init", "--yes", "--coexist"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("WARNING"), "{stderr}");
+    let config = std::fs::read_to_string(dir.path().join(".changelog/config.toml")).unwrap();
+    assert!(!config.contains("develop"), "{config}");
+    assert!(dir.path().join(".changeset/config.json").exists());
+    assert!(dir.path().join(".changeset/quiet-owls-sing.md").exists());
+}
+
+#[test]
+fn init_migrates_changesets() {
+    let dir = setup_changesets();
+
+    let output = changelogs(dir.path(), &["init", "--yes", "--migrate"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Not converted from config.json: access"),
+        "{stderr}"
+    );
+    let changelog_dir = dir.path().join(".changelog");
+    let config = std::fs::read_to_string(changelog_dir.join("config.toml")).unwrap();
+    assert!(config.contains("ignore = [\"docs\"]"), "{config}");
+    assert!(config.contains("base_branch = \"develop\""), "{config}");
+    assert!(changelog_dir.join("README.md").exists());
+    assert!(changelog_dir.join("quiet-owls-sing.md").exists());
+    assert!(!dir.path().join(".changeset").exists());
+
+    let status = changelogs(dir.path(), &["status"]);
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(stdout.contains("core"), "{stdout}");
+}
+
+#[test]
+fn migrate_command_converts_changesets() {
+    let dir = setup_changesets();
+
+    let output = changelogs(dir.path(), &["migrate"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(dir.path().join(".changelog/quiet-owls-sing.md").exists());
+    assert!(!dir.path().join(".changeset").exists());
+
+    // Nothing left to migrate.
+    let output = changelogs(dir.path(), &["migrate", "--force"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no .changeset/ directory"), "{stderr}");
+}
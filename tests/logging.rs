@@ -0,0 +1,99 @@
+mod common;
+
+use changelogs::Ecosystem;
+use changelogs::changelog_entry;
+use changelogs::changelog_writer::{self, OnExistingVersion};
+use changelogs::config::Config;
+use changelogs::github::AuthorResolver;
+use changelogs::plan;
+use changelogs::workspace::Workspace;
+use common::core_and_app;
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Collects formatted log lines in memory.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn version_run_logs_discovery_plan_and_updates() {
+    let dir = core_and_app();
+    // The only test in this binary, so changing the working directory is safe.
+    std::env::set_current_dir(dir.path()).unwrap();
+
+    let logs = Capture::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_writer({
+            let logs = logs.clone();
+            move || logs.clone()
+        })
+        .without_time()
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, || {
+        let workspace = Workspace::discover_with_ecosystem(Some(Ecosystem::Rust)).unwrap();
+        let config = Config::load(&workspace.changelog_dir()).unwrap();
+        let changelogs = changelog_entry::read_all(&workspace.changelog_dir()).unwrap();
+        let release_plan = plan::assemble(&workspace, changelogs.clone(), &config);
+
+        let mut updates = HashMap::new();
+        for release in &release_plan.releases {
+            workspace
+                .update_version_with_config(&release.name, &release.new_version, &config)
+                .unwrap();
+            updates.insert(release.name.clone(), release.new_version.clone());
+        }
+        workspace.update_dependency_versions(&updates).unwrap();
+        changelog_writer::write_changelogs(
+            &workspace,
+            &release_plan.releases,
+            &changelogs,
+            &config,
+            OnExistingVersion::Fail,
+            None,
+            &AuthorResolver::new(None),
+        )
+        .unwrap();
+    });
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    let line = |needles: &[&str]| {
+        assert!(
+            logs.lines().any(|l| needles.iter().all(|n| l.contains(n))),
+            "no log line with {needles:?} in:\n{logs}"
+        );
+    };
+
+    line(&["DEBUG", "discovered packages", "ecosystem=rust", "count=2"]);
+    line(&["found package", "package=core", "version=1.0.0"]);
+    line(&[
+        "planned release",
+        "package=core",
+        "to=1.1.0",
+        "changelog brave-fox (minor)",
+    ]);
+    line(&[
+        "planned release",
+        "package=app",
+        "to=0.3.1",
+        "depends on core",
+    ]);
+    line(&[
+        "updated dependency requirements",
+        "app/Cargo.toml",
+        "modified=true",
+    ]);
+}
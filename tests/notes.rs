@@ -0,0 +1,150 @@
+mod common;
+
+use common::{cargo_workspace, changelogs, git_at, write};
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A two-crate workspace with several released sections and no `.changelog/`.
+fn setup() -> TempDir {
+    let dir = cargo_workspace(&[("core", "1.5.0", &[]), ("utils", "0.3.0", &[])]);
+    write(
+        dir.path(),
+        "core/CHANGELOG.md",
+        "# Changelog\n\n\
+         ## 1.5.0 (2025-03-01)\n\n### Minor Changes\n\n- Added retries.\n\n\
+         ## 1.4.1 (2025-02-10)\n\n### Patch Changes\n\n- Fixed a timeout.\n\n\
+         ## 1.4.0 (2025-02-01)\n\n### Minor Changes\n\n- Added streaming.\n\n\
+         ## 1.3.0 (2025-01-05)\n\n### Minor Changes\n\n- Added a client.\n",
+    );
+    write(
+        dir.path(),
+        "utils/CHANGELOG.md",
+        "# Changelog\n\n\
+         ## 0.3.0 (2025-02-20)\n\n### Minor Changes\n\n- Added a helper.\n\n\
+         ## 0.2.0 (2025-02-01)\n\n### Minor Changes\n\n- Released with core 1.4.0.\n\n\
+         ## 0.1.0 (2025-01-05)\n\n- Initial release.\n",
+    );
+    dir
+}
+
+#[test]
+fn notes_since_release() {
+    let dir = setup();
+
+    let output = changelogs(dir.path(), &["notes", "--since", "core@1.4.0"]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "# Release notes since core@1.4.0\n\n\
+         ## core\n\n\
+         ### [1.5.0 (2025-03-01)](core/CHANGELOG.md#150-2025-03-01)\n\n\
+         #### Minor Changes\n\n- Added retries.\n\n\
+         ### [1.4.1 (2025-02-10)](core/CHANGELOG.md#141-2025-02-10)\n\n\
+         #### Patch Changes\n\n- Fixed a timeout.\n\n\
+         ## utils\n\n\
+         ### [0.3.0 (2025-02-20)](utils/CHANGELOG.md#030-2025-02-20)\n\n\
+         #### Minor Changes\n\n- Added a helper.\n"
+    );
+}
+
+#[test]
+fn notes_since_date_to_file() {
+    let dir = setup();
+
+    let output = changelogs(
+        dir.path(),
+        &["notes", "--since", "2025-02-15", "--output", "NOTES.md"],
+    );
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    let notes = std::fs::read_to_string(dir.path().join("NOTES.md")).unwrap();
+    assert!(notes.contains("1.5.0 (2025-03-01)"), "{notes}");
+    assert!(notes.contains("0.3.0 (2025-02-20)"), "{notes}");
+    assert!(!notes.contains("1.4.1"), "{notes}");
+}
+
+#[test]
+fn notes_rejects_unknown_release() {
+    let dir = setup();
+
+    let output = changelogs(dir.path(), &["notes", "--since", "core@9.9.9"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("core@9.9.9"), "{stderr}");
+
+    let output = changelogs(dir.path(), &["notes", "--since", "yesterday"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("invalid release reference"), "{stderr}");
+}
+
+/// Runs `changelogs version` for `entry`, then commits and tags the release on `date`.
+fn release(dir: &Path, date: &str, entry: &str, tags: &[&str]) {
+    write(dir, ".changelog/entry.md", entry);
+    let output = changelogs(dir, &["version", "--allow-dirty"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    git_at(dir, Some(date), &["add", "-A"]);
+    git_at(dir, Some(date), &["commit", "-q", "-m", "release"]);
+    for tag in tags {
+        git_at(dir, Some(date), &["tag", tag]);
+    }
+}
+
+#[test]
+fn notes_date_per_crate_sections_by_tag() {
+    let dir = cargo_workspace(&[("core", "1.3.0", &[]), ("utils", "0.1.0", &[])]);
+    write(dir.path(), ".changelog/config.toml", "");
+    git_at(dir.path(), Some("2025-01-05T12:00:00Z"), &["init", "-q"]);
+    git_at(dir.path(), Some("2025-01-05T12:00:00Z"), &["add", "-A"]);
+    git_at(
+        dir.path(),
+        Some("2025-01-05T12:00:00Z"),
+        &["commit", "-q", "-m", "init"],
+    );
+
+    release(
+        dir.path(),
+        "2025-02-01T12:00:00Z",
+        "---\ncore: minor\n---\n\nAdded streaming.\n",
+        &["core@1.4.0"],
+    );
+    release(
+        dir.path(),
+        "2025-02-10T12:00:00Z",
+        "---\ncore: patch\nutils: minor\n---\n\nFixed a timeout.\n",
+        &["core@1.4.1", "utils@0.2.0"],
+    );
+    let core = std::fs::read_to_string(dir.path().join("core/CHANGELOG.md")).unwrap();
+    assert!(core.contains("## `core@1.4.1`"), "{core}");
+
+    let notes = |since: &str| {
+        let output = changelogs(dir.path(), &["notes", "--since", since]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let since_release = notes("core@1.4.0");
+    assert!(since_release.contains("`core@1.4.1`"), "{since_release}");
+    assert!(since_release.contains("`utils@0.2.0`"), "{since_release}");
+    assert!(!since_release.contains("`core@1.4.0`"), "{since_release}");
+
+    let since_date = notes("2025-02-01");
+    assert!(since_date.contains("`core@1.4.0`"), "{since_date}");
+    assert!(since_date.contains("`utils@0.2.0`"), "{since_date}");
+
+    assert!(notes("2025-02-11").contains("No releases."));
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::{cargo_workspace, changelogs, stdout, write};
+use tempfile::TempDir;
+
+/// A two-crate workspace, not yet initialized: `app` depends on the private `core`.
+fn setup() -> TempDir {
+    let dir = cargo_workspace(&[("core", "1.0.0", &[]), ("app", "0.3.0", &["core"])]);
+    write(
+        dir.path(),
+        "core/Cargo.toml",
+        "[package]\nname = \"core\"\nversion = \"1.0.0\"\nedition = \"2021\"\npublish = false\n",
+    );
+    dir
+}
+
+#[test]
+fn packages_json_lists_discovered_packages() {
+    let dir = setup();
+    let output = stdout(&changelogs(dir.path(), &["packages", "--json"]));
+
+    let mut value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert!(value["root"].is_string());
+    value["root"] = "<root>".into();
+
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "root": "<root>",
+            "ecosystem": "rust",
+            "packages": [
+                {
+                    "name": "app",
+                    "version": "0.3.0",
+                    "path": "app",
+                    "manifest_path": "app/Cargo.toml",
+                    "ecosystem": "rust",
+                    "dependencies": [{ "name": "core", "kind": "normal" }],
+                    "private": false,
+                    "ignored": false,
+                    "publishable": true
+                },
+                {
+                    "name": "core",
+                    "version": "1.0.0",
+                    "path": "core",
+                    "manifest_path": "core/Cargo.toml",
+                    "ecosystem": "rust",
+                    "dependencies": [],
+                    "private": true,
+                    "ignored": false,
+                    "publishable": false
+                }
+            ]
+        })
+    );
+}
+
+#[test]
+fn packages_marks_ignored_packages() {
+    let dir = setup();
+    write(dir.path(), ".changelog/config.toml", "ignore = [\"app\"]\n");
+
+    let output = stdout(&changelogs(dir.path(), &["packages"]));
+
+    let lines: Vec<&str> = output.lines().skip(2).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "  • app 0.3.0 (ignored)",
+            "    manifest: app/Cargo.toml",
+            "    depends on: core",
+            "  • core 1.0.0 (private)",
+            "    manifest: core/Cargo.toml",
+        ]
+    );
+}
+
+#[test]
+fn packages_graph_prints_dot() {
+    let dir = setup();
+    let output = stdout(&changelogs(dir.path(), &["packages", "--graph"]));
+
+    assert_eq!(
+        output,
+        "digraph dependencies {\n    \"app\";\n    \"core\";\n    \"app\" -> \"core\";\n}\n"
+    );
+}
@@ -1,4 +1,4 @@
-use changelogs::ecosystems::{Ecosystem, EcosystemAdapter, PythonAdapter};
+use changelogs::ecosystems::{Dependency, Ecosystem, EcosystemAdapter, PythonAdapter};
 use semver::Version;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -233,9 +233,9 @@ dependencies = [
     let packages = PythonAdapter::discover(temp_dir.path()).unwrap();
     let deps = &packages[0].dependencies;
 
-    assert!(deps.contains(&"foo-bar".to_string()));
-    assert!(deps.contains(&"foo-baz".to_string()));
-    assert!(deps.contains(&"foo-qux".to_string()));
+    assert!(deps.contains(&Dependency::normal("foo-bar")));
+    assert!(deps.contains(&Dependency::normal("foo-baz")));
+    assert!(deps.contains(&Dependency::normal("foo-qux")));
 }
 
 #[test]
@@ -290,10 +290,26 @@ pytest = "^7.0"
     assert_eq!(packages.len(), 1);
     assert_eq!(packages[0].name, "poetry-package");
     assert_eq!(packages[0].version, Version::new(1, 2, 3));
-    assert!(packages[0].dependencies.contains(&"requests".to_string()));
-    assert!(packages[0].dependencies.contains(&"click".to_string()));
-    assert!(packages[0].dependencies.contains(&"pytest".to_string()));
-    assert!(!packages[0].dependencies.contains(&"python".to_string()));
+    assert!(
+        packages[0]
+            .dependencies
+            .contains(&Dependency::normal("requests"))
+    );
+    assert!(
+        packages[0]
+            .dependencies
+            .contains(&Dependency::normal("click"))
+    );
+    assert!(
+        packages[0]
+            .dependencies
+            .contains(&Dependency::dev("pytest"))
+    );
+    assert!(
+        !packages[0]
+            .dependencies
+            .contains(&Dependency::normal("python"))
+    );
 }
 
 #[test]
@@ -348,3 +364,19 @@ version = "2.0.0"
     assert_eq!(packages[0].name, "pep621-package");
     assert_eq!(packages[0].version, Version::new(1, 0, 0));
 }
+
+#[test]
+fn test_python_broken_manifest_reports_location() {
+    let dir = TempDir::new().unwrap();
+    create_pyproject(
+        dir.path(),
+        "[project]\nname = \"my-package\"\nversion = 0.1.0\"\n",
+    );
+
+    let err = PythonAdapter::discover(dir.path()).unwrap_err();
+
+    let message = err.to_string();
+    assert!(message.starts_with("failed to load python manifests:\n"));
+    assert!(message.contains("pyproject.toml"), "{message}");
+    assert!(message.contains("line 3"), "{message}");
+}
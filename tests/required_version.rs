@@ -0,0 +1,50 @@
+mod common;
+
+use common::{changelogs, single_crate, write};
+use tempfile::TempDir;
+
+/// A one-crate workspace whose config requires `required`.
+fn setup(required: &str) -> TempDir {
+    let dir = single_crate();
+    write(
+        dir.path(),
+        ".changelog/config.toml",
+        &format!("required_version = \"{}\"\n", required),
+    );
+    dir
+}
+
+#[test]
+fn version_json_includes_format_version() {
+    let dir = TempDir::new().unwrap();
+    let output = changelogs(dir.path(), &["--version", "--json"]);
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        json["format_version"],
+        changelogs::changelog_writer::FORMAT_VERSION
+    );
+}
+
+#[test]
+fn commands_refuse_to_run_outside_required_version() {
+    let dir = setup(&format!("={}", env!("CARGO_PKG_VERSION")));
+    assert!(changelogs(dir.path(), &["status"]).status.success());
+
+    let dir = setup(">=99");
+    // Subdirectories find the config too.
+    let output = changelogs(&dir.path().join("src"), &["status"]);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("requires changelogs >=99"), "{stderr}");
+    assert!(stderr.contains(env!("CARGO_PKG_VERSION")), "{stderr}");
+
+    let output = changelogs(dir.path(), &["status", "--ignore-version-check"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}